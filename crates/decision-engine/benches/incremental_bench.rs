@@ -0,0 +1,84 @@
+//! Single-cell sweeps: full re-evaluation versus `evaluate_incremental`.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use decision_engine::{
+    evaluate_decision, evaluate_incremental, ActionOption, DecisionInput, MissingCellPolicy,
+    OutcomeChange, Scenario,
+};
+
+fn matrix(actions: usize, scenarios: usize) -> DecisionInput {
+    let actions: Vec<ActionOption> = (0..actions)
+        .map(|i| ActionOption {
+            id: format!("a{i:04}"),
+            label: format!("Action {i}"),
+        })
+        .collect();
+    let scenarios: Vec<Scenario> = (0..scenarios)
+        .map(|j| Scenario {
+            id: format!("s{j:04}"),
+            probability: None,
+            adversarial: j % 5 == 0,
+            severity: None,
+            adversary_objective: None,
+        })
+        .collect();
+    let outcomes = actions
+        .iter()
+        .enumerate()
+        .flat_map(|(i, action)| {
+            scenarios.iter().enumerate().map(move |(j, scenario)| {
+                let utility = f64::from(u32::try_from((i * 31 + j * 17) % 200).unwrap());
+                (action.id.clone(), scenario.id.clone(), utility)
+            })
+        })
+        .collect();
+    DecisionInput {
+        id: Some("bench".to_string()),
+        actions,
+        scenarios,
+        outcomes,
+        constraints: None,
+        evidence: None,
+        meta: None,
+        action_priors: None,
+        tie_break_seed: None,
+        recommended_set_size: None,
+        recommended_set_epsilon: None,
+        allow_missing_as_zero: false,
+        missing_cell_policy: MissingCellPolicy::Error,
+        precision: None,
+        strict: false,
+        portfolios: None,
+        target: None,
+        ranking_mode: None,
+    }
+}
+
+fn bench_single_cell_change(c: &mut Criterion) {
+    let mut group = c.benchmark_group("single_cell_change");
+    for size in [20, 80] {
+        let input = matrix(size, size);
+        let output = evaluate_decision(&input).unwrap();
+        let change = OutcomeChange {
+            action_id: input.actions[size / 2].id.clone(),
+            scenario_id: input.scenarios[size / 3].id.clone(),
+            utility: 250.0,
+        };
+        let changed = change.apply(&input);
+
+        group.bench_with_input(BenchmarkId::new("full", size), &changed, |b, changed| {
+            b.iter(|| evaluate_decision(black_box(changed)).unwrap());
+        });
+        group.bench_with_input(
+            BenchmarkId::new("incremental", size),
+            &change,
+            |b, change| {
+                b.iter(|| evaluate_incremental(&output, &input, black_box(change)).unwrap());
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_single_cell_change);
+criterion_main!(benches);
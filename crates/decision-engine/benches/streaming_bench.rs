@@ -0,0 +1,156 @@
+//! Large scenario sets: `evaluate_decision` versus `evaluate_decision_streaming`.
+//!
+//! Besides timing both paths, reports each one's peak resident memory on
+//! Linux. Each peak is taken in a fresh child process, so memory the
+//! allocator kept from an earlier run cannot hide it.
+
+use std::env;
+use std::fs;
+use std::process::Command;
+
+use criterion::{black_box, criterion_group, BenchmarkId, Criterion};
+use decision_engine::{
+    evaluate_decision, evaluate_decision_streaming, ActionOption, DecisionInput, MissingCellPolicy,
+    Scenario,
+};
+
+const SIZES: [usize; 2] = [10_000, 50_000];
+
+/// Set to `<full|streaming>:<scenarios>` in a child measuring one peak.
+const PEAK_ENV: &str = "STREAMING_BENCH_PEAK";
+
+/// A `kB` line of `/proc/self/status`, e.g. `VmHWM`.
+fn status_kib(field: &str) -> Option<u64> {
+    let status = fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|line| line.starts_with(field))?;
+    line.split_whitespace().nth(1)?.parse().ok()
+}
+
+fn actions(count: usize) -> Vec<ActionOption> {
+    (0..count)
+        .map(|i| ActionOption {
+            id: format!("a{i:02}"),
+            label: format!("Action {i}"),
+        })
+        .collect()
+}
+
+fn scenarios(count: usize) -> Vec<Scenario> {
+    (0..count)
+        .map(|j| Scenario {
+            id: format!("path{j:06}"),
+            probability: None,
+            adversarial: j % 100 == 0,
+            severity: None,
+            adversary_objective: None,
+        })
+        .collect()
+}
+
+/// Synthetic path outcomes, generated on demand.
+fn outcomes<'a>(
+    actions: &'a [ActionOption],
+    scenarios: &'a [Scenario],
+) -> impl Iterator<Item = (String, String, f64)> + 'a {
+    actions.iter().enumerate().flat_map(move |(i, action)| {
+        scenarios.iter().enumerate().map(move |(j, scenario)| {
+            let utility = f64::from(u32::try_from((i * 7919 + j * 104_729) % 1000).unwrap());
+            (action.id.clone(), scenario.id.clone(), utility)
+        })
+    })
+}
+
+fn full_input(actions: &[ActionOption], scenarios: &[Scenario]) -> DecisionInput {
+    DecisionInput {
+        id: None,
+        actions: actions.to_vec(),
+        scenarios: scenarios.to_vec(),
+        outcomes: outcomes(actions, scenarios).collect(),
+        constraints: None,
+        evidence: None,
+        meta: None,
+        action_priors: None,
+        tie_break_seed: None,
+        recommended_set_size: None,
+        recommended_set_epsilon: None,
+        allow_missing_as_zero: false,
+        missing_cell_policy: MissingCellPolicy::Error,
+        precision: None,
+        strict: false,
+        portfolios: None,
+        target: None,
+        ranking_mode: None,
+    }
+}
+
+/// Run one evaluation for `spec` (see `PEAK_ENV`) and print the resident
+/// KiB it added at its peak.
+fn measure_peak(spec: &str) {
+    let (path, size) = spec
+        .split_once(':')
+        .expect("PEAK_ENV is <path>:<scenarios>");
+    let actions = actions(10);
+    let scenarios = scenarios(size.parse().expect("scenario count"));
+    let base = status_kib("VmRSS:").unwrap_or(0);
+    if path == "full" {
+        drop(evaluate_decision(&full_input(&actions, &scenarios)).unwrap());
+    } else {
+        let outcomes = outcomes(&actions, &scenarios);
+        drop(evaluate_decision_streaming(&actions, &scenarios, outcomes, false).unwrap());
+    }
+    println!("{}", status_kib("VmHWM:").unwrap_or(0).saturating_sub(base));
+}
+
+/// Peak resident KiB of one evaluation, measured in a child process; None
+/// where `/proc` is unavailable.
+fn child_peak_kib(path: &str, size: usize) -> Option<u64> {
+    status_kib("VmHWM:")?;
+    let output = Command::new(env::current_exe().ok()?)
+        .env(PEAK_ENV, format!("{path}:{size}"))
+        .output()
+        .ok()?;
+    String::from_utf8(output.stdout).ok()?.trim().parse().ok()
+}
+
+fn report_peak_memory() {
+    for size in SIZES {
+        if let (Some(full), Some(streamed)) = (
+            child_peak_kib("full", size),
+            child_peak_kib("streaming", size),
+        ) {
+            println!("{size} scenarios: peak memory {full} KiB full, {streamed} KiB streaming");
+        }
+    }
+}
+
+fn bench_large_scenario_sets(c: &mut Criterion) {
+    let mut group = c.benchmark_group("large_scenario_set");
+    group.sample_size(10);
+    let actions = actions(10);
+    for size in SIZES {
+        let scenarios = scenarios(size);
+        group.bench_with_input(BenchmarkId::new("full", size), &size, |b, _| {
+            b.iter(|| evaluate_decision(&full_input(&actions, black_box(&scenarios))).unwrap());
+        });
+        group.bench_with_input(BenchmarkId::new("streaming", size), &size, |b, _| {
+            b.iter(|| {
+                let outcomes = outcomes(&actions, black_box(&scenarios));
+                evaluate_decision_streaming(&actions, &scenarios, outcomes, false).unwrap()
+            });
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_large_scenario_sets);
+
+// `criterion_main!`, after answering a peak-memory child
+fn main() {
+    if let Ok(spec) = env::var(PEAK_ENV) {
+        measure_peak(&spec);
+        return;
+    }
+    report_peak_memory();
+    benches();
+    Criterion::default().configure_from_args().final_summary();
+}
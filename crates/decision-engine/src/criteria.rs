@@ -0,0 +1,517 @@
+//! Pluggable scoring criteria.
+//!
+//! [`evaluate_with_criteria`] ranks actions by a weighted composite of any
+//! [`ScoringCriterion`]s, so callers can add rules of their own next to the
+//! engine's. The engine's criteria are available as [`BuiltinCriterion`]s;
+//! [`builtin_criteria`] pairs them with an input's weights and ranks exactly
+//! as [`evaluate_decision`](crate::engine::evaluate_decision) does by
+//! composite score.
+
+use crate::determinism::{compute_fingerprint, float_normalize_with, stable_hash, FLOAT_PRECISION};
+use crate::engine::{
+    bayesian_posterior, compute_adversarial_regret, compute_adversarial_scores,
+    compute_epsilon_contamination, compute_minimax_regret_scores, compute_prior_scores,
+    compute_robustness, compute_target_probability, compute_worst_case_scores,
+    decision_fingerprint, finite_score, scenario_probabilities, sums_to_one, tie_break_cmp,
+    utility_table_for, validate_input, DecisionError,
+};
+use crate::types::{
+    CompositeWeights, CriteriaOutput, CriteriaRankedAction, Criterion, DecisionInput, Scenario,
+    SeverityDiscount,
+};
+use std::collections::BTreeMap;
+
+/// Criteria with their weights, as `evaluate_with_criteria` takes them.
+pub type WeightedCriteria = Vec<(Box<dyn ScoringCriterion>, f64)>;
+
+/// A rule scoring every action from the utility table.
+pub trait ScoringCriterion {
+    /// Name keying the criterion's weight and scores. Names are unique within
+    /// an evaluation and part of its fingerprint.
+    fn name(&self) -> &str;
+
+    /// Score of every action (and portfolio) in `utility_table`
+    /// (`action_id` -> `scenario_id` -> utility), by action ID.
+    fn score(
+        &self,
+        utility_table: &BTreeMap<String, BTreeMap<String, f64>>,
+        scenarios: &[Scenario],
+    ) -> Result<BTreeMap<String, f64>, DecisionError>;
+
+    /// What a score adds to the composite, before weighting; higher is
+    /// better. The score itself unless overridden.
+    fn composite_term(&self, score: f64) -> f64 {
+        score
+    }
+}
+
+/// One of the engine's criteria, configured from an input the way
+/// `evaluate_decision` configures it.
+#[derive(Debug, Clone)]
+pub struct BuiltinCriterion {
+    criterion: Criterion,
+    precision: f64,
+    discount: SeverityDiscount,
+    epsilon: f64,
+    /// Scenario probabilities after any evidence.
+    probabilities: BTreeMap<String, f64>,
+    /// Each action's share of the prior mass.
+    priors: BTreeMap<String, f64>,
+    target: Option<f64>,
+}
+
+impl BuiltinCriterion {
+    /// Configure `criterion` from `input`'s precision, constraints, evidence,
+    /// priors and target.
+    pub fn new(criterion: Criterion, input: &DecisionInput) -> Result<Self, DecisionError> {
+        let precision = input.normalization_precision();
+        let constraints = input.constraints.as_ref();
+        let scenario_priors = scenario_probabilities(&input.scenarios);
+        let probabilities = match input
+            .evidence
+            .as_ref()
+            .map(|e| &e.scenario_likelihoods)
+            .filter(|l| !l.is_empty())
+        {
+            Some(likelihoods) => bayesian_posterior(&scenario_priors, likelihoods)?,
+            None => scenario_priors,
+        };
+        Ok(Self {
+            criterion,
+            precision,
+            discount: constraints
+                .and_then(|c| c.severity_discount)
+                .unwrap_or_default(),
+            epsilon: constraints.and_then(|c| c.epsilon).unwrap_or(0.0),
+            probabilities,
+            priors: compute_prior_scores(&input.actions, input.action_priors.as_ref(), precision),
+            target: input.target,
+        })
+    }
+
+    /// `criterion` with no input behind it: its scores are meaningless, only
+    /// its `composite_term` is used, to combine scores already computed.
+    fn unconfigured(criterion: Criterion) -> Self {
+        Self {
+            criterion,
+            precision: FLOAT_PRECISION,
+            discount: SeverityDiscount::default(),
+            epsilon: 0.0,
+            probabilities: BTreeMap::new(),
+            priors: BTreeMap::new(),
+            target: None,
+        }
+    }
+
+    /// The criterion this scores.
+    #[must_use]
+    pub fn criterion(&self) -> Criterion {
+        self.criterion
+    }
+}
+
+impl ScoringCriterion for BuiltinCriterion {
+    fn name(&self) -> &str {
+        self.criterion.name()
+    }
+
+    fn score(
+        &self,
+        utility_table: &BTreeMap<String, BTreeMap<String, f64>>,
+        scenarios: &[Scenario],
+    ) -> Result<BTreeMap<String, f64>, DecisionError> {
+        let precision = self.precision;
+        match self.criterion {
+            Criterion::WorstCase => compute_worst_case_scores(utility_table, precision),
+            Criterion::MinimaxRegret => {
+                Ok(compute_minimax_regret_scores(utility_table, scenarios, precision)?.1)
+            }
+            Criterion::Adversarial => {
+                compute_adversarial_scores(utility_table, scenarios, self.discount, precision)
+            }
+            Criterion::EpsilonContamination => compute_epsilon_contamination(
+                utility_table,
+                scenarios,
+                &self.probabilities,
+                self.epsilon,
+                self.discount,
+                precision,
+            ),
+            Criterion::AdversarialRegret => {
+                let (regret_table, _) =
+                    compute_minimax_regret_scores(utility_table, scenarios, precision)?;
+                Ok(compute_adversarial_regret(
+                    &regret_table,
+                    scenarios,
+                    precision,
+                ))
+            }
+            // Portfolios carry no prior, and without a target nothing is
+            // reached: both score 0.0
+            Criterion::Prior => Ok(utility_table
+                .keys()
+                .map(|id| (id.clone(), self.priors.get(id).copied().unwrap_or(0.0)))
+                .collect()),
+            Criterion::TargetProbability => Ok(match self.target {
+                Some(target) => compute_target_probability(
+                    utility_table,
+                    &self.probabilities,
+                    target,
+                    precision,
+                ),
+                None => utility_table.keys().map(|id| (id.clone(), 0.0)).collect(),
+            }),
+        }
+    }
+
+    fn composite_term(&self, score: f64) -> f64 {
+        match self.criterion {
+            // Lower regret is better
+            Criterion::MinimaxRegret | Criterion::AdversarialRegret => 100.0 - score,
+            // Probabilities are scaled to 0-100
+            Criterion::Prior | Criterion::TargetProbability => 100.0 * score,
+            Criterion::WorstCase | Criterion::Adversarial | Criterion::EpsilonContamination => {
+                score
+            }
+        }
+    }
+}
+
+/// Every built-in criterion with its weight in `input` (the default weights
+/// unless the constraints or ranking mode set them), in `Criterion::ALL`
+/// order.
+pub fn builtin_criteria(input: &DecisionInput) -> Result<WeightedCriteria, DecisionError> {
+    let weights = input.weights().cloned().unwrap_or_default();
+    let template = BuiltinCriterion::new(Criterion::WorstCase, input)?;
+    Ok(Criterion::ALL
+        .into_iter()
+        .map(|criterion| {
+            let builtin = BuiltinCriterion {
+                criterion,
+                ..template.clone()
+            };
+            (
+                Box::new(builtin) as Box<dyn ScoringCriterion>,
+                weights.weight(criterion),
+            )
+        })
+        .collect())
+}
+
+/// Rank actions by a weighted composite of `criteria`.
+///
+/// Each criterion scores every action (and portfolio) of the utility table
+/// `evaluate_decision` builds; an action's composite score is the sum of the
+/// criteria's `composite_term`s, each weighted by its share of the total
+/// weight. Actions are ranked by composite score, descending, with the input's
+/// tie-break rule. With [`builtin_criteria`] the ranking, composite scores and
+/// robustness match `evaluate_decision`'s for an input without a
+/// lexicographic ranking mode.
+///
+/// The input is validated as by `evaluate_decision`. Criterion names must be
+/// non-empty and unique and weights finite, non-negative and summing to 1.0;
+/// every criterion must give every action a finite score.
+pub fn evaluate_with_criteria(
+    input: &DecisionInput,
+    criteria: &[(Box<dyn ScoringCriterion>, f64)],
+) -> Result<CriteriaOutput, DecisionError> {
+    input.validate_finite()?;
+    validate_input(input)?;
+    let weights = validate_criteria(input, criteria)?;
+    let precision = input.normalization_precision();
+    let (utility_table, _) = utility_table_for(input)?;
+
+    let mut criterion_scores: BTreeMap<String, BTreeMap<String, f64>> = BTreeMap::new();
+    for (criterion, _) in criteria {
+        let name = criterion.name();
+        let mut scores = criterion.score(&utility_table, &input.scenarios)?;
+        for action_id in utility_table.keys() {
+            let score = scores.get_mut(action_id).ok_or_else(|| {
+                DecisionError::InvalidInput(format!(
+                    "criterion '{name}' gave no score for '{action_id}'"
+                ))
+            })?;
+            *score = finite_score(*score, precision, || {
+                format!("'{name}' score of '{action_id}'")
+            })?;
+        }
+        scores.retain(|action_id, _| utility_table.contains_key(action_id));
+        criterion_scores.insert(name.to_string(), scores);
+    }
+
+    let terms: Vec<_> = criteria
+        .iter()
+        .map(|(criterion, weight)| {
+            (
+                criterion.as_ref(),
+                *weight,
+                &criterion_scores[criterion.name()],
+            )
+        })
+        .collect();
+    let composite = composite_scores(&utility_table, &terms, precision)?;
+
+    let mut ranked: Vec<(&String, &f64)> = composite.iter().collect();
+    ranked.sort_by(|a, b| {
+        b.1.partial_cmp(a.1)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| tie_break_cmp(input.tie_break_seed, a.0, b.0))
+    });
+    let robustness = compute_robustness(&ranked, precision);
+
+    let mut ranked_actions: Vec<CriteriaRankedAction> = ranked
+        .iter()
+        .enumerate()
+        .map(
+            |(rank, &(action_id, &composite_score))| CriteriaRankedAction {
+                action_id: action_id.clone(),
+                composite_score,
+                rank: rank + 1,
+                tied_with: Vec::new(),
+            },
+        )
+        .collect();
+    let tied = |a: &CriteriaRankedAction, b: &CriteriaRankedAction| {
+        float_normalize_with((a.composite_score - b.composite_score).abs(), precision) == 0.0
+    };
+    for group in ranked_actions.chunk_by_mut(tied) {
+        if group.len() < 2 {
+            continue;
+        }
+        let mut ids: Vec<String> = group.iter().map(|a| a.action_id.clone()).collect();
+        ids.sort();
+        for action in group {
+            action.tied_with = ids
+                .iter()
+                .filter(|&id| *id != action.action_id)
+                .cloned()
+                .collect();
+        }
+    }
+
+    let fingerprint = stable_hash(
+        format!(
+            "{}:criteria={}",
+            decision_fingerprint(input),
+            compute_fingerprint(&weights)
+        )
+        .as_bytes(),
+    );
+
+    Ok(CriteriaOutput {
+        ranked_actions,
+        robustness,
+        determinism_fingerprint: fingerprint,
+        weights,
+        criterion_scores,
+    })
+}
+
+/// Weighted composite of each criterion's scores, for each action keyed in
+/// `actions`.
+///
+/// `criteria` pairs each criterion with its weight and its scores. An
+/// action's composite score is the sum of the criteria's `composite_term`s
+/// of its scores, each weighted by its share of the total weight; a missing
+/// score counts as 0.0.
+pub(crate) fn composite_scores<V>(
+    actions: &BTreeMap<String, V>,
+    criteria: &[(&dyn ScoringCriterion, f64, &BTreeMap<String, f64>)],
+    precision: f64,
+) -> Result<BTreeMap<String, f64>, DecisionError> {
+    let total: f64 = criteria.iter().map(|(_, weight, _)| weight).sum();
+    actions
+        .keys()
+        .map(|action_id| {
+            let mut sum = 0.0;
+            for (criterion, weight, scores) in criteria {
+                let score = scores.get(action_id).copied().unwrap_or(0.0);
+                sum += weight / total * criterion.composite_term(score);
+            }
+            let score = finite_score(sum, precision, || {
+                format!("composite score of '{action_id}'")
+            })?;
+            Ok((action_id.clone(), score))
+        })
+        .collect()
+}
+
+/// Composite scores of the built-in criteria under `weights`, given each
+/// criterion's score table, for every action in the worst-case table.
+///
+/// This is how `evaluate_decision` combines the tables it traces, through
+/// the same [`BuiltinCriterion::composite_term`]s and [`composite_scores`]
+/// as [`evaluate_with_criteria`] with [`builtin_criteria`].
+pub(crate) fn builtin_composite<'t>(
+    table: impl Fn(Criterion) -> &'t BTreeMap<String, f64>,
+    weights: &CompositeWeights,
+    precision: f64,
+) -> Result<BTreeMap<String, f64>, DecisionError> {
+    let builtins = Criterion::ALL.map(BuiltinCriterion::unconfigured);
+    let terms: Vec<_> = builtins
+        .iter()
+        .map(|builtin| {
+            (
+                builtin as &dyn ScoringCriterion,
+                weights.weight(builtin.criterion),
+                table(builtin.criterion),
+            )
+        })
+        .collect();
+    composite_scores(table(Criterion::WorstCase), &terms, precision)
+}
+
+/// Check criterion names and weights, returning the weight per name.
+fn validate_criteria(
+    input: &DecisionInput,
+    criteria: &[(Box<dyn ScoringCriterion>, f64)],
+) -> Result<BTreeMap<String, f64>, DecisionError> {
+    if criteria.is_empty() {
+        return Err(DecisionError::InvalidInput(
+            "at least one criterion is required".to_string(),
+        ));
+    }
+    let mut weights: BTreeMap<String, f64> = BTreeMap::new();
+    for (criterion, weight) in criteria {
+        let name = criterion.name();
+        if name.is_empty() {
+            return Err(DecisionError::InvalidInput(
+                "criterion names must be non-empty".to_string(),
+            ));
+        }
+        if !(weight.is_finite() && *weight >= 0.0) {
+            return Err(DecisionError::InvalidInput(format!(
+                "weight of criterion '{name}' must be finite and non-negative, got {weight}"
+            )));
+        }
+        if weights.insert(name.to_string(), *weight).is_some() {
+            return Err(DecisionError::DuplicateId {
+                kind: "criterion".to_string(),
+                id: name.to_string(),
+            });
+        }
+    }
+    let sum: f64 = criteria.iter().map(|(_, weight)| weight).sum();
+    if !sums_to_one(sum, criteria.len(), input.strict) {
+        return Err(DecisionError::InvalidWeights { sum });
+    }
+    Ok(weights)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::evaluate_decision;
+
+    fn test_input() -> DecisionInput {
+        serde_json::from_value(serde_json::json!({
+            "id": "test_decision",
+            "actions": [
+                {"id": "a1", "label": "Action 1"},
+                {"id": "a2", "label": "Action 2"}
+            ],
+            "scenarios": [
+                {"id": "s1", "probability": 0.5, "adversarial": false},
+                {"id": "s2", "probability": 0.3, "adversarial": true},
+                {"id": "s3", "probability": 0.2, "adversarial": false}
+            ],
+            "outcomes": [
+                ["a1", "s1", 100.0], ["a1", "s2", 50.0], ["a1", "s3", 80.0],
+                ["a2", "s1", 90.0], ["a2", "s2", 60.0], ["a2", "s3", 70.0]
+            ]
+        }))
+        .unwrap()
+    }
+
+    /// Utility in one scenario: a stand-in for a caller's own rule.
+    struct UtilityIn(&'static str);
+
+    impl ScoringCriterion for UtilityIn {
+        fn name(&self) -> &str {
+            "utility_in_scenario"
+        }
+
+        fn score(
+            &self,
+            utility_table: &BTreeMap<String, BTreeMap<String, f64>>,
+            _scenarios: &[Scenario],
+        ) -> Result<BTreeMap<String, f64>, DecisionError> {
+            Ok(utility_table
+                .iter()
+                .map(|(id, row)| (id.clone(), row[self.0]))
+                .collect())
+        }
+    }
+
+    #[test]
+    fn test_builtin_criteria_match_evaluate_decision() {
+        let input = test_input();
+        let expected = evaluate_decision(&input).unwrap();
+        let output = evaluate_with_criteria(&input, &builtin_criteria(&input).unwrap()).unwrap();
+
+        assert_eq!(output.ranked_actions.len(), expected.ranked_actions.len());
+        for (got, want) in output.ranked_actions.iter().zip(&expected.ranked_actions) {
+            assert_eq!(got.action_id, want.action_id);
+            assert_eq!(got.rank, want.rank);
+            assert_eq!(
+                got.composite_score.to_bits(),
+                want.composite_score.to_bits()
+            );
+        }
+        assert_eq!(output.robustness.to_bits(), expected.robustness.to_bits());
+        assert_eq!(
+            output.criterion_scores["worst_case"],
+            expected.trace.worst_case_table
+        );
+        assert_eq!(
+            output.criterion_scores["minimax_regret"],
+            expected.trace.max_regret_table
+        );
+        assert_eq!(
+            output.criterion_scores["adversarial"],
+            expected.trace.adversarial_table
+        );
+        // The default path is untouched
+        assert_eq!(evaluate_decision(&input).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_custom_criterion_ranks_and_fingerprints() {
+        let input = test_input();
+        let builtin = evaluate_with_criteria(&input, &builtin_criteria(&input).unwrap()).unwrap();
+        assert_eq!(builtin.ranked_actions[0].action_id, "a2");
+
+        // a1 leads in s3 (80 vs 70); weighted heavily enough, that wins
+        let mut criteria = builtin_criteria(&input).unwrap();
+        for (_, weight) in &mut criteria {
+            *weight /= 2.0;
+        }
+        criteria.push((Box::new(UtilityIn("s3")), 0.5));
+        let custom = evaluate_with_criteria(&input, &criteria).unwrap();
+        assert_eq!(custom.ranked_actions[0].action_id, "a1");
+        assert_eq!(custom.criterion_scores["utility_in_scenario"]["a1"], 80.0);
+        assert_eq!(custom.weights["utility_in_scenario"], 0.5);
+        assert_ne!(
+            custom.determinism_fingerprint,
+            builtin.determinism_fingerprint
+        );
+        assert_eq!(
+            evaluate_with_criteria(&input, &criteria)
+                .unwrap()
+                .determinism_fingerprint,
+            custom.determinism_fingerprint
+        );
+
+        // Names are unique and weights must sum to 1.0
+        criteria.push((Box::new(UtilityIn("s1")), 0.0));
+        assert!(matches!(
+            evaluate_with_criteria(&input, &criteria),
+            Err(DecisionError::DuplicateId { .. })
+        ));
+        let unbalanced: WeightedCriteria = vec![(Box::new(UtilityIn("s1")), 0.5)];
+        assert!(matches!(
+            evaluate_with_criteria(&input, &unbalanced),
+            Err(DecisionError::InvalidWeights { .. })
+        ));
+    }
+}
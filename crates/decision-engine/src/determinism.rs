@@ -1,14 +1,20 @@
 //! Determinism utilities for byte-stable serialization.
 //!
 //! This module provides the core determinism guarantees:
-//! - **Float normalization**: Fixed precision (1e-9) for deterministic numeric comparison
+//! - **Float normalization**: Fixed precision (1e-9 unless configured) for deterministic numeric comparison
 //! - **Canonical JSON**: Sorted keys, normalized floats, no undefined values
+//! - **Canonical CBOR**: The same canonical tree in CBOR, hashing to the same fingerprint
 //! - **Stable hashing**: BLAKE3 fingerprinting of canonical bytes (unified hash primitive)
 
-use serde::{Deserialize, Serialize};
 use blake3::Hasher;
+use ciborium::value::Value as CborValue;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
 use std::collections::BTreeMap;
 
+use crate::engine::DecisionError;
+use crate::types::DecisionInput;
+
 /// Precision for float normalization (1e-9).
 pub const FLOAT_PRECISION: f64 = 1e-9;
 
@@ -17,6 +23,17 @@ pub const FLOAT_PRECISION: f64 = 1e-9;
 /// This eliminates floating-point noise by rounding to a fixed number
 /// of decimal places (9 digits after the decimal point).
 ///
+/// Non-finite inputs are clamped rather than rejected so canonical
+/// serialization never fails: NaN becomes 0.0 and the infinities become
+/// `f64::MAX` / `f64::MIN`. Callers computing scores should use
+/// [`checked_float_normalize`] instead, which refuses them. Finite values too
+/// large to scale by `1 / FLOAT_PRECISION` already have no fractional part at
+/// that precision and are returned unchanged.
+///
+/// Zero has a single representation: `-0.0`, subnormals and anything else
+/// that rounds to zero come back as `0.0`, so logically equal values
+/// serialize to the same bytes on every platform.
+///
 /// # Example
 ///
 /// ```
@@ -27,6 +44,24 @@ pub const FLOAT_PRECISION: f64 = 1e-9;
 /// assert!((normalized - 0.3).abs() < 1e-9);
 /// ```
 pub fn float_normalize(value: f64) -> f64 {
+    float_normalize_with(value, FLOAT_PRECISION)
+}
+
+/// Normalize a float like [`float_normalize`], rounding to a multiple of
+/// `precision` instead of [`FLOAT_PRECISION`].
+///
+/// `precision` must be finite and positive (see `DecisionInput::precision`).
+///
+/// # Example
+///
+/// ```
+/// use decision_engine::determinism::float_normalize_with;
+///
+/// assert_eq!(float_normalize_with(0.123_456, 1e-3), 0.123);
+/// assert_eq!(float_normalize_with(1_234.0, 100.0), 1_200.0);
+/// ```
+#[must_use]
+pub fn float_normalize_with(value: f64, precision: f64) -> f64 {
     if value.is_nan() {
         return 0.0; // NaN is not deterministic, convert to 0
     }
@@ -37,7 +72,73 @@ pub fn float_normalize(value: f64) -> f64 {
             return f64::MIN;
         }
     }
-    (value / FLOAT_PRECISION).round() * FLOAT_PRECISION
+    if value.is_subnormal() {
+        return 0.0;
+    }
+    let scaled = value / precision;
+    if !scaled.is_finite() {
+        return value;
+    }
+    let normalized = scaled.round() * precision;
+    // Flushes -0.0, and subnormals left by a subnormal precision
+    if normalized.is_normal() {
+        normalized
+    } else {
+        0.0
+    }
+}
+
+/// `SplitMix64` pseudo-random generator.
+///
+/// Its output depends only on the seed and uses wrapping integer arithmetic,
+/// so sampled analyses reproduce bit-for-bit on every platform.
+pub(crate) struct SplitMix64(u64);
+
+impl SplitMix64 {
+    pub(crate) fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in `[0, 1)`, from the top 53 bits of the next output.
+    #[allow(clippy::cast_precision_loss)] // 53-bit integers are exact in an f64
+    pub(crate) fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// Normalize a float like [`float_normalize`], rejecting NaN and infinities.
+///
+/// Returns `None` for non-finite input instead of clamping it, so an overflow
+/// in a computed score surfaces as an error rather than a silent `f64::MAX`.
+///
+/// # Example
+///
+/// ```
+/// use decision_engine::determinism::checked_float_normalize;
+///
+/// assert_eq!(checked_float_normalize(f64::MAX), Some(f64::MAX));
+/// assert_eq!(checked_float_normalize(f64::MAX * 2.0), None);
+/// ```
+#[must_use]
+pub fn checked_float_normalize(value: f64) -> Option<f64> {
+    checked_float_normalize_with(value, FLOAT_PRECISION)
+}
+
+/// Normalize a float like [`float_normalize_with`], rejecting NaN and
+/// infinities.
+#[must_use]
+pub fn checked_float_normalize_with(value: f64, precision: f64) -> Option<f64> {
+    value
+        .is_finite()
+        .then(|| float_normalize_with(value, precision))
 }
 
 /// Internal representation for canonical JSON values.
@@ -95,14 +196,81 @@ impl CanonicalValue {
     }
 }
 
+impl CanonicalValue {
+    /// Write the canonical form indented by two spaces per level, starting
+    /// at `depth`. Scalars are written exactly as in
+    /// [`CanonicalValue::to_canonical_string`]; only whitespace is added.
+    fn write_pretty(&self, depth: usize, out: &mut String) {
+        let items: Vec<(Option<&String>, &CanonicalValue)> = match self {
+            CanonicalValue::Array(arr) if !arr.is_empty() => {
+                arr.iter().map(|v| (None, v)).collect()
+            }
+            CanonicalValue::Object(obj) if !obj.is_empty() => {
+                obj.iter().map(|(k, v)| (Some(k), v)).collect()
+            }
+            _ => {
+                out.push_str(&self.to_canonical_string());
+                return;
+            }
+        };
+        let (open, close) = if matches!(self, CanonicalValue::Array(_)) {
+            ('[', ']')
+        } else {
+            ('{', '}')
+        };
+        out.push(open);
+        for (i, (key, value)) in items.into_iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            out.push('\n');
+            out.push_str(&"  ".repeat(depth + 1));
+            if let Some(key) = key {
+                out.push_str(&CanonicalValue::String(key.clone()).to_canonical_string());
+                out.push_str(": ");
+            }
+            value.write_pretty(depth + 1, out);
+        }
+        out.push('\n');
+        out.push_str(&"  ".repeat(depth));
+        out.push(close);
+    }
+
+    /// Convert to CBOR, mirroring [`CanonicalValue::to_canonical_string`]:
+    /// numbers written as integers in JSON become CBOR integers, maps keep
+    /// the sorted key order.
+    #[allow(clippy::cast_possible_truncation)] // same cast as `to_canonical_string`
+    fn to_cbor(&self) -> CborValue {
+        match self {
+            CanonicalValue::Null => CborValue::Null,
+            CanonicalValue::Bool(b) => CborValue::Bool(*b),
+            CanonicalValue::Number(n) => {
+                let normalized = float_normalize(*n);
+                if normalized.fract() == 0.0 {
+                    CborValue::Integer((normalized as i64).into())
+                } else {
+                    CborValue::Float(normalized)
+                }
+            }
+            CanonicalValue::String(s) => CborValue::Text(s.clone()),
+            CanonicalValue::Array(arr) => {
+                CborValue::Array(arr.iter().map(CanonicalValue::to_cbor).collect())
+            }
+            CanonicalValue::Object(obj) => CborValue::Map(
+                obj.iter()
+                    .map(|(k, v)| (CborValue::Text(k.clone()), v.to_cbor()))
+                    .collect(),
+            ),
+        }
+    }
+}
+
 impl From<&serde_json::Value> for CanonicalValue {
     fn from(value: &serde_json::Value) -> Self {
         match value {
             serde_json::Value::Null => CanonicalValue::Null,
             serde_json::Value::Bool(b) => CanonicalValue::Bool(*b),
-            serde_json::Value::Number(n) => {
-                CanonicalValue::Number(n.as_f64().unwrap_or(0.0))
-            }
+            serde_json::Value::Number(n) => CanonicalValue::Number(n.as_f64().unwrap_or(0.0)),
             serde_json::Value::String(s) => CanonicalValue::String(s.clone()),
             serde_json::Value::Array(arr) => {
                 CanonicalValue::Array(arr.iter().map(CanonicalValue::from).collect())
@@ -146,11 +314,216 @@ pub fn canonical_json<T: Serialize>(value: &T) -> Vec<u8> {
     // First serialize to serde_json::Value
     let json_value = serde_json::to_value(value).unwrap_or(serde_json::Value::Null);
 
-    // Convert to canonical form
-    let canonical = CanonicalValue::from(&json_value);
+    to_canonical(&json_value)
+}
 
-    // Produce canonical string
-    canonical.to_canonical_string().into_bytes()
+/// Produce an indented rendering of [`canonical_json`] for review and diffs.
+///
+/// Keys keep the canonical sorted order and numbers and strings are written
+/// exactly as in the canonical bytes; only newlines and two-space indentation
+/// are added between tokens. Removing that whitespace gives back the bytes
+/// [`compute_fingerprint`] hashes, so the pretty form never changes the
+/// fingerprint.
+///
+/// # Example
+///
+/// ```
+/// use decision_engine::determinism::canonical_json_pretty;
+/// use serde_json::json;
+///
+/// let pretty = canonical_json_pretty(&json!({"b": 2, "a": [1]}));
+/// assert_eq!(pretty, "{\n  \"a\": [\n    1\n  ],\n  \"b\": 2\n}");
+/// ```
+#[must_use]
+pub fn canonical_json_pretty<T: Serialize>(value: &T) -> String {
+    let json_value = serde_json::to_value(value).unwrap_or(serde_json::Value::Null);
+    let mut out = String::new();
+    CanonicalValue::from(&json_value).write_pretty(0, &mut out);
+    out
+}
+
+/// Produce canonical JSON bytes from a JSON value.
+///
+/// These are exactly the bytes [`compute_fingerprint`] hashes: sorted object
+/// keys, no insignificant whitespace, floats normalized to 1e-9.
+///
+/// # Example
+///
+/// ```
+/// use decision_engine::determinism::to_canonical;
+/// use serde_json::json;
+///
+/// let bytes = to_canonical(&json!({"b": 2, "a": [1, 2]}));
+/// assert_eq!(bytes, br#"{"a":[1,2],"b":2}"#);
+/// ```
+#[must_use]
+pub fn to_canonical(value: &serde_json::Value) -> Vec<u8> {
+    CanonicalValue::from(value)
+        .to_canonical_string()
+        .into_bytes()
+}
+
+/// Check whether bytes are already in canonical JSON form.
+///
+/// Returns false for invalid JSON and for any valid JSON that
+/// [`to_canonical`] would rewrite (unsorted keys, whitespace, float noise).
+///
+/// # Example
+///
+/// ```
+/// use decision_engine::determinism::is_canonical;
+///
+/// assert!(is_canonical(br#"{"a":1,"b":2}"#));
+/// assert!(!is_canonical(br#"{"b":2,"a":1}"#));
+/// assert!(!is_canonical(br#"{"a": 1}"#));
+/// ```
+#[must_use]
+pub fn is_canonical(bytes: &[u8]) -> bool {
+    serde_json::from_slice::<serde_json::Value>(bytes)
+        .is_ok_and(|value| to_canonical(&value) == bytes)
+}
+
+/// Produce canonical CBOR bytes from a serializable value.
+///
+/// Field-ordering rule: the value goes through the same canonical tree as
+/// [`canonical_json`], so map keys are sorted by the byte order of their UTF-8
+/// text (not the RFC 8949 length-first order) and floats are normalized to
+/// 1e-9. Numbers canonical JSON writes as integers are CBOR integers, all
+/// others are CBOR floats. Decoding these bytes with [`cbor_to_canonical_json`]
+/// yields exactly the canonical JSON of the same value.
+///
+/// # Example
+///
+/// ```
+/// use decision_engine::determinism::{canonical_cbor, canonical_json, cbor_to_canonical_json};
+/// use serde_json::json;
+///
+/// let value = json!({"b": 0.5, "a": [1, 2]});
+/// let cbor = canonical_cbor(&value).unwrap();
+/// assert_eq!(cbor_to_canonical_json(&cbor).unwrap(), canonical_json(&value));
+/// ```
+pub fn canonical_cbor<T: Serialize>(value: &T) -> Result<Vec<u8>, DecisionError> {
+    let json_value =
+        serde_json::to_value(value).map_err(|e| DecisionError::Encoding(e.to_string()))?;
+    let mut bytes = Vec::new();
+    ciborium::into_writer(&CanonicalValue::from(&json_value).to_cbor(), &mut bytes)
+        .map_err(|e| DecisionError::Encoding(e.to_string()))?;
+    Ok(bytes)
+}
+
+/// Re-encode CBOR bytes as canonical JSON.
+///
+/// This is how a fingerprint is taken over CBOR: hashing the result with
+/// [`stable_hash`] gives the same digest as hashing the canonical JSON of the
+/// same logical value.
+pub fn cbor_to_canonical_json(bytes: &[u8]) -> Result<Vec<u8>, DecisionError> {
+    Ok(to_canonical(&cbor_to_json_value(bytes)?))
+}
+
+/// Decode CBOR into any type, accepting what JSON would accept (e.g. an
+/// integer for an `f64` field).
+pub(crate) fn decode_cbor<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, DecisionError> {
+    serde_json::from_value(cbor_to_json_value(bytes)?)
+        .map_err(|e| DecisionError::Encoding(e.to_string()))
+}
+
+fn cbor_to_json_value(bytes: &[u8]) -> Result<serde_json::Value, DecisionError> {
+    let value: CborValue =
+        ciborium::from_reader(bytes).map_err(|e| DecisionError::Encoding(e.to_string()))?;
+    cbor_to_json(&value)
+}
+
+/// Convert CBOR to JSON; only the JSON data model (text keys, no tags or byte
+/// strings) is accepted. Non-finite floats become null, as in `serde_json`.
+fn cbor_to_json(value: &CborValue) -> Result<serde_json::Value, DecisionError> {
+    use serde_json::Value;
+
+    Ok(match value {
+        CborValue::Null => Value::Null,
+        CborValue::Bool(b) => Value::Bool(*b),
+        CborValue::Integer(i) => {
+            let i = i128::from(*i);
+            if let Ok(n) = i64::try_from(i) {
+                Value::from(n)
+            } else if let Ok(n) = u64::try_from(i) {
+                Value::from(n)
+            } else {
+                return Err(DecisionError::Encoding(format!(
+                    "CBOR integer out of range: {i}"
+                )));
+            }
+        }
+        CborValue::Float(f) => serde_json::Number::from_f64(*f).map_or(Value::Null, Value::Number),
+        CborValue::Text(s) => Value::String(s.clone()),
+        CborValue::Array(arr) => {
+            Value::Array(arr.iter().map(cbor_to_json).collect::<Result<_, _>>()?)
+        }
+        CborValue::Map(entries) => {
+            let mut map = serde_json::Map::new();
+            for (k, v) in entries {
+                let CborValue::Text(key) = k else {
+                    return Err(DecisionError::Encoding(
+                        "CBOR map keys must be text".to_string(),
+                    ));
+                };
+                map.insert(key.clone(), cbor_to_json(v)?);
+            }
+            Value::Object(map)
+        }
+        other => {
+            return Err(DecisionError::Encoding(format!(
+                "unsupported CBOR value: {other:?}"
+            )))
+        }
+    })
+}
+
+/// Fingerprint `value` as [`compute_fingerprint`] would with its top-level
+/// `key` array holding `items`, without building that array.
+///
+/// `value` must serialize to an object. Each item is hashed in canonical form
+/// as it arrives, so a large array costs one item of memory at a time.
+pub(crate) fn fingerprint_with_items<T: Serialize>(
+    value: &T,
+    key: &str,
+    items: impl IntoIterator<Item = serde_json::Value>,
+) -> String {
+    let json_value = serde_json::to_value(value).unwrap_or(serde_json::Value::Null);
+    let CanonicalValue::Object(mut fields) = CanonicalValue::from(&json_value) else {
+        return compute_fingerprint(value);
+    };
+    fields.insert(key.to_string(), CanonicalValue::Array(Vec::new()));
+    let mut items = Some(items.into_iter());
+
+    // Mirrors `to_canonical_string` for the top-level object
+    let mut hasher = Hasher::new();
+    hasher.update(b"{");
+    for (i, (field, field_value)) in fields.iter().enumerate() {
+        if i > 0 {
+            hasher.update(b",");
+        }
+        let field_key = CanonicalValue::String(field.clone()).to_canonical_string();
+        hasher.update(field_key.as_bytes());
+        hasher.update(b":");
+        let streamed = if field == key { items.take() } else { None };
+        match streamed {
+            Some(items) => {
+                hasher.update(b"[");
+                for (j, item) in items.enumerate() {
+                    if j > 0 {
+                        hasher.update(b",");
+                    }
+                    hasher.update(CanonicalValue::from(&item).to_canonical_string().as_bytes());
+                }
+                hasher.update(b"]");
+            }
+            None => {
+                hasher.update(field_value.to_canonical_string().as_bytes());
+            }
+        }
+    }
+    hasher.update(b"}");
+    hasher.finalize().to_hex().to_string()
 }
 
 /// Compute BLAKE3 hash of bytes, returning hex-encoded string.
@@ -194,6 +567,49 @@ pub fn compute_fingerprint<T: Serialize>(value: &T) -> String {
     stable_hash(&bytes)
 }
 
+/// Shard in `0..shards` for `input`, for spreading decision batches over
+/// workers.
+///
+/// The first 64 bits of [`compute_fingerprint`] (its first 16 hex digits)
+/// modulo `shards`, so an input lands on the same shard in every run and on
+/// every platform. Like the fingerprint, it hashes the input as given: the
+/// same outcomes listed in another order may land elsewhere.
+///
+/// # Panics
+///
+/// If `shards` is 0.
+#[must_use]
+pub fn shard_of(input: &DecisionInput, shards: u32) -> u32 {
+    assert!(shards > 0, "shard count must be positive");
+    let digest = blake3::hash(&canonical_json(input));
+    let mut prefix = [0u8; 8];
+    prefix.copy_from_slice(&digest.as_bytes()[..8]);
+    let shard = u64::from_be_bytes(prefix) % u64::from(shards);
+    u32::try_from(shard).expect("remainder is below a u32 shard count")
+}
+
+/// Split a batch into `shards` groups by [`shard_of`].
+///
+/// Group `i` holds exactly the inputs with `shard_of(input, shards) == i`,
+/// in batch order, so rerunning a batch sends every input to the same group.
+///
+/// # Panics
+///
+/// If `shards` is 0.
+#[must_use]
+pub fn partition_batch(
+    inputs: impl IntoIterator<Item = DecisionInput>,
+    shards: u32,
+) -> Vec<Vec<DecisionInput>> {
+    assert!(shards > 0, "shard count must be positive");
+    let mut groups: Vec<Vec<DecisionInput>> = (0..shards).map(|_| Vec::new()).collect();
+    for input in inputs {
+        let shard = shard_of(&input, shards);
+        groups[shard as usize].push(input);
+    }
+    groups
+}
+
 /// Trait for types that can produce a determinism fingerprint.
 pub trait DeterminismFingerprint {
     /// Compute the deterministic fingerprint.
@@ -240,6 +656,36 @@ mod tests {
         assert!(neg_inf < -1e308);
     }
 
+    #[test]
+    fn test_float_normalize_extreme_finite() {
+        assert_eq!(float_normalize(f64::MAX), f64::MAX);
+        assert_eq!(float_normalize(f64::MIN), f64::MIN);
+        assert_eq!(checked_float_normalize(f64::MAX), Some(f64::MAX));
+        assert_eq!(checked_float_normalize(f64::INFINITY), None);
+        assert_eq!(checked_float_normalize(f64::NAN), None);
+    }
+
+    #[test]
+    fn test_float_normalize_signed_zero() {
+        let negative = float_normalize(-0.0);
+        assert_eq!(negative.to_bits(), 0.0_f64.to_bits());
+        assert_eq!(
+            serde_json::to_vec(&negative).unwrap(),
+            serde_json::to_vec(&float_normalize(0.0)).unwrap()
+        );
+        // Negative noise that rounds away is positive zero too
+        assert_eq!(float_normalize(-1e-12).to_bits(), 0.0_f64.to_bits());
+    }
+
+    #[test]
+    fn test_float_normalize_flushes_subnormals() {
+        let subnormal = f64::MIN_POSITIVE / 4.0;
+        assert!(subnormal.is_subnormal());
+        assert_eq!(float_normalize(subnormal).to_bits(), 0.0_f64.to_bits());
+        assert_eq!(float_normalize(-subnormal).to_bits(), 0.0_f64.to_bits());
+        assert_eq!(checked_float_normalize(-subnormal), Some(0.0));
+    }
+
     #[test]
     fn test_canonical_json_sorted_keys() {
         let value = json!({
@@ -291,6 +737,78 @@ mod tests {
         assert_eq!(bytes1, bytes2);
     }
 
+    #[test]
+    fn test_canonical_json_pretty_strips_to_canonical() {
+        let value = json!({
+            "zebra": [1, {"b": 0.30000000000000004, "a": null}],
+            "apple": {"empty": {}, "none": [], "label": "x-y"},
+            "mango": -0.0
+        });
+
+        let pretty = canonical_json_pretty(&value);
+        assert!(pretty.starts_with("{\n  \"apple\": {\n    \"empty\": {},"));
+        let stripped: String = pretty.chars().filter(|c| !c.is_whitespace()).collect();
+        assert_eq!(stripped.into_bytes(), canonical_json(&value));
+    }
+
+    #[test]
+    fn test_to_canonical_roundtrip() {
+        let original = br#"{ "zebra": 1, "apple": {"y": 0.30000000000000004, "x": [3, 1]} }"#;
+        let value: serde_json::Value = serde_json::from_slice(original).unwrap();
+
+        let canonical = to_canonical(&value);
+        let text = String::from_utf8(canonical.clone()).unwrap();
+        assert!(text.starts_with(r#"{"apple":{"x":[3,1],"y":"#));
+        assert!(text.ends_with(r#"},"zebra":1}"#));
+        // Float noise is normalized away
+        assert_eq!(
+            canonical,
+            to_canonical(&json!({"zebra": 1, "apple": {"x": [3, 1], "y": 0.3}}))
+        );
+        assert!(is_canonical(&canonical));
+        assert!(!is_canonical(original));
+        assert!(!is_canonical(b"not json"));
+
+        // Canonicalizing is idempotent and is what the fingerprint hashes
+        let reparsed: serde_json::Value = serde_json::from_slice(&canonical).unwrap();
+        assert_eq!(to_canonical(&reparsed), canonical);
+        assert_eq!(compute_fingerprint(&value), stable_hash(&canonical));
+    }
+
+    #[test]
+    fn test_canonical_cbor_matches_canonical_json() {
+        let value = json!({
+            "zebra": [1, 2.5, -3],
+            "apple": {"nested": 0.1 + 0.2, "flag": true, "none": null},
+            "big": 1e12
+        });
+
+        let cbor = canonical_cbor(&value).unwrap();
+        assert_eq!(
+            cbor_to_canonical_json(&cbor).unwrap(),
+            canonical_json(&value)
+        );
+
+        // Key order in the source does not leak into the bytes
+        let reordered = json!({
+            "big": 1e12,
+            "apple": {"none": null, "flag": true, "nested": 0.3},
+            "zebra": [1, 2.5, -3]
+        });
+        assert_eq!(canonical_cbor(&reordered).unwrap(), cbor);
+    }
+
+    #[test]
+    fn test_cbor_to_canonical_json_rejects_non_json_model() {
+        let mut bytes = Vec::new();
+        ciborium::into_writer(&CborValue::Bytes(vec![1, 2, 3]), &mut bytes).unwrap();
+        assert!(matches!(
+            cbor_to_canonical_json(&bytes),
+            Err(DecisionError::Encoding(_))
+        ));
+        assert!(cbor_to_canonical_json(&[0xff, 0x00]).is_err());
+    }
+
     #[test]
     fn test_stable_hash_length() {
         let bytes = b"test data";
@@ -371,4 +889,62 @@ mod tests {
 
         assert_eq!(fp.len(), 64);
     }
-}
\ No newline at end of file
+
+    fn batch_input(id: &str) -> DecisionInput {
+        serde_json::from_value(json!({
+            "id": id,
+            "actions": [{"id": "a1", "label": "A1"}, {"id": "a2", "label": "A2"}],
+            "scenarios": [{"id": "s1", "probability": null, "adversarial": false}],
+            "outcomes": [["a1", "s1", 1.0], ["a2", "s1", 2.0]]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_split_mix64_golden_draws() {
+        let mut rng = SplitMix64::new(42);
+        assert_eq!(
+            [rng.next_u64(), rng.next_u64(), rng.next_u64()],
+            [
+                0xBDD7_3226_2FEB_6E95,
+                0x28EF_E333_B266_F103,
+                0x4752_6757_130F_9F52
+            ]
+        );
+        let mut rng = SplitMix64::new(42);
+        assert_eq!(rng.next_f64(), 0.741_564_878_771_823_3);
+        assert_eq!(rng.next_f64(), 0.159_910_392_876_920_1);
+    }
+
+    #[test]
+    fn test_shard_of_is_stable() {
+        let input = batch_input("batch-0");
+        let prefix = u64::from_str_radix(&compute_fingerprint(&input)[..16], 16).unwrap();
+        for shards in [1, 2, 7, 64, u32::MAX] {
+            let shard = shard_of(&input, shards);
+            assert!(shard < shards);
+            assert_eq!(shard_of(&input.clone(), shards), shard);
+            assert_eq!(u64::from(shard), prefix % u64::from(shards));
+        }
+    }
+
+    #[test]
+    fn test_partition_batch_is_stable_and_exhaustive() {
+        let batch: Vec<DecisionInput> = (0..40)
+            .map(|i| batch_input(&format!("batch-{i}")))
+            .collect();
+        let groups = partition_batch(batch.clone(), 5);
+        assert_eq!(groups.len(), 5);
+        assert_eq!(groups.iter().map(Vec::len).sum::<usize>(), batch.len());
+        assert!(groups.iter().filter(|group| !group.is_empty()).count() > 1);
+        for (shard, group) in (0..).zip(&groups) {
+            // Exactly the batch's inputs for this shard, in batch order
+            let expected: Vec<&DecisionInput> = batch
+                .iter()
+                .filter(|input| shard_of(input, 5) == shard)
+                .collect();
+            assert_eq!(group.iter().collect::<Vec<_>>(), expected);
+        }
+        assert_eq!(partition_batch(batch, 5), groups);
+    }
+}
@@ -6,14 +6,21 @@
 //! - Adversarial Robustness: Score against worst adversarial scenarios
 //! - Composite Scoring: Weighted combination of all metrics
 
-use crate::determinism::{compute_fingerprint, float_normalize, stable_hash};
+use crate::determinism::{
+    checked_float_normalize_with, compute_fingerprint, fingerprint_with_items,
+    float_normalize_with, stable_hash, SplitMix64, FLOAT_PRECISION,
+};
 use crate::types::*;
-use serde::{Deserialize, Serialize};
-use std::collections::BTreeMap;
+use serde::ser::SerializeStruct;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::{BTreeMap, BTreeSet};
 use thiserror::Error;
 
 /// Errors that can occur during decision evaluation.
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+///
+/// Serializes as `{"code": ..., "message": ...}` and deserializes back from
+/// it; see [`DecisionError::code`].
+#[derive(Debug, Clone, PartialEq)]
 pub enum DecisionError {
     /// No actions provided.
     NoActions,
@@ -25,8 +32,28 @@ pub enum DecisionError {
     InvalidOutcome(String),
     /// Weights don't sum to 1.0.
     InvalidWeights { sum: f64 },
+    /// Epsilon is outside [0, 1].
+    InvalidEpsilon { epsilon: f64 },
     /// Outcome data is incomplete.
     IncompleteOutcomes,
+    /// An (action, scenario) cell has no outcome and
+    /// `allow_missing_as_zero` is off.
+    MissingCell { action: String, scenario: String },
+    /// Two actions or two scenarios share an id.
+    DuplicateId { kind: String, id: String },
+    /// Scenario probabilities are out of range or don't sum to 1.0.
+    InvalidProbability(String),
+    /// Action priors are negative, non-finite, unknown or all zero.
+    InvalidPrior(String),
+    /// A scenario severity or severity penalty is negative or non-finite.
+    InvalidSeverity(String),
+    /// An input setting is out of range, or an intermediate score overflowed
+    /// to infinity or became NaN.
+    InvalidInput(String),
+    /// Bytes could not be encoded or decoded (CBOR/JSON).
+    Encoding(String),
+    /// No action's maximum regret is within `constraints.max_regret`.
+    NoFeasibleActions { regret_cap: f64 },
 }
 
 impl std::fmt::Display for DecisionError {
@@ -39,30 +66,178 @@ impl std::fmt::Display for DecisionError {
             DecisionError::InvalidWeights { sum } => {
                 write!(f, "Weights must sum to 1.0, got {}", sum)
             }
+            DecisionError::InvalidEpsilon { epsilon } => {
+                write!(f, "Epsilon must be between 0.0 and 1.0, got {epsilon}")
+            }
             DecisionError::IncompleteOutcomes => {
                 write!(f, "Outcome matrix is incomplete")
             }
+            DecisionError::MissingCell { action, scenario } => {
+                write!(
+                    f,
+                    "No outcome for action '{action}' in scenario '{scenario}'"
+                )
+            }
+            DecisionError::DuplicateId { kind, id } => {
+                write!(f, "Duplicate {kind} id: {id}")
+            }
+            DecisionError::InvalidProbability(msg) => {
+                write!(f, "Invalid probability: {msg}")
+            }
+            DecisionError::InvalidPrior(msg) => write!(f, "Invalid action prior: {msg}"),
+            DecisionError::InvalidSeverity(msg) => write!(f, "Invalid severity: {msg}"),
+            DecisionError::InvalidInput(msg) => write!(f, "Invalid input: {msg}"),
+            DecisionError::Encoding(msg) => write!(f, "Encoding error: {msg}"),
+            DecisionError::NoFeasibleActions { regret_cap } => {
+                write!(f, "No action has maximum regret within {regret_cap}")
+            }
         }
     }
 }
 
 impl std::error::Error for DecisionError {}
 
+impl DecisionError {
+    /// Stable machine-readable code for FFI and wire protocols.
+    ///
+    /// Codes never change once released, so callers can branch on them instead
+    /// of matching messages:
+    ///
+    /// | Variant              | Code                      |
+    /// |----------------------|---------------------------|
+    /// | `NoActions`          | `E_NO_ACTIONS`            |
+    /// | `NoScenarios`        | `E_NO_SCENARIOS`          |
+    /// | `NoOutcomes`         | `E_NO_OUTCOMES`           |
+    /// | `InvalidOutcome`     | `E_INVALID_OUTCOME`       |
+    /// | `InvalidWeights`     | `E_INVALID_WEIGHTS`       |
+    /// | `InvalidEpsilon`     | `E_INVALID_EPSILON`       |
+    /// | `IncompleteOutcomes` | `E_MISSING_OUTCOME`       |
+    /// | `MissingCell`        | `E_MISSING_OUTCOME`       |
+    /// | `DuplicateId`        | `E_DUPLICATE_ID`          |
+    /// | `InvalidProbability` | `E_INVALID_PROBABILITIES` |
+    /// | `InvalidPrior`       | `E_INVALID_PRIOR`         |
+    /// | `InvalidSeverity`    | `E_INVALID_SEVERITY`      |
+    /// | `InvalidInput`       | `E_INVALID_INPUT`         |
+    /// | `Encoding`           | `E_ENCODING`              |
+    /// | `NoFeasibleActions`  | `E_NO_FEASIBLE_ACTIONS`   |
+    #[must_use]
+    pub fn code(&self) -> &'static str {
+        match self {
+            DecisionError::NoActions => "E_NO_ACTIONS",
+            DecisionError::NoScenarios => "E_NO_SCENARIOS",
+            DecisionError::NoOutcomes => "E_NO_OUTCOMES",
+            DecisionError::InvalidOutcome(_) => "E_INVALID_OUTCOME",
+            DecisionError::InvalidWeights { .. } => "E_INVALID_WEIGHTS",
+            DecisionError::InvalidEpsilon { .. } => "E_INVALID_EPSILON",
+            DecisionError::IncompleteOutcomes | DecisionError::MissingCell { .. } => {
+                "E_MISSING_OUTCOME"
+            }
+            DecisionError::DuplicateId { .. } => "E_DUPLICATE_ID",
+            DecisionError::InvalidProbability(_) => "E_INVALID_PROBABILITIES",
+            DecisionError::InvalidPrior(_) => "E_INVALID_PRIOR",
+            DecisionError::InvalidSeverity(_) => "E_INVALID_SEVERITY",
+            DecisionError::InvalidInput(_) => "E_INVALID_INPUT",
+            DecisionError::Encoding(_) => "E_ENCODING",
+            DecisionError::NoFeasibleActions { .. } => "E_NO_FEASIBLE_ACTIONS",
+        }
+    }
+
+    /// The error with `code` whose `Display` is `message`, if any: the
+    /// inverse of serialization.
+    fn from_code(code: &str, message: &str) -> Option<Self> {
+        let text = |prefix: &str| message.strip_prefix(prefix).map(str::to_string);
+        let number = |prefix: &str| message.strip_prefix(prefix)?.parse::<f64>().ok();
+        Some(match code {
+            "E_NO_ACTIONS" => DecisionError::NoActions,
+            "E_NO_SCENARIOS" => DecisionError::NoScenarios,
+            "E_NO_OUTCOMES" => DecisionError::NoOutcomes,
+            "E_INVALID_OUTCOME" => DecisionError::InvalidOutcome(text("Invalid outcome: ")?),
+            "E_INVALID_WEIGHTS" => DecisionError::InvalidWeights {
+                sum: number("Weights must sum to 1.0, got ")?,
+            },
+            "E_INVALID_EPSILON" => DecisionError::InvalidEpsilon {
+                epsilon: number("Epsilon must be between 0.0 and 1.0, got ")?,
+            },
+            "E_MISSING_OUTCOME" if message == "Outcome matrix is incomplete" => {
+                DecisionError::IncompleteOutcomes
+            }
+            "E_MISSING_OUTCOME" => {
+                let (action, scenario) = message
+                    .strip_prefix("No outcome for action '")?
+                    .strip_suffix('\'')?
+                    .split_once("' in scenario '")?;
+                DecisionError::MissingCell {
+                    action: action.to_string(),
+                    scenario: scenario.to_string(),
+                }
+            }
+            "E_DUPLICATE_ID" => {
+                let (kind, id) = message.strip_prefix("Duplicate ")?.split_once(" id: ")?;
+                DecisionError::DuplicateId {
+                    kind: kind.to_string(),
+                    id: id.to_string(),
+                }
+            }
+            "E_INVALID_PROBABILITIES" => {
+                DecisionError::InvalidProbability(text("Invalid probability: ")?)
+            }
+            "E_INVALID_PRIOR" => DecisionError::InvalidPrior(text("Invalid action prior: ")?),
+            "E_INVALID_SEVERITY" => DecisionError::InvalidSeverity(text("Invalid severity: ")?),
+            "E_INVALID_INPUT" => DecisionError::InvalidInput(text("Invalid input: ")?),
+            "E_ENCODING" => DecisionError::Encoding(text("Encoding error: ")?),
+            "E_NO_FEASIBLE_ACTIONS" => DecisionError::NoFeasibleActions {
+                regret_cap: number("No action has maximum regret within ")?,
+            },
+            _ => return None,
+        })
+    }
+}
+
+impl Serialize for DecisionError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("DecisionError", 2)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for DecisionError {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        struct Wire {
+            code: String,
+            message: String,
+        }
+
+        let Wire { code, message } = Wire::deserialize(deserializer)?;
+        DecisionError::from_code(&code, &message).ok_or_else(|| {
+            de::Error::custom(format!("unrecognized {code} error message: {message}"))
+        })
+    }
+}
+
 /// Build utility table from outcomes.
 ///
+/// Ids must be unique (see `validate_input`); a duplicate id would collapse
+/// two actions or scenarios into one row or column.
+///
 /// Returns: action_id -> scenario_id -> utility
 fn build_utility_table(
     actions: &[ActionOption],
     scenarios: &[Scenario],
     outcomes: &[(String, String, f64)],
+    fill: f64,
+    precision: f64,
 ) -> BTreeMap<String, BTreeMap<String, f64>> {
     let mut table: BTreeMap<String, BTreeMap<String, f64>> = BTreeMap::new();
 
-    // Initialize with zeros
+    // Initialize with the missing-cell fill
+    let fill = float_normalize_with(fill, precision);
     for action in actions {
         let mut scenario_map: BTreeMap<String, f64> = BTreeMap::new();
         for scenario in scenarios {
-            scenario_map.insert(scenario.id.clone(), 0.0);
+            scenario_map.insert(scenario.id.clone(), fill);
         }
         table.insert(action.id.clone(), scenario_map);
     }
@@ -71,7 +246,7 @@ fn build_utility_table(
     for (action_id, scenario_id, utility) in outcomes {
         if let Some(scenario_map) = table.get_mut(action_id) {
             if let Some(u) = scenario_map.get_mut(scenario_id) {
-                *u = float_normalize(*utility);
+                *u = float_normalize_with(*utility, precision);
             }
         }
     }
@@ -79,136 +254,679 @@ fn build_utility_table(
     table
 }
 
+/// One warning per (action, scenario) cell the missing-cell policy filled,
+/// in input order.
+fn missing_cell_warnings(input: &DecisionInput) -> Vec<String> {
+    let Some(fill) = input.missing_cell_fill() else {
+        return Vec::new();
+    };
+    let covered: BTreeSet<(&str, &str)> = input
+        .outcomes
+        .iter()
+        .map(|(a, s, _)| (a.as_str(), s.as_str()))
+        .collect();
+    let mut warnings = Vec::new();
+    for action in &input.actions {
+        for scenario in &input.scenarios {
+            if !covered.contains(&(action.id.as_str(), scenario.id.as_str())) {
+                warnings.push(format!(
+                    "missing outcome for '{}' in '{}' filled with {fill}",
+                    action.id, scenario.id
+                ));
+            }
+        }
+    }
+    warnings
+}
+
+/// A portfolio's distinct members, sorted, and the ID it is ranked under:
+/// those members joined by `+`.
+fn portfolio_members(portfolio: &[String]) -> (String, Vec<String>) {
+    let members: Vec<String> = portfolio
+        .iter()
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .cloned()
+        .collect();
+    (members.join("+"), members)
+}
+
+/// Add a utility row per portfolio: the mean of its members' utilities in
+/// each scenario.
+///
+/// Portfolios must be validated (see `validate_input`). Returns the
+/// portfolio table: portfolio ID -> sorted member IDs.
+fn add_portfolio_rows(
+    table: &mut BTreeMap<String, BTreeMap<String, f64>>,
+    portfolios: &[Vec<String>],
+    precision: f64,
+) -> Result<BTreeMap<String, Vec<String>>, DecisionError> {
+    let mut members_by_id: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for portfolio in portfolios {
+        let (id, members) = portfolio_members(portfolio);
+        let scenario_ids: Vec<&String> = table[&members[0]].keys().collect();
+
+        let mut row: BTreeMap<String, f64> = BTreeMap::new();
+        for scenario_id in scenario_ids {
+            let mean = portfolio_utility(table, &id, &members, scenario_id, precision)?;
+            row.insert(scenario_id.clone(), mean);
+        }
+        table.insert(id.clone(), row);
+        members_by_id.insert(id, members);
+    }
+    Ok(members_by_id)
+}
+
+/// Mean utility of a portfolio's members in one scenario.
+#[allow(clippy::cast_precision_loss)] // member counts are far below 2^52
+fn portfolio_utility(
+    table: &BTreeMap<String, BTreeMap<String, f64>>,
+    id: &str,
+    members: &[String],
+    scenario_id: &str,
+    precision: f64,
+) -> Result<f64, DecisionError> {
+    let total: f64 = members.iter().map(|m| table[m][scenario_id]).sum();
+    finite_score(total / members.len() as f64, precision, || {
+        format!("utility of portfolio '{id}' in '{scenario_id}'")
+    })
+}
+
+/// Normalize a computed score to `precision`, rejecting NaN and infinities.
+///
+/// Utilities are validated finite, but arithmetic on extreme ones (e.g. the
+/// regret between `f64::MAX` and `-f64::MAX`) can still overflow; `what`
+/// names the score in the resulting `InvalidInput` error.
+pub(crate) fn finite_score(
+    value: f64,
+    precision: f64,
+    what: impl FnOnce() -> String,
+) -> Result<f64, DecisionError> {
+    checked_float_normalize_with(value, precision)
+        .ok_or_else(|| DecisionError::InvalidInput(format!("{} is not finite ({})", what(), value)))
+}
+
 /// Compute worst-case (maximin) scores.
 ///
 /// For each action, find the minimum utility across all scenarios.
 /// Then select the action with the maximum of these minimums.
-fn compute_worst_case_scores(
+pub(crate) fn compute_worst_case_scores(
     utility_table: &BTreeMap<String, BTreeMap<String, f64>>,
-) -> BTreeMap<String, f64> {
+    precision: f64,
+) -> Result<BTreeMap<String, f64>, DecisionError> {
     let mut worst_case: BTreeMap<String, f64> = BTreeMap::new();
 
     for (action_id, scenario_map) in utility_table {
-        let min_utility = scenario_map
-            .values()
-            .fold(f64::INFINITY, |acc, &v| acc.min(v));
-        worst_case.insert(action_id.clone(), float_normalize(min_utility));
+        worst_case.insert(
+            action_id.clone(),
+            worst_case_of(action_id, scenario_map, precision)?,
+        );
     }
 
-    worst_case
+    Ok(worst_case)
+}
+
+/// Minimum utility in one action's row.
+fn worst_case_of(
+    action_id: &str,
+    scenario_map: &BTreeMap<String, f64>,
+    precision: f64,
+) -> Result<f64, DecisionError> {
+    // Validation guarantees every action has at least one scenario, so the
+    // fold never returns its infinite seed
+    let min_utility = scenario_map
+        .values()
+        .fold(f64::INFINITY, |acc, &v| acc.min(v));
+    finite_score(min_utility, precision, || {
+        format!("worst case of '{action_id}'")
+    })
+}
+
+/// Constrained maximin: among actions whose maximum regret is at most
+/// `regret_cap`, pick the one with the highest worst case, breaking ties by
+/// action ID.
+///
+/// Fails with `NoFeasibleActions` when every action exceeds the cap.
+fn compute_constrained_maximin(
+    worst_case_table: &BTreeMap<String, f64>,
+    max_regret_table: &BTreeMap<String, f64>,
+    regret_cap: f64,
+) -> Result<ConstrainedMaximin, DecisionError> {
+    let feasible: Vec<String> = max_regret_table
+        .iter()
+        .filter(|(_, &regret)| regret <= regret_cap)
+        .map(|(action_id, _)| action_id.clone())
+        .collect();
+    // Feasible ids are sorted, so keeping the first maximum breaks ties by id
+    let mut chosen: Option<(&String, f64)> = None;
+    for action_id in &feasible {
+        let worst = worst_case_table
+            .get(action_id)
+            .copied()
+            .unwrap_or(f64::NEG_INFINITY);
+        if chosen.is_none_or(|(_, best)| worst > best) {
+            chosen = Some((action_id, worst));
+        }
+    }
+    let chosen = chosen
+        .map(|(action_id, _)| action_id.clone())
+        .ok_or(DecisionError::NoFeasibleActions { regret_cap })?;
+    Ok(ConstrainedMaximin {
+        regret_cap,
+        feasible,
+        chosen,
+    })
 }
 
+/// Per-scenario regrets by action, and each action's maximum regret.
+pub(crate) type RegretTables = (
+    BTreeMap<String, BTreeMap<String, f64>>,
+    BTreeMap<String, f64>,
+);
+
 /// Compute minimax regret scores.
 ///
 /// 1. Build regret table: for each scenario, regret = best_utility_in_scenario - action_utility
 /// 2. For each action, find maximum regret across all scenarios
 /// 3. Select action with minimum of these maximum regrets
-fn compute_minimax_regret_scores(
+pub(crate) fn compute_minimax_regret_scores(
     utility_table: &BTreeMap<String, BTreeMap<String, f64>>,
     scenarios: &[Scenario],
-) -> (BTreeMap<String, BTreeMap<String, f64>>, BTreeMap<String, f64>) {
+    precision: f64,
+) -> Result<RegretTables, DecisionError> {
     let mut regret_table: BTreeMap<String, BTreeMap<String, f64>> = BTreeMap::new();
     let mut max_regret: BTreeMap<String, f64> = BTreeMap::new();
 
     // For each scenario, find the best utility
     let mut best_by_scenario: BTreeMap<String, f64> = BTreeMap::new();
     for scenario in scenarios {
-        let best = utility_table
-            .values()
-            .filter_map(|sm| sm.get(&scenario.id))
-            .fold(f64::NEG_INFINITY, |acc, &v| acc.max(v));
-        best_by_scenario.insert(scenario.id.clone(), float_normalize(best));
+        let best = best_utility_in(utility_table, &scenario.id, precision)?;
+        best_by_scenario.insert(scenario.id.clone(), best);
     }
 
     // Compute regret for each action in each scenario
     for (action_id, scenario_map) in utility_table {
         let mut action_regrets: BTreeMap<String, f64> = BTreeMap::new();
-        let mut max_r = 0.0;
 
         for (scenario_id, &utility) in scenario_map {
-            if let Some(best) = best_by_scenario.get(scenario_id) {
-                let regret = float_normalize(best - utility);
+            if let Some(&best) = best_by_scenario.get(scenario_id) {
+                let regret = regret_of(action_id, scenario_id, best, utility, precision)?;
                 action_regrets.insert(scenario_id.clone(), regret);
-                max_r = max_r.max(regret);
             }
         }
 
+        max_regret.insert(action_id.clone(), max_regret_of(&action_regrets, precision));
         regret_table.insert(action_id.clone(), action_regrets);
-        max_regret.insert(action_id.clone(), float_normalize(max_r));
     }
 
-    (regret_table, max_regret)
+    Ok((regret_table, max_regret))
+}
+
+/// Best utility any action reaches in one scenario.
+fn best_utility_in(
+    utility_table: &BTreeMap<String, BTreeMap<String, f64>>,
+    scenario_id: &str,
+    precision: f64,
+) -> Result<f64, DecisionError> {
+    let best = utility_table
+        .values()
+        .filter_map(|sm| sm.get(scenario_id))
+        .fold(f64::NEG_INFINITY, |acc, &v| acc.max(v));
+    finite_score(best, precision, || {
+        format!("best utility in '{scenario_id}'")
+    })
+}
+
+/// Regret of one action in one scenario: the scenario's best utility minus
+/// the action's.
+fn regret_of(
+    action_id: &str,
+    scenario_id: &str,
+    best: f64,
+    utility: f64,
+    precision: f64,
+) -> Result<f64, DecisionError> {
+    finite_score(best - utility, precision, || {
+        format!("regret of '{action_id}' in '{scenario_id}'")
+    })
+}
+
+/// Largest regret in one action's row (0.0 when none is positive).
+fn max_regret_of(regrets: &BTreeMap<String, f64>, precision: f64) -> f64 {
+    let max_r = regrets.values().fold(0.0_f64, |acc, &r| acc.max(r));
+    float_normalize_with(max_r, precision)
 }
 
 /// Compute adversarial robustness scores.
 ///
-/// For each action, find the minimum utility across adversarial scenarios only.
+/// For each action, the adversary picks a scenario according to its
+/// `AdversaryObjective`: among minimize-utility scenarios the one with the
+/// lowest utility, among maximize-regret scenarios the one with the highest
+/// regret. A utility pick scores its utility, a regret pick `ceiling -
+/// regret`, where `ceiling` is the highest utility any action reaches in a
+/// maximize-regret scenario; each is discounted by its scenario's severity
+/// (see `SeverityDiscount`; severity 1.0 leaves it unchanged). The action
+/// scores the lower of the two. Both picks are on the utility scale, so
+/// shifting or scaling every utility does not change which one decides.
+/// The adversary realizes one scenario, never several at once: bad
+/// scenarios do not compound, so mutually exclusive scenarios need no
+/// grouping to avoid overstating the risk.
 /// If no adversarial scenarios exist, fall back to overall worst-case.
-fn compute_adversarial_scores(
+pub(crate) fn compute_adversarial_scores(
     utility_table: &BTreeMap<String, BTreeMap<String, f64>>,
     scenarios: &[Scenario],
-) -> BTreeMap<String, f64> {
-    let adversarial: Vec<&Scenario> = scenarios
-        .iter()
-        .filter(|s| s.adversarial)
-        .collect();
+    discount: SeverityDiscount,
+    precision: f64,
+) -> Result<BTreeMap<String, f64>, DecisionError> {
+    let adversarial: Vec<&Scenario> = scenarios.iter().filter(|s| s.adversarial).collect();
 
     let mut adversarial_scores: BTreeMap<String, f64> = BTreeMap::new();
 
     if adversarial.is_empty() {
         // No adversarial scenarios, use worst-case
-        return compute_worst_case_scores(utility_table);
+        return compute_worst_case_scores(utility_table, precision);
     }
 
+    let best = regret_adversary_bests(utility_table, &adversarial);
     for (action_id, scenario_map) in utility_table {
-        let adv_ids: Vec<&str> = adversarial.iter().map(|s| s.id.as_str()).collect();
+        let score = adversarial_score_of(
+            action_id,
+            scenario_map,
+            &adversarial,
+            &best,
+            discount,
+            precision,
+        )?;
+        adversarial_scores.insert(action_id.clone(), score);
+    }
 
-        let min_adv = scenario_map
-            .iter()
-            .filter(|(sid, _)| adv_ids.contains(&sid.as_str()))
-            .map(|(_, &v)| v)
-            .fold(f64::INFINITY, |acc, v| acc.min(v));
+    Ok(adversarial_scores)
+}
+
+/// Best utility per maximize-regret adversarial scenario, for the regret
+/// adversary.
+fn regret_adversary_bests<'a>(
+    utility_table: &BTreeMap<String, BTreeMap<String, f64>>,
+    adversarial: &[&'a Scenario],
+) -> BTreeMap<&'a str, f64> {
+    adversarial
+        .iter()
+        .filter(|s| s.adversary_objective == Some(AdversaryObjective::MaximizeRegret))
+        .map(|s| {
+            let best = utility_table
+                .values()
+                .filter_map(|sm| sm.get(&s.id))
+                .fold(f64::NEG_INFINITY, |acc, &v| acc.max(v));
+            (s.id.as_str(), best)
+        })
+        .collect()
+}
+
+/// Adversarial score of one action's row (see `compute_adversarial_scores`);
+/// `adversarial` must not be empty.
+fn adversarial_score_of(
+    action_id: &str,
+    scenario_map: &BTreeMap<String, f64>,
+    adversarial: &[&Scenario],
+    best: &BTreeMap<&str, f64>,
+    discount: SeverityDiscount,
+    precision: f64,
+) -> Result<f64, DecisionError> {
+    let discounted = |s: &Scenario| {
+        let utility = *scenario_map.get(&s.id)?;
+        Some(discount.apply(utility, s.severity.unwrap_or(1.0)))
+    };
+    let min_utility = adversarial
+        .iter()
+        .filter(|s| !best.contains_key(s.id.as_str()))
+        .filter_map(|s| discounted(s))
+        .fold(f64::INFINITY, f64::min);
+    // The regret adversary's pick is measured down from the best utility in
+    // its scenarios, so that no regret scores like the best outcome there
+    let ceiling = best.values().copied().fold(f64::NEG_INFINITY, f64::max);
+    let regret_term = adversarial
+        .iter()
+        .filter_map(|s| {
+            let regret = best.get(s.id.as_str())? - scenario_map.get(&s.id)?;
+            Some(ceiling + discount.apply(-regret, s.severity.unwrap_or(1.0)))
+        })
+        .fold(f64::INFINITY, f64::min);
+    let min_adv = min_utility.min(regret_term);
+
+    finite_score(min_adv, precision, || {
+        format!("adversarial score of '{action_id}'")
+    })
+}
+
+/// Compute adversarial regret scores.
+///
+/// For each action, find the maximum regret across adversarial scenarios only.
+/// If no adversarial scenarios exist, fall back to maximum regret over all scenarios.
+pub(crate) fn compute_adversarial_regret(
+    regret_table: &BTreeMap<String, BTreeMap<String, f64>>,
+    scenarios: &[Scenario],
+    precision: f64,
+) -> BTreeMap<String, f64> {
+    let adversarial: BTreeSet<&str> = scenarios
+        .iter()
+        .filter(|s| s.adversarial)
+        .map(|s| s.id.as_str())
+        .collect();
+
+    let mut adversarial_regret: BTreeMap<String, f64> = BTreeMap::new();
+
+    for (action_id, regrets) in regret_table {
+        adversarial_regret.insert(
+            action_id.clone(),
+            adversarial_regret_of(regrets, &adversarial, precision),
+        );
+    }
+
+    adversarial_regret
+}
+
+/// Largest regret in one action's row over the `adversarial` scenarios (all
+/// scenarios when the set is empty).
+fn adversarial_regret_of(
+    regrets: &BTreeMap<String, f64>,
+    adversarial: &BTreeSet<&str>,
+    precision: f64,
+) -> f64 {
+    let max_r = regrets
+        .iter()
+        .filter(|(sid, _)| adversarial.is_empty() || adversarial.contains(sid.as_str()))
+        .map(|(_, &r)| r)
+        .fold(0.0_f64, f64::max);
+    float_normalize_with(max_r, precision)
+}
+
+/// Scenario probabilities before any evidence.
+///
+/// The given probabilities, normalized, when every scenario has one (and they
+/// do not all vanish); otherwise scenarios are weighted equally.
+#[allow(clippy::cast_precision_loss)] // scenario counts are far below 2^52
+pub(crate) fn scenario_probabilities(scenarios: &[Scenario]) -> BTreeMap<String, f64> {
+    let explicit: Option<Vec<f64>> = scenarios.iter().map(|s| s.probability).collect();
+    match explicit {
+        Some(ps) if ps.iter().sum::<f64>() > 0.0 => {
+            let total: f64 = ps.iter().sum();
+            scenarios
+                .iter()
+                .zip(ps)
+                .map(|(scenario, p)| (scenario.id.clone(), p / total))
+                .collect()
+        }
+        _ => {
+            let uniform = 1.0 / scenarios.len() as f64;
+            scenarios
+                .iter()
+                .map(|scenario| (scenario.id.clone(), uniform))
+                .collect()
+        }
+    }
+}
+
+/// Update scenario probabilities with evidence by Bayes' rule.
+///
+/// `posterior(s) = prior(s) * L(s) / sum_t prior(t) * L(t)`, where `L(s)` is
+/// the evidence likelihood under scenario `s`
+/// (`DecisionEvidence::scenario_likelihoods`, 1.0 for unlisted scenarios).
+/// Fails when the evidence gives every scenario with prior mass likelihood 0.
+pub(crate) fn bayesian_posterior(
+    prior: &BTreeMap<String, f64>,
+    likelihoods: &BTreeMap<String, f64>,
+) -> Result<BTreeMap<String, f64>, DecisionError> {
+    let joint: BTreeMap<&String, f64> = prior
+        .iter()
+        .map(|(id, &p)| (id, p * likelihoods.get(id).copied().unwrap_or(1.0)))
+        .collect();
+    let total: f64 = joint.values().sum();
+    if !(total.is_finite() && total > 0.0) {
+        return Err(DecisionError::InvalidInput(
+            "evidence leaves no scenario with positive probability".to_string(),
+        ));
+    }
+    Ok(joint
+        .into_iter()
+        .map(|(id, mass)| (id.clone(), mass / total))
+        .collect())
+}
+
+/// Compute epsilon-contamination scores.
+///
+/// For each action: `(1 - epsilon) * expected_value + epsilon * adversarial_worst_case`.
+/// Expected value weights scenarios by `probabilities` (see
+/// `scenario_probabilities` and `bayesian_posterior`). The worst case is
+/// taken over the adversarial subset, falling back to all scenarios when none
+/// are flagged.
+pub(crate) fn compute_epsilon_contamination(
+    utility_table: &BTreeMap<String, BTreeMap<String, f64>>,
+    scenarios: &[Scenario],
+    probabilities: &BTreeMap<String, f64>,
+    epsilon: f64,
+    discount: SeverityDiscount,
+    precision: f64,
+) -> Result<BTreeMap<String, f64>, DecisionError> {
+    let adversarial = compute_adversarial_scores(utility_table, scenarios, discount, precision)?;
 
-        adversarial_scores.insert(action_id.clone(), float_normalize(min_adv));
+    let mut scores: BTreeMap<String, f64> = BTreeMap::new();
+    for (action_id, scenario_map) in utility_table {
+        let worst = adversarial.get(action_id).copied().unwrap_or(0.0);
+        let score = epsilon_score_of(
+            action_id,
+            scenario_map,
+            probabilities,
+            epsilon,
+            worst,
+            precision,
+        )?;
+        scores.insert(action_id.clone(), score);
     }
 
-    adversarial_scores
+    Ok(scores)
+}
+
+/// Epsilon-contamination score of one action's row, given its adversarial
+/// score `worst`.
+fn epsilon_score_of(
+    action_id: &str,
+    scenario_map: &BTreeMap<String, f64>,
+    probabilities: &BTreeMap<String, f64>,
+    epsilon: f64,
+    worst: f64,
+    precision: f64,
+) -> Result<f64, DecisionError> {
+    let expected: f64 = scenario_map
+        .iter()
+        .map(|(sid, &u)| probabilities.get(sid).copied().unwrap_or(0.0) * u)
+        .sum();
+    finite_score(
+        (1.0 - epsilon) * expected + epsilon * worst,
+        precision,
+        || format!("epsilon-contamination score of '{action_id}'"),
+    )
 }
 
-/// Compute composite scores from individual metrics.
-fn compute_composite_scores(
-    worst_case: &BTreeMap<String, f64>,
-    minimax_regret: &BTreeMap<String, f64>,
-    adversarial: &BTreeMap<String, f64>,
-    weights: &CompositeWeights,
+/// Compute target-probability scores.
+///
+/// For each action: the probability mass (by `probabilities`, see
+/// `scenario_probabilities` and `bayesian_posterior`) of the scenarios where
+/// its utility reaches `target`.
+pub(crate) fn compute_target_probability(
+    utility_table: &BTreeMap<String, BTreeMap<String, f64>>,
+    probabilities: &BTreeMap<String, f64>,
+    target: f64,
+    precision: f64,
 ) -> BTreeMap<String, f64> {
-    let mut composite: BTreeMap<String, f64> = BTreeMap::new();
+    utility_table
+        .iter()
+        .map(|(action_id, scenario_map)| {
+            let score = target_probability_of(scenario_map, probabilities, target, precision);
+            (action_id.clone(), score)
+        })
+        .collect()
+}
 
-    // Normalize weights to ensure they sum to 1
-    let sum = weights.worst_case + weights.minimax_regret + weights.adversarial;
-    let w_wc = weights.worst_case / sum;
-    let w_mr = weights.minimax_regret / sum;
-    let w_adv = weights.adversarial / sum;
+/// Target-probability score of one action's row.
+fn target_probability_of(
+    scenario_map: &BTreeMap<String, f64>,
+    probabilities: &BTreeMap<String, f64>,
+    target: f64,
+    precision: f64,
+) -> f64 {
+    let mass: f64 = scenario_map
+        .iter()
+        .filter(|(_, &u)| u >= target)
+        .map(|(sid, _)| probabilities.get(sid).copied().unwrap_or(0.0))
+        .sum();
+    float_normalize_with(mass.clamp(0.0, 1.0), precision)
+}
 
-    for action_id in worst_case.keys() {
-        let wc_score = worst_case.get(action_id).copied().unwrap_or(0.0);
-        let mr_score = minimax_regret.get(action_id).copied().unwrap_or(0.0);
-        let adv_score = adversarial.get(action_id).copied().unwrap_or(0.0);
+/// Actions no other action first-order stochastically dominates, in ID order.
+///
+/// `a` dominates `b` when, for every utility level `t`, `a` is at least as
+/// likely as `b` to score above `t`, and more likely for some `t`: their
+/// utility CDFs under `probabilities` satisfy `F_a(t) <= F_b(t)` everywhere,
+/// strictly somewhere. Cumulative probabilities are normalized to
+/// `precision`, so rounding noise alone never makes one action dominate.
+fn compute_stochastic_frontier(
+    utility_table: &BTreeMap<String, BTreeMap<String, f64>>,
+    probabilities: &BTreeMap<String, f64>,
+    precision: f64,
+) -> Vec<String> {
+    let cdfs: BTreeMap<&String, Vec<(f64, f64)>> = utility_table
+        .iter()
+        .map(|(action_id, scenario_map)| {
+            (
+                action_id,
+                utility_cdf(scenario_map, probabilities, precision),
+            )
+        })
+        .collect();
+    cdfs.iter()
+        .filter(|&(id, cdf)| {
+            !cdfs
+                .iter()
+                .any(|(other, other_cdf)| other != id && stochastically_dominates(other_cdf, cdf))
+        })
+        .map(|(id, _)| (*id).clone())
+        .collect()
+}
 
-        // Composite: higher is better, but minimax regret needs to be inverted
-        // (lower max regret = better)
-        let composite_score = float_normalize(
-            w_wc * wc_score + w_mr * (100.0 - mr_score) + w_adv * adv_score,
-        );
+/// The utility CDF of one action's row: `(utility, P(U <= utility))` at each
+/// distinct utility, ascending.
+fn utility_cdf(
+    scenario_map: &BTreeMap<String, f64>,
+    probabilities: &BTreeMap<String, f64>,
+    precision: f64,
+) -> Vec<(f64, f64)> {
+    let mut outcomes: Vec<(f64, f64)> = scenario_map
+        .iter()
+        .map(|(sid, &u)| (u, probabilities.get(sid).copied().unwrap_or(0.0)))
+        .collect();
+    outcomes.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let mut cdf: Vec<(f64, f64)> = Vec::with_capacity(outcomes.len());
+    let mut mass = 0.0;
+    for (u, p) in outcomes {
+        mass += p;
+        let cumulative = float_normalize_with(mass, precision);
+        match cdf.last_mut() {
+            Some(last) if last.0.total_cmp(&u).is_eq() => last.1 = cumulative,
+            _ => cdf.push((u, cumulative)),
+        }
+    }
+    cdf
+}
 
-        composite.insert(action_id.clone(), composite_score);
+/// Whether CDF `upper` first-order stochastically dominates CDF `lower`: it
+/// lies on or below `lower` at every utility where either steps, and strictly
+/// below at one of them.
+fn stochastically_dominates(upper: &[(f64, f64)], lower: &[(f64, f64)]) -> bool {
+    let (mut next_upper, mut next_lower) = (0, 0);
+    let (mut cdf_upper, mut cdf_lower) = (0.0, 0.0);
+    let mut strict = false;
+    // Walk both step functions in utility order, like a merge
+    while next_upper < upper.len() || next_lower < lower.len() {
+        let level = match (upper.get(next_upper), lower.get(next_lower)) {
+            (Some(&(u, _)), Some(&(v, _))) => u.min(v),
+            (Some(&(u, _)), None) | (None, Some(&(u, _))) => u,
+            (None, None) => break,
+        };
+        if let Some(&(_, cumulative)) = upper.get(next_upper).filter(|&&(u, _)| u <= level) {
+            cdf_upper = cumulative;
+            next_upper += 1;
+        }
+        if let Some(&(_, cumulative)) = lower.get(next_lower).filter(|&&(u, _)| u <= level) {
+            cdf_lower = cumulative;
+            next_lower += 1;
+        }
+        if cdf_upper > cdf_lower {
+            return false;
+        }
+        strict |= cdf_upper < cdf_lower;
     }
+    strict
+}
 
-    composite
+/// Compute each action's share of the prior mass.
+///
+/// Priors are normalized to sum to 1.0; actions without a prior (or with no
+/// priors given at all) get 0.0.
+pub(crate) fn compute_prior_scores(
+    actions: &[ActionOption],
+    priors: Option<&BTreeMap<String, f64>>,
+    precision: f64,
+) -> BTreeMap<String, f64> {
+    let total: f64 = priors.map_or(0.0, |p| p.values().sum());
+
+    actions
+        .iter()
+        .map(|action| {
+            let share = match priors.and_then(|p| p.get(&action.id)) {
+                Some(&prior) if total > 0.0 => float_normalize_with(prior / total, precision),
+                _ => 0.0,
+            };
+            (action.id.clone(), share)
+        })
+        .collect()
 }
 
-/// Validate input and return error if invalid.
-fn validate_input(input: &DecisionInput) -> Result<(), DecisionError> {
+/// Validate input without evaluating it.
+///
+/// Performs the structural, outcome-coverage and probability checks that
+/// `evaluate_decision` runs first, so callers can cheaply pre-check an input:
+/// - at least one action, scenario and outcome
+/// - action and scenario ids are unique
+/// - every outcome names a known action and scenario with a finite utility,
+///   and every (action, scenario) pair has at most one outcome, and one
+///   outcome unless `missing_cell_policy` or `allow_missing_as_zero` fills
+///   missing cells, with a finite utility
+/// - scenario probabilities lie in [0, 1] and, when all are given, sum to 1.0
+/// - scenario severities and the severity penalty are finite and non-negative
+/// - action priors name known actions, are finite and non-negative, and are
+///   not all zero
+/// - `recommended_set_size` is at least 1 and `recommended_set_epsilon` is
+///   finite and non-negative
+/// - `precision` is finite and positive
+/// - portfolios have at least two distinct known actions, and their IDs
+///   clash with no action or other portfolio
+/// - evidence likelihoods name known scenarios, are finite and non-negative,
+///   and leave some scenario with positive probability
+/// - constraint weights sum to 1.0, epsilon lies in [0, 1] and `max_regret`
+///   is finite and non-negative
+/// - `ranking_mode` weights sum to 1.0, and a lexicographic ranking has at
+///   least one criterion
+///
+/// With `strict` set, inputs must arrive already normalized and complete:
+/// - every (action, scenario) pair has an outcome, whatever the missing-cell
+///   policy
+/// - probabilities are given for every scenario or none
+/// - probabilities, action priors and weights sum to 1.0 up to the rounding
+///   of their addition, rather than within 1e-9
+/// - action priors, when given, cover every action
+/// - evidence likelihoods, when given, cover every scenario
+#[allow(clippy::too_many_lines)] // one check per documented rule
+pub fn validate_input(input: &DecisionInput) -> Result<(), DecisionError> {
     if input.actions.is_empty() {
         return Err(DecisionError::NoActions);
     }
@@ -219,80 +937,661 @@ fn validate_input(input: &DecisionInput) -> Result<(), DecisionError> {
         return Err(DecisionError::NoOutcomes);
     }
 
-    // Validate weights if provided
-    if let Some(constraints) = &input.constraints {
-        if let Some(max_regret) = constraints.max_regret {
-            let weights = CompositeWeights::default();
-            let sum = weights.worst_case + weights.minimax_regret + weights.adversarial;
-            if (sum - 1.0).abs() > 1e-9 {
-                return Err(DecisionError::InvalidWeights { sum });
+    let (action_ids, scenario_ids) = unique_ids(input)?;
+
+    // Outcome coverage
+    let mut covered: BTreeSet<(&str, &str)> = BTreeSet::new();
+    for (action_id, scenario_id, utility) in &input.outcomes {
+        if !action_ids.contains(action_id.as_str()) {
+            return Err(DecisionError::InvalidOutcome(format!(
+                "unknown action '{action_id}'"
+            )));
+        }
+        if !scenario_ids.contains(scenario_id.as_str()) {
+            return Err(DecisionError::InvalidOutcome(format!(
+                "unknown scenario '{scenario_id}'"
+            )));
+        }
+        if !utility.is_finite() {
+            return Err(DecisionError::InvalidOutcome(format!(
+                "utility for '{action_id}' in '{scenario_id}' must be finite"
+            )));
+        }
+        if !covered.insert((action_id.as_str(), scenario_id.as_str())) {
+            return Err(DecisionError::InvalidOutcome(format!(
+                "duplicate outcome for '{action_id}' in '{scenario_id}'"
+            )));
+        }
+    }
+    if let MissingCellPolicy::Fill(utility) = input.missing_cell_policy {
+        if !utility.is_finite() {
+            return Err(DecisionError::InvalidInput(format!(
+                "missing-cell fill must be finite, got {utility}"
+            )));
+        }
+    }
+    if input.missing_cell_fill().is_none() || input.strict {
+        // Report the first hole in input order, so the error is stable
+        for action in &input.actions {
+            for scenario in &input.scenarios {
+                if !covered.contains(&(action.id.as_str(), scenario.id.as_str())) {
+                    return Err(DecisionError::MissingCell {
+                        action: action.id.clone(),
+                        scenario: scenario.id.clone(),
+                    });
+                }
             }
         }
     }
 
-    Ok(())
-}
+    validate_scenarios(input)?;
 
-/// Main entry point: evaluate a decision problem.
-///
-/// Returns ranked actions with scores and a trace of the computation.
-pub fn evaluate_decision(input: &DecisionInput) -> Result<DecisionOutput, DecisionError> {
-    // Validate input
-    validate_input(input)?;
+    // Action priors
+    if let Some(priors) = &input.action_priors {
+        for (action_id, &prior) in priors {
+            if !action_ids.contains(action_id.as_str()) {
+                return Err(DecisionError::InvalidPrior(format!(
+                    "unknown action '{action_id}'"
+                )));
+            }
+            if !prior.is_finite() || prior < 0.0 {
+                return Err(DecisionError::InvalidPrior(format!(
+                    "prior for '{action_id}' must be finite and non-negative, got {prior}"
+                )));
+            }
+        }
+        if !priors.is_empty() && priors.values().all(|&p| p == 0.0) {
+            return Err(DecisionError::InvalidPrior(
+                "priors must not all be zero".to_string(),
+            ));
+        }
+        if input.strict {
+            if let Some(action) = input.actions.iter().find(|a| !priors.contains_key(&a.id)) {
+                return Err(DecisionError::InvalidPrior(format!(
+                    "strict mode needs a prior for '{}'",
+                    action.id
+                )));
+            }
+            let sum: f64 = priors.values().sum();
+            if !sums_to_one(sum, priors.len(), true) {
+                return Err(DecisionError::InvalidPrior(format!(
+                    "strict mode needs priors summing to 1.0, got {sum}"
+                )));
+            }
+        }
+    }
 
-    // Build utility table
-    let utility_table =
-        build_utility_table(&input.actions, &input.scenarios, &input.outcomes);
+    // Recommended set
+    if input.recommended_set_size == Some(0) {
+        return Err(DecisionError::InvalidInput(
+            "recommended_set_size must be at least 1".to_string(),
+        ));
+    }
+    if let Some(epsilon) = input.recommended_set_epsilon {
+        if !epsilon.is_finite() || epsilon < 0.0 {
+            return Err(DecisionError::InvalidInput(format!(
+                "recommended_set_epsilon must be finite and non-negative, got {epsilon}"
+            )));
+        }
+    }
 
-    // Compute all scores
-    let worst_case = compute_worst_case_scores(&utility_table);
-    let (regret_table, max_regret) = compute_minimax_regret_scores(&utility_table, &input.scenarios);
-    let adversarial = compute_adversarial_scores(&utility_table, &input.scenarios);
+    // Portfolios
+    if let Some(portfolios) = &input.portfolios {
+        let mut portfolio_ids: BTreeSet<String> = BTreeSet::new();
+        for portfolio in portfolios {
+            let (id, members) = portfolio_members(portfolio);
+            if members.len() < 2 || members.len() != portfolio.len() {
+                return Err(DecisionError::InvalidInput(format!(
+                    "portfolio '{id}' needs at least two distinct actions"
+                )));
+            }
+            if let Some(unknown) = members.iter().find(|m| !action_ids.contains(m.as_str())) {
+                return Err(DecisionError::InvalidInput(format!(
+                    "portfolio '{id}' names unknown action '{unknown}'"
+                )));
+            }
+            if action_ids.contains(id.as_str()) || !portfolio_ids.insert(id.clone()) {
+                return Err(DecisionError::DuplicateId {
+                    kind: "portfolio".to_string(),
+                    id,
+                });
+            }
+        }
+    }
 
-    // Get weights (default or from constraints)
-    let weights = input
-        .constraints
-        .as_ref()
-        .map(|_| CompositeWeights::default())
-        .unwrap_or_default();
+    // Evidence likelihoods
+    if let Some(evidence) = &input.evidence {
+        for (scenario_id, &likelihood) in &evidence.scenario_likelihoods {
+            if !scenario_ids.contains(scenario_id.as_str()) {
+                return Err(DecisionError::InvalidInput(format!(
+                    "evidence names unknown scenario '{scenario_id}'"
+                )));
+            }
+            if !likelihood.is_finite() || likelihood < 0.0 {
+                return Err(DecisionError::InvalidInput(format!(
+                    "likelihood for '{scenario_id}' must be finite and non-negative, got {likelihood}"
+                )));
+            }
+        }
+        if input.strict && !evidence.scenario_likelihoods.is_empty() {
+            if let Some(scenario) = input
+                .scenarios
+                .iter()
+                .find(|s| !evidence.scenario_likelihoods.contains_key(&s.id))
+            {
+                return Err(DecisionError::InvalidInput(format!(
+                    "strict mode needs a likelihood for '{}'",
+                    scenario.id
+                )));
+            }
+        }
+        if !evidence.scenario_likelihoods.is_empty() {
+            bayesian_posterior(
+                &scenario_probabilities(&input.scenarios),
+                &evidence.scenario_likelihoods,
+            )?;
+        }
+    }
 
-    let composite = compute_composite_scores(&worst_case, &max_regret, &adversarial, &weights);
+    if let Some(target) = input.target {
+        if !target.is_finite() {
+            return Err(DecisionError::InvalidInput(format!(
+                "target must be finite, got {target}"
+            )));
+        }
+    }
 
-    // Rank actions (sort by composite score, descending)
-    let mut ranked: Vec<(&String, f64)> = composite.iter().collect();
-    ranked.sort_by(|a, b| {
-        let cmp = b.1.partial_cmp(a.1).unwrap_or(std::cmp::Ordering::Equal);
-        if cmp == std::cmp::Ordering::Equal {
-            // Tie-break: lexicographic by action_id
-            a.0.cmp(b.0)
-        } else {
-            cmp
+    // Normalization precision
+    if let Some(precision) = input.precision {
+        if !precision.is_finite() || precision <= 0.0 {
+            return Err(DecisionError::InvalidInput(format!(
+                "precision must be finite and positive, got {precision}"
+            )));
         }
-    });
+    }
 
-    // Build ranked actions
-    let mut ranked_actions: Vec<RankedAction> = Vec::new();
-    let mut best_composite = ranked.first().map(|(_, &s)| s).unwrap_or(0.0);
+    // Validate weights if provided
+    if let Some(constraints) = &input.constraints {
+        if let Some(max_regret) = constraints.max_regret {
+            if !max_regret.is_finite() || max_regret < 0.0 {
+                return Err(DecisionError::InvalidInput(format!(
+                    "max_regret must be finite and non-negative, got {max_regret}"
+                )));
+            }
+        }
+        if let Some(epsilon) = constraints.epsilon {
+            if !(0.0..=1.0).contains(&epsilon) {
+                return Err(DecisionError::InvalidEpsilon { epsilon });
+            }
+        }
+    }
+    if let Some(weights) = input.weights() {
+        let sum = weights.total();
+        // `total` adds up seven weights
+        if !sums_to_one(sum, 7, input.strict) {
+            return Err(DecisionError::InvalidWeights { sum });
+        }
+        if weights.target_probability != 0.0 && input.target.is_none() {
+            return Err(DecisionError::InvalidInput(
+                "a target_probability weight needs a target".to_string(),
+            ));
+        }
+    }
+    if let Some(RankingMode::Lexicographic(criteria)) = &input.ranking_mode {
+        if criteria.is_empty() {
+            return Err(DecisionError::InvalidInput(
+                "a lexicographic ranking needs at least one criterion".to_string(),
+            ));
+        }
+        if criteria.contains(&Criterion::TargetProbability) && input.target.is_none() {
+            return Err(DecisionError::InvalidInput(
+                "a target_probability criterion needs a target".to_string(),
+            ));
+        }
+    }
 
-    for (rank, (action_id, &comp_score)) in ranked.iter().enumerate() {
-        let wc = worst_case.get(action_id).copied().unwrap_or(0.0);
-        let mr = max_regret.get(action_id).copied().unwrap_or(0.0);
-        let adv = adversarial.get(action_id).copied().unwrap_or(0.0);
+    Ok(())
+}
 
-        ranked_actions.push(RankedAction {
-            action_id: action_id.clone(),
-            score_worst_case: wc,
-            score_minimax_regret: mr,
-            score_adversarial: adv,
-            composite_score: comp_score,
-            recommended: rank == 0,
-            rank: rank + 1,
-        });
+/// Check action and scenario ids are unique, returning each set.
+fn unique_ids(input: &DecisionInput) -> Result<(BTreeSet<&str>, BTreeSet<&str>), DecisionError> {
+    let mut action_ids: BTreeSet<&str> = BTreeSet::new();
+    for action in &input.actions {
+        if !action_ids.insert(action.id.as_str()) {
+            return Err(DecisionError::DuplicateId {
+                kind: "action".to_string(),
+                id: action.id.clone(),
+            });
+        }
     }
+    let mut scenario_ids: BTreeSet<&str> = BTreeSet::new();
+    for scenario in &input.scenarios {
+        if !scenario_ids.insert(scenario.id.as_str()) {
+            return Err(DecisionError::DuplicateId {
+                kind: "scenario".to_string(),
+                id: scenario.id.clone(),
+            });
+        }
+    }
+    Ok((action_ids, scenario_ids))
+}
+
+/// Check scenario probabilities and severities (see `validate_input`).
+fn validate_scenarios(input: &DecisionInput) -> Result<(), DecisionError> {
+    // Probabilities
+    let mut probability_sum = 0.0;
+    for scenario in &input.scenarios {
+        if let Some(p) = scenario.probability {
+            if !(0.0..=1.0).contains(&p) {
+                return Err(DecisionError::InvalidProbability(format!(
+                    "scenario '{}' has probability {}",
+                    scenario.id, p
+                )));
+            }
+            probability_sum += p;
+        }
+    }
+    let given = input
+        .scenarios
+        .iter()
+        .filter(|s| s.probability.is_some())
+        .count();
+    if given == input.scenarios.len() && !sums_to_one(probability_sum, given, input.strict) {
+        return Err(DecisionError::InvalidProbability(format!(
+            "probabilities must sum to 1.0, got {probability_sum}"
+        )));
+    }
+    if input.strict && given != 0 && given != input.scenarios.len() {
+        return Err(DecisionError::InvalidProbability(
+            "strict mode needs a probability for every scenario or none".to_string(),
+        ));
+    }
+
+    // Severities
+    for scenario in &input.scenarios {
+        if let Some(severity) = scenario.severity {
+            if !severity.is_finite() || severity < 0.0 {
+                return Err(DecisionError::InvalidSeverity(format!(
+                    "scenario '{}' has severity {}",
+                    scenario.id, severity
+                )));
+            }
+        }
+    }
+    if let Some(SeverityDiscount::Penalty { per_unit }) =
+        input.constraints.as_ref().and_then(|c| c.severity_discount)
+    {
+        if !per_unit.is_finite() || per_unit < 0.0 {
+            return Err(DecisionError::InvalidSeverity(format!(
+                "penalty per unit must be finite and non-negative, got {per_unit}"
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+/// Whether `sum`, the total of `terms` values, is 1.0.
+///
+/// Lenient inputs may be off by 1e-9 (the engine normalizes them); strict
+/// inputs only by the rounding error of adding up `terms` values.
+#[allow(clippy::cast_precision_loss)] // term counts are far below 2^52
+pub(crate) fn sums_to_one(sum: f64, terms: usize, strict: bool) -> bool {
+    let tolerance = if strict {
+        f64::EPSILON * terms as f64
+    } else {
+        1e-9
+    };
+    (sum - 1.0).abs() <= tolerance
+}
+
+/// Sort key for breaking an exact tie with `tie_break_seed`.
+///
+/// BLAKE3 of the seed (little-endian) followed by the action id, so the order
+/// is reproducible for a given seed but not biased toward small ids.
+fn seeded_tie_key(seed: u64, action_id: &str) -> String {
+    let mut bytes = seed.to_le_bytes().to_vec();
+    bytes.extend_from_slice(action_id.as_bytes());
+    stable_hash(&bytes)
+}
+
+/// Order two tied actions: by seeded hash if `seed` is given, then
+/// lexicographically by action ID.
+pub(crate) fn tie_break_cmp(seed: Option<u64>, a: &str, b: &str) -> std::cmp::Ordering {
+    let seeded = match seed {
+        Some(seed) => seeded_tie_key(seed, a).cmp(&seeded_tie_key(seed, b)),
+        None => std::cmp::Ordering::Equal,
+    };
+    seeded.then_with(|| a.cmp(b))
+}
+
+/// Utilities by action and scenario, and the portfolio table.
+pub(crate) type UtilityTables = (
+    BTreeMap<String, BTreeMap<String, f64>>,
+    BTreeMap<String, Vec<String>>,
+);
+
+/// Build a validated input's utility table, portfolio rows included.
+///
+/// Returns the table (action or portfolio ID -> scenario ID -> utility) and
+/// the portfolio table (see `add_portfolio_rows`).
+pub(crate) fn utility_table_for(input: &DecisionInput) -> Result<UtilityTables, DecisionError> {
+    let precision = input.normalization_precision();
+    let mut utility_table = build_utility_table(
+        &input.actions,
+        &input.scenarios,
+        &input.outcomes,
+        input.missing_cell_fill().unwrap_or(0.0),
+        precision,
+    );
+    let portfolios = match &input.portfolios {
+        Some(portfolios) => add_portfolio_rows(&mut utility_table, portfolios, precision)?,
+        None => BTreeMap::new(),
+    };
+    Ok((utility_table, portfolios))
+}
+
+/// Main entry point: evaluate a decision problem.
+///
+/// Returns ranked actions with scores and a trace of the computation.
+pub fn evaluate_decision(input: &DecisionInput) -> Result<DecisionOutput, DecisionError> {
+    #[cfg(test)]
+    tests::EVALUATIONS.with(|n| n.set(n.get() + 1));
+
+    // Validate input, non-finite numbers first so they are named precisely
+    input.validate_finite()?;
+    validate_input(input)?;
+
+    let precision = input.normalization_precision();
+    let (utility_table, portfolios) = utility_table_for(input)?;
+
+    // Compute all scores
+    let worst_case = compute_worst_case_scores(&utility_table, precision)?;
+    let (regret_table, max_regret) =
+        compute_minimax_regret_scores(&utility_table, &input.scenarios, precision)?;
+    let discount = input
+        .constraints
+        .as_ref()
+        .and_then(|c| c.severity_discount)
+        .unwrap_or_default();
+    let adversarial =
+        compute_adversarial_scores(&utility_table, &input.scenarios, discount, precision)?;
+    let epsilon = input
+        .constraints
+        .as_ref()
+        .and_then(|c| c.epsilon)
+        .unwrap_or(0.0);
+    // Evidence updates the scenario probabilities behind expected values
+    let likelihoods = input
+        .evidence
+        .as_ref()
+        .map(|e| &e.scenario_likelihoods)
+        .filter(|l| !l.is_empty());
+    let scenario_priors = scenario_probabilities(&input.scenarios);
+    let scenario_posteriors = match likelihoods {
+        Some(likelihoods) => bayesian_posterior(&scenario_priors, likelihoods)?,
+        None => scenario_priors.clone(),
+    };
+    let epsilon_contamination = compute_epsilon_contamination(
+        &utility_table,
+        &input.scenarios,
+        &scenario_posteriors,
+        epsilon,
+        discount,
+        precision,
+    )?;
+    let adversarial_regret = compute_adversarial_regret(&regret_table, &input.scenarios, precision);
+    let prior = compute_prior_scores(&input.actions, input.action_priors.as_ref(), precision);
+    let target_probability = input.target.map_or_else(BTreeMap::new, |target| {
+        compute_target_probability(&utility_table, &scenario_posteriors, target, precision)
+    });
+
+    rank_and_trace(
+        input,
+        ScoreTables {
+            utility_table,
+            portfolios,
+            worst_case,
+            regret_table,
+            max_regret,
+            adversarial,
+            epsilon_contamination,
+            adversarial_regret,
+            prior,
+            target_probability,
+            scenario_update: likelihoods.map(|_| (scenario_priors, scenario_posteriors)),
+        },
+    )
+}
+
+/// Every per-action table behind a decision, before ranking.
+struct ScoreTables {
+    utility_table: BTreeMap<String, BTreeMap<String, f64>>,
+    portfolios: BTreeMap<String, Vec<String>>,
+    worst_case: BTreeMap<String, f64>,
+    regret_table: BTreeMap<String, BTreeMap<String, f64>>,
+    max_regret: BTreeMap<String, f64>,
+    adversarial: BTreeMap<String, f64>,
+    epsilon_contamination: BTreeMap<String, f64>,
+    adversarial_regret: BTreeMap<String, f64>,
+    prior: BTreeMap<String, f64>,
+    target_probability: BTreeMap<String, f64>,
+    /// Scenario priors and posteriors, when evidence updated them.
+    scenario_update: Option<(BTreeMap<String, f64>, BTreeMap<String, f64>)>,
+}
+
+/// Check an outcome change against the input it applies to, returning the
+/// changed scenario.
+fn validate_change<'a>(
+    input: &'a DecisionInput,
+    change: &OutcomeChange,
+) -> Result<&'a Scenario, DecisionError> {
+    let OutcomeChange {
+        action_id,
+        scenario_id,
+        utility,
+    } = change;
+    if !input.actions.iter().any(|a| a.id == *action_id) {
+        return Err(DecisionError::InvalidOutcome(format!(
+            "unknown action '{action_id}'"
+        )));
+    }
+    let Some(scenario) = input.scenarios.iter().find(|s| s.id == *scenario_id) else {
+        return Err(DecisionError::InvalidOutcome(format!(
+            "unknown scenario '{scenario_id}'"
+        )));
+    };
+    if !utility.is_finite() {
+        return Err(DecisionError::InvalidOutcome(format!(
+            "utility for '{action_id}' in '{scenario_id}' must be finite"
+        )));
+    }
+    Ok(scenario)
+}
+
+/// Write a changed outcome into the utility table, refreshing the portfolio
+/// rows that hold its action. Returns the rows that changed.
+fn update_utility_cell<'a>(
+    utility_table: &mut BTreeMap<String, BTreeMap<String, f64>>,
+    portfolios: &'a BTreeMap<String, Vec<String>>,
+    change: &'a OutcomeChange,
+    precision: f64,
+) -> Result<BTreeSet<&'a String>, DecisionError> {
+    let OutcomeChange {
+        action_id,
+        scenario_id,
+        utility,
+    } = change;
+    let mut rows: BTreeSet<&String> = BTreeSet::new();
+    if let Some(row) = utility_table.get_mut(action_id) {
+        row.insert(
+            scenario_id.clone(),
+            float_normalize_with(*utility, precision),
+        );
+        rows.insert(action_id);
+    }
+    for (id, members) in portfolios {
+        if members.contains(action_id) {
+            let mean = portfolio_utility(utility_table, id, members, scenario_id, precision)?;
+            if let Some(row) = utility_table.get_mut(id) {
+                row.insert(scenario_id.clone(), mean);
+            }
+            rows.insert(id);
+        }
+    }
+    Ok(rows)
+}
+
+/// Recompute one scenario's regret column, and the maximum regret of every
+/// row whose regret there changed. Returns those rows.
+fn update_regret_column(
+    utility_table: &BTreeMap<String, BTreeMap<String, f64>>,
+    regret_table: &mut BTreeMap<String, BTreeMap<String, f64>>,
+    max_regret: &mut BTreeMap<String, f64>,
+    scenario_id: &str,
+    precision: f64,
+) -> Result<BTreeSet<String>, DecisionError> {
+    let mut changed: BTreeSet<String> = BTreeSet::new();
+    let best = best_utility_in(utility_table, scenario_id, precision)?;
+    for (id, regrets) in regret_table {
+        let regret = regret_of(
+            id,
+            scenario_id,
+            best,
+            utility_table[id][scenario_id],
+            precision,
+        )?;
+        let previous = regrets.insert(scenario_id.to_string(), regret);
+        if previous.map(f64::to_bits) != Some(regret.to_bits()) {
+            max_regret.insert(id.clone(), max_regret_of(regrets, precision));
+            changed.insert(id.clone());
+        }
+    }
+    Ok(changed)
+}
+
+/// Combine score tables into composite scores, rank, and build the output.
+#[allow(clippy::too_many_lines)]
+fn rank_and_trace(
+    input: &DecisionInput,
+    tables: ScoreTables,
+) -> Result<DecisionOutput, DecisionError> {
+    let ScoreTables {
+        utility_table,
+        portfolios,
+        worst_case,
+        regret_table,
+        max_regret,
+        adversarial,
+        epsilon_contamination,
+        adversarial_regret,
+        prior,
+        target_probability,
+        scenario_update,
+    } = tables;
+    let precision = input.normalization_precision();
+
+    // Get weights (default, from constraints, or from the ranking mode)
+    let weights = input.weights().cloned().unwrap_or_default();
+    let criteria = input.ranking_criteria();
+    let criterion_table = |criterion: Criterion| match criterion {
+        Criterion::WorstCase => &worst_case,
+        Criterion::MinimaxRegret => &max_regret,
+        Criterion::Adversarial => &adversarial,
+        Criterion::EpsilonContamination => &epsilon_contamination,
+        Criterion::AdversarialRegret => &adversarial_regret,
+        Criterion::Prior => &prior,
+        Criterion::TargetProbability => &target_probability,
+    };
+
+    let composite = crate::criteria::builtin_composite(criterion_table, &weights, precision)?;
+
+    // Rank actions (by criteria in turn if lexicographic, else by composite
+    // score, descending)
+    let mut ranked: Vec<(&String, &f64)> = composite.iter().collect();
+    ranked.sort_by(|a, b| {
+        let cmp = if criteria.is_empty() {
+            b.1.partial_cmp(a.1).unwrap_or(std::cmp::Ordering::Equal)
+        } else {
+            criteria_cmp(criteria, criterion_table, a.0, b.0, precision)
+        };
+        // Tie-break: seeded hash if requested, then lexicographic by action_id
+        cmp.then_with(|| tie_break_cmp(input.tie_break_seed, a.0, b.0))
+    });
+
+    let robustness = compute_robustness(&ranked, precision);
+    let constrained_maximin = input
+        .constraints
+        .as_ref()
+        .and_then(|c| c.max_regret)
+        .map(|cap| compute_constrained_maximin(&worst_case, &max_regret, cap))
+        .transpose()?;
+
+    // Build ranked actions
+    let mut ranked_actions: Vec<RankedAction> = Vec::new();
+    let best_composite = ranked.first().map_or(0.0, |(_, &s)| s);
+    let set_size = input.recommended_set_size.unwrap_or(1);
+    let set_epsilon = input.recommended_set_epsilon.unwrap_or(0.0);
+
+    for (rank, &(action_id, &comp_score)) in ranked.iter().enumerate() {
+        let wc = worst_case.get(action_id).copied().unwrap_or(0.0);
+        let mr = max_regret.get(action_id).copied().unwrap_or(0.0);
+        let adv = adversarial.get(action_id).copied().unwrap_or(0.0);
+
+        ranked_actions.push(RankedAction {
+            action_id: action_id.clone(),
+            score_worst_case: wc,
+            score_minimax_regret: mr,
+            score_adversarial: adv,
+            composite_score: comp_score,
+            recommended: rank == 0
+                || (rank < set_size
+                    && float_normalize_with(best_composite - comp_score, precision) <= set_epsilon),
+            rank: rank + 1,
+            tied_with: Vec::new(),
+        });
+    }
+    if criteria.is_empty() {
+        annotate_ties(&mut ranked_actions, |a, b| {
+            float_normalize_with((a.composite_score - b.composite_score).abs(), precision) == 0.0
+        });
+    } else {
+        annotate_ties(&mut ranked_actions, |a, b| {
+            criteria_cmp(
+                criteria,
+                criterion_table,
+                &a.action_id,
+                &b.action_id,
+                precision,
+            ) == std::cmp::Ordering::Equal
+        });
+    }
+
+    let degenerate = if ranked_actions.len() == 1 {
+        Some(DegenerateReason::SingleAction)
+    } else if input.scenarios.len() == 1 {
+        Some(DegenerateReason::SingleScenario)
+    } else if ranked_actions[0].tied_with.len() + 1 == ranked_actions.len() {
+        Some(DegenerateReason::AllScoresEqual)
+    } else {
+        None
+    };
+
+    let fingerprint = decision_fingerprint(input);
+    let (scenario_priors, scenario_posteriors) = scenario_update.unwrap_or_default();
+    // Dominance is only meaningful under stated probabilities, updated by
+    // evidence when there is any
+    let stochastic_frontier = if input.scenarios.iter().all(|s| s.probability.is_some()) {
+        if scenario_posteriors.is_empty() {
+            let probabilities = scenario_probabilities(&input.scenarios);
+            compute_stochastic_frontier(&utility_table, &probabilities, precision)
+        } else {
+            compute_stochastic_frontier(&utility_table, &scenario_posteriors, precision)
+        }
+    } else {
+        Vec::new()
+    };
 
-    // Compute fingerprint
-    let fingerprint = compute_fingerprint(input);
-
     // Build trace
     let trace = DecisionTrace {
         utility_table,
@@ -300,17 +1599,561 @@ pub fn evaluate_decision(input: &DecisionInput) -> Result<DecisionOutput, Decisi
         regret_table,
         max_regret_table: max_regret,
         adversarial_table: adversarial,
+        epsilon_contamination_table: if weights.epsilon_contamination != 0.0
+            || criteria.contains(&Criterion::EpsilonContamination)
+        {
+            epsilon_contamination
+        } else {
+            BTreeMap::new()
+        },
+        adversarial_regret_table: adversarial_regret,
+        prior_table: if input.action_priors.is_some()
+            && (weights.prior != 0.0 || criteria.contains(&Criterion::Prior))
+        {
+            prior
+        } else {
+            BTreeMap::new()
+        },
+        target_probability_table: target_probability,
         composite_weights: weights,
-        tie_break_rule: "lexicographic_by_action_id".to_string(),
+        tie_break_rule: if input.tie_break_seed.is_some() {
+            "seeded_hash_by_action_id".to_string()
+        } else {
+            "lexicographic_by_action_id".to_string()
+        },
+        precision: input.precision.filter(|&p| p != FLOAT_PRECISION),
+        scenario_priors,
+        scenario_posteriors,
+        portfolios,
+        stochastic_frontier,
+        constrained_maximin,
+        ranking_criteria: criteria.to_vec(),
     };
 
     Ok(DecisionOutput {
         ranked_actions,
+        robustness,
         determinism_fingerprint: fingerprint,
         trace,
+        warnings: missing_cell_warnings(input),
+        degenerate,
     })
 }
 
+/// Order two actions by `criteria` in turn, best first.
+///
+/// Scores within `precision` of each other fall through to the next
+/// criterion; `Equal` means tied on every one.
+pub(crate) fn criteria_cmp<'a>(
+    criteria: &[Criterion],
+    table: impl Fn(Criterion) -> &'a BTreeMap<String, f64>,
+    a: &str,
+    b: &str,
+    precision: f64,
+) -> std::cmp::Ordering {
+    for &criterion in criteria {
+        let table = table(criterion);
+        let score_a = table.get(a).copied().unwrap_or(0.0);
+        let score_b = table.get(b).copied().unwrap_or(0.0);
+        if float_normalize_with((score_a - score_b).abs(), precision) == 0.0 {
+            continue;
+        }
+        return if criterion.lower_is_better() {
+            score_a.total_cmp(&score_b)
+        } else {
+            score_b.total_cmp(&score_a)
+        };
+    }
+    std::cmp::Ordering::Equal
+}
+
+/// Fill in `tied_with` on a ranking sorted best first, where `tied` says
+/// whether two actions scored the same: tied actions are adjacent, so each
+/// run of ties is one tie.
+fn annotate_ties(
+    ranked_actions: &mut [RankedAction],
+    tied: impl FnMut(&RankedAction, &RankedAction) -> bool,
+) {
+    for group in ranked_actions.chunk_by_mut(tied) {
+        if group.len() < 2 {
+            continue;
+        }
+        let mut ids: Vec<String> = group.iter().map(|a| a.action_id.clone()).collect();
+        ids.sort();
+        for action in group {
+            action.tied_with = ids
+                .iter()
+                .filter(|&id| *id != action.action_id)
+                .cloned()
+                .collect();
+        }
+    }
+}
+
+/// Re-evaluate a decision after one outcome changes, reusing the previous
+/// evaluation.
+///
+/// `prev_output` must be `evaluate_decision(prev_input)`. Only the changed
+/// cell's row (and the rows of portfolios holding that action) and its
+/// scenario's regret column are recomputed, along with the scores that read
+/// them; every other entry is carried over before re-ranking. The result is
+/// identical, byte for byte, to `evaluate_decision(&change.apply(prev_input))`.
+/// The determinism fingerprint still hashes the whole changed input, so that
+/// cost is not saved.
+#[allow(clippy::too_many_lines)]
+pub fn evaluate_incremental(
+    prev_output: &DecisionOutput,
+    prev_input: &DecisionInput,
+    change: &OutcomeChange,
+) -> Result<DecisionOutput, DecisionError> {
+    let changed = validate_change(prev_input, change)?;
+    let scenario_id = &change.scenario_id;
+    let input = change.apply(prev_input);
+    let precision = input.normalization_precision();
+    let trace = &prev_output.trace;
+
+    let mut utility_table = trace.utility_table.clone();
+    let rows = update_utility_cell(&mut utility_table, &trace.portfolios, change, precision)?;
+
+    let mut worst_case = trace.worst_case_table.clone();
+    for &id in &rows {
+        worst_case.insert(
+            id.clone(),
+            worst_case_of(id, &utility_table[id], precision)?,
+        );
+    }
+
+    // The scenario's best utility may move, so its whole regret column is
+    // refreshed; maxima are recomputed only for rows whose regret changed
+    let mut regret_table = trace.regret_table.clone();
+    let mut max_regret = trace.max_regret_table.clone();
+    let regret_rows = update_regret_column(
+        &utility_table,
+        &mut regret_table,
+        &mut max_regret,
+        scenario_id,
+        precision,
+    )?;
+
+    let adversarial_scenarios: Vec<&Scenario> =
+        input.scenarios.iter().filter(|s| s.adversarial).collect();
+    let (adversarial, adversarial_rows) = if adversarial_scenarios.is_empty() {
+        (worst_case.clone(), rows.clone())
+    } else if changed.adversarial {
+        let discount = input
+            .constraints
+            .as_ref()
+            .and_then(|c| c.severity_discount)
+            .unwrap_or_default();
+        let bests = regret_adversary_bests(&utility_table, &adversarial_scenarios);
+        // A regret adversary's pick depends on every action's utility there
+        let adversarial_rows: BTreeSet<&String> = if bests.contains_key(scenario_id.as_str()) {
+            utility_table.keys().collect()
+        } else {
+            rows.clone()
+        };
+        let mut adversarial = trace.adversarial_table.clone();
+        for &id in &adversarial_rows {
+            let row = &utility_table[id];
+            let score =
+                adversarial_score_of(id, row, &adversarial_scenarios, &bests, discount, precision)?;
+            adversarial.insert(id.clone(), score);
+        }
+        (adversarial, adversarial_rows)
+    } else {
+        (trace.adversarial_table.clone(), BTreeSet::new())
+    };
+
+    let epsilon = input
+        .constraints
+        .as_ref()
+        .and_then(|c| c.epsilon)
+        .unwrap_or(0.0);
+    let scenario_posteriors = if trace.scenario_posteriors.is_empty() {
+        scenario_probabilities(&input.scenarios)
+    } else {
+        trace.scenario_posteriors.clone()
+    };
+    // The trace omits the table when it was unused; then score every row
+    let mut epsilon_contamination = trace.epsilon_contamination_table.clone();
+    let epsilon_rows: BTreeSet<&String> = if epsilon_contamination.is_empty() {
+        utility_table.keys().collect()
+    } else {
+        rows.union(&adversarial_rows).copied().collect()
+    };
+    for id in epsilon_rows {
+        let worst = adversarial.get(id).copied().unwrap_or(0.0);
+        let row = &utility_table[id];
+        let score = epsilon_score_of(id, row, &scenario_posteriors, epsilon, worst, precision)?;
+        epsilon_contamination.insert(id.clone(), score);
+    }
+
+    let adversarial_ids: BTreeSet<&str> = adversarial_scenarios
+        .iter()
+        .map(|s| s.id.as_str())
+        .collect();
+    let mut adversarial_regret = trace.adversarial_regret_table.clone();
+    for id in regret_rows {
+        let score = adversarial_regret_of(&regret_table[&id], &adversarial_ids, precision);
+        adversarial_regret.insert(id, score);
+    }
+
+    let prior = compute_prior_scores(&input.actions, input.action_priors.as_ref(), precision);
+    let mut target_probability = trace.target_probability_table.clone();
+    if let Some(target) = input.target {
+        for &id in &rows {
+            let score =
+                target_probability_of(&utility_table[id], &scenario_posteriors, target, precision);
+            target_probability.insert(id.clone(), score);
+        }
+    }
+    let scenario_update = (!trace.scenario_posteriors.is_empty())
+        .then(|| (trace.scenario_priors.clone(), scenario_posteriors));
+
+    rank_and_trace(
+        &input,
+        ScoreTables {
+            utility_table,
+            portfolios: trace.portfolios.clone(),
+            worst_case,
+            regret_table,
+            max_regret,
+            adversarial,
+            epsilon_contamination,
+            adversarial_regret,
+            prior,
+            target_probability,
+            scenario_update,
+        },
+    )
+}
+
+/// Evaluate a decision whose outcomes arrive as a stream, for scenario sets
+/// too large to hold as nested tables.
+///
+/// Equivalent to `evaluate_decision` on an input with these actions,
+/// scenarios and outcomes and every other field absent or default: the
+/// ranking and the determinism fingerprint are identical. Outcomes are read
+/// in one pass into a dense utility matrix; scores are then computed an
+/// action at a time, so only one row of regrets exists at once. The trace
+/// carries no utility table or stochastic frontier, and a regret table only
+/// with `include_regret_table`.
+#[allow(clippy::too_many_lines)]
+pub fn evaluate_decision_streaming(
+    actions: &[ActionOption],
+    scenarios: &[Scenario],
+    outcomes: impl IntoIterator<Item = (String, String, f64)>,
+    include_regret_table: bool,
+) -> Result<DecisionOutput, DecisionError> {
+    let input = streamed_input(actions, scenarios);
+    input.validate_finite()?;
+    let mut cells = read_streamed_outcomes(&input, outcomes)?;
+    validate_scenarios(&input)?;
+    let fingerprint = streamed_fingerprint(&input, &cells);
+
+    let precision = input.normalization_precision();
+    let width = scenarios.len();
+    let mut scenario_best = vec![f64::NEG_INFINITY; width];
+    for row in cells.chunks_mut(width) {
+        for (best, u) in scenario_best.iter_mut().zip(row) {
+            *u = float_normalize_with(*u, precision);
+            *best = best.max(*u);
+        }
+    }
+    let best_by_scenario = scenarios
+        .iter()
+        .zip(&scenario_best)
+        .map(|(scenario, &best)| {
+            finite_score(best, precision, || {
+                format!("best utility in '{}'", scenario.id)
+            })
+        })
+        .collect::<Result<Vec<f64>, DecisionError>>()?;
+    let adversarial_scenarios: Vec<&Scenario> =
+        scenarios.iter().filter(|s| s.adversarial).collect();
+    let adversarial_ids: BTreeSet<&str> = adversarial_scenarios
+        .iter()
+        .map(|s| s.id.as_str())
+        .collect();
+    let regret_bests: BTreeMap<&str, f64> = scenarios
+        .iter()
+        .zip(&scenario_best)
+        .filter(|(s, _)| {
+            s.adversarial && s.adversary_objective == Some(AdversaryObjective::MaximizeRegret)
+        })
+        .map(|(s, &best)| (s.id.as_str(), best))
+        .collect();
+    let probabilities = scenario_probabilities(scenarios);
+
+    // One action's utilities and regrets, keyed like the full tables' rows;
+    // `scenario_order` maps their (sorted) entries back to matrix columns
+    let mut row: BTreeMap<String, f64> = scenarios.iter().map(|s| (s.id.clone(), 0.0)).collect();
+    let mut regrets = row.clone();
+    let scenario_order = id_order(scenarios.iter().map(|s| &s.id));
+
+    let mut worst_case = BTreeMap::new();
+    let mut regret_table = BTreeMap::new();
+    let mut max_regret = BTreeMap::new();
+    let mut adversarial = BTreeMap::new();
+    let mut epsilon_contamination = BTreeMap::new();
+    let mut adversarial_regret = BTreeMap::new();
+    for (action, utilities) in actions.iter().zip(cells.chunks(width)) {
+        let id = &action.id;
+        for (((scenario_id, utility), regret), &column) in row
+            .iter_mut()
+            .zip(regrets.values_mut())
+            .zip(&scenario_order)
+        {
+            *utility = utilities[column];
+            *regret = regret_of(
+                id,
+                scenario_id,
+                best_by_scenario[column],
+                utilities[column],
+                precision,
+            )?;
+        }
+        let worst = worst_case_of(id, &row, precision)?;
+        let adversarial_score = if adversarial_scenarios.is_empty() {
+            worst
+        } else {
+            let discount = SeverityDiscount::default();
+            adversarial_score_of(
+                id,
+                &row,
+                &adversarial_scenarios,
+                &regret_bests,
+                discount,
+                precision,
+            )?
+        };
+        let epsilon_score =
+            epsilon_score_of(id, &row, &probabilities, 0.0, adversarial_score, precision)?;
+
+        worst_case.insert(id.clone(), worst);
+        adversarial.insert(id.clone(), adversarial_score);
+        epsilon_contamination.insert(id.clone(), epsilon_score);
+        max_regret.insert(id.clone(), max_regret_of(&regrets, precision));
+        adversarial_regret.insert(
+            id.clone(),
+            adversarial_regret_of(&regrets, &adversarial_ids, precision),
+        );
+        if include_regret_table {
+            regret_table.insert(id.clone(), regrets.clone());
+        }
+    }
+
+    let mut output = rank_and_trace(
+        &input,
+        ScoreTables {
+            utility_table: BTreeMap::new(),
+            portfolios: BTreeMap::new(),
+            worst_case,
+            regret_table,
+            max_regret,
+            adversarial,
+            epsilon_contamination,
+            adversarial_regret,
+            prior: compute_prior_scores(actions, None, precision),
+            target_probability: BTreeMap::new(),
+            scenario_update: None,
+        },
+    )?;
+    output.determinism_fingerprint = fingerprint;
+    Ok(output)
+}
+
+/// The input `evaluate_decision_streaming` stands for, without its outcomes.
+fn streamed_input(actions: &[ActionOption], scenarios: &[Scenario]) -> DecisionInput {
+    DecisionInput {
+        id: None,
+        actions: actions.to_vec(),
+        scenarios: scenarios.to_vec(),
+        outcomes: Vec::new(),
+        constraints: None,
+        evidence: None,
+        meta: None,
+        action_priors: None,
+        tie_break_seed: None,
+        allow_missing_as_zero: false,
+        missing_cell_policy: MissingCellPolicy::Error,
+        recommended_set_size: None,
+        recommended_set_epsilon: None,
+        precision: None,
+        strict: false,
+        portfolios: None,
+        target: None,
+        ranking_mode: None,
+    }
+}
+
+/// Read streamed outcomes into a dense utility matrix: one row per action of
+/// `input`, one column per scenario, in input order.
+///
+/// Checks what `validate_input` checks of actions, scenarios and outcomes,
+/// rejecting a repeated cell as it does.
+fn read_streamed_outcomes(
+    input: &DecisionInput,
+    outcomes: impl IntoIterator<Item = (String, String, f64)>,
+) -> Result<Vec<f64>, DecisionError> {
+    if input.actions.is_empty() {
+        return Err(DecisionError::NoActions);
+    }
+    if input.scenarios.is_empty() {
+        return Err(DecisionError::NoScenarios);
+    }
+    unique_ids(input)?;
+    let action_position: BTreeMap<&str, usize> = input
+        .actions
+        .iter()
+        .enumerate()
+        .map(|(i, a)| (a.id.as_str(), i))
+        .collect();
+    let scenario_position: BTreeMap<&str, usize> = input
+        .scenarios
+        .iter()
+        .enumerate()
+        .map(|(i, s)| (s.id.as_str(), i))
+        .collect();
+
+    // Utilities are finite, so NaN marks a cell no outcome has filled
+    let width = input.scenarios.len();
+    let mut cells = vec![f64::NAN; input.actions.len() * width];
+    let mut any = false;
+    for (i, (action_id, scenario_id, utility)) in outcomes.into_iter().enumerate() {
+        finite(utility, || {
+            format!("outcomes[{i}] ('{action_id}', '{scenario_id}')")
+        })?;
+        let Some(&row) = action_position.get(action_id.as_str()) else {
+            return Err(DecisionError::InvalidOutcome(format!(
+                "unknown action '{action_id}'"
+            )));
+        };
+        let Some(&column) = scenario_position.get(scenario_id.as_str()) else {
+            return Err(DecisionError::InvalidOutcome(format!(
+                "unknown scenario '{scenario_id}'"
+            )));
+        };
+        let cell = &mut cells[row * width + column];
+        if !cell.is_nan() {
+            return Err(DecisionError::InvalidOutcome(format!(
+                "duplicate outcome for '{action_id}' in '{scenario_id}'"
+            )));
+        }
+        *cell = utility;
+        any = true;
+    }
+    if !any {
+        return Err(DecisionError::NoOutcomes);
+    }
+    if let Some(hole) = cells.iter().position(|u| u.is_nan()) {
+        return Err(DecisionError::MissingCell {
+            action: input.actions[hole / width].id.clone(),
+            scenario: input.scenarios[hole % width].id.clone(),
+        });
+    }
+    Ok(cells)
+}
+
+/// `decision_fingerprint` of `input` with the outcomes in `cells` (see
+/// `read_streamed_outcomes`), hashing them in canonical order without
+/// listing them.
+fn streamed_fingerprint(input: &DecisionInput, cells: &[f64]) -> String {
+    let action_order = id_order(input.actions.iter().map(|a| &a.id));
+    let scenario_order = id_order(input.scenarios.iter().map(|s| &s.id));
+    let width = input.scenarios.len();
+    let items = action_order.iter().flat_map(|&row| {
+        scenario_order.iter().map(move |&column| {
+            serde_json::json!([
+                input.actions[row].id,
+                input.scenarios[column].id,
+                cells[row * width + column]
+            ])
+        })
+    });
+    fingerprint_with_items(input, "outcomes", items)
+}
+
+/// Positions of `ids` in ID order.
+fn id_order<'a>(ids: impl Iterator<Item = &'a String>) -> Vec<usize> {
+    let ids: Vec<&String> = ids.collect();
+    let mut order: Vec<usize> = (0..ids.len()).collect();
+    order.sort_by(|&a, &b| ids[a].cmp(ids[b]));
+    order
+}
+
+/// Fingerprint of a decision input, as carried by its `DecisionOutput`.
+///
+/// The seed is folded in explicitly because canonical JSON carries numbers as
+/// f64, which cannot distinguish every u64.
+pub(crate) fn decision_fingerprint(input: &DecisionInput) -> String {
+    match input.tie_break_seed {
+        Some(seed) => {
+            stable_hash(format!("{}:tie_break_seed={seed}", input_fingerprint(input)).as_bytes())
+        }
+        None => input_fingerprint(input),
+    }
+}
+
+/// Fingerprint of a decision input with its outcomes in canonical order.
+///
+/// Outcomes are hashed sorted by action id then scenario id, keeping the last
+/// entry for a repeated cell as `build_utility_table` does, so listing the
+/// same cells in another order does not change the fingerprint. Every other
+/// field, `strict` included, is hashed as given.
+///
+/// A non-default `precision` is folded in explicitly, since canonical JSON
+/// would itself round it to `FLOAT_PRECISION`; an explicit default precision
+/// fingerprints like an absent one.
+pub(crate) fn input_fingerprint(input: &DecisionInput) -> String {
+    let mut cells: BTreeMap<(&str, &str), f64> = BTreeMap::new();
+    for (action_id, scenario_id, utility) in &input.outcomes {
+        cells.insert((action_id.as_str(), scenario_id.as_str()), *utility);
+    }
+    if input.precision.is_none()
+        && cells.len() == input.outcomes.len()
+        && cells
+            .keys()
+            .zip(&input.outcomes)
+            .all(|(&(a, s), (action_id, scenario_id, _))| a == action_id && s == scenario_id)
+    {
+        return compute_fingerprint(input);
+    }
+
+    let canonical = DecisionInput {
+        outcomes: cells
+            .into_iter()
+            .map(|((a, s), u)| (a.to_string(), s.to_string(), u))
+            .collect(),
+        precision: None,
+        ..input.clone()
+    };
+    let fingerprint = compute_fingerprint(&canonical);
+    match input.precision {
+        Some(precision) if precision != FLOAT_PRECISION => {
+            stable_hash(format!("{fingerprint}:precision={precision:e}").as_bytes())
+        }
+        _ => fingerprint,
+    }
+}
+
+/// Robustness of the top recommendation from composite scores ranked best first.
+///
+/// The gap between the top two scores divided by the full score range, so 1.0
+/// means the runner-up sits at the bottom and 0.0 means a tie for first.
+pub(crate) fn compute_robustness(ranked: &[(&String, &f64)], precision: f64) -> f64 {
+    let (Some(&(_, &top)), Some(&(_, &runner_up)), Some(&(_, &bottom))) =
+        (ranked.first(), ranked.get(1), ranked.last())
+    else {
+        return 1.0;
+    };
+    let range = top - bottom;
+    if range <= 0.0 {
+        return 0.0;
+    }
+    float_normalize_with(((top - runner_up) / range).clamp(0.0, 1.0), precision)
+}
+
 /// Compute flip distances for sensitivity analysis.
 ///
 /// Measures how much each scenario's utility would need to change
@@ -318,7 +2161,14 @@ pub fn evaluate_decision(input: &DecisionInput) -> Result<DecisionOutput, Decisi
 pub fn compute_flip_distances(input: &DecisionInput) -> Result<Vec<FlipDistance>, DecisionError> {
     // First evaluate to get current ranking
     let output = evaluate_decision(input)?;
+    flip_distances_for(input, &output)
+}
 
+/// Flip distances of an already evaluated decision.
+fn flip_distances_for(
+    input: &DecisionInput,
+    output: &DecisionOutput,
+) -> Result<Vec<FlipDistance>, DecisionError> {
     let top_action = output
         .ranked_actions
         .first()
@@ -351,7 +2201,10 @@ pub fn compute_flip_distances(input: &DecisionInput) -> Result<Vec<FlipDistance>
                 .unwrap_or(0.0);
 
             // Flip distance is the gap
-            let flip_distance = float_normalize((top_utility - second_utility).abs());
+            let flip_distance = float_normalize_with(
+                (top_utility - second_utility).abs(),
+                input.normalization_precision(),
+            );
 
             distances.push(FlipDistance {
                 variable_id: scenario.id.clone(),
@@ -372,27 +2225,59 @@ pub fn compute_flip_distances(input: &DecisionInput) -> Result<Vec<FlipDistance>
 }
 
 /// Rank evidence by Value of Information (VOI).
+///
+/// Empty when the decision is degenerate: see `DecisionOutput::degenerate`
+/// for why.
 pub fn rank_evidence_by_voi(
     input: &DecisionInput,
     min_evoi: f64,
 ) -> Result<Vec<VoiRanking>, DecisionError> {
     // Evaluate to get current state
     let output = evaluate_decision(input)?;
+    Ok(voi_rankings_for(input, &output, min_evoi))
+}
 
+/// VOI rankings of an already evaluated decision; none when it is
+/// degenerate.
+fn voi_rankings_for(
+    input: &DecisionInput,
+    output: &DecisionOutput,
+    min_evoi: f64,
+) -> Vec<VoiRanking> {
     let mut rankings: Vec<VoiRanking> = Vec::new();
+    if output.degenerate.is_some() {
+        return rankings;
+    }
+    let precision = input.normalization_precision();
+    let utility_of = |rank: usize, scenario_id: &str| {
+        output
+            .ranked_actions
+            .get(rank)
+            .and_then(|a| output.trace.utility_table.get(&a.action_id))
+            .and_then(|m| m.get(scenario_id))
+            .copied()
+    };
 
     // Simple VOI heuristic: rank by sensitivity (inverse of flip distance)
     for scenario in &input.scenarios {
         // Find how much this scenario affects the decision
-        let flip_distance = output
-            .trace
-            .utility_table
-            .get(&output.ranked_actions.first().map(|a| &a.action_id).unwrap_or(&String::new()))
-            .and_then(|m| m.get(&scenario.id))
-            .map(|&u| 1.0 / (u.abs() + 0.1)) // Inverse utility as proxy for sensitivity
-            .unwrap_or(0.0);
+        // Inverse utility as proxy for sensitivity
+        let sensitivity = utility_of(0, &scenario.id).map_or(0.0, |u| 1.0 / (u.abs() + 0.1));
+
+        let evoi = float_normalize_with(sensitivity, precision);
 
-        let evoi = float_normalize(flip_distance);
+        // Each ranking is driven by its own scenario alone
+        let factors = vec![VoiFactor {
+            scenario_id: scenario.id.clone(),
+            flip_distance: utility_of(1, &scenario.id).map(|runner_up| {
+                float_normalize_with(
+                    (utility_of(0, &scenario.id).unwrap_or(0.0) - runner_up).abs(),
+                    precision,
+                )
+            }),
+            probability: scenario.probability,
+            contribution: evoi,
+        }];
 
         let recommendation = if evoi > min_evoi * 2.0 {
             "do_now"
@@ -406,39 +2291,245 @@ pub fn rank_evidence_by_voi(
             action_id: scenario.id.clone(),
             evoi,
             recommendation: recommendation.to_string(),
-            rationale: vec![
-                format!("Scenario {} has sensitivity {}", scenario.id, evoi),
-                format!(
-                    "Cost-adjusted information gain is {}",
-                    evoi.to_string()
-                ),
-            ],
+            rationale: voi_rationale(&factors, evoi),
+            factors,
         });
     }
 
     // Sort by VOI (highest first)
-    rankings.sort_by(|a, b| b.evoi.partial_cmp(&a.evoi).unwrap_or(std::cmp::Ordering::Equal));
+    rankings.sort_by(|a, b| {
+        b.evoi
+            .partial_cmp(&a.evoi)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
 
-    Ok(rankings)
+    rankings
 }
 
-/// Generate a regret-bounded plan.
-pub fn generate_regret_bounded_plan(
-    input: &DecisionInput,
-    horizon: usize,
-    min_evoi: f64,
-) -> Result<RegretBoundedPlan, DecisionError> {
+/// Human-readable rationale derived from VOI factors.
+fn voi_rationale(factors: &[VoiFactor], evoi: f64) -> Vec<String> {
+    factors
+        .iter()
+        .map(|f| {
+            format!(
+                "Scenario {} has sensitivity {}",
+                f.scenario_id, f.contribution
+            )
+        })
+        .chain(std::iter::once(format!(
+            "Cost-adjusted information gain is {evoi}"
+        )))
+        .collect()
+}
+
+/// Per-scenario regret leaders from a regret table (`DecisionTrace::regret_table`).
+///
+/// Transposes action -> scenario -> regret and, for each scenario, picks the
+/// actions with the lowest and highest regret. Ties go to the smallest action
+/// ID, so the result is deterministic.
+#[must_use]
+pub fn scenario_regret_leaders(
+    regret_table: &BTreeMap<String, BTreeMap<String, f64>>,
+) -> BTreeMap<String, ScenarioRegretLeaders> {
+    let mut leaders: BTreeMap<String, ScenarioRegretLeaders> = BTreeMap::new();
+    // Actions are visited in ID order, so only a strictly better regret
+    // replaces a leader
+    for (action_id, regrets) in regret_table {
+        for (scenario_id, &regret) in regrets {
+            let entry =
+                leaders
+                    .entry(scenario_id.clone())
+                    .or_insert_with(|| ScenarioRegretLeaders {
+                        min_regret_action: action_id.clone(),
+                        min_regret: regret,
+                        max_regret_action: action_id.clone(),
+                        max_regret: regret,
+                    });
+            if regret < entry.min_regret {
+                entry.min_regret_action.clone_from(action_id);
+                entry.min_regret = regret;
+            }
+            if regret > entry.max_regret {
+                entry.max_regret_action.clone_from(action_id);
+                entry.max_regret = regret;
+            }
+        }
+    }
+    leaders
+}
+
+/// Actions that no other action first-order stochastically dominates.
+///
+/// Unlike state-by-state dominance, this compares each action's distribution
+/// of utility under the scenario probabilities (`Scenario::probability`,
+/// normalized; equal weights unless every scenario has one; updated by
+/// `DecisionEvidence::scenario_likelihoods` when given): an action is
+/// dominated when another is at least as likely to reach every utility level
+/// and more likely to reach some. Portfolios count as actions. The frontier
+/// is sorted by action ID. `evaluate_decision` records it as
+/// `DecisionTrace::stochastic_frontier` when every scenario has a probability.
+pub fn stochastic_dominance_frontier(input: &DecisionInput) -> Result<Vec<String>, DecisionError> {
+    validate_input(input)?;
+    let precision = input.normalization_precision();
+    let mut utility_table = build_utility_table(
+        &input.actions,
+        &input.scenarios,
+        &input.outcomes,
+        input.missing_cell_fill().unwrap_or(0.0),
+        precision,
+    );
+    if let Some(portfolios) = &input.portfolios {
+        add_portfolio_rows(&mut utility_table, portfolios, precision)?;
+    }
+    let mut probabilities = scenario_probabilities(&input.scenarios);
+    if let Some(evidence) = input
+        .evidence
+        .as_ref()
+        .filter(|e| !e.scenario_likelihoods.is_empty())
+    {
+        probabilities = bayesian_posterior(&probabilities, &evidence.scenario_likelihoods)?;
+    }
+    Ok(compute_stochastic_frontier(
+        &utility_table,
+        &probabilities,
+        precision,
+    ))
+}
+
+/// Monte Carlo robustness of an evaluated decision.
+///
+/// Draws `samples` scenario-probability vectors, each scaling every
+/// probability (the posterior when evidence updated it) by a factor in
+/// `[1 - band, 1 + band]` and renormalizing, and counts how often the
+/// recommended action keeps the highest expected utility. Ties keep the
+/// recommendation. Draws come from a `SplitMix64` generator seeded with
+/// `seed`, so the report is identical across runs and platforms.
+pub fn monte_carlo_robustness(
+    input: &DecisionInput,
+    output: &DecisionOutput,
+    samples: usize,
+    seed: u64,
+    band: f64,
+) -> Result<RobustnessReport, DecisionError> {
+    let recommended = output
+        .recommended_action_id()
+        .ok_or(DecisionError::NoActions)?
+        .to_string();
+    if samples == 0 {
+        return Err(DecisionError::InvalidInput(
+            "Monte Carlo robustness needs at least one sample".to_string(),
+        ));
+    }
+    if !(0.0..=1.0).contains(&band) {
+        return Err(DecisionError::InvalidInput(format!(
+            "Perturbation band must be in [0, 1], got {band}"
+        )));
+    }
+
+    let precision = input.normalization_precision();
+    let table = &output.trace.utility_table;
+    let base = if output.trace.scenario_posteriors.is_empty() {
+        scenario_probabilities(&input.scenarios)
+    } else {
+        output.trace.scenario_posteriors.clone()
+    };
+    let expected_utility = |action_id: &str, probabilities: &BTreeMap<&String, f64>| {
+        let utilities = table.get(action_id);
+        let total: f64 = probabilities
+            .iter()
+            .map(|(scenario_id, p)| {
+                p * utilities
+                    .and_then(|row| row.get(*scenario_id))
+                    .copied()
+                    .unwrap_or(0.0)
+            })
+            .sum();
+        float_normalize_with(total, precision)
+    };
+
+    let mut rng = SplitMix64::new(seed);
+    let mut holds = 0;
+    let mut challengers: BTreeMap<String, usize> = BTreeMap::new();
+    for _ in 0..samples {
+        let mut probabilities: BTreeMap<&String, f64> = base
+            .iter()
+            .map(|(id, &p)| (id, p * (1.0 + band * (2.0 * rng.next_f64() - 1.0))))
+            .collect();
+        let total: f64 = probabilities.values().sum();
+        if total > 0.0 {
+            for p in probabilities.values_mut() {
+                *p /= total;
+            }
+        }
+
+        // Actions are visited in ID order, so only a strictly higher expected
+        // utility displaces the current leader
+        let mut leader = recommended.as_str();
+        let mut best = expected_utility(leader, &probabilities);
+        for action_id in table.keys() {
+            let value = expected_utility(action_id, &probabilities);
+            if value > best {
+                leader = action_id;
+                best = value;
+            }
+        }
+        if leader == recommended {
+            holds += 1;
+        } else {
+            *challengers.entry(leader.to_string()).or_insert(0) += 1;
+        }
+    }
+
+    #[allow(clippy::cast_precision_loss)] // sample counts are far below 2^52
+    let hold_rate = float_normalize_with(holds as f64 / samples as f64, precision);
+    Ok(RobustnessReport {
+        recommended_action: recommended,
+        seed,
+        samples,
+        band,
+        holds,
+        hold_rate,
+        challengers,
+    })
+}
+
+/// Generate a regret-bounded plan.
+pub fn generate_regret_bounded_plan(
+    input: &DecisionInput,
+    horizon: usize,
+    min_evoi: f64,
+) -> Result<RegretBoundedPlan, DecisionError> {
     let rankings = rank_evidence_by_voi(input, min_evoi)?;
+    Ok(plan_from_rankings(input, &rankings, horizon, min_evoi))
+}
 
-    let selected: Vec<PlannedAction> = rankings
-        .iter()
-        .filter(|r| r.recommendation == "do_now")
-        .take(horizon)
-        .map(|r| PlannedAction {
+/// Regret-bounded plan over already computed VOI rankings.
+fn plan_from_rankings(
+    input: &DecisionInput,
+    rankings: &[VoiRanking],
+    horizon: usize,
+    min_evoi: f64,
+) -> RegretBoundedPlan {
+    let mut selected: Vec<PlannedAction> = Vec::new();
+    let mut deferred: Vec<PlannedAction> = Vec::new();
+    let mut horizon_truncated = false;
+    for r in rankings {
+        let mut planned = PlannedAction {
             id: r.action_id.clone(),
             rationale: r.rationale.clone(),
-        })
-        .collect();
+        };
+        if r.recommendation != "do_now" {
+            deferred.push(planned);
+        } else if selected.len() < horizon {
+            selected.push(planned);
+        } else {
+            horizon_truncated = true;
+            planned
+                .rationale
+                .push(format!("Deferred: plan horizon of {horizon} reached"));
+            deferred.push(planned);
+        }
+    }
 
     // Generate deterministic plan ID
     let plan_content = format!(
@@ -453,63 +2544,301 @@ pub fn generate_regret_bounded_plan(
     );
     let plan_id = stable_hash(plan_content.as_bytes())[..16].to_string();
 
-    Ok(RegretBoundedPlan {
+    RegretBoundedPlan {
         id: plan_id,
-        decision_id: input
-            .id
-            .clone()
-            .unwrap_or_else(|| "unknown".to_string()),
+        decision_id: input.id.clone().unwrap_or_else(|| "unknown".to_string()),
         actions: selected,
         bounded_horizon: horizon,
-    })
+        deferred,
+        horizon_truncated,
+    }
 }
 
 /// Explain the decision boundary.
-pub fn explain_decision_boundary(
-    input: &DecisionInput,
-) -> Result<DecisionBoundary, DecisionError> {
+pub fn explain_decision_boundary(input: &DecisionInput) -> Result<DecisionBoundary, DecisionError> {
     let output = evaluate_decision(input)?;
-    let flip_distances = compute_flip_distances(input)?;
+    let flip_distances = flip_distances_for(input, &output)?;
+    Ok(boundary_from(&output, &flip_distances))
+}
 
-    Ok(DecisionBoundary {
+/// Decision boundary from an evaluated decision and its flip distances.
+fn boundary_from(output: &DecisionOutput, flip_distances: &[FlipDistance]) -> DecisionBoundary {
+    DecisionBoundary {
         top_action: output
             .ranked_actions
             .first()
             .map(|a| a.action_id.clone())
             .unwrap_or_else(|| "unknown".to_string()),
-        nearest_flips: flip_distances.into_iter().take(2).collect(),
+        nearest_flips: flip_distances.iter().take(2).cloned().collect(),
+    }
+}
+
+/// Explain why `action_id` ranked where it did in `output`.
+///
+/// A pure read over the ranking and trace: the action's rank and
+/// per-criterion scores, its composite gap to the top action, the weighted
+/// criterion where it lost the most composite score (ties go to the earlier
+/// `CompositeWeights` field), whether the constrained maximin cap excludes
+/// it, and which actions dominate it scenario by scenario. Returns `None`
+/// for an action that was not ranked.
+#[must_use]
+#[allow(clippy::too_many_lines)]
+pub fn explain_action(output: &DecisionOutput, action_id: &str) -> Option<ActionExplanation> {
+    let action = output
+        .ranked_actions
+        .iter()
+        .find(|a| a.action_id == action_id)?;
+    let top = output.ranked_actions.first()?;
+    let trace = &output.trace;
+    let precision = trace.precision.unwrap_or(FLOAT_PRECISION);
+
+    let mut scores = BTreeMap::new();
+    let mut shortfall: Option<CriterionShortfall> = None;
+    let weights = &trace.composite_weights;
+    let total = weights.total();
+    for (criterion, weight, table, lower_is_better) in [
+        (
+            "worst_case",
+            weights.worst_case,
+            &trace.worst_case_table,
+            false,
+        ),
+        (
+            "minimax_regret",
+            weights.minimax_regret,
+            &trace.max_regret_table,
+            true,
+        ),
+        (
+            "adversarial",
+            weights.adversarial,
+            &trace.adversarial_table,
+            false,
+        ),
+        (
+            "epsilon_contamination",
+            weights.epsilon_contamination,
+            &trace.epsilon_contamination_table,
+            false,
+        ),
+        (
+            "adversarial_regret",
+            weights.adversarial_regret,
+            &trace.adversarial_regret_table,
+            true,
+        ),
+        ("prior", weights.prior, &trace.prior_table, false),
+        (
+            "target_probability",
+            weights.target_probability,
+            &trace.target_probability_table,
+            false,
+        ),
+    ] {
+        let Some(&score) = table.get(action_id) else {
+            continue;
+        };
+        scores.insert(criterion.to_string(), score);
+        let top_score = table.get(&top.action_id).copied().unwrap_or(score);
+        let margin = float_normalize_with(
+            if lower_is_better {
+                score - top_score
+            } else {
+                top_score - score
+            },
+            precision,
+        );
+        // The composite scales shares (prior, target probability) to 0-100
+        let scale = if matches!(criterion, "prior" | "target_probability") {
+            100.0
+        } else {
+            1.0
+        };
+        let weighted_margin = float_normalize_with(weight / total * scale * margin, precision);
+        if margin > 0.0
+            && weighted_margin > 0.0
+            && shortfall
+                .as_ref()
+                .is_none_or(|s| weighted_margin > s.weighted_margin)
+        {
+            shortfall = Some(CriterionShortfall {
+                criterion: criterion.to_string(),
+                margin,
+                weighted_margin,
+            });
+        }
+    }
+
+    let utilities = trace.utility_table.get(action_id);
+    let dominated_by = trace
+        .utility_table
+        .iter()
+        .filter(|(other, other_utilities)| {
+            other.as_str() != action_id
+                && utilities.is_some_and(|utilities| dominates(other_utilities, utilities))
+        })
+        .map(|(other, _)| other.clone())
+        .collect();
+
+    Some(ActionExplanation {
+        action_id: action.action_id.clone(),
+        rank: action.rank,
+        recommended: action.recommended,
+        top_action: top.action_id.clone(),
+        scores,
+        composite_gap: float_normalize_with(
+            top.composite_score - action.composite_score,
+            precision,
+        ),
+        shortfall,
+        infeasible: trace
+            .constrained_maximin
+            .as_ref()
+            .is_some_and(|c| !c.feasible.iter().any(|id| id == action_id)),
+        dominated_by,
     })
 }
 
+/// Whether utilities `a` are at least `b`'s in every scenario and greater
+/// in some.
+fn dominates(a: &BTreeMap<String, f64>, b: &BTreeMap<String, f64>) -> bool {
+    let mut strictly = false;
+    for (scenario_id, &utility) in b {
+        match a.get(scenario_id) {
+            Some(&other) if other < utility => return false,
+            Some(&other) => strictly |= other > utility,
+            None => return false,
+        }
+    }
+    strictly
+}
+
 /// Referee a proposal against the computed decision.
 pub fn referee_proposal(
     input: &DecisionInput,
     claim: &str,
 ) -> Result<RefereeAdjudication, DecisionError> {
     let boundary = explain_decision_boundary(input)?;
+    Ok(adjudicate(boundary, claim))
+}
+
+/// Strict adjudication of `claim` against a computed boundary.
+fn adjudicate(boundary: DecisionBoundary, claim: &str) -> RefereeAdjudication {
+    RefereeAdjudication {
+        accepted: claim == boundary.top_action,
+        agent_claim: Some(claim.to_string()),
+        what_would_change: describe_flips(&boundary),
+        boundary,
+        regret_gap: None,
+    }
+}
+
+/// Run every sensitivity analysis from one evaluation.
+///
+/// Equivalent to calling [`compute_flip_distances`],
+/// [`explain_decision_boundary`], [`rank_evidence_by_voi`],
+/// [`generate_regret_bounded_plan`] and (given a `claim`) [`referee_proposal`]
+/// separately, but the decision is evaluated and its flip distances computed
+/// once, so every part of the report shares the same basis.
+pub fn build_decision_report(
+    input: &DecisionInput,
+    horizon: usize,
+    min_evoi: f64,
+    claim: Option<&str>,
+) -> Result<DecisionReport, DecisionError> {
+    let output = evaluate_decision(input)?;
+    let flip_distances = flip_distances_for(input, &output)?;
+    let boundary = boundary_from(&output, &flip_distances);
+    let voi = voi_rankings_for(input, &output, min_evoi);
+    let voi_note = output
+        .degenerate
+        .map(|reason| reason.explanation().to_string());
+    let plan = plan_from_rankings(input, &voi, horizon, min_evoi);
+    let adjudication = claim.map(|claim| adjudicate(boundary.clone(), claim));
+
+    Ok(DecisionReport {
+        output,
+        flip_distances,
+        boundary,
+        voi,
+        plan,
+        adjudication,
+        voi_note,
+    })
+}
+
+/// Referee a proposal, accepting near-ties on max regret.
+///
+/// The claim is accepted when it is the top action of `output`, or when its
+/// max regret exceeds the top action's by no more than `max_extra_regret`.
+/// A tolerance of 0 (or any non-positive value) is the strict behavior of
+/// [`referee_proposal`]. The gap is recorded in `regret_gap` either way.
+pub fn referee_proposal_with_tolerance(
+    input: &DecisionInput,
+    output: &DecisionOutput,
+    claim: &str,
+    max_extra_regret: f64,
+) -> Result<RefereeAdjudication, DecisionError> {
+    let boundary = explain_decision_boundary(input)?;
+    let top_action = output
+        .ranked_actions
+        .first()
+        .map(|a| a.action_id.as_str())
+        .ok_or(DecisionError::NoActions)?;
+
+    let regret_of = |action_id: &str| {
+        output
+            .trace
+            .max_regret_table
+            .get(action_id)
+            .copied()
+            .ok_or_else(|| DecisionError::InvalidOutcome(format!("Unknown action: {action_id}")))
+    };
+    let regret_gap = float_normalize_with(
+        regret_of(claim)? - regret_of(top_action)?,
+        input.normalization_precision(),
+    );
 
-    let accepted = claim == boundary.top_action;
+    let accepted =
+        claim == top_action || (max_extra_regret > 0.0 && regret_gap <= max_extra_regret);
 
     Ok(RefereeAdjudication {
         accepted,
         agent_claim: Some(claim.to_string()),
         boundary: boundary.clone(),
-        what_would_change: boundary
-            .nearest_flips
-            .iter()
-            .map(|f| {
-                format!(
-                    "{} at {} changes top action",
-                    f.variable_id, f.flip_distance
-                )
-            })
-            .collect(),
+        what_would_change: describe_flips(&boundary),
+        regret_gap: Some(regret_gap),
     })
 }
 
+fn describe_flips(boundary: &DecisionBoundary) -> Vec<String> {
+    boundary
+        .nearest_flips
+        .iter()
+        .map(|f| {
+            format!(
+                "{} at {} changes top action",
+                f.variable_id, f.flip_distance
+            )
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::cell::Cell;
+
+    thread_local! {
+        /// Calls to `evaluate_decision` on this test thread.
+        pub(super) static EVALUATIONS: Cell<usize> = const { Cell::new(0) };
+    }
+
+    fn count_evaluations<T>(f: impl FnOnce() -> T) -> (T, usize) {
+        let before = EVALUATIONS.with(Cell::get);
+        let result = f();
+        (result, EVALUATIONS.with(Cell::get) - before)
+    }
 
     fn create_test_input() -> DecisionInput {
         DecisionInput {
@@ -529,16 +2858,22 @@ mod tests {
                     id: "s1".to_string(),
                     probability: Some(0.5),
                     adversarial: false,
+                    severity: None,
+                    adversary_objective: None,
                 },
                 Scenario {
                     id: "s2".to_string(),
                     probability: Some(0.3),
                     adversarial: true,
+                    severity: None,
+                    adversary_objective: None,
                 },
                 Scenario {
                     id: "s3".to_string(),
                     probability: Some(0.2),
                     adversarial: false,
+                    severity: None,
+                    adversary_objective: None,
                 },
             ],
             outcomes: vec![
@@ -552,7 +2887,94 @@ mod tests {
             constraints: None,
             evidence: None,
             meta: None,
+            action_priors: None,
+            tie_break_seed: None,
+            allow_missing_as_zero: false,
+            missing_cell_policy: MissingCellPolicy::Error,
+            recommended_set_size: None,
+            recommended_set_epsilon: None,
+            precision: None,
+            strict: false,
+            portfolios: None,
+            target: None,
+            ranking_mode: None,
+        }
+    }
+
+    #[test]
+    fn test_decision_error_codes() {
+        let cases = [
+            (DecisionError::NoActions, "E_NO_ACTIONS"),
+            (DecisionError::NoScenarios, "E_NO_SCENARIOS"),
+            (DecisionError::NoOutcomes, "E_NO_OUTCOMES"),
+            (
+                DecisionError::InvalidOutcome("x".to_string()),
+                "E_INVALID_OUTCOME",
+            ),
+            (
+                DecisionError::InvalidWeights { sum: 2.0 },
+                "E_INVALID_WEIGHTS",
+            ),
+            (
+                DecisionError::InvalidEpsilon { epsilon: 2.0 },
+                "E_INVALID_EPSILON",
+            ),
+            (DecisionError::IncompleteOutcomes, "E_MISSING_OUTCOME"),
+            (
+                DecisionError::MissingCell {
+                    action: "a1".to_string(),
+                    scenario: "s1".to_string(),
+                },
+                "E_MISSING_OUTCOME",
+            ),
+            (
+                DecisionError::DuplicateId {
+                    kind: "action".to_string(),
+                    id: "a1".to_string(),
+                },
+                "E_DUPLICATE_ID",
+            ),
+            (
+                DecisionError::InvalidProbability("x".to_string()),
+                "E_INVALID_PROBABILITIES",
+            ),
+            (
+                DecisionError::InvalidPrior("x".to_string()),
+                "E_INVALID_PRIOR",
+            ),
+            (
+                DecisionError::InvalidSeverity("x".to_string()),
+                "E_INVALID_SEVERITY",
+            ),
+            (
+                DecisionError::InvalidInput("x".to_string()),
+                "E_INVALID_INPUT",
+            ),
+            (DecisionError::Encoding("x".to_string()), "E_ENCODING"),
+            (
+                DecisionError::NoFeasibleActions {
+                    regret_cap: 0.1 + 0.2,
+                },
+                "E_NO_FEASIBLE_ACTIONS",
+            ),
+        ];
+
+        for (error, code) in cases {
+            assert_eq!(error.code(), code);
+            let json = serde_json::to_value(&error).unwrap();
+            assert_eq!(
+                json,
+                serde_json::json!({ "code": code, "message": error.to_string() })
+            );
+            let parsed: DecisionError = serde_json::from_value(json).unwrap();
+            assert_eq!(parsed, error);
         }
+
+        // Codes and messages must agree
+        let mismatched = serde_json::json!({ "code": "E_ENCODING", "message": "Invalid input: x" });
+        assert!(serde_json::from_value::<DecisionError>(mismatched).is_err());
+        let unknown = serde_json::json!({ "code": "E_UNKNOWN", "message": "x" });
+        assert!(serde_json::from_value::<DecisionError>(unknown).is_err());
     }
 
     #[test]
@@ -673,86 +3095,834 @@ mod tests {
     }
 
     #[test]
-    fn test_compute_flip_distances() {
+    fn test_shuffled_outcomes_fingerprint_identically() {
         let input = create_test_input();
-        let distances = compute_flip_distances(&input).unwrap();
+        let mut shuffled = input.clone();
+        shuffled.outcomes.reverse();
+        shuffled.outcomes.swap(0, 3);
+        assert_ne!(input.outcomes, shuffled.outcomes);
 
-        assert!(!distances.is_empty());
-        for d in &distances {
-            assert!(d.flip_distance >= 0.0);
-        }
-    }
+        let output = evaluate_decision(&input).unwrap();
+        let shuffled_output = evaluate_decision(&shuffled).unwrap();
+        assert_eq!(
+            output.determinism_fingerprint,
+            shuffled_output.determinism_fingerprint
+        );
+        assert_eq!(
+            serde_json::to_vec(&output).unwrap(),
+            serde_json::to_vec(&shuffled_output).unwrap()
+        );
+        assert!(output.verify(&shuffled));
 
-    #[test]
-    fn test_rank_evidence_by_voi() {
-        let input = create_test_input();
-        let rankings = rank_evidence_by_voi(&input, 0.1).unwrap();
+        // Strict mode is part of the fingerprint whatever the outcome order
+        let strict = DecisionInput {
+            strict: true,
+            ..input.clone()
+        };
+        let strict_shuffled = DecisionInput {
+            strict: true,
+            ..shuffled.clone()
+        };
+        assert_eq!(
+            input_fingerprint(&strict_shuffled),
+            input_fingerprint(&strict)
+        );
+        assert_ne!(
+            input_fingerprint(&strict_shuffled),
+            input_fingerprint(&shuffled)
+        );
+        assert_ne!(input_fingerprint(&strict), input_fingerprint(&input));
 
-        assert!(!rankings.is_empty());
-        for r in &rankings {
-            assert!(!r.recommendation.is_empty());
-            assert!(!r.rationale.is_empty());
+        // A repeated cell is rejected rather than fingerprinted
+        let mut repeated = shuffled.clone();
+        let (action_id, scenario_id, _) = repeated.outcomes[0].clone();
+        repeated.outcomes.insert(0, (action_id, scenario_id, -1.0));
+        assert!(matches!(
+            evaluate_decision(&repeated),
+            Err(DecisionError::InvalidOutcome(_))
+        ));
+    }
+
+    fn epsilon_input(epsilon: f64) -> DecisionInput {
+        DecisionInput {
+            id: Some("epsilon".to_string()),
+            actions: vec![
+                ActionOption {
+                    id: "risky".to_string(),
+                    label: "Risky".to_string(),
+                },
+                ActionOption {
+                    id: "safe".to_string(),
+                    label: "Safe".to_string(),
+                },
+            ],
+            scenarios: vec![
+                Scenario {
+                    id: "calm".to_string(),
+                    probability: Some(0.5),
+                    adversarial: false,
+                    severity: None,
+                    adversary_objective: None,
+                },
+                Scenario {
+                    id: "attack".to_string(),
+                    probability: Some(0.5),
+                    adversarial: true,
+                    severity: None,
+                    adversary_objective: None,
+                },
+            ],
+            outcomes: vec![
+                ("risky".to_string(), "calm".to_string(), 100.0),
+                ("risky".to_string(), "attack".to_string(), -20.0),
+                ("safe".to_string(), "calm".to_string(), 40.0),
+                ("safe".to_string(), "attack".to_string(), 30.0),
+            ],
+            constraints: Some(DecisionConstraint {
+                epsilon: Some(epsilon),
+                weights: Some(CompositeWeights {
+                    worst_case: 0.0,
+                    minimax_regret: 0.0,
+                    adversarial: 0.0,
+                    epsilon_contamination: 1.0,
+                    adversarial_regret: 0.0,
+                    prior: 0.0,
+                    target_probability: 0.0,
+                }),
+                ..Default::default()
+            }),
+            evidence: None,
+            meta: None,
+            action_priors: None,
+            tie_break_seed: None,
+            allow_missing_as_zero: false,
+            missing_cell_policy: MissingCellPolicy::Error,
+            recommended_set_size: None,
+            recommended_set_epsilon: None,
+            precision: None,
+            strict: false,
+            portfolios: None,
+            target: None,
+            ranking_mode: None,
         }
     }
 
     #[test]
-    fn test_generate_regret_bounded_plan() {
-        let input = create_test_input();
-        let plan = generate_regret_bounded_plan(&input, 2, 0.1).unwrap();
+    fn test_epsilon_contamination_shifts_toward_safe_action() {
+        // eps = 0: pure expected value, risky (40) beats safe (35)
+        let low = evaluate_decision(&epsilon_input(0.0)).unwrap();
+        assert_eq!(low.recommended_action_id(), Some("risky"));
+        assert!((low.trace.epsilon_contamination_table["risky"] - 40.0).abs() < 1e-9);
 
-        assert!(!plan.id.is_empty());
-        assert!(!plan.actions.is_empty());
-        assert_eq!(plan.bounded_horizon, 2);
+        // eps = 0.5: risky 10, safe 32.5
+        let high = evaluate_decision(&epsilon_input(0.5)).unwrap();
+        assert_eq!(high.recommended_action_id(), Some("safe"));
+        assert!((high.trace.epsilon_contamination_table["safe"] - 32.5).abs() < 1e-9);
+
+        // Unweighted, the table is left out of the trace and its JSON
+        let mut unweighted = epsilon_input(0.5);
+        unweighted.constraints = None;
+        let output = evaluate_decision(&unweighted).unwrap();
+        assert!(output.trace.epsilon_contamination_table.is_empty());
+        let json = serde_json::to_value(&output).unwrap();
+        assert!(json["trace"].get("epsilon_contamination_table").is_none());
+        assert!(serde_json::to_value(&high).unwrap()["trace"]
+            .get("epsilon_contamination_table")
+            .is_some());
     }
 
     #[test]
-    fn test_explain_decision_boundary() {
-        let input = create_test_input();
-        let boundary = explain_decision_boundary(&input).unwrap();
+    fn test_diversified_portfolio_beats_single_actions() {
+        let mut input = create_test_input();
+        input.scenarios.truncate(2);
+        input.scenarios[0].probability = Some(0.5);
+        input.scenarios[1].probability = Some(0.5);
+        input.actions.push(ActionOption {
+            id: "a3".to_string(),
+            label: "Action 3".to_string(),
+        });
+        input.outcomes = [("a1", 100.0, 0.0), ("a2", 0.0, 100.0), ("a3", 30.0, 30.0)]
+            .into_iter()
+            .flat_map(|(a, s1, s2)| {
+                [
+                    (a.to_string(), "s1".to_string(), s1),
+                    (a.to_string(), "s2".to_string(), s2),
+                ]
+            })
+            .collect();
+        let singles = evaluate_decision(&input).unwrap();
+        assert_eq!(singles.recommended_action_id(), Some("a3"));
 
-        assert!(!boundary.top_action.is_empty());
-        // Should have up to 2 nearest flips
-        assert!(boundary.nearest_flips.len() <= 2);
+        input.portfolios = Some(vec![vec!["a2".to_string(), "a1".to_string()]]);
+        let output = evaluate_decision(&input).unwrap();
+        assert_eq!(output.trace.portfolios["a1+a2"], vec!["a1", "a2"]);
+        assert_eq!(output.trace.utility_table["a1+a2"]["s1"], 50.0);
+        assert_eq!(output.trace.worst_case_table["a1+a2"], 50.0);
+        assert!(singles.trace.worst_case_table.values().all(|&w| w < 50.0));
+        assert_eq!(output.recommended_action_id(), Some("a1+a2"));
+        assert_eq!(output.ranked_actions.len(), 4);
+        assert!(output.verify_self_consistent());
+        assert_ne!(
+            output.determinism_fingerprint,
+            singles.determinism_fingerprint
+        );
+        // Also when outcomes arrive out of canonical order
+        let mut reordered = input.clone();
+        reordered.outcomes.reverse();
+        let mut reordered_singles = reordered.clone();
+        reordered_singles.portfolios = None;
+        assert_eq!(
+            decision_fingerprint(&reordered),
+            output.determinism_fingerprint
+        );
+        assert_ne!(
+            decision_fingerprint(&reordered),
+            decision_fingerprint(&reordered_singles)
+        );
+
+        let rejected = |portfolio: &[&str]| {
+            let mut invalid = input.clone();
+            invalid.portfolios = Some(vec![portfolio.iter().map(ToString::to_string).collect()]);
+            validate_input(&invalid)
+        };
+        assert!(matches!(
+            rejected(&["a1"]),
+            Err(DecisionError::InvalidInput(_))
+        ));
+        assert!(matches!(
+            rejected(&["a1", "a1"]),
+            Err(DecisionError::InvalidInput(_))
+        ));
+        assert!(matches!(
+            rejected(&["a1", "a9"]),
+            Err(DecisionError::InvalidInput(_))
+        ));
+        input.portfolios = Some(vec![
+            vec!["a1".to_string(), "a3".to_string()],
+            vec!["a3".to_string(), "a1".to_string()],
+        ]);
+        assert!(matches!(
+            validate_input(&input),
+            Err(DecisionError::DuplicateId { .. })
+        ));
     }
 
     #[test]
-    fn test_referee_proposal_accepted() {
-        let input = create_test_input();
-        let boundary = explain_decision_boundary(&input).unwrap();
+    fn test_evidence_updates_scenario_probabilities() {
+        let input = epsilon_input(0.0);
+        let mut informed = input.clone();
+        informed.evidence = Some(DecisionEvidence {
+            scenario_likelihoods: BTreeMap::from([
+                ("attack".to_string(), 0.9),
+                ("calm".to_string(), 0.1),
+            ]),
+            ..Default::default()
+        });
 
-        // Proposal matching top action should be accepted
-        let adjudication = referee_proposal(&input, &boundary.top_action).unwrap();
-        assert!(adjudication.accepted);
-    }
+        // Posterior: calm 0.1, attack 0.9, so risky -8 and safe 31
+        let output = evaluate_decision(&informed).unwrap();
+        assert_eq!(output.recommended_action_id(), Some("safe"));
+        assert!((output.trace.scenario_posteriors["attack"] - 0.9).abs() < 1e-9);
+        assert!((output.trace.scenario_priors["attack"] - 0.5).abs() < 1e-9);
+        assert!((output.trace.epsilon_contamination_table["risky"] + 8.0).abs() < 1e-9);
+        assert!((output.trace.epsilon_contamination_table["safe"] - 31.0).abs() < 1e-9);
+        assert_ne!(
+            output.determinism_fingerprint,
+            evaluate_decision(&input).unwrap().determinism_fingerprint
+        );
 
-    #[test]
-    fn test_referee_proposal_rejected() {
-        let input = create_test_input();
+        // No likelihoods leaves the output as it was
+        let mut empty = input.clone();
+        empty.evidence = Some(DecisionEvidence::default());
+        let unchanged = evaluate_decision(&empty).unwrap();
+        assert_eq!(unchanged.recommended_action_id(), Some("risky"));
+        assert!(unchanged.trace.scenario_posteriors.is_empty());
 
-        // Proposal NOT matching top action should be rejected
-        let wrong_action = if input.actions[0].id == "a1" {
-            "a2"
-        } else {
-            "a1"
-        };
-        let adjudication = referee_proposal(&input, wrong_action).unwrap();
-        assert!(!adjudication.accepted);
+        // Evidence ruling out every scenario is rejected
+        informed
+            .evidence
+            .as_mut()
+            .unwrap()
+            .scenario_likelihoods
+            .values_mut()
+            .for_each(|l| *l = 0.0);
+        assert!(matches!(
+            evaluate_decision(&informed),
+            Err(DecisionError::InvalidInput(_))
+        ));
     }
 
     #[test]
-    fn test_error_no_actions() {
-        let input = DecisionInput {
-            id: None,
-            actions: vec![],
-            scenarios: vec![Scenario {
-                id: "s1".to_string(),
-                probability: Some(1.0),
+    fn test_epsilon_out_of_range() {
+        let result = evaluate_decision(&epsilon_input(1.5));
+        assert!(matches!(
+            result.unwrap_err(),
+            DecisionError::InvalidEpsilon { .. }
+        ));
+    }
+
+    fn regret_input(weights: CompositeWeights) -> DecisionInput {
+        DecisionInput {
+            id: Some("adversarial_regret".to_string()),
+            actions: vec![
+                ActionOption {
+                    id: "growth".to_string(),
+                    label: "Growth".to_string(),
+                },
+                ActionOption {
+                    id: "hedge".to_string(),
+                    label: "Hedge".to_string(),
+                },
+            ],
+            scenarios: vec![
+                Scenario {
+                    id: "boom".to_string(),
+                    probability: Some(0.5),
+                    adversarial: false,
+                    severity: None,
+                    adversary_objective: None,
+                },
+                Scenario {
+                    id: "squeeze".to_string(),
+                    probability: Some(0.5),
+                    adversarial: true,
+                    severity: None,
+                    adversary_objective: None,
+                },
+            ],
+            outcomes: vec![
+                ("growth".to_string(), "boom".to_string(), 100.0),
+                ("growth".to_string(), "squeeze".to_string(), 50.0),
+                ("hedge".to_string(), "boom".to_string(), 40.0),
+                ("hedge".to_string(), "squeeze".to_string(), 60.0),
+            ],
+            constraints: Some(DecisionConstraint {
+                weights: Some(weights),
+                ..Default::default()
+            }),
+            evidence: None,
+            meta: None,
+            action_priors: None,
+            tie_break_seed: None,
+            allow_missing_as_zero: false,
+            missing_cell_policy: MissingCellPolicy::Error,
+            recommended_set_size: None,
+            recommended_set_epsilon: None,
+            precision: None,
+            strict: false,
+            portfolios: None,
+            target: None,
+            ranking_mode: None,
+        }
+    }
+
+    #[test]
+    fn test_adversarial_regret_differs_from_full_regret() {
+        let full = evaluate_decision(&regret_input(CompositeWeights {
+            worst_case: 0.0,
+            minimax_regret: 1.0,
+            adversarial: 0.0,
+            epsilon_contamination: 0.0,
+            adversarial_regret: 0.0,
+            prior: 0.0,
+            target_probability: 0.0,
+        }))
+        .unwrap();
+        let hostile = evaluate_decision(&regret_input(CompositeWeights {
+            worst_case: 0.0,
+            minimax_regret: 0.0,
+            adversarial: 0.0,
+            epsilon_contamination: 0.0,
+            adversarial_regret: 1.0,
+            prior: 0.0,
+            target_probability: 0.0,
+        }))
+        .unwrap();
+
+        // Full-set regret: growth 10 (squeeze), hedge 60 (boom)
+        assert!((full.trace.max_regret_table["growth"] - 10.0).abs() < 1e-9);
+        assert!((full.trace.max_regret_table["hedge"] - 60.0).abs() < 1e-9);
+        assert_eq!(full.recommended_action_id(), Some("growth"));
+
+        // Adversarial-only regret ignores the boom: growth 10, hedge 0
+        assert!((hostile.trace.adversarial_regret_table["growth"] - 10.0).abs() < 1e-9);
+        assert!((hostile.trace.adversarial_regret_table["hedge"] - 0.0).abs() < 1e-9);
+        assert_eq!(hostile.recommended_action_id(), Some("hedge"));
+    }
+
+    #[test]
+    fn test_adversarial_regret_falls_back_to_all_scenarios() {
+        let mut input = create_test_input();
+        for scenario in &mut input.scenarios {
+            scenario.adversarial = false;
+        }
+        let output = evaluate_decision(&input).unwrap();
+        assert_eq!(
+            output.trace.adversarial_regret_table,
+            output.trace.max_regret_table
+        );
+    }
+
+    #[test]
+    fn test_compute_flip_distances() {
+        let input = create_test_input();
+        let distances = compute_flip_distances(&input).unwrap();
+
+        assert!(!distances.is_empty());
+        for d in &distances {
+            assert!(d.flip_distance >= 0.0);
+        }
+    }
+
+    #[test]
+    fn test_rank_evidence_by_voi() {
+        let input = create_test_input();
+        let rankings = rank_evidence_by_voi(&input, 0.1).unwrap();
+
+        assert!(!rankings.is_empty());
+        for r in &rankings {
+            assert!(!r.recommendation.is_empty());
+            assert!(!r.rationale.is_empty());
+        }
+    }
+
+    fn extreme_input(low: f64) -> DecisionInput {
+        let mut input = create_test_input();
+        input.scenarios.truncate(2);
+        input.scenarios[0].probability = Some(0.5);
+        input.scenarios[1].probability = Some(0.5);
+        input.outcomes = vec![
+            ("a1".to_string(), "s1".to_string(), f64::MAX),
+            ("a1".to_string(), "s2".to_string(), low),
+            ("a2".to_string(), "s1".to_string(), low),
+            ("a2".to_string(), "s2".to_string(), f64::MAX),
+        ];
+        input
+    }
+
+    /// Three actions within 0.5 composite points of each other, and a fourth far behind
+    fn shortlist_input(size: Option<usize>, epsilon: Option<f64>) -> DecisionInput {
+        let mut input = severity_input(None);
+        input.actions = ["a1", "a2", "a3", "a4"]
+            .iter()
+            .map(|id| ActionOption {
+                id: (*id).to_string(),
+                label: (*id).to_string(),
+            })
+            .collect();
+        input.outcomes = [("a1", 50.0), ("a2", 50.2), ("a3", 49.8), ("a4", 10.0)]
+            .iter()
+            .flat_map(|&(action, utility)| {
+                ["x", "y"].map(|scenario| (action.to_string(), scenario.to_string(), utility))
+            })
+            .collect();
+        input.recommended_set_size = size;
+        input.recommended_set_epsilon = epsilon;
+        input
+    }
+
+    fn recommended_ids(output: &DecisionOutput) -> Vec<&str> {
+        output
+            .ranked_actions
+            .iter()
+            .filter(|a| a.recommended)
+            .map(|a| a.action_id.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_recommended_set_within_epsilon() {
+        let output = evaluate_decision(&shortlist_input(Some(3), Some(0.5))).unwrap();
+        assert_eq!(recommended_ids(&output), vec!["a2", "a1", "a3"]);
+        assert_eq!(output.recommended_action_id(), Some("a2"));
+        assert!(output.verify_self_consistent());
+
+        let top: Vec<&str> = output
+            .top_k(2)
+            .iter()
+            .map(|a| a.action_id.as_str())
+            .collect();
+        assert_eq!(top, vec!["a2", "a1"]);
+        assert_eq!(output.top_k(10).len(), 4);
+
+        // The set never grows past its size, even when a4 would not qualify anyway
+        let capped = evaluate_decision(&shortlist_input(Some(2), Some(100.0))).unwrap();
+        assert_eq!(recommended_ids(&capped), vec!["a2", "a1"]);
+    }
+
+    #[test]
+    fn test_recommended_set_keeps_clear_winner_alone() {
+        let mut input = shortlist_input(Some(3), Some(0.5));
+        input
+            .outcomes
+            .retain(|(action, _, _)| action != "a1" && action != "a3");
+        input.actions.retain(|a| a.id != "a1" && a.id != "a3");
+        let output = evaluate_decision(&input).unwrap();
+        assert_eq!(recommended_ids(&output), vec!["a2"]);
+
+        // Without the setting only the winner is recommended, as before
+        let default = evaluate_decision(&shortlist_input(None, None)).unwrap();
+        assert_eq!(recommended_ids(&default), vec!["a2"]);
+        assert_eq!(
+            default.ranked_actions,
+            evaluate_decision(&shortlist_input(Some(1), Some(0.5)))
+                .unwrap()
+                .ranked_actions
+        );
+
+        for (size, epsilon) in [(Some(0), None), (None, Some(-1.0)), (None, Some(f64::NAN))] {
+            let err = evaluate_decision(&shortlist_input(size, epsilon)).unwrap_err();
+            assert_eq!(err.code(), "E_INVALID_INPUT");
+        }
+    }
+
+    #[test]
+    fn test_output_verifies_against_its_input() {
+        let input = create_test_input();
+        let mut output = evaluate_decision(&input).unwrap();
+        assert!(output.verify(&input));
+        assert!(output.verify_self_consistent());
+
+        let mut seeded = input.clone();
+        seeded.tie_break_seed = Some(7);
+        assert!(!output.verify(&seeded));
+        assert!(evaluate_decision(&seeded).unwrap().verify(&seeded));
+
+        output.determinism_fingerprint = stable_hash(b"tampered");
+        assert!(!output.verify(&input));
+        // The fingerprint is not part of the ranking
+        assert!(output.verify_self_consistent());
+    }
+
+    #[test]
+    fn test_tampered_ranking_is_not_self_consistent() {
+        // Tied utilities, so the prior alone puts a2 first
+        let output = evaluate_decision(&prior_input([50.0, 50.0], [1.0, 3.0], 0.1)).unwrap();
+        assert_eq!(output.recommended_action_id(), Some("a2"));
+        assert!(output.verify_self_consistent());
+
+        let mut swapped = output.clone();
+        swapped.ranked_actions.swap(0, 1);
+        assert!(!swapped.verify_self_consistent());
+
+        let mut rescored = output.clone();
+        rescored.ranked_actions[1].composite_score += 1.0;
+        assert!(!rescored.verify_self_consistent());
+
+        let mut retraced = output.clone();
+        retraced.trace.prior_table.clear();
+        assert!(!retraced.verify_self_consistent());
+
+        let mut dropped = output;
+        dropped.ranked_actions.pop();
+        assert!(!dropped.verify_self_consistent());
+    }
+
+    #[test]
+    fn test_overflowing_regret_is_rejected() {
+        let err = evaluate_decision(&extreme_input(-f64::MAX)).unwrap_err();
+        assert_eq!(err.code(), "E_INVALID_INPUT");
+        assert!(
+            err.to_string().contains("regret of 'a1' in 's2'"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_extreme_finite_utilities_stay_finite() {
+        let input = extreme_input(0.0);
+        let output = evaluate_decision(&input).unwrap();
+
+        let trace = &output.trace;
+        let tables = [
+            &trace.worst_case_table,
+            &trace.max_regret_table,
+            &trace.adversarial_table,
+            &trace.epsilon_contamination_table,
+            &trace.adversarial_regret_table,
+        ];
+        for value in tables.iter().flat_map(|t| t.values()) {
+            assert!(value.is_finite());
+        }
+        for action in &output.ranked_actions {
+            assert!(action.composite_score.is_finite());
+        }
+        assert_eq!(trace.max_regret_table["a1"], f64::MAX);
+        assert_eq!(evaluate_decision(&input).unwrap(), output);
+    }
+
+    #[test]
+    fn test_decision_report_matches_standalone_analyses() {
+        let input = create_test_input();
+
+        let (report, report_evaluations) =
+            count_evaluations(|| build_decision_report(&input, 2, 0.001, Some("a2")).unwrap());
+        assert_eq!(report_evaluations, 1);
+
+        let (standalone, standalone_evaluations) = count_evaluations(|| {
+            (
+                compute_flip_distances(&input).unwrap(),
+                explain_decision_boundary(&input).unwrap(),
+                rank_evidence_by_voi(&input, 0.001).unwrap(),
+                generate_regret_bounded_plan(&input, 2, 0.001).unwrap(),
+                referee_proposal(&input, "a2").unwrap(),
+            )
+        });
+        assert_eq!(standalone_evaluations, 5);
+
+        let (flips, boundary, voi, plan, adjudication) = standalone;
+        assert_eq!(report.output, evaluate_decision(&input).unwrap());
+        assert_eq!(report.flip_distances, flips);
+        assert_eq!(report.boundary, boundary);
+        assert_eq!(report.voi, voi);
+        assert_eq!(report.plan, plan);
+        assert_eq!(report.adjudication, Some(adjudication));
+
+        let without_claim = build_decision_report(&input, 2, 0.001, None).unwrap();
+        assert!(without_claim.adjudication.is_none());
+    }
+
+    #[test]
+    fn test_voi_factors_sum_to_evoi() {
+        // "hold" dominates "switch" (0 everywhere), so every scenario's factor
+        // is driven by hold's utility there: 1 / (|u| + 0.1)
+        let mut input = create_test_input();
+        input.actions[0].id = "hold".to_string();
+        input.actions[1].id = "switch".to_string();
+        input.outcomes = [("s1", 0.9), ("s2", 1.9), ("s3", 9.9)]
+            .into_iter()
+            .flat_map(|(scenario, utility)| {
+                [
+                    ("hold".to_string(), scenario.to_string(), utility),
+                    ("switch".to_string(), scenario.to_string(), 0.0),
+                ]
+            })
+            .collect();
+        let rankings = rank_evidence_by_voi(&input, 0.3).unwrap();
+
+        let expected = [
+            ("s1", 1.0, 0.9, 0.5, "do_now"),
+            ("s2", 0.5, 1.9, 0.3, "plan_later"),
+            ("s3", 0.1, 9.9, 0.2, "defer"),
+        ];
+        assert_eq!(rankings.len(), expected.len());
+        for (r, (scenario_id, contribution, flip_distance, probability, recommendation)) in
+            rankings.iter().zip(expected)
+        {
+            assert_eq!(r.action_id, scenario_id);
+            assert_eq!(r.recommendation, recommendation);
+            assert_eq!(r.factors.len(), 1);
+            let factor = &r.factors[0];
+            assert_eq!(factor.scenario_id, scenario_id);
+            assert!((factor.contribution - contribution).abs() < 1e-9);
+            assert!((factor.flip_distance.unwrap() - flip_distance).abs() < 1e-9);
+            assert_eq!(factor.probability, Some(probability));
+
+            let total: f64 = r.factors.iter().map(|f| f.contribution).sum();
+            assert!((total - r.evoi).abs() < 1e-9);
+            assert_eq!(r.rationale, voi_rationale(&r.factors, r.evoi));
+        }
+        assert_eq!(rankings, rank_evidence_by_voi(&input, 0.3).unwrap());
+    }
+
+    #[test]
+    fn test_degenerate_inputs_are_marked() {
+        let mut single_action = create_test_input();
+        single_action.actions.truncate(1);
+        single_action.outcomes.retain(|(a, _, _)| a == "a1");
+        let mut single_scenario = create_test_input();
+        single_scenario.scenarios.truncate(1);
+        single_scenario.scenarios[0].probability = Some(1.0);
+        single_scenario.outcomes.retain(|(_, s, _)| s == "s1");
+        let mut all_equal = create_test_input();
+        for outcome in &mut all_equal.outcomes {
+            outcome.2 = if outcome.1 == "s2" { 50.0 } else { 75.0 };
+        }
+
+        for (input, reason) in [
+            (single_action, DegenerateReason::SingleAction),
+            (single_scenario, DegenerateReason::SingleScenario),
+            (all_equal, DegenerateReason::AllScoresEqual),
+        ] {
+            let output = evaluate_decision(&input).unwrap();
+            assert_eq!(output.degenerate, Some(reason));
+            assert!(rank_evidence_by_voi(&input, 0.1).unwrap().is_empty());
+            let report = build_decision_report(&input, 2, 0.1, None).unwrap();
+            assert!(report.voi.is_empty());
+            assert!(report.plan.actions.is_empty());
+            assert_eq!(report.voi_note.as_deref(), Some(reason.explanation()));
+        }
+
+        let output = evaluate_decision(&create_test_input()).unwrap();
+        assert_eq!(output.degenerate, None);
+        let report = build_decision_report(&create_test_input(), 2, 0.1, None).unwrap();
+        assert_eq!(report.voi_note, None);
+    }
+
+    #[test]
+    fn test_generate_regret_bounded_plan() {
+        let input = create_test_input();
+        let plan = generate_regret_bounded_plan(&input, 2, 0.1).unwrap();
+
+        assert!(!plan.id.is_empty());
+        assert!(!plan.actions.is_empty());
+        assert_eq!(plan.bounded_horizon, 2);
+    }
+
+    #[test]
+    fn test_regret_bounded_plan_reports_deferred() {
+        // a1 dominates; small utilities make s1 and s2 do_now, s3 is deferred
+        let mut input = create_test_input();
+        input.outcomes = vec![
+            ("a1".to_string(), "s1".to_string(), 0.5),
+            ("a1".to_string(), "s2".to_string(), 1.0),
+            ("a1".to_string(), "s3".to_string(), 1000.0),
+            ("a2".to_string(), "s1".to_string(), 0.0),
+            ("a2".to_string(), "s2".to_string(), 0.0),
+            ("a2".to_string(), "s3".to_string(), 0.0),
+        ];
+
+        let plan = generate_regret_bounded_plan(&input, 1, 0.1).unwrap();
+        assert!(plan.horizon_truncated);
+        let ids =
+            |actions: &[PlannedAction]| actions.iter().map(|a| a.id.clone()).collect::<Vec<_>>();
+        assert_eq!(ids(&plan.actions), vec!["s1"]);
+        assert_eq!(ids(&plan.deferred), vec!["s2", "s3"]);
+        assert_eq!(
+            plan.deferred[0].rationale.last().unwrap(),
+            "Deferred: plan horizon of 1 reached"
+        );
+
+        let wide = generate_regret_bounded_plan(&input, 2, 0.1).unwrap();
+        assert!(!wide.horizon_truncated);
+        assert_eq!(ids(&wide.actions), vec!["s1", "s2"]);
+        assert_eq!(ids(&wide.deferred), vec!["s3"]);
+
+        assert_eq!(plan, generate_regret_bounded_plan(&input, 1, 0.1).unwrap());
+    }
+
+    #[test]
+    fn test_explain_decision_boundary() {
+        let input = create_test_input();
+        let boundary = explain_decision_boundary(&input).unwrap();
+
+        assert!(!boundary.top_action.is_empty());
+        // Should have up to 2 nearest flips
+        assert!(boundary.nearest_flips.len() <= 2);
+    }
+
+    #[test]
+    fn test_referee_proposal_accepted() {
+        let input = create_test_input();
+        let boundary = explain_decision_boundary(&input).unwrap();
+
+        // Proposal matching top action should be accepted
+        let adjudication = referee_proposal(&input, &boundary.top_action).unwrap();
+        assert!(adjudication.accepted);
+    }
+
+    #[test]
+    fn test_referee_proposal_rejected() {
+        let input = create_test_input();
+
+        // Proposal NOT matching top action should be rejected
+        let wrong_action = if input.actions[0].id == "a1" {
+            "a2"
+        } else {
+            "a1"
+        };
+        let adjudication = referee_proposal(&input, wrong_action).unwrap();
+        assert!(!adjudication.accepted);
+    }
+
+    #[test]
+    fn test_referee_proposal_with_tolerance_accepts_near_tie() {
+        let input = utility_input(&[100.0, 99.5, 0.0]);
+        let output = evaluate_decision(&input).unwrap();
+
+        let adjudication = referee_proposal_with_tolerance(&input, &output, "a2", 1.0).unwrap();
+        assert!(adjudication.accepted);
+        assert!((adjudication.regret_gap.unwrap() - 0.5).abs() < 1e-9);
+
+        // Tolerance 0 is the strict behavior
+        let strict = referee_proposal_with_tolerance(&input, &output, "a2", 0.0).unwrap();
+        assert!(!strict.accepted);
+        let top = referee_proposal_with_tolerance(&input, &output, "a1", 0.0).unwrap();
+        assert!(top.accepted);
+        assert_eq!(top.regret_gap, Some(0.0));
+    }
+
+    #[test]
+    fn test_referee_proposal_with_tolerance_rejects_outside_tolerance() {
+        let input = utility_input(&[100.0, 99.5, 0.0]);
+        let output = evaluate_decision(&input).unwrap();
+
+        let adjudication = referee_proposal_with_tolerance(&input, &output, "a3", 1.0).unwrap();
+        assert!(!adjudication.accepted);
+        assert!((adjudication.regret_gap.unwrap() - 100.0).abs() < 1e-9);
+        assert_eq!(adjudication.agent_claim.as_deref(), Some("a3"));
+
+        assert!(matches!(
+            referee_proposal_with_tolerance(&input, &output, "missing", 1.0),
+            Err(DecisionError::InvalidOutcome(_))
+        ));
+    }
+
+    #[test]
+    fn test_scenario_regret_leaders() {
+        let output = evaluate_decision(&create_test_input()).unwrap();
+        let leaders = scenario_regret_leaders(&output.trace.regret_table);
+
+        // Best utilities: s1 = 100 (a1), s2 = 60 (a2), s3 = 80 (a1)
+        let expected = [
+            ("s1", "a1", 0.0, "a2", 10.0),
+            ("s2", "a2", 0.0, "a1", 10.0),
+            ("s3", "a1", 0.0, "a2", 10.0),
+        ];
+        assert_eq!(leaders.len(), expected.len());
+        for (scenario, min_action, min_regret, max_action, max_regret) in expected {
+            let leader = &leaders[scenario];
+            assert_eq!(leader.min_regret_action, min_action, "{scenario}");
+            assert_eq!(leader.min_regret, min_regret, "{scenario}");
+            assert_eq!(leader.max_regret_action, max_action, "{scenario}");
+            assert_eq!(leader.max_regret, max_regret, "{scenario}");
+        }
+
+        // Every action ties in s1, so both leaders are the smallest ID
+        let tied = evaluate_decision(&utility_input(&[5.0, 5.0, 5.0])).unwrap();
+        let leader = &scenario_regret_leaders(&tied.trace.regret_table)["s1"];
+        assert_eq!(leader.min_regret_action, "a1");
+        assert_eq!(leader.max_regret_action, "a1");
+    }
+
+    #[test]
+    fn test_error_no_actions() {
+        let input = DecisionInput {
+            id: None,
+            actions: vec![],
+            scenarios: vec![Scenario {
+                id: "s1".to_string(),
+                probability: Some(1.0),
                 adversarial: false,
+                severity: None,
+                adversary_objective: None,
             }],
             outcomes: vec![],
             constraints: None,
             evidence: None,
             meta: None,
+            action_priors: None,
+            tie_break_seed: None,
+            allow_missing_as_zero: false,
+            missing_cell_policy: MissingCellPolicy::Error,
+            recommended_set_size: None,
+            recommended_set_epsilon: None,
+            precision: None,
+            strict: false,
+            portfolios: None,
+            target: None,
+            ranking_mode: None,
         };
 
         let result = evaluate_decision(&input);
@@ -773,6 +3943,17 @@ mod tests {
             constraints: None,
             evidence: None,
             meta: None,
+            action_priors: None,
+            tie_break_seed: None,
+            allow_missing_as_zero: false,
+            missing_cell_policy: MissingCellPolicy::Error,
+            recommended_set_size: None,
+            recommended_set_epsilon: None,
+            precision: None,
+            strict: false,
+            portfolios: None,
+            target: None,
+            ranking_mode: None,
         };
 
         let result = evaluate_decision(&input);
@@ -781,24 +3962,571 @@ mod tests {
     }
 
     #[test]
-    fn test_tie_break_deterministic() {
-        // Create input where scores might tie
-        let mut input = create_test_input();
-        // Make utilities identical
-        input.outcomes = vec![
-            ("a1".to_string(), "s1".to_string(), 50.0),
-            ("a1".to_string(), "s2".to_string(), 50.0),
-            ("a2".to_string(), "s1".to_string(), 50.0),
-            ("a2".to_string(), "s2".to_string(), 50.0),
-        ];
+    fn test_validate_input_clean() {
+        let input = create_test_input();
+        assert_eq!(validate_input(&input), Ok(()));
+    }
 
-        let output1 = evaluate_decision(&input).unwrap();
-        let output2 = evaluate_decision(&input).unwrap();
+    #[test]
+    fn test_validate_input_duplicate_ids() {
+        let mut input = create_test_input();
+        input.actions.push(input.actions[0].clone());
+        assert_eq!(
+            validate_input(&input),
+            Err(DecisionError::DuplicateId {
+                kind: "action".to_string(),
+                id: "a1".to_string(),
+            })
+        );
 
-        // Both should have same ranking order
+        let mut input = create_test_input();
+        input.scenarios.push(input.scenarios[2].clone());
         assert_eq!(
-            output1.ranked_actions[0].action_id,
-            output2.ranked_actions[0].action_id
+            validate_input(&input),
+            Err(DecisionError::DuplicateId {
+                kind: "scenario".to_string(),
+                id: "s3".to_string(),
+            })
+        );
+    }
+
+    /// a1 is worst where every action does badly, a2 where a1 has the regret
+    fn objective_input(objective: Option<AdversaryObjective>) -> DecisionInput {
+        let mut input = severity_input(None);
+        for scenario in &mut input.scenarios {
+            scenario.adversary_objective = objective;
+        }
+        input.outcomes = vec![
+            ("a1".to_string(), "x".to_string(), 0.0),
+            ("a1".to_string(), "y".to_string(), 50.0),
+            ("a2".to_string(), "x".to_string(), 5.0),
+            ("a2".to_string(), "y".to_string(), 100.0),
+        ];
+        input
+    }
+
+    #[test]
+    fn test_regret_adversary_changes_adversarial_ranking() {
+        let default = evaluate_decision(&objective_input(None)).unwrap();
+        assert_eq!(default.ranked_actions[0].action_id, "a2");
+        assert_eq!(default.trace.adversarial_table["a1"], 0.0);
+        assert_eq!(default.trace.adversarial_table["a2"], 5.0);
+
+        let explicit =
+            evaluate_decision(&objective_input(Some(AdversaryObjective::MinimizeUtility))).unwrap();
+        assert_eq!(explicit.ranked_actions, default.ranked_actions);
+
+        // a1's worst regret is in y (50 behind a2); a2 has no regret
+        let regret =
+            evaluate_decision(&objective_input(Some(AdversaryObjective::MaximizeRegret))).unwrap();
+        assert_eq!(regret.trace.adversarial_table["a1"], 50.0);
+        assert_eq!(regret.trace.adversarial_table["a2"], 100.0);
+        assert_ne!(
+            regret.determinism_fingerprint,
+            default.determinism_fingerprint
+        );
+        // a2 dominates a1, so no severity lets the regret adversary rank a1
+        // first
+        assert_eq!(regret.ranked_actions[0].action_id, "a2");
+        for severity in [Some(0.5), Some(3.0)] {
+            let mut input = objective_input(Some(AdversaryObjective::MaximizeRegret));
+            input.scenarios[1].severity = severity;
+            let output = evaluate_decision(&input).unwrap();
+            assert_eq!(output.ranked_actions[0].action_id, "a2");
+            assert!(output.trace.adversarial_table["a2"] > output.trace.adversarial_table["a1"]);
+        }
+
+        // Mixed: x minimizes utility, y maximizes regret
+        let mut mixed = objective_input(None);
+        mixed.scenarios[1].adversary_objective = Some(AdversaryObjective::MaximizeRegret);
+        let mixed = evaluate_decision(&mixed).unwrap();
+        assert_eq!(mixed.trace.adversarial_table["a1"], 0.0);
+        assert_eq!(mixed.trace.adversarial_table["a2"], 5.0);
+    }
+
+    /// x minimizes utility; y and z maximize regret, z being bad for everyone
+    fn mixed_objective_input(offset: f64) -> DecisionInput {
+        let mut input = severity_input(None);
+        let mut z = input.scenarios[1].clone();
+        z.id = "z".to_string();
+        input.scenarios.push(z);
+        input.scenarios[1].adversary_objective = Some(AdversaryObjective::MaximizeRegret);
+        input.scenarios[2].adversary_objective = Some(AdversaryObjective::MaximizeRegret);
+        input.outcomes = [
+            ("a1", "x", 60.0),
+            ("a1", "y", 80.0),
+            ("a1", "z", 10.0),
+            ("a2", "x", 50.0),
+            ("a2", "y", 100.0),
+            ("a2", "z", 12.0),
+        ]
+        .iter()
+        .map(|&(action, scenario, utility)| {
+            (action.to_string(), scenario.to_string(), utility + offset)
+        })
+        .collect();
+        input
+    }
+
+    #[test]
+    fn test_regret_adversary_ranking_ignores_utility_scale() {
+        for offset in [0.0, 1000.0] {
+            let mut utility_only = mixed_objective_input(offset);
+            for scenario in &mut utility_only.scenarios {
+                scenario.adversary_objective = None;
+            }
+            let utility_only = evaluate_decision(&utility_only).unwrap();
+            // Both actions do worst in z, a2 slightly less badly
+            assert_eq!(utility_only.ranked_actions[0].action_id, "a2");
+
+            // a1 trails by at most 20 where the regret adversary looks, so x
+            // decides, where a1 is 10 ahead
+            let mixed = evaluate_decision(&mixed_objective_input(offset)).unwrap();
+            assert_eq!(mixed.ranked_actions[0].action_id, "a1");
+            assert!((mixed.trace.adversarial_table["a1"] - (60.0 + offset)).abs() < 1e-9);
+            assert!((mixed.trace.adversarial_table["a2"] - (50.0 + offset)).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_adversary_realizes_a_single_scenario() {
+        let base = evaluate_decision(&severity_input(None)).unwrap();
+
+        // A third adversarial scenario as bad as each action's worst one
+        // leaves the scores where they were instead of compounding them
+        let mut input = severity_input(None);
+        let mut z = input.scenarios[0].clone();
+        z.id = "z".to_string();
+        input.scenarios.push(z);
+        input
+            .outcomes
+            .push(("a1".to_string(), "z".to_string(), -10.0));
+        input
+            .outcomes
+            .push(("a2".to_string(), "z".to_string(), -8.0));
+        let widened = evaluate_decision(&input).unwrap();
+
+        assert_eq!(
+            widened.trace.adversarial_table,
+            base.trace.adversarial_table
+        );
+        assert_eq!(widened.trace.adversarial_table["a1"], -10.0);
+        assert_eq!(widened.trace.adversarial_table["a2"], -8.0);
+    }
+
+    /// Two adversarial scenarios scored by the adversarial criterion alone
+    fn severity_input(severity_y: Option<f64>) -> DecisionInput {
+        let mut input = create_test_input();
+        input.scenarios = ["x", "y"]
+            .iter()
+            .map(|id| Scenario {
+                id: (*id).to_string(),
+                probability: None,
+                adversarial: true,
+                severity: None,
+                adversary_objective: None,
+            })
+            .collect();
+        input.scenarios[1].severity = severity_y;
+        input.outcomes = vec![
+            ("a1".to_string(), "x".to_string(), -10.0),
+            ("a1".to_string(), "y".to_string(), 20.0),
+            ("a2".to_string(), "x".to_string(), 5.0),
+            ("a2".to_string(), "y".to_string(), -8.0),
+        ];
+        input.constraints = Some(DecisionConstraint {
+            weights: Some(CompositeWeights {
+                worst_case: 0.0,
+                minimax_regret: 0.0,
+                adversarial: 1.0,
+                epsilon_contamination: 0.0,
+                adversarial_regret: 0.0,
+                prior: 0.0,
+                target_probability: 0.0,
+            }),
+            ..Default::default()
+        });
+        input
+    }
+
+    #[test]
+    fn test_severity_changes_adversarial_recommendation() {
+        let baseline = evaluate_decision(&severity_input(None)).unwrap();
+        assert_eq!(baseline.ranked_actions[0].action_id, "a2");
+
+        // An explicit 1.0 scores like the default
+        let unit = evaluate_decision(&severity_input(Some(1.0))).unwrap();
+        assert_eq!(unit.ranked_actions, baseline.ranked_actions);
+
+        // Doubling y's severity turns a2's loss there into its worst case
+        let severe = evaluate_decision(&severity_input(Some(2.0))).unwrap();
+        assert_eq!(severe.ranked_actions[0].action_id, "a1");
+        let a2 = severe
+            .ranked_actions
+            .iter()
+            .find(|a| a.action_id == "a2")
+            .unwrap();
+        assert_eq!(a2.score_adversarial, -16.0);
+        assert_ne!(
+            compute_fingerprint(&severity_input(Some(2.0))),
+            compute_fingerprint(&severity_input(None))
+        );
+
+        let mut penalty = severity_input(Some(2.0));
+        penalty.constraints.as_mut().unwrap().severity_discount =
+            Some(SeverityDiscount::Penalty { per_unit: 10.0 });
+        let output = evaluate_decision(&penalty).unwrap();
+        assert_eq!(output.ranked_actions[0].action_id, "a1");
+        let a2 = output
+            .ranked_actions
+            .iter()
+            .find(|a| a.action_id == "a2")
+            .unwrap();
+        assert_eq!(a2.score_adversarial, -18.0);
+    }
+
+    #[test]
+    fn test_validate_input_rejects_bad_severity() {
+        for severity in [-1.0, f64::NAN, f64::INFINITY] {
+            assert!(matches!(
+                validate_input(&severity_input(Some(severity))),
+                Err(DecisionError::InvalidSeverity(_))
+            ));
+        }
+
+        let mut input = severity_input(None);
+        input.constraints.as_mut().unwrap().severity_discount =
+            Some(SeverityDiscount::Penalty { per_unit: -1.0 });
+        assert!(matches!(
+            validate_input(&input),
+            Err(DecisionError::InvalidSeverity(_))
+        ));
+    }
+
+    #[test]
+    fn test_validate_input_missing_outcome() {
+        let mut input = create_test_input();
+        input.outcomes.pop();
+        assert_eq!(
+            validate_input(&input),
+            Err(DecisionError::MissingCell {
+                action: "a2".to_string(),
+                scenario: "s3".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_validate_input_unknown_outcome_ids() {
+        let mut input = create_test_input();
+        input
+            .outcomes
+            .push(("a9".to_string(), "s1".to_string(), 1.0));
+        assert!(matches!(
+            validate_input(&input),
+            Err(DecisionError::InvalidOutcome(_))
+        ));
+
+        let mut input = create_test_input();
+        input.outcomes[0].1 = "s9".to_string();
+        assert!(matches!(
+            validate_input(&input),
+            Err(DecisionError::InvalidOutcome(_))
+        ));
+    }
+
+    #[test]
+    fn test_validate_input_non_finite_utility() {
+        let mut input = create_test_input();
+        input.outcomes[0].2 = f64::NAN;
+        assert!(matches!(
+            validate_input(&input),
+            Err(DecisionError::InvalidOutcome(_))
+        ));
+    }
+
+    #[test]
+    fn test_evaluate_decision_names_non_finite_field() {
+        let mut input = create_test_input();
+        input.outcomes[4].2 = f64::INFINITY;
+        assert_eq!(
+            evaluate_decision(&input).unwrap_err(),
+            DecisionError::InvalidInput(
+                "outcomes[4] ('a2', 's2') must be finite, got inf".to_string()
+            )
+        );
+
+        let mut input = create_test_input();
+        input.scenarios[1].probability = Some(f64::NAN);
+        assert_eq!(
+            evaluate_decision(&input).unwrap_err(),
+            DecisionError::InvalidInput(
+                "scenarios[1].probability must be finite, got NaN".to_string()
+            )
+        );
+
+        let mut input = create_test_input();
+        input.constraints = Some(DecisionConstraint {
+            weights: Some(CompositeWeights {
+                adversarial: f64::NEG_INFINITY,
+                ..CompositeWeights::default()
+            }),
+            ..Default::default()
+        });
+        assert_eq!(
+            input.validate_finite().unwrap_err().to_string(),
+            "Invalid input: constraints.weights.adversarial must be finite, got -inf"
+        );
+    }
+
+    #[test]
+    fn test_validate_input_probabilities() {
+        let mut input = create_test_input();
+        input.scenarios[0].probability = Some(1.5);
+        assert!(matches!(
+            validate_input(&input),
+            Err(DecisionError::InvalidProbability(_))
+        ));
+
+        let mut input = create_test_input();
+        input.scenarios[0].probability = Some(0.1);
+        assert!(matches!(
+            validate_input(&input),
+            Err(DecisionError::InvalidProbability(_))
+        ));
+
+        // Partially specified probabilities skip the sum check
+        let mut input = create_test_input();
+        input.scenarios[0].probability = None;
+        assert_eq!(validate_input(&input), Ok(()));
+    }
+
+    #[test]
+    fn test_strict_mode_rejects_what_lenient_mode_repairs() {
+        let strict = |mut input: DecisionInput| {
+            input.strict = true;
+            input
+        };
+
+        // Off by 5e-10: lenient mode normalizes, strict mode rejects
+        let mut drifted = create_test_input();
+        drifted.scenarios[2].probability = Some(0.2 + 5e-10);
+        assert!(evaluate_decision(&drifted).is_ok());
+        assert!(matches!(
+            evaluate_decision(&strict(drifted)),
+            Err(DecisionError::InvalidProbability(_))
+        ));
+
+        // Partial probabilities: lenient mode weights scenarios equally
+        let mut partial = create_test_input();
+        partial.scenarios[0].probability = None;
+        assert!(evaluate_decision(&partial).is_ok());
+        assert!(matches!(
+            evaluate_decision(&strict(partial)),
+            Err(DecisionError::InvalidProbability(_))
+        ));
+
+        // Holes stay holes, even when lenient mode would fill them
+        let mut holed = create_test_input();
+        holed.outcomes.remove(1);
+        holed.allow_missing_as_zero = true;
+        assert!(evaluate_decision(&holed).is_ok());
+        assert!(matches!(
+            evaluate_decision(&strict(holed)),
+            Err(DecisionError::MissingCell { .. })
+        ));
+
+        // Repeated cells are ambiguous in either mode
+        let mut repeated = create_test_input();
+        repeated
+            .outcomes
+            .push(("a1".to_string(), "s1".to_string(), 0.0));
+        assert!(matches!(
+            evaluate_decision(&repeated),
+            Err(DecisionError::InvalidOutcome(_))
+        ));
+        assert!(matches!(
+            evaluate_decision(&strict(repeated)),
+            Err(DecisionError::InvalidOutcome(_))
+        ));
+
+        // Already valid input scores the same, under its own fingerprint
+        let lenient = evaluate_decision(&create_test_input()).unwrap();
+        let exact = evaluate_decision(&strict(create_test_input())).unwrap();
+        assert_eq!(exact.ranked_actions, lenient.ranked_actions);
+        assert_ne!(
+            exact.determinism_fingerprint,
+            lenient.determinism_fingerprint
+        );
+    }
+
+    #[test]
+    fn test_evaluate_decision_rejects_duplicate_action_ids() {
+        let mut input = create_test_input();
+        input.actions.push(ActionOption {
+            id: "a2".to_string(),
+            label: "Shadowed Action 2".to_string(),
+        });
+
+        assert_eq!(
+            evaluate_decision(&input),
+            Err(DecisionError::DuplicateId {
+                kind: "action".to_string(),
+                id: "a2".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_evaluate_decision_rejects_duplicate_scenario_ids() {
+        let mut input = create_test_input();
+        input.scenarios[2].id = "s1".to_string();
+
+        assert_eq!(
+            evaluate_decision(&input),
+            Err(DecisionError::DuplicateId {
+                kind: "scenario".to_string(),
+                id: "s1".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_evaluate_decision_unique_ids_unchanged() {
+        let output = evaluate_decision(&create_test_input()).unwrap();
+
+        let ids: Vec<&str> = output
+            .ranked_actions
+            .iter()
+            .map(|a| a.action_id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["a2", "a1"]);
+        assert_eq!(output.trace.utility_table.len(), 2);
+        assert_eq!(output.trace.utility_table["a1"].len(), 3);
+    }
+
+    #[test]
+    fn test_evaluate_decision_runs_validation() {
+        let mut input = create_test_input();
+        input.outcomes.pop();
+        assert_eq!(
+            evaluate_decision(&input),
+            Err(DecisionError::MissingCell {
+                action: "a2".to_string(),
+                scenario: "s3".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_missing_cell_reported_unless_allowed() {
+        // Complete matrix, including an explicit 0.0
+        let mut input = create_test_input();
+        input.outcomes[2].2 = 0.0;
+        assert!(evaluate_decision(&input).is_ok());
+
+        // A hole in the middle of the matrix is named
+        input.outcomes.remove(1);
+        assert_eq!(
+            evaluate_decision(&input),
+            Err(DecisionError::MissingCell {
+                action: "a1".to_string(),
+                scenario: "s2".to_string(),
+            })
+        );
+
+        // Opting in scores the hole as 0.0
+        input.allow_missing_as_zero = true;
+        let output = evaluate_decision(&input).unwrap();
+        assert_eq!(output.trace.utility_table["a1"]["s2"], 0.0);
+        assert!(
+            serde_json::to_value(&input).unwrap()["allow_missing_as_zero"]
+                .as_bool()
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_missing_cell_policy_variants() {
+        // Holes at (a1, s2) and (a2, s3)
+        let mut input = create_test_input();
+        input.outcomes.remove(5);
+        input.outcomes.remove(1);
+
+        input.missing_cell_policy = MissingCellPolicy::Error;
+        assert_eq!(
+            evaluate_decision(&input),
+            Err(DecisionError::MissingCell {
+                action: "a1".to_string(),
+                scenario: "s2".to_string(),
+            })
+        );
+
+        input.missing_cell_policy = MissingCellPolicy::FillZero;
+        let zero = evaluate_decision(&input).unwrap();
+        assert_eq!(zero.trace.utility_table["a1"]["s2"], 0.0);
+        assert_eq!(
+            zero.warnings,
+            vec![
+                "missing outcome for 'a1' in 's2' filled with 0",
+                "missing outcome for 'a2' in 's3' filled with 0",
+            ]
+        );
+
+        input.missing_cell_policy = MissingCellPolicy::Fill(-25.0);
+        let floor = evaluate_decision(&input).unwrap();
+        assert_eq!(floor.trace.utility_table["a1"]["s2"], -25.0);
+        assert_eq!(floor.trace.utility_table["a2"]["s3"], -25.0);
+        assert_eq!(floor.trace.utility_table["a1"]["s1"], 100.0);
+        assert_eq!(
+            floor.warnings,
+            vec![
+                "missing outcome for 'a1' in 's2' filled with -25",
+                "missing outcome for 'a2' in 's3' filled with -25",
+            ]
+        );
+
+        // The policy and its fill value are part of the fingerprint
+        input.missing_cell_policy = MissingCellPolicy::Fill(-30.0);
+        let lower = evaluate_decision(&input).unwrap();
+        let fingerprints: BTreeSet<&str> = [&zero, &floor, &lower]
+            .iter()
+            .map(|o| o.determinism_fingerprint.as_str())
+            .collect();
+        assert_eq!(fingerprints.len(), 3);
+
+        // A complete matrix fills nothing, and a fill must be finite
+        let mut complete = create_test_input();
+        complete.missing_cell_policy = MissingCellPolicy::FillZero;
+        assert!(evaluate_decision(&complete).unwrap().warnings.is_empty());
+        input.missing_cell_policy = MissingCellPolicy::Fill(f64::NAN);
+        assert!(matches!(
+            validate_input(&input),
+            Err(DecisionError::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn test_tie_break_deterministic() {
+        // Create input where scores might tie
+        let mut input = create_test_input();
+        // Make utilities identical
+        input.scenarios.truncate(2);
+        input.scenarios[1].probability = Some(0.5);
+        input.outcomes = vec![
+            ("a1".to_string(), "s1".to_string(), 50.0),
+            ("a1".to_string(), "s2".to_string(), 50.0),
+            ("a2".to_string(), "s1".to_string(), 50.0),
+            ("a2".to_string(), "s2".to_string(), 50.0),
+        ];
+
+        let output1 = evaluate_decision(&input).unwrap();
+        let output2 = evaluate_decision(&input).unwrap();
+
+        // Both should have same ranking order
+        assert_eq!(
+            output1.ranked_actions[0].action_id,
+            output2.ranked_actions[0].action_id
         );
 
         // a1 should come before a2 (lexicographic tie-break)
@@ -806,10 +4534,980 @@ mod tests {
         assert_eq!(output1.ranked_actions[1].action_id, "a2");
     }
 
+    /// Eight actions with identical utilities in every scenario
+    fn tied_input(seed: Option<u64>) -> DecisionInput {
+        let mut input = create_test_input();
+        input.scenarios.truncate(2);
+        input.scenarios[1].probability = Some(0.5);
+        input.actions = (1..=8)
+            .map(|i| ActionOption {
+                id: format!("a{i}"),
+                label: format!("Action {i}"),
+            })
+            .collect();
+        input.outcomes = input
+            .actions
+            .iter()
+            .flat_map(|a| {
+                input
+                    .scenarios
+                    .iter()
+                    .map(|s| (a.id.clone(), s.id.clone(), 50.0))
+            })
+            .collect();
+        input.tie_break_seed = seed;
+        input
+    }
+
+    fn ranked_ids(output: &DecisionOutput) -> Vec<&str> {
+        output
+            .ranked_actions
+            .iter()
+            .map(|a| a.action_id.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_seeded_tie_break_reproducible() {
+        let first = evaluate_decision(&tied_input(Some(42))).unwrap();
+        let again = evaluate_decision(&tied_input(Some(42))).unwrap();
+
+        assert_eq!(ranked_ids(&first), ranked_ids(&again));
+        assert_eq!(first.determinism_fingerprint, again.determinism_fingerprint);
+        assert_eq!(first.trace.tie_break_rule, "seeded_hash_by_action_id");
+    }
+
+    #[test]
+    fn test_seeded_tie_break_differs_across_seeds() {
+        let unseeded = evaluate_decision(&tied_input(None)).unwrap();
+        let orders: BTreeSet<Vec<String>> = (0..4)
+            .map(|seed| {
+                let output = evaluate_decision(&tied_input(Some(seed))).unwrap();
+                assert_ne!(
+                    output.determinism_fingerprint,
+                    unseeded.determinism_fingerprint
+                );
+                ranked_ids(&output).into_iter().map(String::from).collect()
+            })
+            .collect();
+
+        assert!(orders.len() > 1);
+    }
+
+    #[test]
+    fn test_unseeded_tie_break_is_lexicographic() {
+        let output = evaluate_decision(&tied_input(None)).unwrap();
+        assert_eq!(
+            ranked_ids(&output),
+            vec!["a1", "a2", "a3", "a4", "a5", "a6", "a7", "a8"]
+        );
+        assert_eq!(output.trace.tie_break_rule, "lexicographic_by_action_id");
+
+        // An explicit null seed is the same input as an omitted one
+        let mut json = serde_json::to_value(tied_input(None)).unwrap();
+        assert!(json.get("tie_break_seed").is_none());
+        json["tie_break_seed"] = serde_json::Value::Null;
+        let parsed: DecisionInput = serde_json::from_value(json).unwrap();
+        let reparsed = evaluate_decision(&parsed).unwrap();
+        assert_eq!(
+            reparsed.determinism_fingerprint,
+            output.determinism_fingerprint
+        );
+    }
+
+    #[test]
+    fn test_tied_actions_list_each_other() {
+        // Three identical rows (listed out of ID order) and one clear loser
+        let mut input = tied_input(None);
+        input.actions.truncate(4);
+        input.actions.swap(0, 2);
+        input
+            .outcomes
+            .retain(|(a, _, _)| a != "a5" && a != "a6" && a != "a7" && a != "a8");
+        for outcome in &mut input.outcomes {
+            if outcome.0 == "a4" {
+                outcome.2 = 10.0;
+            }
+        }
+
+        let output = evaluate_decision(&input).unwrap();
+        let tied: Vec<(&str, Vec<&str>)> = output
+            .ranked_actions
+            .iter()
+            .map(|a| {
+                (
+                    a.action_id.as_str(),
+                    a.tied_with.iter().map(String::as_str).collect(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            tied,
+            vec![
+                ("a1", vec!["a2", "a3"]),
+                ("a2", vec!["a1", "a3"]),
+                ("a3", vec!["a1", "a2"]),
+                ("a4", vec![]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_distinct_scores_have_no_ties() {
+        let output = evaluate_decision(&create_test_input()).unwrap();
+        assert!(output.ranked_actions.iter().all(|a| a.tied_with.is_empty()));
+        // An empty tie list is not serialized
+        let json = String::from_utf8(output.to_canonical_json()).unwrap();
+        assert!(!json.contains("tied_with"));
+    }
+
+    #[test]
+    fn test_identical_outputs_have_empty_diff() {
+        let output = evaluate_decision(&create_test_input()).unwrap();
+        let diff = output.diff(&output.clone());
+        assert!(diff.is_empty());
+        assert_eq!(diff.recommendation, None);
+    }
+
+    #[test]
+    fn test_diff_names_old_and_new_winner() {
+        use crate::types::{ActionDelta, RecommendationChange};
+
+        let input = create_test_input();
+        let before = evaluate_decision(&input).unwrap();
+        let after = evaluate_decision(
+            &OutcomeChange {
+                action_id: "a1".to_string(),
+                scenario_id: "s2".to_string(),
+                utility: 100.0,
+            }
+            .apply(&input),
+        )
+        .unwrap();
+
+        let diff = before.diff(&after);
+        assert_eq!(
+            diff.recommendation,
+            Some(RecommendationChange {
+                from: Some("a2".to_string()),
+                to: Some("a1".to_string()),
+            })
+        );
+        let moves: Vec<(&str, Option<usize>, Option<usize>)> = diff
+            .actions
+            .iter()
+            .map(|d: &ActionDelta| (d.action_id.as_str(), d.rank_before, d.rank_after))
+            .collect();
+        assert_eq!(
+            moves,
+            vec![("a1", Some(2), Some(1)), ("a2", Some(1), Some(2))]
+        );
+        assert!(diff.actions[0].composite_score_delta > 0.0);
+        assert!(diff.changed_tables.contains(&"utility_table".to_string()));
+        assert!(diff.changed_tables.contains(&"regret_table".to_string()));
+        assert!(!diff.changed_tables.contains(&"prior_table".to_string()));
+    }
+
+    #[test]
+    fn test_decision_capsule_roundtrip_verifies() {
+        use crate::types::DecisionCapsule;
+
+        let capsule = DecisionCapsule::new(create_test_input()).unwrap();
+        assert_eq!(capsule.engine_version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(
+            capsule.algorithm_set,
+            vec!["worst_case", "minimax_regret", "adversarial"]
+        );
+        assert_eq!(capsule.verify(), Ok(()));
+
+        let archived = DecisionCapsule::from_canonical_json(&capsule.to_canonical_json()).unwrap();
+        assert_eq!(archived, capsule);
+        assert_eq!(archived.to_canonical_json(), capsule.to_canonical_json());
+        assert_eq!(archived.verify(), Ok(()));
+
+        let lexicographic = DecisionCapsule::new(ranked_input(RankingMode::Lexicographic(vec![
+            Criterion::EpsilonContamination,
+        ])))
+        .unwrap();
+        assert_eq!(
+            lexicographic.algorithm_set,
+            vec![
+                "worst_case",
+                "minimax_regret",
+                "adversarial",
+                "lexicographic:epsilon_contamination"
+            ]
+        );
+        assert_eq!(lexicographic.verify(), Ok(()));
+    }
+
+    #[test]
+    fn test_tampered_decision_capsule_fails_verification() {
+        use crate::types::{CapsuleError, DecisionCapsule};
+
+        let capsule = DecisionCapsule::new(create_test_input()).unwrap();
+
+        let mut tampered = capsule.clone();
+        tampered.output.ranked_actions[1].composite_score += 1.0;
+        assert_eq!(
+            tampered.verify(),
+            Err(CapsuleError::OutputMismatch {
+                path: "/ranked_actions/1/composite_score".to_string()
+            })
+        );
+
+        // Drift far below the canonical 1e-9 precision is still caught
+        let mut drifted = capsule.clone();
+        let score = &mut drifted.output.ranked_actions[0].composite_score;
+        *score = f64::from_bits(score.to_bits() + 1);
+        assert_eq!(
+            drifted.verify(),
+            Err(CapsuleError::OutputMismatch {
+                path: "/ranked_actions/0/composite_score".to_string()
+            })
+        );
+
+        let mut relabeled = capsule.clone();
+        relabeled
+            .algorithm_set
+            .push("lexicographic:worst_case".to_string());
+        assert!(matches!(
+            relabeled.verify(),
+            Err(CapsuleError::AlgorithmSetMismatch { .. })
+        ));
+
+        let mut forged = capsule.clone();
+        forged.output.determinism_fingerprint = "0".repeat(64);
+        assert!(matches!(
+            forged.verify(),
+            Err(CapsuleError::FingerprintMismatch { ref actual, .. }) if *actual == "0".repeat(64)
+        ));
+
+        let mut stale = capsule;
+        stale.engine_version = "0.0.0".to_string();
+        assert!(matches!(
+            stale.verify(),
+            Err(CapsuleError::EngineVersionMismatch { .. })
+        ));
+    }
+
+    /// One action per utility, each certain in a single scenario
+    #[test]
+    fn test_monte_carlo_robustness_is_reproducible() {
+        let input = create_test_input();
+        let output = evaluate_decision(&input).unwrap();
+
+        let report = monte_carlo_robustness(&input, &output, 500, 42, 0.9).unwrap();
+        assert_eq!(
+            report,
+            monte_carlo_robustness(&input, &output, 500, 42, 0.9).unwrap()
+        );
+        assert_eq!(
+            report.recommended_action,
+            output.recommended_action_id().unwrap()
+        );
+        assert_eq!((report.seed, report.samples, report.band), (42, 500, 0.9));
+        assert_eq!(
+            report.holds + report.challengers.values().sum::<usize>(),
+            500
+        );
+        // Golden counts for these seeds; a change means the draws changed
+        assert_eq!(report.holds, 60);
+        assert_eq!(
+            report.challengers,
+            BTreeMap::from([("a1".to_string(), 440)])
+        );
+        let other_seed = monte_carlo_robustness(&input, &output, 500, 7, 0.9).unwrap();
+        assert_eq!(other_seed.holds, 39);
+        assert_eq!(
+            other_seed.challengers,
+            BTreeMap::from([("a1".to_string(), 461)])
+        );
+
+        // Without perturbation every sample agrees with the unperturbed
+        // expected utilities, where a1 (81) beats the recommended a2 (77)
+        let fixed = monte_carlo_robustness(&input, &output, 10, 42, 0.0).unwrap();
+        assert_eq!(fixed.holds, 0);
+        assert_eq!(fixed.challengers, BTreeMap::from([("a1".to_string(), 10)]));
+
+        assert!(matches!(
+            monte_carlo_robustness(&input, &output, 0, 42, 0.5),
+            Err(DecisionError::InvalidInput(_))
+        ));
+        assert!(matches!(
+            monte_carlo_robustness(&input, &output, 10, 42, f64::NAN),
+            Err(DecisionError::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn test_incremental_matches_full_evaluation() {
+        // A third action in a portfolio, a regret adversary, evidence,
+        // contamination and a target exercise every table the change can reach
+        let mut rich = create_test_input();
+        rich.actions.push(ActionOption {
+            id: "a3".to_string(),
+            label: "Action 3".to_string(),
+        });
+        rich.outcomes.extend([
+            ("a3".to_string(), "s1".to_string(), 70.0),
+            ("a3".to_string(), "s2".to_string(), 75.0),
+            ("a3".to_string(), "s3".to_string(), 40.0),
+        ]);
+        rich.scenarios[1].adversary_objective = Some(AdversaryObjective::MaximizeRegret);
+        rich.scenarios[2].adversarial = true;
+        rich.scenarios[2].severity = Some(0.5);
+        rich.portfolios = Some(vec![vec!["a1".to_string(), "a3".to_string()]]);
+        rich.target = Some(70.0);
+        rich.evidence = Some(DecisionEvidence {
+            scenario_likelihoods: BTreeMap::from([("s3".to_string(), 0.4)]),
+            ..Default::default()
+        });
+        rich.constraints = Some(DecisionConstraint {
+            epsilon: Some(0.2),
+            ..Default::default()
+        });
+        let mut plain = create_test_input();
+        plain.scenarios[1].adversarial = false;
+
+        let mut rng = SplitMix64::new(1106);
+        for start in [rich, plain] {
+            let mut input = start;
+            let mut output = evaluate_decision(&input).unwrap();
+            for _ in 0..100 {
+                let action = &input.actions[(rng.next_u64() % 3) as usize % input.actions.len()];
+                let scenario = &input.scenarios[(rng.next_u64() % 3) as usize];
+                // Coarse utilities make ties with the scenario's best common
+                let utility = (rng.next_f64() * 20.0).floor() * 10.0 - 50.0;
+                let change = OutcomeChange {
+                    action_id: action.id.clone(),
+                    scenario_id: scenario.id.clone(),
+                    utility,
+                };
+
+                let (incremental, evaluations) =
+                    count_evaluations(|| evaluate_incremental(&output, &input, &change).unwrap());
+                assert_eq!(evaluations, 0);
+                input = change.apply(&input);
+                output = evaluate_decision(&input).unwrap();
+                assert_eq!(incremental, output, "after {change:?}");
+                assert_eq!(incremental.to_canonical_json(), output.to_canonical_json());
+            }
+        }
+    }
+
+    #[test]
+    fn test_incremental_rejects_unknown_cells() {
+        let input = create_test_input();
+        let output = evaluate_decision(&input).unwrap();
+        let change = |action: &str, scenario: &str, utility: f64| OutcomeChange {
+            action_id: action.to_string(),
+            scenario_id: scenario.to_string(),
+            utility,
+        };
+
+        for bad in [
+            change("a9", "s1", 1.0),
+            change("a1", "s9", 1.0),
+            change("a1", "s1", f64::NAN),
+        ] {
+            assert!(matches!(
+                evaluate_incremental(&output, &input, &bad),
+                Err(DecisionError::InvalidOutcome(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_streaming_matches_full_evaluation() {
+        let mut rng = SplitMix64::new(1130);
+        let mut input = create_test_input();
+        input.id = None;
+        input.actions = (0..6)
+            .map(|i| ActionOption {
+                id: format!("a{i}"),
+                label: format!("Action {i}"),
+            })
+            .collect();
+        input.scenarios = (0..60)
+            .map(|j| Scenario {
+                id: format!("s{j}"),
+                probability: None,
+                adversarial: j % 7 == 0,
+                severity: None,
+                adversary_objective: (j == 14).then_some(AdversaryObjective::MaximizeRegret),
+            })
+            .collect();
+        input.outcomes = Vec::new();
+        for action in &input.actions {
+            for scenario in &input.scenarios {
+                let utility = (rng.next_f64() * 40.0).floor() * 2.5;
+                input
+                    .outcomes
+                    .push((action.id.clone(), scenario.id.clone(), utility));
+            }
+        }
+        // Out of order
+        input.outcomes.reverse();
+
+        let full = evaluate_decision(&input).unwrap();
+        for include_regret_table in [false, true] {
+            let streamed = evaluate_decision_streaming(
+                &input.actions,
+                &input.scenarios,
+                input.outcomes.iter().cloned(),
+                include_regret_table,
+            )
+            .unwrap();
+            assert_eq!(streamed.ranked_actions, full.ranked_actions);
+            assert_eq!(streamed.robustness, full.robustness);
+            assert_eq!(
+                streamed.determinism_fingerprint,
+                full.determinism_fingerprint
+            );
+            assert_eq!(streamed.trace.max_regret_table, full.trace.max_regret_table);
+            assert_eq!(
+                streamed.trace.adversarial_table,
+                full.trace.adversarial_table
+            );
+            assert_eq!(
+                streamed.trace.epsilon_contamination_table,
+                full.trace.epsilon_contamination_table
+            );
+            assert!(streamed.trace.utility_table.is_empty());
+            if include_regret_table {
+                assert_eq!(streamed.trace.regret_table, full.trace.regret_table);
+            } else {
+                assert!(streamed.trace.regret_table.is_empty());
+            }
+        }
+    }
+
+    #[test]
+    fn test_streaming_rejects_bad_outcomes() {
+        let input = create_test_input();
+        let stream = |outcomes: Vec<(String, String, f64)>| {
+            evaluate_decision_streaming(&input.actions, &input.scenarios, outcomes, false)
+        };
+
+        let mut missing = input.outcomes.clone();
+        missing.remove(4);
+        assert_eq!(
+            stream(missing),
+            Err(DecisionError::MissingCell {
+                action: "a2".to_string(),
+                scenario: "s2".to_string(),
+            })
+        );
+        let mut unknown = input.outcomes.clone();
+        unknown[0].0 = "a9".to_string();
+        assert!(matches!(
+            stream(unknown),
+            Err(DecisionError::InvalidOutcome(_))
+        ));
+        let mut repeated = input.outcomes.clone();
+        repeated.push(repeated[0].clone());
+        assert!(matches!(
+            stream(repeated),
+            Err(DecisionError::InvalidOutcome(_))
+        ));
+        let mut infinite = input.outcomes.clone();
+        infinite[2].2 = f64::INFINITY;
+        assert!(matches!(
+            stream(infinite),
+            Err(DecisionError::InvalidInput(_))
+        ));
+        assert_eq!(stream(Vec::new()), Err(DecisionError::NoOutcomes));
+    }
+
+    fn utility_input(utilities: &[f64]) -> DecisionInput {
+        let mut input = create_test_input();
+        input.scenarios.truncate(1);
+        input.scenarios[0].probability = Some(1.0);
+        input.actions = (1..=utilities.len())
+            .map(|i| ActionOption {
+                id: format!("a{i}"),
+                label: format!("Action {i}"),
+            })
+            .collect();
+        input.outcomes = utilities
+            .iter()
+            .enumerate()
+            .map(|(i, &u)| (format!("a{}", i + 1), "s1".to_string(), u))
+            .collect();
+        input
+    }
+
+    #[test]
+    fn test_coarse_precision_merges_near_ties() {
+        let fine = utility_input(&[100.0, 99.999, 0.0]);
+        let coarse = DecisionInput {
+            precision: Some(0.01),
+            ..fine.clone()
+        };
+
+        let separated = evaluate_decision(&fine).unwrap();
+        assert!(
+            separated.ranked_actions[0].composite_score
+                > separated.ranked_actions[1].composite_score
+        );
+
+        let merged = evaluate_decision(&coarse).unwrap();
+        assert_eq!(merged.trace.utility_table["a2"]["s1"], 100.0);
+        assert_eq!(
+            merged.ranked_actions[0].composite_score,
+            merged.ranked_actions[1].composite_score
+        );
+        assert_eq!(merged.robustness, 0.0);
+        assert!(merged.verify(&coarse));
+        assert!(merged.verify_self_consistent());
+
+        for precision in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+            let invalid = DecisionInput {
+                precision: Some(precision),
+                ..fine.clone()
+            };
+            assert!(matches!(
+                validate_input(&invalid),
+                Err(DecisionError::InvalidInput(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_default_precision_is_unchanged() {
+        let input = create_test_input();
+        let explicit = DecisionInput {
+            precision: Some(FLOAT_PRECISION),
+            ..input.clone()
+        };
+
+        // Omitted and explicit default precision give today's bytes
+        let output = evaluate_decision(&input).unwrap();
+        assert_eq!(output.determinism_fingerprint, compute_fingerprint(&input));
+        assert!(!serde_json::to_string(&output)
+            .unwrap()
+            .contains("precision"));
+        assert_eq!(
+            evaluate_decision(&explicit).unwrap().to_canonical_json(),
+            output.to_canonical_json()
+        );
+
+        // Any other precision changes the fingerprint, even one canonical
+        // JSON would round away
+        let fingerprints: BTreeSet<String> = [None, Some(1e-10), Some(1e-11), Some(0.01)]
+            .into_iter()
+            .map(|precision| {
+                evaluate_decision(&DecisionInput {
+                    precision,
+                    ..input.clone()
+                })
+                .unwrap()
+                .determinism_fingerprint
+            })
+            .collect();
+        assert_eq!(fingerprints.len(), 4);
+    }
+
+    #[test]
+    fn test_robustness_landslide_and_near_tie() {
+        let landslide = evaluate_decision(&utility_input(&[100.0, 1.0, 0.0])).unwrap();
+        assert!(landslide.robustness > 0.95, "{}", landslide.robustness);
+
+        let near_tie = evaluate_decision(&utility_input(&[100.0, 99.0, 0.0])).unwrap();
+        assert!(near_tie.robustness < 0.05, "{}", near_tie.robustness);
+
+        let tie = evaluate_decision(&utility_input(&[50.0, 50.0])).unwrap();
+        assert!(tie.robustness.abs() < 1e-12);
+
+        let single = evaluate_decision(&utility_input(&[50.0])).unwrap();
+        assert!((single.robustness - 1.0).abs() < 1e-12);
+
+        // Derived from scores only: stable and outside the input fingerprint
+        let again = evaluate_decision(&utility_input(&[100.0, 99.0, 0.0])).unwrap();
+        assert_eq!(near_tie.robustness.to_bits(), again.robustness.to_bits());
+        assert_eq!(
+            near_tie.determinism_fingerprint,
+            compute_fingerprint(&utility_input(&[100.0, 99.0, 0.0]))
+        );
+    }
+
+    fn prior_input(utilities: [f64; 2], priors: [f64; 2], prior_weight: f64) -> DecisionInput {
+        let mut input = create_test_input();
+        input.scenarios.truncate(2);
+        input.scenarios[1].probability = Some(0.5);
+        input.outcomes = vec![
+            ("a1".to_string(), "s1".to_string(), utilities[0]),
+            ("a1".to_string(), "s2".to_string(), utilities[0]),
+            ("a2".to_string(), "s1".to_string(), utilities[1]),
+            ("a2".to_string(), "s2".to_string(), utilities[1]),
+        ];
+        input.action_priors = Some(BTreeMap::from([
+            ("a1".to_string(), priors[0]),
+            ("a2".to_string(), priors[1]),
+        ]));
+        input.constraints = Some(DecisionConstraint {
+            weights: Some(CompositeWeights {
+                adversarial: 0.2 - prior_weight,
+                prior: prior_weight,
+                ..CompositeWeights::default()
+            }),
+            ..Default::default()
+        });
+        input
+    }
+
+    #[test]
+    fn test_action_prior_breaks_tie() {
+        // Identical utilities: without the prior a1 wins on id order
+        let output = evaluate_decision(&prior_input([50.0, 50.0], [1.0, 3.0], 0.1)).unwrap();
+        assert_eq!(output.recommended_action_id(), Some("a2"));
+
+        let unweighted = evaluate_decision(&prior_input([50.0, 50.0], [1.0, 3.0], 0.0)).unwrap();
+        assert_eq!(unweighted.recommended_action_id(), Some("a1"));
+    }
+
+    #[test]
+    fn test_action_prior_does_not_override_clear_winner() {
+        let output = evaluate_decision(&prior_input([100.0, 10.0], [1.0, 9.0], 0.1)).unwrap();
+        assert_eq!(output.recommended_action_id(), Some("a1"));
+    }
+
+    #[test]
+    fn test_zero_prior_weight_leaves_output_unchanged() {
+        let mut without = prior_input([80.0, 60.0], [1.0, 3.0], 0.0);
+        without.action_priors = None;
+        let with = prior_input([80.0, 60.0], [1.0, 3.0], 0.0);
+
+        let without = evaluate_decision(&without).unwrap();
+        let with = evaluate_decision(&with).unwrap();
+        assert_eq!(without.ranked_actions, with.ranked_actions);
+        assert_eq!(without.trace, with.trace);
+        // Priors are part of the input, so they change the fingerprint
+        assert_ne!(
+            without.determinism_fingerprint,
+            with.determinism_fingerprint
+        );
+        // A zero prior weight is not serialized
+        let json = String::from_utf8(without.to_canonical_json()).unwrap();
+        assert!(!json.contains("prior"));
+    }
+
+    #[test]
+    fn test_validate_input_action_priors() {
+        let mut input = prior_input([50.0, 50.0], [-1.0, 1.0], 0.1);
+        assert!(matches!(
+            validate_input(&input),
+            Err(DecisionError::InvalidPrior(_))
+        ));
+
+        input.action_priors = Some(BTreeMap::from([("a1".to_string(), f64::NAN)]));
+        assert!(matches!(
+            validate_input(&input),
+            Err(DecisionError::InvalidPrior(_))
+        ));
+
+        input.action_priors = Some(BTreeMap::from([("a9".to_string(), 1.0)]));
+        assert!(matches!(
+            validate_input(&input),
+            Err(DecisionError::InvalidPrior(_))
+        ));
+
+        input.action_priors = Some(BTreeMap::from([("a1".to_string(), 0.0)]));
+        assert!(matches!(
+            validate_input(&input),
+            Err(DecisionError::InvalidPrior(_))
+        ));
+
+        input.action_priors = Some(BTreeMap::from([("a1".to_string(), 2.0)]));
+        assert!(validate_input(&input).is_ok());
+    }
+
+    #[test]
+    fn test_stochastic_frontier_excludes_distributionally_dominated_action() {
+        // a2 beats a1 in s2, so neither dominates state by state, but a1
+        // reaches every utility level at least as often (50/80/100 with
+        // 0.3/0.2/0.5 against 50/70/100 with 0.5/0.2/0.3)
+        let mut input = create_test_input();
+        input.outcomes = vec![
+            ("a1".to_string(), "s1".to_string(), 100.0),
+            ("a1".to_string(), "s2".to_string(), 50.0),
+            ("a1".to_string(), "s3".to_string(), 80.0),
+            ("a2".to_string(), "s1".to_string(), 50.0),
+            ("a2".to_string(), "s2".to_string(), 100.0),
+            ("a2".to_string(), "s3".to_string(), 70.0),
+        ];
+        assert_eq!(stochastic_dominance_frontier(&input).unwrap(), vec!["a1"]);
+        let output = evaluate_decision(&input).unwrap();
+        assert_eq!(output.trace.stochastic_frontier, vec!["a1"]);
+
+        // Evidence for s2 moves a2's 100 to the likely outcome, and the
+        // frontier follows the posterior
+        let mut informed = input.clone();
+        informed.evidence = Some(DecisionEvidence {
+            scenario_likelihoods: BTreeMap::from([("s2".to_string(), 10.0)]),
+            ..Default::default()
+        });
+        assert_eq!(
+            stochastic_dominance_frontier(&informed).unwrap(),
+            vec!["a2"]
+        );
+        let output = evaluate_decision(&informed).unwrap();
+        assert_eq!(output.trace.stochastic_frontier, vec!["a2"]);
+
+        // Crossing CDFs dominate neither way: a1 risks 50, a2 tops out at 90
+        let crossing = create_test_input();
+        assert_eq!(
+            stochastic_dominance_frontier(&crossing).unwrap(),
+            vec!["a1", "a2"]
+        );
+
+        // Without stated probabilities the trace leaves it out
+        input.scenarios[0].probability = None;
+        let output = evaluate_decision(&input).unwrap();
+        assert!(output.trace.stochastic_frontier.is_empty());
+    }
+
+    fn regret_capped_input(regret_cap: f64) -> DecisionInput {
+        // a1 = 100/55/80 and a2 = 90/60/70: a2 has the better worst case
+        // (60 against 55) but the larger maximum regret (10 against 5)
+        let mut input = create_test_input();
+        input.outcomes[1].2 = 55.0;
+        input.constraints = Some(DecisionConstraint {
+            max_regret: Some(regret_cap),
+            ..Default::default()
+        });
+        input
+    }
+
+    #[test]
+    fn test_constrained_maximin_tightening_cap_changes_winner() {
+        let output = evaluate_decision(&regret_capped_input(10.0)).unwrap();
+        let constrained = output.trace.constrained_maximin.unwrap();
+        assert_eq!(constrained.feasible, vec!["a1", "a2"]);
+        assert_eq!(constrained.chosen, "a2");
+
+        let output = evaluate_decision(&regret_capped_input(5.0)).unwrap();
+        let constrained = output.trace.constrained_maximin.unwrap();
+        assert_eq!(constrained.regret_cap, 5.0);
+        assert_eq!(constrained.feasible, vec!["a1"]);
+        assert_eq!(constrained.chosen, "a1");
+
+        // The cap is part of the input, so it changes the fingerprint
+        assert_ne!(
+            decision_fingerprint(&regret_capped_input(10.0)),
+            decision_fingerprint(&regret_capped_input(5.0))
+        );
+        // Without a cap the trace leaves it out
+        assert!(evaluate_decision(&create_test_input())
+            .unwrap()
+            .trace
+            .constrained_maximin
+            .is_none());
+    }
+
+    #[test]
+    fn test_constrained_maximin_impossible_cap_is_infeasible() {
+        let err = evaluate_decision(&regret_capped_input(1.0)).unwrap_err();
+        assert_eq!(err, DecisionError::NoFeasibleActions { regret_cap: 1.0 });
+        assert_eq!(err.code(), "E_NO_FEASIBLE_ACTIONS");
+
+        let err = evaluate_decision(&regret_capped_input(-1.0)).unwrap_err();
+        assert_eq!(err.code(), "E_INVALID_INPUT");
+    }
+
+    fn ranked_input(mode: RankingMode) -> DecisionInput {
+        DecisionInput {
+            ranking_mode: Some(mode),
+            ..create_test_input()
+        }
+    }
+
+    #[test]
+    fn test_lexicographic_ranking_disagrees_with_composite() {
+        use Criterion::*;
+        // a1 = 100/50/80 and a2 = 90/60/70 both have max regret 10; a2 has
+        // the better worst case (60 against 50), a1 the better expected
+        // value (81 against 77)
+        let composite = evaluate_decision(&create_test_input()).unwrap();
+        assert_eq!(composite.recommended_action_id(), Some("a2"));
+        let explicit = evaluate_decision(&ranked_input(RankingMode::Composite(
+            CompositeWeights::default(),
+        )))
+        .unwrap();
+        assert_eq!(explicit.ranked_actions, composite.ranked_actions);
+
+        let lexicographic = evaluate_decision(&ranked_input(RankingMode::Lexicographic(vec![
+            EpsilonContamination,
+        ])))
+        .unwrap();
+        assert_eq!(lexicographic.recommended_action_id(), Some("a1"));
+        assert_eq!(
+            lexicographic.trace.ranking_criteria,
+            vec![EpsilonContamination]
+        );
+        assert!(lexicographic.verify_self_consistent());
+        assert_ne!(
+            lexicographic.determinism_fingerprint,
+            composite.determinism_fingerprint
+        );
+    }
+
+    #[test]
+    fn test_lexicographic_ranking_follows_criterion_order() {
+        use Criterion::*;
+        let winner = |criteria: Vec<Criterion>| {
+            let output =
+                evaluate_decision(&ranked_input(RankingMode::Lexicographic(criteria))).unwrap();
+            assert!(output.verify_self_consistent());
+            output.recommended_action_id().unwrap().to_string()
+        };
+        assert_eq!(winner(vec![WorstCase, EpsilonContamination]), "a2");
+        assert_eq!(winner(vec![EpsilonContamination, WorstCase]), "a1");
+        // Equal max regrets fall through to the next criterion
+        assert_eq!(winner(vec![MinimaxRegret, WorstCase]), "a2");
+        assert_eq!(winner(vec![MinimaxRegret, EpsilonContamination]), "a1");
+
+        // Tied on every criterion: the tie-break rule decides, and the tie
+        // is recorded
+        let output = evaluate_decision(&ranked_input(RankingMode::Lexicographic(vec![
+            MinimaxRegret,
+        ])))
+        .unwrap();
+        assert_eq!(output.recommended_action_id(), Some("a1"));
+        assert_eq!(output.ranked_actions[0].tied_with, vec!["a2"]);
+
+        let err =
+            evaluate_decision(&ranked_input(RankingMode::Lexicographic(Vec::new()))).unwrap_err();
+        assert_eq!(err.code(), "E_INVALID_INPUT");
+    }
+
+    #[test]
+    fn test_explain_action_runner_up_shortfall() {
+        let output = evaluate_decision(&create_test_input()).unwrap();
+        let top = explain_action(&output, "a2").unwrap();
+        assert_eq!((top.rank, top.composite_gap, top.shortfall), (1, 0.0, None));
+
+        // a1 trails by 6 composite points, 4 of them from its worst case
+        // (50 against 60, at weight 0.4)
+        let runner_up = explain_action(&output, "a1").unwrap();
+        assert_eq!(runner_up.rank, 2);
+        assert_eq!(runner_up.top_action, "a2");
+        assert_eq!(runner_up.composite_gap, 6.0);
+        assert_eq!(
+            runner_up.shortfall,
+            Some(CriterionShortfall {
+                criterion: "worst_case".to_string(),
+                margin: 10.0,
+                weighted_margin: 4.0,
+            })
+        );
+        assert_eq!(runner_up.scores["minimax_regret"], 10.0);
+        assert!(runner_up.dominated_by.is_empty());
+        assert!(!runner_up.infeasible);
+
+        assert!(explain_action(&output, "a9").is_none());
+    }
+
+    #[test]
+    fn test_explain_action_reports_dominance() {
+        let mut input = create_test_input();
+        input.actions.push(ActionOption {
+            id: "a3".to_string(),
+            label: "Dominated".to_string(),
+        });
+        for (scenario, utility) in [("s1", 80.0), ("s2", 60.0), ("s3", 60.0)] {
+            input
+                .outcomes
+                .push(("a3".to_string(), scenario.to_string(), utility));
+        }
+        let output = evaluate_decision(&input).unwrap();
+        // a3 = 80/60/60 is nowhere better than a2 = 90/60/70
+        let dominated = explain_action(&output, "a3").unwrap();
+        assert_eq!(dominated.dominated_by, vec!["a2"]);
+        assert_eq!(dominated.shortfall.unwrap().criterion, "minimax_regret");
+        assert!(explain_action(&output, "a1")
+            .unwrap()
+            .dominated_by
+            .is_empty());
+    }
+
+    fn target_input(target: Option<f64>, weight: f64) -> DecisionInput {
+        let mut input = create_test_input();
+        input.outcomes = vec![
+            ("a1".to_string(), "s1".to_string(), 200.0),
+            ("a1".to_string(), "s2".to_string(), 50.0),
+            ("a1".to_string(), "s3".to_string(), 50.0),
+            ("a2".to_string(), "s1".to_string(), 70.0),
+            ("a2".to_string(), "s2".to_string(), 70.0),
+            ("a2".to_string(), "s3".to_string(), 60.0),
+        ];
+        input.target = target;
+        input.constraints = Some(DecisionConstraint {
+            weights: Some(CompositeWeights {
+                worst_case: 0.0,
+                minimax_regret: 0.0,
+                adversarial: 0.0,
+                epsilon_contamination: 1.0 - weight,
+                target_probability: weight,
+                ..CompositeWeights::default()
+            }),
+            ..Default::default()
+        });
+        input
+    }
+
+    #[test]
+    fn test_target_probability_differs_from_expected_value_winner() {
+        // a1 has the higher expected value (125 vs 68) but reaches 65 only
+        // in s1 (0.5); a2 misses it only in s3 (0.8)
+        let expected = evaluate_decision(&target_input(Some(65.0), 0.0)).unwrap();
+        assert_eq!(expected.recommended_action_id(), Some("a1"));
+
+        let output = evaluate_decision(&target_input(Some(65.0), 1.0)).unwrap();
+        assert_eq!(output.recommended_action_id(), Some("a2"));
+        assert_eq!(
+            output.trace.target_probability_table,
+            BTreeMap::from([("a1".to_string(), 0.5), ("a2".to_string(), 0.8)])
+        );
+        // Both the target and its weight are part of the fingerprint
+        let moved = evaluate_decision(&target_input(Some(55.0), 1.0)).unwrap();
+        assert_ne!(
+            output.determinism_fingerprint,
+            expected.determinism_fingerprint
+        );
+        assert_ne!(
+            output.determinism_fingerprint,
+            moved.determinism_fingerprint
+        );
+        assert!(output.verify_self_consistent());
+    }
+
+    #[test]
+    fn test_validate_input_target() {
+        let input = target_input(Some(f64::INFINITY), 0.5);
+        assert!(matches!(
+            validate_input(&input),
+            Err(DecisionError::InvalidInput(_))
+        ));
+
+        let input = target_input(None, 0.5);
+        assert!(matches!(
+            validate_input(&input),
+            Err(DecisionError::InvalidInput(_))
+        ));
+
+        // A target without a weight is only traced
+        let output = evaluate_decision(&target_input(Some(65.0), 0.0)).unwrap();
+        assert_eq!(output.trace.target_probability_table.len(), 2);
+    }
+
     #[test]
     fn test_float_normalization_in_scores() {
         // Input with floating-point noise
         let mut input = create_test_input();
+        input.scenarios.truncate(2);
+        input.scenarios[1].probability = Some(0.5);
         input.outcomes = vec![
             (
                 "a1".to_string(),
@@ -11,6 +11,7 @@
 //! - **Composite Scoring**: Weighted combination of all metrics
 //! - **Deterministic Outputs**: Byte-stable JSON with SHA-256 fingerprints
 //! - **WASM Support**: Full WebAssembly bindings for JavaScript/TypeScript
+//! - **JSON Schema**: Draft 2020-12 schemas for the input and output types
 //!
 //! ## Quick Start
 //!
@@ -24,8 +25,20 @@
 //!         ActionOption { id: "a2".to_string(), label: "Action 2".to_string() },
 //!     ],
 //!     scenarios: vec![
-//!         Scenario { id: "s1".to_string(), probability: Some(0.5), adversarial: false },
-//!         Scenario { id: "s2".to_string(), probability: Some(0.5), adversarial: true },
+//!         Scenario {
+//!             id: "s1".to_string(),
+//!             probability: Some(0.5),
+//!             adversarial: false,
+//!             severity: None,
+//!             adversary_objective: None,
+//!         },
+//!         Scenario {
+//!             id: "s2".to_string(),
+//!             probability: Some(0.5),
+//!             adversarial: true,
+//!             severity: None,
+//!             adversary_objective: None,
+//!         },
 //!     ],
 //!     outcomes: vec![
 //!         ("a1".to_string(), "s1".to_string(), 100.0),
@@ -36,6 +49,17 @@
 //!     constraints: None,
 //!     evidence: None,
 //!     meta: None,
+//!     action_priors: None,
+//!     tie_break_seed: None,
+//!     allow_missing_as_zero: false,
+//!     missing_cell_policy: MissingCellPolicy::Error,
+//!     recommended_set_size: None,
+//!     recommended_set_epsilon: None,
+//!     precision: None,
+//!     strict: false,
+//!     portfolios: None,
+//!     target: None,
+//!     ranking_mode: None,
 //! };
 //!
 //! let output = evaluate_decision(&input).unwrap();
@@ -50,42 +74,92 @@
 //! const result = wasm.evaluate_decision_json(JSON.stringify(input));
 //! const output = JSON.parse(result);
 //! ```
+//!
+//! ## Dependency Boundary
+//!
+//! This crate never depends on the workflow engine (`engine`): tool calls,
+//! policies and `serde_json::Value` payloads stay on the other side of the
+//! boundary. The [`schema`] and [`wasm`] modules sit behind the default-on
+//! `schema` and `wasm` features; building with `default-features = false`
+//! leaves only [`evaluate_decision`] and the other algorithms, the trace
+//! types, and the determinism helpers.
 
+pub mod criteria;
 pub mod determinism;
 pub mod engine;
+#[cfg(feature = "schema")]
+pub mod schema;
 pub mod types;
+#[cfg(feature = "wasm")]
 pub mod wasm;
 
 // Re-export main types and functions for convenience
+pub use criteria::{
+    builtin_criteria, evaluate_with_criteria, BuiltinCriterion, ScoringCriterion, WeightedCriteria,
+};
+
 pub use determinism::{
-    canonical_json, compute_fingerprint, float_normalize, stable_hash, DeterminismFingerprint,
+    canonical_cbor, canonical_json, canonical_json_pretty, cbor_to_canonical_json,
+    checked_float_normalize, checked_float_normalize_with, compute_fingerprint, float_normalize,
+    float_normalize_with, is_canonical, partition_batch, shard_of, stable_hash, to_canonical,
+    DeterminismFingerprint,
 };
 
 pub use engine::{
-    compute_flip_distines, evaluate_decision, explain_decision_boundary,
-    generate_regret_bounded_plan, rank_evidence_by_voi, referee_proposal, DecisionError,
+    build_decision_report, compute_flip_distances, evaluate_decision, evaluate_decision_streaming,
+    evaluate_incremental, explain_action, explain_decision_boundary, generate_regret_bounded_plan,
+    monte_carlo_robustness, rank_evidence_by_voi, referee_proposal,
+    referee_proposal_with_tolerance, scenario_regret_leaders, stochastic_dominance_frontier,
+    validate_input, DecisionError,
 };
 
 pub use types::{
-    ActionOption, CompositeWeights, DecisionBoundary, DecisionConstraint, DecisionError,
-    DecisionEvidence, DecisionInput, DecisionMeta, DecisionOutput, DecisionTrace,
-    FlipDistance, PlannedAction, RankedAction, RefereeAdjudication, RegretBoundedPlan,
-    Scenario, VoiRanking,
+    ActionDelta, ActionExplanation, ActionOption, AdversaryObjective, CapsuleError,
+    CompositeWeights, ConstrainedMaximin, CriteriaOutput, CriteriaRankedAction, Criterion,
+    CriterionShortfall, DecisionBoundary, DecisionCapsule, DecisionConstraint, DecisionDiff,
+    DecisionEvidence, DecisionInput, DecisionMeta, DecisionOutput, DecisionReport, DecisionTrace,
+    DegenerateReason, FlipDistance, MissingCellPolicy, OutcomeChange, PlannedAction, RankedAction,
+    RankingMode, RecommendationChange, RefereeAdjudication, RegretBoundedPlan, RobustnessReport,
+    Scenario, ScenarioRegretLeaders, SeverityDiscount, VoiFactor, VoiRanking,
 };
 
 // Re-export WASM functions for non-WASM builds
-#[cfg(not(target_arch = "wasm32"))]
+#[cfg(all(not(target_arch = "wasm32"), feature = "wasm"))]
 pub use wasm::{
-    compute_fingerprint_json, evaluate_decision_json, get_engine_version, ErrorDetail,
-    WasmError, WasmSuccess,
+    compute_fingerprint_json, evaluate_decision_json, get_engine_version, ErrorDetail, WasmError,
+    WasmSuccess,
 };
 
-#[cfg(target_arch = "wasm32")]
+#[cfg(all(target_arch = "wasm32", feature = "wasm"))]
 pub use wasm::{
-    compute_fingerprint_json, evaluate_decision_json, get_engine_version, ErrorDetail,
-    WasmError, WasmSuccess,
+    compute_fingerprint_json, evaluate_decision_json, get_engine_version, ErrorDetail, WasmError,
+    WasmSuccess,
 };
 
+#[cfg(all(test, not(feature = "schema"), not(feature = "wasm")))]
+mod minimal_tests {
+    use super::*;
+
+    #[test]
+    fn test_minimal_surface_has_no_engine_dependency() {
+        // The lean build must stay usable on its own ...
+        let _: fn(&DecisionInput) -> Result<DecisionOutput, DecisionError> = evaluate_decision;
+        let _: fn(&DecisionTrace) -> Vec<u8> = canonical_json::<DecisionTrace>;
+        let _: fn(&[u8]) -> String = stable_hash;
+
+        // ... and must not pull in the workflow engine (and its tool types).
+        let manifest = include_str!("../Cargo.toml");
+        let deps = manifest
+            .split("[dependencies]")
+            .nth(1)
+            .and_then(|rest| rest.split("\n[").next())
+            .unwrap();
+        assert!(deps
+            .lines()
+            .all(|line| !line.trim_start().starts_with("engine")));
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -113,16 +187,22 @@ mod tests {
                     id: "bull".to_string(),
                     probability: Some(0.4),
                     adversarial: false,
+                    severity: None,
+                    adversary_objective: None,
                 },
                 Scenario {
                     id: "bear".to_string(),
                     probability: Some(0.3),
                     adversarial: true,
+                    severity: None,
+                    adversary_objective: None,
                 },
                 Scenario {
                     id: "flat".to_string(),
                     probability: Some(0.3),
                     adversarial: false,
+                    severity: None,
+                    adversary_objective: None,
                 },
             ],
             outcomes: vec![
@@ -142,6 +222,17 @@ mod tests {
             constraints: None,
             evidence: None,
             meta: None,
+            action_priors: None,
+            tie_break_seed: None,
+            allow_missing_as_zero: false,
+            missing_cell_policy: MissingCellPolicy::Error,
+            recommended_set_size: None,
+            recommended_set_epsilon: None,
+            precision: None,
+            strict: false,
+            portfolios: None,
+            target: None,
+            ranking_mode: None,
         };
 
         // Evaluate decision
@@ -177,6 +268,152 @@ mod tests {
         assert!(referee.accepted);
     }
 
+    #[test]
+    #[cfg(feature = "wasm")]
+    fn test_json_entry_point_round_trips_native_output() {
+        // Same-process check of the JSON envelope: it serializes and parses back
+        // to the native output. It does not run a wasm32 build.
+        let input = DecisionInput {
+            id: Some("cross_check".to_string()),
+            actions: vec![
+                ActionOption {
+                    id: "buy".to_string(),
+                    label: "Buy".to_string(),
+                },
+                ActionOption {
+                    id: "sell".to_string(),
+                    label: "Sell".to_string(),
+                },
+            ],
+            scenarios: vec![
+                Scenario {
+                    id: "bull".to_string(),
+                    probability: Some(0.5),
+                    adversarial: false,
+                    severity: None,
+                    adversary_objective: None,
+                },
+                Scenario {
+                    id: "bear".to_string(),
+                    probability: Some(0.5),
+                    adversarial: true,
+                    severity: None,
+                    adversary_objective: None,
+                },
+            ],
+            outcomes: vec![
+                ("buy".to_string(), "bull".to_string(), 100.0),
+                ("buy".to_string(), "bear".to_string(), -50.0),
+                ("sell".to_string(), "bull".to_string(), 0.1 + 0.2),
+                ("sell".to_string(), "bear".to_string(), 20.0),
+            ],
+            constraints: None,
+            evidence: None,
+            meta: None,
+            action_priors: None,
+            tie_break_seed: None,
+            allow_missing_as_zero: false,
+            missing_cell_policy: MissingCellPolicy::Error,
+            recommended_set_size: None,
+            recommended_set_epsilon: None,
+            precision: None,
+            strict: false,
+            portfolios: None,
+            target: None,
+            ranking_mode: None,
+        };
+
+        let native = evaluate_decision(&input).unwrap();
+
+        let json = evaluate_decision_json(&serde_json::to_string(&input).unwrap());
+        let envelope: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert!(envelope["ok"].as_bool().unwrap());
+        let parsed: DecisionOutput = serde_json::from_value(envelope["data"].clone()).unwrap();
+
+        assert_eq!(
+            native.determinism_fingerprint,
+            parsed.determinism_fingerprint
+        );
+        assert_eq!(native.to_canonical_json(), parsed.to_canonical_json());
+        assert_eq!(
+            stable_hash(&native.to_canonical_json()),
+            stable_hash(&parsed.to_canonical_json())
+        );
+    }
+
+    #[test]
+    fn test_cbor_roundtrip_and_fingerprint() {
+        let input = DecisionInput {
+            id: Some("cbor".to_string()),
+            actions: vec![
+                ActionOption {
+                    id: "buy".to_string(),
+                    label: "Buy".to_string(),
+                },
+                ActionOption {
+                    id: "sell".to_string(),
+                    label: "Sell".to_string(),
+                },
+            ],
+            scenarios: vec![
+                Scenario {
+                    id: "bull".to_string(),
+                    probability: Some(0.75),
+                    adversarial: false,
+                    severity: None,
+                    adversary_objective: None,
+                },
+                Scenario {
+                    id: "bear".to_string(),
+                    probability: Some(0.25),
+                    adversarial: true,
+                    severity: None,
+                    adversary_objective: None,
+                },
+            ],
+            outcomes: vec![
+                ("buy".to_string(), "bull".to_string(), 100.5),
+                ("buy".to_string(), "bear".to_string(), -50.0),
+                ("sell".to_string(), "bull".to_string(), -20.25),
+                ("sell".to_string(), "bear".to_string(), 20.0),
+            ],
+            constraints: None,
+            evidence: None,
+            meta: None,
+            action_priors: Some(
+                [("buy".to_string(), 1.0), ("sell".to_string(), 3.0)]
+                    .into_iter()
+                    .collect(),
+            ),
+            tie_break_seed: Some(7),
+            allow_missing_as_zero: false,
+            missing_cell_policy: MissingCellPolicy::Error,
+            recommended_set_size: None,
+            recommended_set_epsilon: None,
+            precision: None,
+            strict: false,
+            portfolios: None,
+            target: None,
+            ranking_mode: None,
+        };
+
+        let cbor = canonical_cbor(&input).unwrap();
+        let decoded = DecisionInput::from_cbor(&cbor).unwrap();
+        assert_eq!(decoded, input);
+        assert_eq!(compute_fingerprint(&decoded), compute_fingerprint(&input));
+
+        let output = evaluate_decision(&decoded).unwrap();
+        let output_cbor = output.to_canonical_cbor().unwrap();
+        assert_eq!(
+            cbor_to_canonical_json(&output_cbor).unwrap(),
+            output.to_canonical_json()
+        );
+        assert_eq!(
+            stable_hash(&cbor_to_canonical_json(&output_cbor).unwrap()),
+            stable_hash(&output.to_canonical_json())
+        );
+    }
+
     #[test]
     fn test_determinism_comprehensive() {
         // Test 1: Same input produces same fingerprint
@@ -196,6 +433,8 @@ mod tests {
                 id: "s".to_string(),
                 probability: Some(1.0),
                 adversarial: false,
+                severity: None,
+                adversary_objective: None,
             }],
             outcomes: vec![
                 ("a".to_string(), "s".to_string(), 10.0),
@@ -204,6 +443,17 @@ mod tests {
             constraints: None,
             evidence: None,
             meta: None,
+            action_priors: None,
+            tie_break_seed: None,
+            allow_missing_as_zero: false,
+            missing_cell_policy: MissingCellPolicy::Error,
+            recommended_set_size: None,
+            recommended_set_epsilon: None,
+            precision: None,
+            strict: false,
+            portfolios: None,
+            target: None,
+            ranking_mode: None,
         };
 
         let input2 = input1.clone();
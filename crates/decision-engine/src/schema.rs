@@ -0,0 +1,736 @@
+//! JSON Schema export for the decision engine's wire types.
+//!
+//! Provides JSON Schema (draft 2020-12) documents for `DecisionInput` and
+//! `DecisionOutput` so non-Rust clients can validate payloads without
+//! reverse-engineering the shape from the docs.
+//!
+//! The schemas mirror the serde attributes on the types in [`crate::types`]:
+//! a property is `required` exactly when deserialization fails without it.
+//! Object keys are emitted in sorted order regardless of `serde_json` features,
+//! so the serialized schema is byte-stable and safe to check into client repos.
+
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+
+/// JSON Schema dialect used by the exported documents.
+pub const SCHEMA_DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// JSON Schema for [`DecisionInput`](crate::types::DecisionInput).
+#[must_use]
+pub fn input_schema() -> Value {
+    sorted(json!({
+        "$schema": SCHEMA_DIALECT,
+        "title": "DecisionInput",
+        "description": "Input to the decision engine.",
+        "type": "object",
+        "properties": input_properties(),
+        "required": ["actions", "scenarios", "outcomes"],
+        "$defs": {
+            "ActionOption": {
+                "description": "An action option in a decision problem.",
+                "type": "object",
+                "properties": {
+                    "id": { "description": "Unique identifier for the action.", "type": "string" },
+                    "label": { "description": "Human-readable label for the action.", "type": "string" }
+                },
+                "required": ["id", "label"]
+            },
+            "Scenario": scenario_schema(),
+            "AdversaryObjective": {
+                "description": "What the adversary of an adversarial scenario optimizes.",
+                "enum": ["minimize_utility", "maximize_regret"]
+            },
+            "RankingMode": ranking_mode_schema(),
+            "Criterion": criterion_schema(),
+            "DecisionConstraint": decision_constraint_schema(),
+            "SeverityDiscount": severity_discount_schema(),
+            "DecisionEvidence": {
+                "description": "Evidence for the decision problem.",
+                "type": "object",
+                "properties": {
+                    "drift": { "description": "Drift score (0.0 to 1.0).", "type": ["number", "null"] },
+                    "trust": { "description": "Trust score (0.0 to 1.0).", "type": ["number", "null"] },
+                    "policy": { "description": "Policy compliance score (0.0 to 1.0).", "type": ["number", "null"] },
+                    "provenance": { "description": "Provenance information.", "type": ["string", "null"] },
+                    "scenario_likelihoods": {
+                        "description": "Likelihood of the observed evidence under each scenario (finite, non-negative). Scenario probabilities are updated by Bayes' rule before expected values are scored; unlisted scenarios have likelihood 1.0.",
+                        "type": "object",
+                        "additionalProperties": { "type": "number", "minimum": 0 }
+                    }
+                },
+                "required": []
+            },
+            "DecisionMeta": {
+                "description": "Metadata for the decision (does NOT affect scoring).",
+                "type": "object",
+                "properties": {
+                    "created_at": { "description": "Creation timestamp (ISO 8601).", "type": ["string", "null"] },
+                    "version": { "description": "Version string.", "type": ["string", "null"] },
+                    "additional": {
+                        "description": "Additional metadata.",
+                        "type": "object",
+                        "additionalProperties": { "type": "string" }
+                    }
+                },
+                "required": ["additional"]
+            },
+            "CompositeWeights": composite_weights_schema()
+        }
+    }))
+}
+
+fn input_properties() -> Value {
+    json!({
+        "id": {
+            "description": "Optional identifier for the decision.",
+            "type": ["string", "null"]
+        },
+        "actions": {
+            "description": "Available actions.",
+            "type": "array",
+            "items": { "$ref": "#/$defs/ActionOption" }
+        },
+        "scenarios": {
+            "description": "Possible scenarios.",
+            "type": "array",
+            "items": { "$ref": "#/$defs/Scenario" }
+        },
+        "outcomes": {
+            "description": "Outcomes as (action_id, scenario_id, utility) tuples.",
+            "type": "array",
+            "items": outcome_schema()
+        },
+        "constraints": {
+            "description": "Optional constraints.",
+            "anyOf": [{ "$ref": "#/$defs/DecisionConstraint" }, { "type": "null" }]
+        },
+        "evidence": {
+            "description": "Optional evidence.",
+            "anyOf": [{ "$ref": "#/$defs/DecisionEvidence" }, { "type": "null" }]
+        },
+        "meta": {
+            "description": "Optional metadata (does NOT affect scoring).",
+            "anyOf": [{ "$ref": "#/$defs/DecisionMeta" }, { "type": "null" }]
+        },
+        "action_priors": {
+            "description": "Optional prior preference per action (finite, non-negative), normalized to sum to 1.0 and scored with `CompositeWeights::prior`.",
+            "type": ["object", "null"],
+            "additionalProperties": { "type": "number", "minimum": 0 }
+        },
+        "allow_missing_as_zero": {
+            "description": "Treat (action, scenario) cells without an outcome as utility 0.0 instead of rejecting the input.",
+            "type": "boolean",
+            "default": false
+        },
+        "missing_cell_policy": missing_cell_policy_schema(),
+        "recommended_set_size": {
+            "description": "Mark up to this many top-ranked actions recommended, if their composite score is within recommended_set_epsilon of the winner's. Only the winner is recommended when absent.",
+            "type": ["integer", "null"],
+            "minimum": 1
+        },
+        "recommended_set_epsilon": {
+            "description": "How far below the winner's composite score an action may be and still join the recommended set. Defaults to 0.0 (exact ties only).",
+            "type": ["number", "null"],
+            "minimum": 0.0
+        },
+        "tie_break_seed": {
+            "description": "Optional seed for breaking exact ties by a hash of (seed, action_id) instead of by action_id. Omit for lexicographic tie-breaking.",
+            "type": ["integer", "null"],
+            "minimum": 0
+        },
+        "strict": {
+            "description": "Reject inputs the engine would otherwise repair: missing or repeated outcome cells, partial probabilities, and probabilities, priors or weights that are not already normalized.",
+            "type": "boolean",
+            "default": false
+        },
+        "portfolios": {
+            "description": "Optional portfolios: sets of at least two action IDs, each scored as one more action whose utility in a scenario is the mean of its members'. A portfolio is ranked alongside the single actions under its sorted member IDs joined by `+`.",
+            "type": ["array", "null"],
+            "items": { "type": "array", "items": { "type": "string" }, "minItems": 2 }
+        },
+        "precision": {
+            "description": "Precision every score is rounded to. Defaults to 1e-9; coarser values merge near-ties, finer ones keep them apart.",
+            "type": ["number", "null"],
+            "exclusiveMinimum": 0
+        },
+        "target": {
+            "description": "Optional target utility (finite). Each action is scored by the probability mass of the scenarios where its utility reaches it, weighted by `CompositeWeights::target_probability`.",
+            "type": ["number", "null"]
+        },
+        "ranking_mode": {
+            "description": "How actions are ranked: by composite score under constraints.weights when absent.",
+            "anyOf": [{ "$ref": "#/$defs/RankingMode" }, { "type": "null" }]
+        }
+    })
+}
+
+fn outcome_schema() -> Value {
+    json!({
+        "type": "array",
+        "prefixItems": [
+            { "description": "Action identifier.", "type": "string" },
+            { "description": "Scenario identifier.", "type": "string" },
+            { "description": "Utility.", "type": "number" }
+        ],
+        "items": false,
+        "minItems": 3
+    })
+}
+
+fn missing_cell_policy_schema() -> Value {
+    json!({
+        "description": "What (action, scenario) cells without an outcome score: rejected (\"error\", the default), 0.0 (\"fill_zero\"), or a given finite utility ({\"fill\": utility}). allow_missing_as_zero takes precedence.",
+        "oneOf": [
+            { "enum": ["error", "fill_zero"] },
+            {
+                "type": "object",
+                "properties": { "fill": { "type": "number" } },
+                "required": ["fill"]
+            }
+        ],
+        "default": "error"
+    })
+}
+
+fn scenario_schema() -> Value {
+    json!({
+        "description": "A scenario in a decision problem.",
+        "type": "object",
+        "properties": {
+            "id": { "description": "Unique identifier for the scenario.", "type": "string" },
+            "probability": {
+                "description": "Probability of the scenario occurring (0.0 to 1.0). If null, all scenarios are treated equally.",
+                "type": ["number", "null"]
+            },
+            "adversarial": {
+                "description": "Whether this scenario represents an adversarial/worst-case scenario.",
+                "type": "boolean",
+                "default": false
+            },
+            "severity": {
+                "description": "How damaging this scenario is if it occurs; discounts utility in the adversarial criterion. Defaults to 1.0.",
+                "type": ["number", "null"],
+                "minimum": 0.0
+            },
+            "adversary_objective": {
+                "description": "What the adversary behind an adversarial scenario is after (minimize utility when absent).",
+                "anyOf": [{ "$ref": "#/$defs/AdversaryObjective" }, { "type": "null" }]
+            }
+        },
+        "required": ["id"]
+    })
+}
+
+fn ranking_mode_schema() -> Value {
+    json!({
+        "description": "How actions are ordered.",
+        "oneOf": [
+            {
+                "description": "By composite score under these weights, which take precedence over constraints.weights.",
+                "type": "object",
+                "properties": { "composite": { "$ref": "#/$defs/CompositeWeights" } },
+                "required": ["composite"],
+                "additionalProperties": false
+            },
+            {
+                "description": "By each criterion in turn, best first; scores within the normalization precision fall through to the next criterion, then to the tie-break rule.",
+                "type": "object",
+                "properties": {
+                    "lexicographic": {
+                        "type": "array",
+                        "items": { "$ref": "#/$defs/Criterion" },
+                        "minItems": 1
+                    }
+                },
+                "required": ["lexicographic"],
+                "additionalProperties": false
+            }
+        ]
+    })
+}
+
+fn decision_constraint_schema() -> Value {
+    json!({
+        "description": "Constraints on the decision problem.",
+        "type": "object",
+        "properties": {
+            "max_regret": {
+                "description": "Maximum acceptable regret: the cap for the constrained maximin criterion.",
+                "type": ["number", "null"],
+                "minimum": 0
+            },
+            "risk_tolerance": { "description": "Risk tolerance level (0.0 to 1.0).", "type": ["number", "null"] },
+            "epsilon": {
+                "description": "Epsilon-contamination level (0.0 to 1.0).",
+                "type": ["number", "null"],
+                "minimum": 0.0,
+                "maximum": 1.0
+            },
+            "weights": {
+                "description": "Composite score weights (defaults apply when absent).",
+                "anyOf": [{ "$ref": "#/$defs/CompositeWeights" }, { "type": "null" }]
+            },
+            "severity_discount": {
+                "description": "How scenario severity discounts adversarial utility (proportional when absent).",
+                "anyOf": [{ "$ref": "#/$defs/SeverityDiscount" }, { "type": "null" }]
+            },
+            "additional": {
+                "description": "Additional constraints as key-value pairs.",
+                "type": "object",
+                "additionalProperties": { "type": "string" }
+            }
+        },
+        "required": ["additional"]
+    })
+}
+
+fn severity_discount_schema() -> Value {
+    json!({
+        "description": "How scenario severity discounts utility in the adversarial criterion.",
+        "oneOf": [
+            {
+                "description": "utility - (severity - 1.0) * |utility|",
+                "type": "object",
+                "properties": { "mode": { "const": "proportional" } },
+                "required": ["mode"]
+            },
+            {
+                "description": "utility - per_unit * (severity - 1.0)",
+                "type": "object",
+                "properties": {
+                    "mode": { "const": "penalty" },
+                    "per_unit": { "type": "number", "minimum": 0.0 }
+                },
+                "required": ["mode", "per_unit"]
+            }
+        ]
+    })
+}
+
+/// JSON Schema for [`DecisionOutput`](crate::types::DecisionOutput).
+#[must_use]
+pub fn output_schema() -> Value {
+    sorted(json!({
+        "$schema": SCHEMA_DIALECT,
+        "title": "DecisionOutput",
+        "description": "Output from the decision engine.",
+        "type": "object",
+        "properties": {
+            "ranked_actions": {
+                "description": "Ranked actions (best first).",
+                "type": "array",
+                "items": { "$ref": "#/$defs/RankedAction" }
+            },
+            "robustness": {
+                "description": "Gap between the top two composite scores over the score range (1 = landslide, 0 = tie).",
+                "type": "number",
+                "minimum": 0,
+                "maximum": 1
+            },
+            "determinism_fingerprint": {
+                "description": "Fingerprint of the canonical input.",
+                "type": "string"
+            },
+            "trace": { "$ref": "#/$defs/DecisionTrace" },
+            "warnings": {
+                "description": "One note per (action, scenario) cell filled by the missing-cell policy, in input order (present only when cells were filled).",
+                "type": "array",
+                "items": { "type": "string" }
+            },
+            "degenerate": {
+                "description": "Why the ranking compares nothing, checked in this order (present only when it does not): one action, one scenario, or every action tied. Robustness and flip distances are then not informative and VOI rankings are empty.",
+                "enum": ["single_action", "single_scenario", "all_scores_equal"]
+            }
+        },
+        "required": ["ranked_actions", "robustness", "determinism_fingerprint", "trace"],
+        "$defs": {
+            "RankedAction": ranked_action_schema(),
+            "DecisionTrace": decision_trace_schema(),
+            "CompositeWeights": composite_weights_schema()
+        }
+    }))
+}
+
+fn ranked_action_schema() -> Value {
+    json!({
+        "description": "A ranked action with scores.",
+        "type": "object",
+        "properties": {
+            "action_id": { "description": "Action identifier.", "type": "string" },
+            "score_worst_case": { "description": "Worst-case utility score.", "type": "number" },
+            "score_minimax_regret": { "description": "Maximum regret score.", "type": "number" },
+            "score_adversarial": { "description": "Adversarial robustness score.", "type": "number" },
+            "composite_score": { "description": "Composite score (weighted combination).", "type": "number" },
+            "recommended": { "description": "Whether this action is recommended.", "type": "boolean" },
+            "rank": { "description": "Rank (1 = best).", "type": "integer", "minimum": 0 },
+            "tied_with": {
+                "description": "Other actions with the same composite score, sorted by ID (present only on a tie).",
+                "type": "array",
+                "items": { "type": "string" }
+            }
+        },
+        "required": [
+            "action_id",
+            "score_worst_case",
+            "score_minimax_regret",
+            "score_adversarial",
+            "composite_score",
+            "recommended",
+            "rank"
+        ]
+    })
+}
+
+fn decision_trace_schema() -> Value {
+    let number_table = json!({
+        "type": "object",
+        "additionalProperties": { "type": "number" }
+    });
+    let nested_table = json!({
+        "type": "object",
+        "additionalProperties": number_table.clone()
+    });
+    let described = |description: &str, schema: &Value| {
+        let mut schema = schema.clone();
+        schema["description"] = Value::String(description.to_string());
+        schema
+    };
+
+    json!({
+        "description": "Trace of the decision computation for reproducibility.",
+        "type": "object",
+        "properties": {
+            "utility_table": described("Utility table: action_id -> scenario_id -> utility.", &nested_table),
+            "worst_case_table": described("Worst-case table: action_id -> minimum utility.", &number_table),
+            "regret_table": described("Regret table: action_id -> scenario_id -> regret.", &nested_table),
+            "max_regret_table": described("Maximum regret table: action_id -> maximum regret.", &number_table),
+            "adversarial_table": described("Adversarial worst-case table: action_id -> adversarial worst utility.", &number_table),
+            "epsilon_contamination_table": described("Epsilon-contamination table: action_id -> blended expected/adversarial utility.", &number_table),
+            "adversarial_regret_table": described("Adversarial regret table: action_id -> maximum regret over adversarial scenarios.", &number_table),
+            "prior_table": described("Prior share table: action_id -> normalized prior (present only when action priors were given with a non-zero prior weight).", &number_table),
+            "target_probability_table": described("Target probability table: action_id -> probability of reaching the input's target (present only when a target was given).", &number_table),
+            "composite_weights": { "$ref": "#/$defs/CompositeWeights" },
+            "tie_break_rule": { "description": "Tie-breaking rule used.", "type": "string" },
+            "precision": {
+                "description": "Normalization precision, when the input set one (1e-9 otherwise).",
+                "type": "number",
+                "exclusiveMinimum": 0
+            },
+            "scenario_priors": described("Scenario probabilities before evidence: scenario_id -> probability (present only when evidence carried scenario likelihoods).", &number_table),
+            "scenario_posteriors": described("Scenario probabilities after the evidence update, as used for expected values (present only when evidence carried scenario likelihoods).", &number_table),
+            "portfolios": {
+                "description": "Portfolio table: portfolio ID -> member action IDs (present only when portfolios were given).",
+                "type": "object",
+                "additionalProperties": { "type": "array", "items": { "type": "string" } }
+            },
+            "stochastic_frontier": {
+                "description": "Actions no other action first-order stochastically dominates, sorted by action ID (present only when every scenario has a probability).",
+                "type": "array",
+                "items": { "type": "string" }
+            },
+            "constrained_maximin": {
+                "description": "Best worst case among actions whose maximum regret is within constraints.max_regret (present only when a cap is set).",
+                "type": "object",
+                "properties": {
+                    "regret_cap": { "description": "The regret cap.", "type": "number" },
+                    "feasible": {
+                        "description": "Actions within the cap, sorted by action ID.",
+                        "type": "array",
+                        "items": { "type": "string" }
+                    },
+                    "chosen": { "description": "The feasible action with the highest worst case, ties broken by action ID.", "type": "string" }
+                },
+                "required": ["regret_cap", "feasible", "chosen"]
+            },
+            "ranking_criteria": {
+                "description": "The criteria actions were ranked by, in order, under a lexicographic ranking (present only then).",
+                "type": "array",
+                "items": criterion_schema()
+            }
+        },
+        "required": [
+            "utility_table",
+            "worst_case_table",
+            "regret_table",
+            "max_regret_table",
+            "adversarial_table",
+            "composite_weights",
+            "tie_break_rule"
+        ]
+    })
+}
+
+fn criterion_schema() -> Value {
+    json!({
+        "description": "A per-action score a ranking can be ordered by (regrets: lower is better).",
+        "enum": [
+            "worst_case",
+            "minimax_regret",
+            "adversarial",
+            "epsilon_contamination",
+            "adversarial_regret",
+            "prior",
+            "target_probability"
+        ]
+    })
+}
+
+fn composite_weights_schema() -> Value {
+    json!({
+        "description": "Weights for composite score calculation.",
+        "type": "object",
+        "properties": {
+            "worst_case": { "description": "Weight for worst-case score.", "type": "number" },
+            "minimax_regret": { "description": "Weight for minimax regret score.", "type": "number" },
+            "adversarial": { "description": "Weight for adversarial robustness score.", "type": "number" },
+            "epsilon_contamination": {
+                "description": "Weight for epsilon-contamination score.",
+                "type": "number",
+                "default": 0.0
+            },
+            "adversarial_regret": {
+                "description": "Weight for adversarial regret score.",
+                "type": "number",
+                "default": 0.0
+            },
+            "prior": {
+                "description": "Weight for action prior score.",
+                "type": "number",
+                "default": 0.0
+            },
+            "target_probability": {
+                "description": "Weight for target probability score (needs a target on the input).",
+                "type": "number",
+                "default": 0.0
+            }
+        },
+        "required": ["worst_case", "minimax_regret", "adversarial"]
+    })
+}
+
+/// Rebuild every object with lexicographically sorted keys.
+fn sorted(value: Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let ordered: BTreeMap<String, Value> =
+                map.into_iter().map(|(k, v)| (k, sorted(v))).collect();
+            Value::Object(ordered.into_iter().collect::<Map<String, Value>>())
+        }
+        Value::Array(items) => Value::Array(items.into_iter().map(sorted).collect()),
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Minimal validator for the keyword subset the exported schemas use.
+    fn validate(root: &Value, schema: &Value, value: &Value, path: &str) -> Result<(), String> {
+        if let Value::Bool(allowed) = schema {
+            return if *allowed {
+                Ok(())
+            } else {
+                Err(format!("{path}: not allowed"))
+            };
+        }
+        if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+            let name = reference.trim_start_matches("#/$defs/");
+            return validate(root, &root["$defs"][name], value, path);
+        }
+        if let Some(options) = schema.get("anyOf").and_then(Value::as_array) {
+            return if options
+                .iter()
+                .any(|s| validate(root, s, value, path).is_ok())
+            {
+                Ok(())
+            } else {
+                Err(format!("{path}: matched no anyOf branch"))
+            };
+        }
+        validate_type(schema, value, path)?;
+        validate_bounds(schema, value, path)?;
+        match value {
+            Value::Object(object) => validate_object(root, schema, object, path),
+            Value::Array(items) => validate_array(root, schema, items, path),
+            _ => Ok(()),
+        }
+    }
+
+    fn validate_type(schema: &Value, value: &Value, path: &str) -> Result<(), String> {
+        if let Some(expected) = schema.get("type") {
+            let types: Vec<&str> = match expected {
+                Value::String(t) => vec![t.as_str()],
+                Value::Array(ts) => ts.iter().filter_map(Value::as_str).collect(),
+                _ => vec![],
+            };
+            let matches = types.iter().any(|t| match *t {
+                "object" => value.is_object(),
+                "array" => value.is_array(),
+                "string" => value.is_string(),
+                "number" => value.is_number(),
+                "integer" => value.is_i64() || value.is_u64(),
+                "boolean" => value.is_boolean(),
+                "null" => value.is_null(),
+                _ => false,
+            });
+            if !matches {
+                return Err(format!("{path}: expected {types:?}, got {value}"));
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_bounds(schema: &Value, value: &Value, path: &str) -> Result<(), String> {
+        if let (Some(min), Some(n)) = (
+            schema.get("minimum").and_then(Value::as_f64),
+            value.as_f64(),
+        ) {
+            if n < min {
+                return Err(format!("{path}: {n} below minimum {min}"));
+            }
+        }
+        if let (Some(max), Some(n)) = (
+            schema.get("maximum").and_then(Value::as_f64),
+            value.as_f64(),
+        ) {
+            if n > max {
+                return Err(format!("{path}: {n} above maximum {max}"));
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_object(
+        root: &Value,
+        schema: &Value,
+        object: &Map<String, Value>,
+        path: &str,
+    ) -> Result<(), String> {
+        for required in schema
+            .get("required")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+        {
+            let key = required.as_str().unwrap();
+            if !object.contains_key(key) {
+                return Err(format!("{path}: missing required property {key}"));
+            }
+        }
+        let properties = schema.get("properties").and_then(Value::as_object);
+        for (key, item) in object {
+            let child = format!("{path}/{key}");
+            match properties.and_then(|p| p.get(key)) {
+                Some(s) => validate(root, s, item, &child)?,
+                None => {
+                    if let Some(s) = schema.get("additionalProperties") {
+                        validate(root, s, item, &child)?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_array(
+        root: &Value,
+        schema: &Value,
+        items: &[Value],
+        path: &str,
+    ) -> Result<(), String> {
+        if let Some(min) = schema.get("minItems").and_then(Value::as_u64) {
+            if (items.len() as u64) < min {
+                return Err(format!("{path}: fewer than {min} items"));
+            }
+        }
+        let prefix = schema.get("prefixItems").and_then(Value::as_array);
+        let prefix_len = prefix.map_or(0, Vec::len);
+        for (i, item) in items.iter().enumerate() {
+            let child = format!("{path}/{i}");
+            match prefix.and_then(|p| p.get(i)) {
+                Some(s) => validate(root, s, item, &child)?,
+                None if i >= prefix_len => {
+                    if let Some(s) = schema.get("items") {
+                        validate(root, s, item, &child)?;
+                    }
+                }
+                None => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn example_input() -> Value {
+        // Mirrors the crate-level Quick Start example
+        json!({
+            "id": "my_decision",
+            "actions": [
+                { "id": "a1", "label": "Action 1" },
+                { "id": "a2", "label": "Action 2" }
+            ],
+            "scenarios": [
+                { "id": "s1", "probability": 0.5, "adversarial": false },
+                { "id": "s2", "probability": 0.5, "adversarial": true }
+            ],
+            "outcomes": [
+                ["a1", "s1", 100.0],
+                ["a1", "s2", 50.0],
+                ["a2", "s1", 90.0],
+                ["a2", "s2", 60.0]
+            ],
+            "constraints": null,
+            "evidence": null,
+            "meta": null
+        })
+    }
+
+    #[test]
+    fn test_example_input_matches_input_schema() {
+        let schema = input_schema();
+        let input = example_input();
+        validate(&schema, &schema, &input, "").unwrap();
+
+        // The same document deserializes into the Rust type
+        let parsed: crate::types::DecisionInput = serde_json::from_value(input).unwrap();
+        let output = crate::engine::evaluate_decision(&parsed).unwrap();
+
+        let schema = output_schema();
+        validate(
+            &schema,
+            &schema,
+            &serde_json::to_value(&output).unwrap(),
+            "",
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_input_schema_rejects_malformed_input() {
+        let schema = input_schema();
+
+        let mut missing_actions = example_input();
+        missing_actions.as_object_mut().unwrap().remove("actions");
+        assert!(validate(&schema, &schema, &missing_actions, "").is_err());
+
+        let mut short_outcome = example_input();
+        short_outcome["outcomes"][0] = json!(["a1", "s1"]);
+        assert!(validate(&schema, &schema, &short_outcome, "").is_err());
+    }
+
+    #[test]
+    fn test_schemas_are_byte_stable() {
+        let first = serde_json::to_vec(&input_schema()).unwrap();
+        let second = serde_json::to_vec(&input_schema()).unwrap();
+        assert_eq!(first, second);
+        assert_eq!(
+            serde_json::to_vec(&output_schema()).unwrap(),
+            serde_json::to_vec(&output_schema()).unwrap()
+        );
+
+        // Keys are emitted sorted, so serialization matches the canonical form
+        let text = String::from_utf8(first).unwrap();
+        assert!(text.find("\"$defs\"").unwrap() < text.find("\"$schema\"").unwrap());
+        assert!(text.find("\"properties\"").unwrap() < text.find("\"required\"").unwrap());
+    }
+}
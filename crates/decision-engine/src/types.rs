@@ -28,17 +28,183 @@ pub struct Scenario {
     /// Whether this scenario represents an adversarial/worst-case scenario.
     #[serde(default)]
     pub adversarial: bool,
+    /// How damaging this scenario is if it occurs (finite, non-negative;
+    /// default 1.0). Discounts utility in the adversarial criterion, see
+    /// [`SeverityDiscount`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub severity: Option<f64>,
+    /// What the adversary behind an adversarial scenario is after (minimize
+    /// utility when absent). Ignored for non-adversarial scenarios.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub adversary_objective: Option<AdversaryObjective>,
+}
+
+/// What the adversary of an adversarial scenario optimizes.
+///
+/// Decides which scenario the adversary is assumed to pick for each action in
+/// the adversarial criterion, and what the action scores there.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum AdversaryObjective {
+    /// The adversary picks the scenario where our utility is lowest.
+    #[default]
+    MinimizeUtility,
+    /// The adversary picks the scenario where our regret is highest; the
+    /// action scores its regret there below the highest utility any action
+    /// reaches in a maximize-regret scenario.
+    MaximizeRegret,
+}
+
+/// How scenario severity discounts utility in the adversarial criterion.
+///
+/// A severity of 1.0 leaves utility unchanged in every mode.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum SeverityDiscount {
+    /// `utility - (severity - 1.0) * |utility|`: severity 2.0 doubles a loss
+    /// and wipes out a gain.
+    #[default]
+    Proportional,
+    /// `utility - per_unit * (severity - 1.0)`, in utility units.
+    Penalty { per_unit: f64 },
+}
+
+impl SeverityDiscount {
+    /// Utility after discounting it by `severity`.
+    #[must_use]
+    pub fn apply(self, utility: f64, severity: f64) -> f64 {
+        match self {
+            SeverityDiscount::Proportional => utility - (severity - 1.0) * utility.abs(),
+            SeverityDiscount::Penalty { per_unit } => utility - per_unit * (severity - 1.0),
+        }
+    }
+}
+
+/// A per-action score a ranking can be ordered by, named as in
+/// `CompositeWeights`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Criterion {
+    /// Worst-case utility (higher is better).
+    WorstCase,
+    /// Maximum regret (lower is better).
+    MinimaxRegret,
+    /// Adversarial utility (higher is better).
+    Adversarial,
+    /// Epsilon-contamination score (higher is better).
+    EpsilonContamination,
+    /// Maximum regret over adversarial scenarios (lower is better).
+    AdversarialRegret,
+    /// Normalized action prior (higher is better).
+    Prior,
+    /// Probability of reaching the target (higher is better; needs
+    /// `DecisionInput::target`).
+    TargetProbability,
+}
+
+impl Criterion {
+    /// Every criterion, in `CompositeWeights` field order.
+    pub const ALL: [Criterion; 7] = [
+        Criterion::WorstCase,
+        Criterion::MinimaxRegret,
+        Criterion::Adversarial,
+        Criterion::EpsilonContamination,
+        Criterion::AdversarialRegret,
+        Criterion::Prior,
+        Criterion::TargetProbability,
+    ];
+
+    /// Name of the criterion, as its `CompositeWeights` field.
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Criterion::WorstCase => "worst_case",
+            Criterion::MinimaxRegret => "minimax_regret",
+            Criterion::Adversarial => "adversarial",
+            Criterion::EpsilonContamination => "epsilon_contamination",
+            Criterion::AdversarialRegret => "adversarial_regret",
+            Criterion::Prior => "prior",
+            Criterion::TargetProbability => "target_probability",
+        }
+    }
+
+    /// Whether a lower score is better: true for the regret criteria.
+    #[must_use]
+    pub fn lower_is_better(self) -> bool {
+        matches!(
+            self,
+            Criterion::MinimaxRegret | Criterion::AdversarialRegret
+        )
+    }
+}
+
+/// How actions are ordered.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RankingMode {
+    /// By composite score under these weights, which take precedence over
+    /// `DecisionConstraint::weights`.
+    Composite(CompositeWeights),
+    /// By each criterion in turn, best first; scores within the
+    /// normalization precision (1e-9 by default) fall through to the next
+    /// criterion, then to the tie-break rule. No weights are involved, though
+    /// composite scores (under the constraint or default weights) and
+    /// robustness are still reported.
+    Lexicographic(Vec<Criterion>),
+}
+
+/// What to do with an (action, scenario) cell that has no outcome.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum MissingCellPolicy {
+    /// Reject the input with `MissingCell`.
+    #[default]
+    Error,
+    /// Score the cell as 0.0.
+    FillZero,
+    /// Score the cell as the given utility (finite), e.g. a pessimistic floor.
+    Fill(f64),
+}
+
+impl MissingCellPolicy {
+    /// The utility a missing cell scores, or `None` when it is an error.
+    #[must_use]
+    pub fn fill_value(self) -> Option<f64> {
+        match self {
+            MissingCellPolicy::Error => None,
+            MissingCellPolicy::FillZero => Some(0.0),
+            MissingCellPolicy::Fill(utility) => Some(utility),
+        }
+    }
+
+    // serde's skip_serializing_if passes the field by reference
+    #[allow(clippy::trivially_copy_pass_by_ref)]
+    fn is_error(&self) -> bool {
+        *self == MissingCellPolicy::Error
+    }
 }
 
 /// Constraints on the decision problem.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
 pub struct DecisionConstraint {
-    /// Maximum acceptable regret.
+    /// Maximum acceptable regret: the cap for the constrained maximin
+    /// criterion (see `DecisionTrace::constrained_maximin`).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub max_regret: Option<f64>,
     /// Risk tolerance level (0.0 to 1.0).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub risk_tolerance: Option<f64>,
+    /// Epsilon-contamination level (0.0 to 1.0): the weight given to the
+    /// adversarial worst case versus the expected value.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub epsilon: Option<f64>,
+    /// Composite score weights (defaults apply when absent).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weights: Option<CompositeWeights>,
+    /// How scenario severity discounts adversarial utility (proportional
+    /// when absent).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub severity_discount: Option<SeverityDiscount>,
     /// Additional constraints as key-value pairs.
     #[serde(skip_serializing_if = "BTreeMap::is_empty")]
     pub additional: BTreeMap<String, String>,
@@ -59,6 +225,12 @@ pub struct DecisionEvidence {
     /// Provenance information.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub provenance: Option<String>,
+    /// Likelihood of the observed evidence under each scenario
+    /// (`scenario_id -> P(evidence | scenario)`, finite and non-negative). Scenario
+    /// probabilities are updated by Bayes' rule before expected values are
+    /// scored; unlisted scenarios have likelihood 1.0.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub scenario_likelihoods: BTreeMap<String, f64>,
 }
 
 /// Metadata for the decision (does NOT affect scoring).
@@ -96,6 +268,210 @@ pub struct DecisionInput {
     /// Optional metadata (does NOT affect scoring).
     #[serde(default)]
     pub meta: Option<DecisionMeta>,
+    /// Optional prior preference per action (finite, non-negative), normalized
+    /// to sum to 1.0 and scored with `CompositeWeights::prior`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub action_priors: Option<BTreeMap<String, f64>>,
+    /// Optional seed for breaking exact ties by a hash of `(seed, action_id)`
+    /// instead of by `action_id`. Omit for lexicographic tie-breaking.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tie_break_seed: Option<u64>,
+    /// Treat (action, scenario) cells without an outcome as utility 0.0
+    /// instead of rejecting the input with `MissingCell`. Shorthand for
+    /// `MissingCellPolicy::FillZero`, and takes precedence over
+    /// `missing_cell_policy`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub allow_missing_as_zero: bool,
+    /// What (action, scenario) cells without an outcome score. Filled cells
+    /// are listed in `DecisionOutput::warnings`.
+    #[serde(default, skip_serializing_if = "MissingCellPolicy::is_error")]
+    pub missing_cell_policy: MissingCellPolicy,
+    /// Mark up to this many top-ranked actions `recommended` (at least 1),
+    /// as long as their composite score is within `recommended_set_epsilon`
+    /// of the winner's. Only the winner is recommended when absent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recommended_set_size: Option<usize>,
+    /// How far below the winner's composite score an action may be and still
+    /// join the recommended set (finite, non-negative; default 0.0, i.e.
+    /// exact ties only).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recommended_set_epsilon: Option<f64>,
+    /// Precision every score is rounded to (finite and positive). Defaults
+    /// to `FLOAT_PRECISION` (1e-9); coarser values merge near-ties, finer
+    /// ones keep them apart.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub precision: Option<f64>,
+    /// Reject inputs the engine would otherwise repair: missing or repeated
+    /// outcome cells, partial probabilities, and probabilities, priors or
+    /// weights that are not already normalized (see `validate_input`).
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub strict: bool,
+    /// Optional portfolios: sets of at least two action IDs, each scored as
+    /// one more action whose utility in a scenario is the mean of its
+    /// members' (an equal split across them). A portfolio is ranked alongside
+    /// the single actions under its sorted member IDs joined by `+`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub portfolios: Option<Vec<Vec<String>>>,
+    /// Optional target utility (finite). Each action is scored by the
+    /// probability mass of the scenarios where its utility reaches it,
+    /// weighted by `CompositeWeights::target_probability`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<f64>,
+    /// How actions are ranked: by composite score under
+    /// `DecisionConstraint::weights` when absent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ranking_mode: Option<RankingMode>,
+}
+
+impl DecisionInput {
+    /// The precision scores are normalized to: `precision`, or
+    /// `FLOAT_PRECISION` when unset.
+    #[must_use]
+    pub fn normalization_precision(&self) -> f64 {
+        self.precision
+            .unwrap_or(crate::determinism::FLOAT_PRECISION)
+    }
+
+    /// The utility cells without an outcome score, or `None` when they are
+    /// rejected: 0.0 under `allow_missing_as_zero`, otherwise per
+    /// `missing_cell_policy`.
+    #[must_use]
+    pub fn missing_cell_fill(&self) -> Option<f64> {
+        if self.allow_missing_as_zero {
+            Some(0.0)
+        } else {
+            self.missing_cell_policy.fill_value()
+        }
+    }
+
+    /// The composite weights in effect, if any were given: those of
+    /// `RankingMode::Composite`, else `DecisionConstraint::weights`.
+    #[must_use]
+    pub fn weights(&self) -> Option<&CompositeWeights> {
+        match &self.ranking_mode {
+            Some(RankingMode::Composite(weights)) => Some(weights),
+            _ => self.constraints.as_ref().and_then(|c| c.weights.as_ref()),
+        }
+    }
+
+    /// The criteria a `RankingMode::Lexicographic` ranking orders by, in
+    /// order; empty for a composite ranking.
+    #[must_use]
+    pub fn ranking_criteria(&self) -> &[Criterion] {
+        match &self.ranking_mode {
+            Some(RankingMode::Lexicographic(criteria)) => criteria,
+            _ => &[],
+        }
+    }
+
+    /// Decode an input from CBOR, e.g. a protocol payload.
+    pub fn from_cbor(bytes: &[u8]) -> Result<Self, crate::engine::DecisionError> {
+        crate::determinism::decode_cbor(bytes)
+    }
+
+    /// Reject NaN and infinite numbers before any score is computed.
+    ///
+    /// Covers outcome utilities, scenario probabilities and severities,
+    /// constraint weights and settings, action priors, evidence likelihoods,
+    /// the missing-cell fill, `target`, `precision` and
+    /// `recommended_set_epsilon`. Fails with `InvalidInput` naming the first
+    /// offending field, in field order.
+    pub fn validate_finite(&self) -> Result<(), crate::engine::DecisionError> {
+        for (i, (action_id, scenario_id, utility)) in self.outcomes.iter().enumerate() {
+            finite(*utility, || {
+                format!("outcomes[{i}] ('{action_id}', '{scenario_id}')")
+            })?;
+        }
+        for (i, scenario) in self.scenarios.iter().enumerate() {
+            if let Some(probability) = scenario.probability {
+                finite(probability, || format!("scenarios[{i}].probability"))?;
+            }
+            if let Some(severity) = scenario.severity {
+                finite(severity, || format!("scenarios[{i}].severity"))?;
+            }
+        }
+        if let Some(constraints) = &self.constraints {
+            if let Some(weights) = &constraints.weights {
+                finite_weights(weights, "constraints.weights")?;
+            }
+            for (name, value) in [
+                ("max_regret", constraints.max_regret),
+                ("risk_tolerance", constraints.risk_tolerance),
+                ("epsilon", constraints.epsilon),
+            ] {
+                if let Some(value) = value {
+                    finite(value, || format!("constraints.{name}"))?;
+                }
+            }
+            if let Some(SeverityDiscount::Penalty { per_unit }) = constraints.severity_discount {
+                finite(per_unit, || {
+                    "constraints.severity_discount.per_unit".to_string()
+                })?;
+            }
+        }
+        if let Some(evidence) = &self.evidence {
+            for (scenario_id, likelihood) in &evidence.scenario_likelihoods {
+                finite(*likelihood, || {
+                    format!("evidence.scenario_likelihoods.{scenario_id}")
+                })?;
+            }
+        }
+        if let Some(priors) = &self.action_priors {
+            for (action_id, prior) in priors {
+                finite(*prior, || format!("action_priors.{action_id}"))?;
+            }
+        }
+        if let MissingCellPolicy::Fill(utility) = self.missing_cell_policy {
+            finite(utility, || "missing_cell_policy.fill".to_string())?;
+        }
+        if let Some(RankingMode::Composite(weights)) = &self.ranking_mode {
+            finite_weights(weights, "ranking_mode.composite")?;
+        }
+        for (name, value) in [
+            ("recommended_set_epsilon", self.recommended_set_epsilon),
+            ("precision", self.precision),
+            ("target", self.target),
+        ] {
+            if let Some(value) = value {
+                finite(value, || name.to_string())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// `InvalidInput` naming the first non-finite weight under `prefix`.
+fn finite_weights(
+    weights: &CompositeWeights,
+    prefix: &str,
+) -> Result<(), crate::engine::DecisionError> {
+    for (name, weight) in [
+        ("worst_case", weights.worst_case),
+        ("minimax_regret", weights.minimax_regret),
+        ("adversarial", weights.adversarial),
+        ("epsilon_contamination", weights.epsilon_contamination),
+        ("adversarial_regret", weights.adversarial_regret),
+        ("prior", weights.prior),
+        ("target_probability", weights.target_probability),
+    ] {
+        finite(weight, || format!("{prefix}.{name}"))?;
+    }
+    Ok(())
+}
+
+/// `InvalidInput` naming `field` unless `value` is finite.
+pub(crate) fn finite(
+    value: f64,
+    field: impl FnOnce() -> String,
+) -> Result<(), crate::engine::DecisionError> {
+    if value.is_finite() {
+        Ok(())
+    } else {
+        Err(crate::engine::DecisionError::InvalidInput(format!(
+            "{} must be finite, got {value}",
+            field()
+        )))
+    }
 }
 
 /// A ranked action with scores.
@@ -115,6 +491,10 @@ pub struct RankedAction {
     pub recommended: bool,
     /// Rank (1 = best).
     pub rank: usize,
+    /// Other actions with the same composite score, sorted by ID. Non-empty
+    /// means this action's place among them came down to the tie-break rule.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tied_with: Vec<String>,
 }
 
 /// Weights for composite score calculation.
@@ -126,6 +506,62 @@ pub struct CompositeWeights {
     pub minimax_regret: f64,
     /// Weight for adversarial robustness score.
     pub adversarial: f64,
+    /// Weight for epsilon-contamination score.
+    #[serde(default)]
+    pub epsilon_contamination: f64,
+    /// Weight for adversarial regret score.
+    #[serde(default)]
+    pub adversarial_regret: f64,
+    /// Weight for action prior score.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub prior: f64,
+    /// Weight for target probability score (needs `DecisionInput::target`).
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub target_probability: f64,
+}
+
+// serde's skip_serializing_if passes the field by reference
+#[allow(clippy::trivially_copy_pass_by_ref)]
+fn is_zero(value: &f64) -> bool {
+    *value == 0.0
+}
+
+impl CompositeWeights {
+    /// Sum of all weights.
+    #[must_use]
+    pub fn total(&self) -> f64 {
+        self.worst_case
+            + self.minimax_regret
+            + self.adversarial
+            + self.epsilon_contamination
+            + self.adversarial_regret
+            + self.prior
+            + self.target_probability
+    }
+
+    /// Weight of one criterion.
+    #[must_use]
+    pub fn weight(&self, criterion: Criterion) -> f64 {
+        match criterion {
+            Criterion::WorstCase => self.worst_case,
+            Criterion::MinimaxRegret => self.minimax_regret,
+            Criterion::Adversarial => self.adversarial,
+            Criterion::EpsilonContamination => self.epsilon_contamination,
+            Criterion::AdversarialRegret => self.adversarial_regret,
+            Criterion::Prior => self.prior,
+            Criterion::TargetProbability => self.target_probability,
+        }
+    }
+
+    /// Names of the criteria with a non-zero weight, in field order.
+    #[must_use]
+    pub fn criteria(&self) -> Vec<String> {
+        Criterion::ALL
+            .into_iter()
+            .filter(|&criterion| self.weight(criterion) != 0.0)
+            .map(|criterion| criterion.name().to_string())
+            .collect()
+    }
 }
 
 impl Default for CompositeWeights {
@@ -134,6 +570,10 @@ impl Default for CompositeWeights {
             worst_case: 0.4,
             minimax_regret: 0.4,
             adversarial: 0.2,
+            epsilon_contamination: 0.0,
+            adversarial_regret: 0.0,
+            prior: 0.0,
+            target_probability: 0.0,
         }
     }
 }
@@ -151,10 +591,132 @@ pub struct DecisionTrace {
     pub max_regret_table: BTreeMap<String, f64>,
     /// Adversarial worst-case table: action_id -> adversarial worst utility.
     pub adversarial_table: BTreeMap<String, f64>,
+    /// Epsilon-contamination table: `action_id` -> blended expected/adversarial
+    /// utility. Empty unless the epsilon-contamination weight is non-zero or
+    /// it is a ranking criterion.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub epsilon_contamination_table: BTreeMap<String, f64>,
+    /// Adversarial regret table: `action_id` -> maximum regret over adversarial scenarios.
+    #[serde(default)]
+    pub adversarial_regret_table: BTreeMap<String, f64>,
+    /// Prior share table: `action_id` -> normalized prior. Empty unless
+    /// `action_priors` were given with a non-zero prior weight.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub prior_table: BTreeMap<String, f64>,
+    /// Target probability table: `action_id` -> probability of reaching the
+    /// input's `target`. Empty unless a target was given.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub target_probability_table: BTreeMap<String, f64>,
     /// Weights used for composite score.
     pub composite_weights: CompositeWeights,
     /// Tie-breaking rule used.
     pub tie_break_rule: String,
+    /// Normalization precision, when the input set one (`FLOAT_PRECISION`
+    /// otherwise).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub precision: Option<f64>,
+    /// Scenario probabilities before evidence: `scenario_id -> probability`.
+    /// Empty unless the evidence carried scenario likelihoods.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub scenario_priors: BTreeMap<String, f64>,
+    /// Scenario probabilities after the evidence update, as used for
+    /// expected values. Empty unless the evidence carried scenario likelihoods.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub scenario_posteriors: BTreeMap<String, f64>,
+    /// Portfolio table: portfolio ID -> member action IDs. Empty unless
+    /// portfolios were given.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub portfolios: BTreeMap<String, Vec<String>>,
+    /// Actions no other action first-order stochastically dominates, by
+    /// action ID (see `stochastic_dominance_frontier`), under the posterior
+    /// when evidence updated the scenario probabilities. Empty unless every
+    /// scenario has a probability.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub stochastic_frontier: Vec<String>,
+    /// Best worst case among actions within the regret cap, present when
+    /// `constraints.max_regret` is set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub constrained_maximin: Option<ConstrainedMaximin>,
+    /// The criteria actions were ranked by, in order, under
+    /// `RankingMode::Lexicographic`; empty when ranked by composite score.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ranking_criteria: Vec<Criterion>,
+}
+
+impl DecisionTrace {
+    /// The traced per-action scores of `criterion`.
+    #[must_use]
+    pub fn criterion_table(&self, criterion: Criterion) -> &BTreeMap<String, f64> {
+        match criterion {
+            Criterion::WorstCase => &self.worst_case_table,
+            Criterion::MinimaxRegret => &self.max_regret_table,
+            Criterion::Adversarial => &self.adversarial_table,
+            Criterion::EpsilonContamination => &self.epsilon_contamination_table,
+            Criterion::AdversarialRegret => &self.adversarial_regret_table,
+            Criterion::Prior => &self.prior_table,
+            Criterion::TargetProbability => &self.target_probability_table,
+        }
+    }
+
+    /// Names of the criteria that scored and ordered the actions: those with
+    /// a non-zero composite weight, then each lexicographic ranking criterion
+    /// in order as `lexicographic:<name>`.
+    #[must_use]
+    pub fn algorithm_set(&self) -> Vec<String> {
+        let mut set = self.composite_weights.criteria();
+        set.extend(
+            self.ranking_criteria
+                .iter()
+                .map(|criterion| format!("lexicographic:{}", criterion.name())),
+        );
+        set
+    }
+}
+
+/// Constrained maximin: the best worst case among actions whose maximum
+/// regret stays within a cap.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConstrainedMaximin {
+    /// The regret cap (`constraints.max_regret`).
+    pub regret_cap: f64,
+    /// Actions whose maximum regret is at most the cap, by action ID.
+    pub feasible: Vec<String>,
+    /// The feasible action with the highest worst case, ties broken by
+    /// action ID.
+    pub chosen: String,
+}
+
+/// Why a decision's ranking carries no real comparison.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DegenerateReason {
+    /// Only one action: it wins by default, and robustness is 1.0 for want
+    /// of a runner-up.
+    SingleAction,
+    /// Only one scenario: there is no uncertainty, so the worst case is the
+    /// expected value and no evidence can shift the decision.
+    SingleScenario,
+    /// Every action is tied for first: the recommendation is the tie-break
+    /// rule's pick, and robustness is 0.0.
+    AllScoresEqual,
+}
+
+impl DegenerateReason {
+    /// One sentence on what the decision lacks, for reports.
+    #[must_use]
+    pub fn explanation(self) -> &'static str {
+        match self {
+            DegenerateReason::SingleAction => {
+                "only one action: there is nothing to compare it with"
+            }
+            DegenerateReason::SingleScenario => {
+                "only one scenario: there is no uncertainty for evidence to resolve"
+            }
+            DegenerateReason::AllScoresEqual => {
+                "every action is tied: the recommendation comes from the tie-break rule"
+            }
+        }
+    }
 }
 
 /// Output from the decision engine.
@@ -162,10 +724,26 @@ pub struct DecisionTrace {
 pub struct DecisionOutput {
     /// Ranked actions (best first).
     pub ranked_actions: Vec<RankedAction>,
+    /// Robustness of the recommendation in [0, 1]: the gap between the top two
+    /// composite scores divided by the composite score range (1.0 for a single
+    /// action; see `degenerate`). Outputs from before it was recorded read
+    /// as 0.0.
+    #[serde(default)]
+    pub robustness: f64,
     /// SHA-256 fingerprint of the canonical input.
     pub determinism_fingerprint: String,
     /// Trace of the computation.
     pub trace: DecisionTrace,
+    /// Notes on how the input was repaired, one per (action, scenario) cell
+    /// filled by the missing-cell policy, in input order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+    /// Set when the input leaves nothing to weigh (one action, one scenario,
+    /// or every action tied, checked in that order). Robustness and flip
+    /// distances are then not informative, and value-of-information rankings
+    /// are empty.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub degenerate: Option<DegenerateReason>,
 }
 
 impl DecisionOutput {
@@ -176,6 +754,273 @@ impl DecisionOutput {
             .find(|a| a.recommended)
             .map(|a| a.action_id.as_str())
     }
+
+    /// The first `k` ranked actions, best first (fewer if there are fewer).
+    #[must_use]
+    pub fn top_k(&self, k: usize) -> Vec<&RankedAction> {
+        self.ranked_actions.iter().take(k).collect()
+    }
+
+    /// Canonical JSON bytes of this output.
+    ///
+    /// Keys are sorted and floats normalized, so this is the form to hash or
+    /// store as a golden file.
+    #[must_use]
+    pub fn to_canonical_json(&self) -> Vec<u8> {
+        crate::determinism::canonical_json(self)
+    }
+
+    /// Canonical CBOR bytes of this output, for the binary protocol.
+    ///
+    /// Uses the key ordering of [`DecisionOutput::to_canonical_json`]; see
+    /// [`canonical_cbor`](crate::determinism::canonical_cbor) for the exact rule.
+    pub fn to_canonical_cbor(&self) -> Result<Vec<u8>, crate::engine::DecisionError> {
+        crate::determinism::canonical_cbor(self)
+    }
+
+    /// Compare this output (before) with `other` (after).
+    ///
+    /// Reports the recommendation change, every action whose rank or
+    /// composite score differs (by action ID), and which criterion tables in
+    /// the trace differ. Identical outputs give an empty diff.
+    #[must_use]
+    pub fn diff(&self, other: &DecisionOutput) -> DecisionDiff {
+        let before = self.recommended_action_id();
+        let after = other.recommended_action_id();
+        let recommendation = (before != after).then(|| RecommendationChange {
+            from: before.map(str::to_string),
+            to: after.map(str::to_string),
+        });
+
+        let ranked = |output: &DecisionOutput| -> BTreeMap<String, (usize, f64)> {
+            output
+                .ranked_actions
+                .iter()
+                .map(|a| (a.action_id.clone(), (a.rank, a.composite_score)))
+                .collect()
+        };
+        let (old, new) = (ranked(self), ranked(other));
+        let mut ids: Vec<&String> = old.keys().chain(new.keys()).collect();
+        ids.sort();
+        ids.dedup();
+        let actions = ids
+            .into_iter()
+            .filter_map(|id| {
+                let (old, new) = (old.get(id), new.get(id));
+                if old == new {
+                    return None;
+                }
+                let composite_score_delta = match (old, new) {
+                    (Some(&(_, old)), Some(&(_, new))) => {
+                        crate::determinism::float_normalize(new - old)
+                    }
+                    _ => 0.0,
+                };
+                Some(ActionDelta {
+                    action_id: id.clone(),
+                    rank_before: old.map(|&(rank, _)| rank),
+                    rank_after: new.map(|&(rank, _)| rank),
+                    composite_score_delta,
+                })
+            })
+            .collect();
+
+        let (a, b) = (&self.trace, &other.trace);
+        let changed_tables = [
+            ("utility_table", a.utility_table != b.utility_table),
+            ("worst_case_table", a.worst_case_table != b.worst_case_table),
+            ("regret_table", a.regret_table != b.regret_table),
+            ("max_regret_table", a.max_regret_table != b.max_regret_table),
+            (
+                "adversarial_table",
+                a.adversarial_table != b.adversarial_table,
+            ),
+            (
+                "epsilon_contamination_table",
+                a.epsilon_contamination_table != b.epsilon_contamination_table,
+            ),
+            (
+                "adversarial_regret_table",
+                a.adversarial_regret_table != b.adversarial_regret_table,
+            ),
+            ("prior_table", a.prior_table != b.prior_table),
+            (
+                "target_probability_table",
+                a.target_probability_table != b.target_probability_table,
+            ),
+        ]
+        .into_iter()
+        .filter(|(_, changed)| *changed)
+        .map(|(name, _)| name.to_string())
+        .collect();
+
+        DecisionDiff {
+            recommendation,
+            actions,
+            changed_tables,
+        }
+    }
+
+    /// Whether this output's fingerprint is the one `input` produces.
+    ///
+    /// Recomputes the fingerprint exactly as `evaluate_decision` does
+    /// (including any `tie_break_seed`), without re-evaluating the decision.
+    #[must_use]
+    pub fn verify(&self, input: &DecisionInput) -> bool {
+        self.determinism_fingerprint == crate::engine::decision_fingerprint(input)
+    }
+
+    /// Whether `ranked_actions` and `robustness` follow from the trace.
+    ///
+    /// Re-derives every action's composite score from the trace tables and
+    /// weights, then checks that the ranked actions cover exactly the traced
+    /// actions, carry the traced scores, are ordered best first (by the
+    /// `ranking_criteria`, when traced) with ranks 1..n, the recommended
+    /// actions form a prefix starting at the winner (see
+    /// `DecisionInput::recommended_set_size`), and that exact ties follow the
+    /// recorded tie-break rule. A seeded tie-break cannot be replayed without
+    /// the seed, so under that rule tied actions may appear in any order.
+    #[must_use]
+    pub fn verify_self_consistent(&self) -> bool {
+        let trace = &self.trace;
+        let precision = trace
+            .precision
+            .unwrap_or(crate::determinism::FLOAT_PRECISION);
+        let Ok(composite) = crate::criteria::builtin_composite(
+            |criterion| trace.criterion_table(criterion),
+            &trace.composite_weights,
+            precision,
+        ) else {
+            return false;
+        };
+
+        if self.ranked_actions.len() != trace.utility_table.len() {
+            return false;
+        }
+        let lexicographic = trace.tie_break_rule == "lexicographic_by_action_id";
+        for (index, action) in self.ranked_actions.iter().enumerate() {
+            let id = &action.action_id;
+            let traced = |table: &BTreeMap<String, f64>| table.get(id).copied();
+            if !trace.utility_table.contains_key(id)
+                || action.rank != index + 1
+                || (index == 0 && !action.recommended)
+                || (action.recommended
+                    && index
+                        .checked_sub(1)
+                        .is_some_and(|i| !self.ranked_actions[i].recommended))
+                || traced(&trace.worst_case_table) != Some(action.score_worst_case)
+                || traced(&trace.max_regret_table) != Some(action.score_minimax_regret)
+                || traced(&trace.adversarial_table) != Some(action.score_adversarial)
+                || composite.get(id).copied() != Some(action.composite_score)
+            {
+                return false;
+            }
+            if let Some(prev) = index.checked_sub(1).map(|i| &self.ranked_actions[i]) {
+                // Best first: by composite score, or by the ranking criteria
+                let order = if trace.ranking_criteria.is_empty() {
+                    action.composite_score.total_cmp(&prev.composite_score)
+                } else {
+                    crate::engine::criteria_cmp(
+                        &trace.ranking_criteria,
+                        |criterion| trace.criterion_table(criterion),
+                        &prev.action_id,
+                        &action.action_id,
+                        precision,
+                    )
+                };
+                let ordered = match order {
+                    std::cmp::Ordering::Less => true,
+                    std::cmp::Ordering::Equal => {
+                        !lexicographic || prev.action_id < action.action_id
+                    }
+                    std::cmp::Ordering::Greater => false,
+                };
+                if !ordered || prev.action_id == action.action_id {
+                    return false;
+                }
+            }
+        }
+
+        let ranked: Vec<(&String, &f64)> = self
+            .ranked_actions
+            .iter()
+            .map(|a| (&a.action_id, &a.composite_score))
+            .collect();
+        crate::engine::compute_robustness(&ranked, precision).to_bits() == self.robustness.to_bits()
+    }
+}
+
+/// A decision ranked by caller-chosen criteria; see
+/// [`evaluate_with_criteria`](crate::criteria::evaluate_with_criteria).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CriteriaOutput {
+    /// Actions (and portfolios), best first; the first is recommended.
+    pub ranked_actions: Vec<CriteriaRankedAction>,
+    /// Robustness of the recommendation, as in `DecisionOutput::robustness`.
+    pub robustness: f64,
+    /// Fingerprint of the input together with the criterion names and
+    /// weights.
+    pub determinism_fingerprint: String,
+    /// Weight per criterion name, as given.
+    pub weights: BTreeMap<String, f64>,
+    /// Scores per criterion name: criterion -> `action_id` -> score.
+    pub criterion_scores: BTreeMap<String, BTreeMap<String, f64>>,
+}
+
+/// An action ranked by weighted criteria.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CriteriaRankedAction {
+    /// Action identifier.
+    pub action_id: String,
+    /// Weighted combination of the criteria's composite terms.
+    pub composite_score: f64,
+    /// Rank (1 = best).
+    pub rank: usize,
+    /// Other actions with the same composite score, sorted by ID.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tied_with: Vec<String>,
+}
+
+/// What changed between two outputs; see [`DecisionOutput::diff`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DecisionDiff {
+    /// The recommendation before and after, if it changed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recommendation: Option<RecommendationChange>,
+    /// Actions whose rank or composite score moved, sorted by action ID.
+    pub actions: Vec<ActionDelta>,
+    /// Trace tables that differ (e.g. `"regret_table"`), in trace field order.
+    pub changed_tables: Vec<String>,
+}
+
+impl DecisionDiff {
+    /// Whether the two outputs agree on everything the diff compares.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.recommendation.is_none() && self.actions.is_empty() && self.changed_tables.is_empty()
+    }
+}
+
+/// A change of recommended action.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecommendationChange {
+    /// Recommended action before (`None` if nothing was recommended).
+    pub from: Option<String>,
+    /// Recommended action after.
+    pub to: Option<String>,
+}
+
+/// How one action moved between two outputs.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActionDelta {
+    /// Action identifier.
+    pub action_id: String,
+    /// Rank before (`None` if the action was not ranked).
+    pub rank_before: Option<usize>,
+    /// Rank after (`None` if the action is no longer ranked).
+    pub rank_after: Option<usize>,
+    /// Composite score after minus before (0.0 unless ranked in both).
+    pub composite_score_delta: f64,
 }
 
 /// Flip distance for sensitivity analysis.
@@ -198,8 +1043,95 @@ pub struct VoiRanking {
     pub evoi: f64,
     /// Recommendation: "do_now", "plan_later", or "defer".
     pub recommendation: String,
-    /// Rationale for the ranking.
+    /// Rationale for the ranking (derived from `factors`).
     pub rationale: Vec<String>,
+    /// Typed factors behind `evoi`, largest contribution first; their
+    /// contributions sum to `evoi`.
+    #[serde(default)]
+    pub factors: Vec<VoiFactor>,
+}
+
+/// A scenario contributing to a VOI ranking.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VoiFactor {
+    /// Scenario ID.
+    pub scenario_id: String,
+    /// Utility gap between the top action and the runner-up in this scenario
+    /// (None when there is only one action).
+    pub flip_distance: Option<f64>,
+    /// Scenario probability, as given in the input.
+    pub probability: Option<f64>,
+    /// Marginal EVOI contributed by this scenario.
+    pub contribution: f64,
+}
+
+/// Least- and most-regretted actions in one scenario.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScenarioRegretLeaders {
+    /// Action with the lowest regret (ties go to the smallest action ID).
+    pub min_regret_action: String,
+    /// Regret of `min_regret_action`.
+    pub min_regret: f64,
+    /// Action with the highest regret (ties go to the smallest action ID).
+    pub max_regret_action: String,
+    /// Regret of `max_regret_action`.
+    pub max_regret: f64,
+}
+
+/// A new utility for one (action, scenario) cell (see `evaluate_incremental`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OutcomeChange {
+    /// Action ID.
+    pub action_id: String,
+    /// Scenario ID.
+    pub scenario_id: String,
+    /// New utility.
+    pub utility: f64,
+}
+
+impl OutcomeChange {
+    /// `input` with this change applied: every outcome for the cell takes the
+    /// new utility, or one is appended if the cell had none.
+    #[must_use]
+    pub fn apply(&self, input: &DecisionInput) -> DecisionInput {
+        let mut input = input.clone();
+        let mut found = false;
+        for (action_id, scenario_id, utility) in &mut input.outcomes {
+            if *action_id == self.action_id && *scenario_id == self.scenario_id {
+                *utility = self.utility;
+                found = true;
+            }
+        }
+        if !found {
+            input.outcomes.push((
+                self.action_id.clone(),
+                self.scenario_id.clone(),
+                self.utility,
+            ));
+        }
+        input
+    }
+}
+
+/// How often the recommendation survives randomly perturbed scenario
+/// probabilities (see `monte_carlo_robustness`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RobustnessReport {
+    /// The recommended action being tested.
+    pub recommended_action: String,
+    /// PRNG seed; the same seed and parameters give the same report.
+    pub seed: u64,
+    /// Number of perturbed probability vectors drawn.
+    pub samples: usize,
+    /// Relative perturbation band: each probability is scaled by a factor
+    /// drawn uniformly from `[1 - band, 1 + band]` before renormalizing.
+    pub band: f64,
+    /// Samples in which the recommendation still had the highest expected value.
+    pub holds: usize,
+    /// `holds / samples`, in [0, 1].
+    pub hold_rate: f64,
+    /// Action ID -> samples in which it overtook the recommendation.
+    pub challengers: BTreeMap<String, usize>,
 }
 
 /// A planned action in a regret-bounded plan.
@@ -222,6 +1154,13 @@ pub struct RegretBoundedPlan {
     pub actions: Vec<PlannedAction>,
     /// Bounded horizon.
     pub bounded_horizon: usize,
+    /// Considered but not planned now: `plan_later`/`defer` items and any
+    /// `do_now` items beyond the horizon, in VOI order.
+    #[serde(default)]
+    pub deferred: Vec<PlannedAction>,
+    /// Whether more `do_now` actions existed than the horizon allowed.
+    #[serde(default)]
+    pub horizon_truncated: bool,
 }
 
 /// Decision boundary explanation.
@@ -233,6 +1172,46 @@ pub struct DecisionBoundary {
     pub nearest_flips: Vec<FlipDistance>,
 }
 
+/// Why an action ranked where it did, relative to the recommended action.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActionExplanation {
+    /// The explained action.
+    pub action_id: String,
+    /// Its rank (1 = best).
+    pub rank: usize,
+    /// Whether it is recommended.
+    pub recommended: bool,
+    /// The top-ranked action it is compared with.
+    pub top_action: String,
+    /// Its score under each criterion in the trace, keyed by the
+    /// `CompositeWeights` field name (regrets as-is, lower is better).
+    pub scores: BTreeMap<String, f64>,
+    /// The top action's composite score minus this action's.
+    pub composite_gap: f64,
+    /// The weighted criterion that cost it the most composite score against
+    /// the top action; `None` when it is behind on none of them.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shortfall: Option<CriterionShortfall>,
+    /// Whether its maximum regret exceeds the constrained maximin cap.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub infeasible: bool,
+    /// Actions at least as good in every scenario and better in some, by
+    /// action ID.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dominated_by: Vec<String>,
+}
+
+/// How far an action trails the top action on one criterion.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CriterionShortfall {
+    /// Criterion name, as in `CompositeWeights`.
+    pub criterion: String,
+    /// How much worse its score is, in the criterion's own units (positive).
+    pub margin: f64,
+    /// How much composite score the margin costs under the weights.
+    pub weighted_margin: f64,
+}
+
 /// Referee adjudication result.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct RefereeAdjudication {
@@ -244,8 +1223,252 @@ pub struct RefereeAdjudication {
     pub boundary: DecisionBoundary,
     /// What would need to change for acceptance.
     pub what_would_change: Vec<String>,
+    /// Claimed action's max regret minus the top action's, when adjudicated
+    /// with a regret tolerance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub regret_gap: Option<f64>,
+}
+
+/// Every sensitivity analysis of one decision, computed from a single
+/// evaluation and a single set of flip distances.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DecisionReport {
+    /// The evaluated decision.
+    pub output: DecisionOutput,
+    /// Flip distances shared by the boundary and the adjudication.
+    pub flip_distances: Vec<FlipDistance>,
+    /// The decision boundary.
+    pub boundary: DecisionBoundary,
+    /// Evidence ranked by value of information.
+    pub voi: Vec<VoiRanking>,
+    /// Regret-bounded plan built from `voi`.
+    pub plan: RegretBoundedPlan,
+    /// Adjudication of the claim, if one was given.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub adjudication: Option<RefereeAdjudication>,
+    /// Why `voi` is empty, when the decision is degenerate (see
+    /// `DegenerateReason::explanation`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub voi_note: Option<String>,
+}
+
+/// A decision bundled with everything needed to re-verify it later.
+///
+/// The capsule is self-describing: it names the engine version and the
+/// criteria that produced `output`, and [`DecisionCapsule::verify`]
+/// re-evaluates `input` to confirm the output and its fingerprint
+/// bit-for-bit.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DecisionCapsule {
+    /// The evaluated input.
+    pub input: DecisionInput,
+    /// The output `evaluate_decision` produced for `input`.
+    pub output: DecisionOutput,
+    /// Version of the engine that produced `output`.
+    pub engine_version: String,
+    /// Criteria that scored and ordered `output` (see
+    /// [`DecisionTrace::algorithm_set`]).
+    pub algorithm_set: Vec<String>,
+}
+
+impl DecisionCapsule {
+    /// Evaluate `input` and bundle it with its output.
+    pub fn new(input: DecisionInput) -> Result<Self, crate::engine::DecisionError> {
+        let output = crate::engine::evaluate_decision(&input)?;
+        Ok(Self::from_parts(input, output))
+    }
+
+    /// Bundle an already evaluated decision, stamped with this engine's version.
+    #[must_use]
+    pub fn from_parts(input: DecisionInput, output: DecisionOutput) -> Self {
+        Self {
+            algorithm_set: output.trace.algorithm_set(),
+            input,
+            output,
+            engine_version: env!("CARGO_PKG_VERSION").to_string(),
+        }
+    }
+
+    /// Canonical JSON bytes of the capsule, the form to archive.
+    ///
+    /// Keys are sorted as in [`crate::determinism::canonical_json`], but
+    /// floats keep full precision rather than being normalized to 1e-9, so a
+    /// read-back capsule still verifies bit-for-bit.
+    #[must_use]
+    pub fn to_canonical_json(&self) -> Vec<u8> {
+        let value = serde_json::to_value(self).unwrap_or(serde_json::Value::Null);
+        serde_json::to_vec(&sorted_keys(value)).unwrap_or_default()
+    }
+
+    /// Read a capsule written by [`DecisionCapsule::to_canonical_json`].
+    pub fn from_canonical_json(bytes: &[u8]) -> Result<Self, CapsuleError> {
+        serde_json::from_slice(bytes).map_err(|e| CapsuleError::Encoding(e.to_string()))
+    }
+
+    /// Re-run `evaluate_decision` on the embedded input and check the result.
+    ///
+    /// Checks, in order, that the capsule was written by this engine version,
+    /// that it names the criteria the output was scored and ranked with, that
+    /// the embedded fingerprint matches the input, and that the embedded
+    /// output and a fresh evaluation agree field for field, with floats
+    /// compared by their exact bits.
+    pub fn verify(&self) -> Result<(), CapsuleError> {
+        let engine_version = env!("CARGO_PKG_VERSION");
+        if self.engine_version != engine_version {
+            return Err(CapsuleError::EngineVersionMismatch {
+                capsule: self.engine_version.clone(),
+                engine: engine_version.to_string(),
+            });
+        }
+
+        let fresh =
+            crate::engine::evaluate_decision(&self.input).map_err(CapsuleError::Evaluation)?;
+
+        let algorithm_set = fresh.trace.algorithm_set();
+        if self.algorithm_set != algorithm_set {
+            return Err(CapsuleError::AlgorithmSetMismatch {
+                expected: algorithm_set,
+                actual: self.algorithm_set.clone(),
+            });
+        }
+
+        if self.output.determinism_fingerprint != fresh.determinism_fingerprint {
+            return Err(CapsuleError::FingerprintMismatch {
+                expected: fresh.determinism_fingerprint,
+                actual: self.output.determinism_fingerprint.clone(),
+            });
+        }
+
+        let expected = serde_json::to_value(&fresh).unwrap_or_default();
+        let actual = serde_json::to_value(&self.output).unwrap_or_default();
+        let mut path = String::new();
+        if first_difference(&expected, &actual, &mut path) {
+            return Err(CapsuleError::OutputMismatch { path });
+        }
+        Ok(())
+    }
 }
 
+/// Rebuild `value` with every object's keys in sorted order.
+fn sorted_keys(value: serde_json::Value) -> serde_json::Value {
+    use serde_json::Value;
+
+    match value {
+        Value::Object(object) => {
+            let mut entries: Vec<(String, Value)> = object.into_iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+            Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| (key, sorted_keys(value)))
+                    .collect(),
+            )
+        }
+        Value::Array(items) => Value::Array(items.into_iter().map(sorted_keys).collect()),
+        scalar => scalar,
+    }
+}
+
+/// Extend `path` (a JSON pointer) to the first place `expected` and `actual`
+/// differ, visiting object keys in sorted order. Floats differ unless their
+/// bits are identical, so `-0.0` and `0.0` differ. Returns whether they differ.
+fn first_difference(
+    expected: &serde_json::Value,
+    actual: &serde_json::Value,
+    path: &mut String,
+) -> bool {
+    use serde_json::Value;
+
+    match (expected, actual) {
+        (Value::Object(expected), Value::Object(actual)) => {
+            let mut keys: Vec<&String> = expected.keys().chain(actual.keys()).collect();
+            keys.sort();
+            keys.dedup();
+            keys.into_iter().any(|key| {
+                let len = path.len();
+                path.push('/');
+                path.push_str(&key.replace('~', "~0").replace('/', "~1"));
+                let differs = match (expected.get(key), actual.get(key)) {
+                    (Some(expected), Some(actual)) => first_difference(expected, actual, path),
+                    (expected, actual) => expected != actual,
+                };
+                if !differs {
+                    path.truncate(len);
+                }
+                differs
+            })
+        }
+        (Value::Array(expected), Value::Array(actual)) if expected.len() == actual.len() => {
+            expected
+                .iter()
+                .zip(actual)
+                .enumerate()
+                .any(|(index, (expected, actual))| {
+                    let len = path.len();
+                    path.push('/');
+                    path.push_str(&index.to_string());
+                    let differs = first_difference(expected, actual, path);
+                    if !differs {
+                        path.truncate(len);
+                    }
+                    differs
+                })
+        }
+        (Value::Number(expected), Value::Number(actual)) => {
+            expected != actual
+                || expected.as_f64().map(f64::to_bits) != actual.as_f64().map(f64::to_bits)
+        }
+        _ => expected != actual,
+    }
+}
+
+/// Why a [`DecisionCapsule`] failed verification.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CapsuleError {
+    /// The capsule bytes are not a valid capsule.
+    Encoding(String),
+    /// The capsule was written by a different engine version.
+    EngineVersionMismatch { capsule: String, engine: String },
+    /// The embedded input no longer evaluates.
+    Evaluation(crate::engine::DecisionError),
+    /// The capsule names different criteria than the input is scored with.
+    AlgorithmSetMismatch {
+        expected: Vec<String>,
+        actual: Vec<String>,
+    },
+    /// The embedded fingerprint is not the input's.
+    FingerprintMismatch { expected: String, actual: String },
+    /// The embedded output differs from a fresh evaluation; `path` is a JSON
+    /// pointer to the first difference.
+    OutputMismatch { path: String },
+}
+
+impl std::fmt::Display for CapsuleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CapsuleError::Encoding(msg) => write!(f, "Invalid capsule: {msg}"),
+            CapsuleError::EngineVersionMismatch { capsule, engine } => write!(
+                f,
+                "Capsule was written by engine {capsule}, this is engine {engine}"
+            ),
+            CapsuleError::Evaluation(err) => write!(f, "Capsule input does not evaluate: {err}"),
+            CapsuleError::AlgorithmSetMismatch { expected, actual } => write!(
+                f,
+                "Capsule algorithm set {actual:?} does not match {expected:?}"
+            ),
+            CapsuleError::FingerprintMismatch { expected, actual } => write!(
+                f,
+                "Capsule fingerprint {actual} does not match input fingerprint {expected}"
+            ),
+            CapsuleError::OutputMismatch { path } => {
+                write!(f, "Capsule output differs from re-evaluation at {path}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for CapsuleError {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -269,6 +1492,8 @@ mod tests {
             id: "test_scenario".to_string(),
             probability: Some(0.5),
             adversarial: true,
+            severity: None,
+            adversary_objective: None,
         };
 
         let json = serde_json::to_string(&scenario).unwrap();
@@ -297,11 +1522,24 @@ mod tests {
                 id: "s1".to_string(),
                 probability: Some(1.0),
                 adversarial: false,
+                severity: None,
+                adversary_objective: None,
             }],
             outcomes: vec![("a1".to_string(), "s1".to_string(), 100.0)],
             constraints: None,
             evidence: None,
             meta: None,
+            action_priors: None,
+            tie_break_seed: None,
+            allow_missing_as_zero: false,
+            missing_cell_policy: MissingCellPolicy::Error,
+            recommended_set_size: None,
+            recommended_set_epsilon: None,
+            precision: None,
+            strict: false,
+            portfolios: None,
+            target: None,
+            ranking_mode: None,
         };
 
         let json = serde_json::to_string(&input).unwrap();
@@ -320,6 +1558,7 @@ mod tests {
             composite_score: 0.75,
             recommended: true,
             rank: 1,
+            tied_with: Vec::new(),
         };
 
         let json = serde_json::to_string(&action).unwrap();
@@ -353,6 +1592,7 @@ mod tests {
                     composite_score: 0.75,
                     recommended: true,
                     rank: 1,
+                    tied_with: Vec::new(),
                 },
                 RankedAction {
                     action_id: "a2".to_string(),
@@ -362,8 +1602,10 @@ mod tests {
                     composite_score: 0.65,
                     recommended: false,
                     rank: 2,
+                    tied_with: Vec::new(),
                 },
             ],
+            robustness: 1.0,
             determinism_fingerprint: "abc123".to_string(),
             trace: DecisionTrace {
                 utility_table: BTreeMap::new(),
@@ -371,12 +1613,32 @@ mod tests {
                 regret_table: BTreeMap::new(),
                 max_regret_table: BTreeMap::new(),
                 adversarial_table: BTreeMap::new(),
+                epsilon_contamination_table: BTreeMap::new(),
+                adversarial_regret_table: BTreeMap::new(),
+                prior_table: BTreeMap::new(),
+                target_probability_table: BTreeMap::new(),
                 composite_weights: CompositeWeights::default(),
                 tie_break_rule: "lexicographic_by_action_id".to_string(),
+                precision: None,
+                scenario_priors: BTreeMap::new(),
+                scenario_posteriors: BTreeMap::new(),
+                portfolios: BTreeMap::new(),
+                stochastic_frontier: Vec::new(),
+                constrained_maximin: None,
+                ranking_criteria: Vec::new(),
             },
+            warnings: Vec::new(),
+            degenerate: None,
         };
 
         assert_eq!(output.recommended_action_id(), Some("a1"));
+
+        // Outputs serialized before `robustness` existed still parse
+        let mut json = serde_json::to_value(&output).unwrap();
+        json.as_object_mut().unwrap().remove("robustness");
+        let parsed: DecisionOutput = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed.robustness, 0.0);
+        assert_eq!(parsed.ranked_actions, output.ranked_actions);
     }
 
     #[test]
@@ -113,7 +113,8 @@ impl<T: Serialize> WasmSuccess<T> {
 /// # Error Codes
 ///
 /// - `E_SCHEMA`: Invalid JSON schema (malformed JSON or missing required fields)
-/// - `E_INVALID_INPUT`: Invalid input values (e.g., empty actions, invalid probabilities)
+/// - `E_INVALID_INPUT`: Invalid input values (e.g., empty actions, invalid probabilities);
+///   `details.decision_error` carries the specific [`DecisionError::code`](crate::engine::DecisionError::code)
 /// - `E_INTERNAL`: Internal error (should not happen in normal operation)
 ///
 /// # Example (JavaScript)
@@ -170,9 +171,12 @@ fn evaluate_decision_json_impl(input_json: &str) -> String {
             success.to_json()
         }
         Err(e) => {
-            let error = WasmError::new(
+            let error = WasmError::with_details(
                 "E_INVALID_INPUT",
                 &e.to_string(),
+                serde_json::json!({
+                    "decision_error": e.code()
+                }),
             );
             error.to_json()
         }
@@ -219,8 +223,8 @@ fn compute_fingerprint_json_impl(input_json: &str) -> String {
     };
 
     // Compute fingerprint
-    let fingerprint = crate::determinism::compute_fingerprint(&input);
-    
+    let fingerprint = crate::engine::input_fingerprint(&input);
+
     let success = WasmSuccess::new(serde_json::json!({
         "fingerprint": fingerprint
     }));
@@ -296,6 +300,26 @@ mod tests {
         assert_eq!(parsed["error"]["code"], "E_INVALID_INPUT");
     }
 
+    #[test]
+    fn test_evaluate_decision_json_duplicate_ids() {
+        let input = r#"{
+            "actions": [{"id": "buy", "label": "Buy"}, {"id": "buy", "label": "Buy again"}],
+            "scenarios": [{"id": "bull", "probability": 1.0, "adversarial": false}],
+            "outcomes": [["buy", "bull", 100]]
+        }"#;
+
+        let result = evaluate_decision_json(input);
+        let parsed: serde_json::Value = serde_json::from_str(&result).unwrap();
+
+        assert!(!parsed["ok"].as_bool().unwrap());
+        assert_eq!(parsed["error"]["code"], "E_INVALID_INPUT");
+        assert_eq!(parsed["error"]["message"], "Duplicate action id: buy");
+        assert_eq!(
+            parsed["error"]["details"]["decision_error"],
+            "E_DUPLICATE_ID"
+        );
+    }
+
     #[test]
     fn test_compute_fingerprint_json() {
         let input = r#"{
@@ -25,6 +25,20 @@ pub fn deterministic_event_logs_match(
     left == right
 }
 
+/// Extends an event hash chain by one link.
+///
+/// Each link hashes the previous link followed by the canonical JSON encoding
+/// of the event, so a link commits to every event before it. The chain starts
+/// from an empty `previous`.
+#[must_use]
+pub fn event_chain_link(previous: &str, event: &DeterministicEvent) -> String {
+    let encoded = serde_json::to_vec(event).unwrap_or_default();
+    let mut payload = Vec::with_capacity(previous.len() + encoded.len());
+    payload.extend_from_slice(previous.as_bytes());
+    payload.extend_from_slice(&encoded);
+    canonical_hash(&payload)
+}
+
 #[must_use]
 pub fn policy_gate_rejects_undeclared_tools(
     declared_tools: &[&str],
@@ -58,6 +58,71 @@ impl ReplayState {
         }
         Ok(Self::replay(events))
     }
+
+    /// Per-event prefix hashes of the log, as checked by [`Self::replay_verified`].
+    ///
+    /// Entry `i` commits to events `0..=i` in log order.
+    #[must_use]
+    pub fn event_chain(&self) -> Vec<String> {
+        let mut previous = String::new();
+        self.events
+            .iter()
+            .map(|event| {
+                previous = invariants::event_chain_link(&previous, event);
+                previous.clone()
+            })
+            .collect()
+    }
+
+    /// Single digest committing to the whole event log.
+    ///
+    /// This is the canonical way to compare two runs cheaply: store the digest
+    /// alongside a snapshot and compare digests instead of full logs. The
+    /// chain is built from BLAKE3 over canonical JSON, so it is stable across
+    /// platforms and processes. An empty log hashes the empty payload.
+    #[must_use]
+    pub fn chain_hash(&self) -> String {
+        self.event_chain()
+            .pop()
+            .unwrap_or_else(|| invariants::canonical_hash(&[]))
+    }
+
+    /// Replays `events` while checking each one against an expected hash chain.
+    ///
+    /// The chain is folded incrementally with [`invariants::event_chain_link`],
+    /// so the first event whose running hash differs from `expected_chain` is
+    /// reported, along with its position. A chain that is longer or shorter than
+    /// the event log diverges at the first unmatched position.
+    pub fn replay_verified(
+        events: &[DeterministicEvent],
+        expected_chain: &[String],
+    ) -> Result<Self, ReplayDivergence> {
+        let mut previous = String::new();
+        for (index, event) in events.iter().enumerate() {
+            let actual = invariants::event_chain_link(&previous, event);
+            let expected = expected_chain.get(index);
+            if expected != Some(&actual) {
+                return Err(ReplayDivergence {
+                    index,
+                    event: Some(event.clone()),
+                    expected: expected.cloned(),
+                    actual: Some(actual),
+                });
+            }
+            previous = actual;
+        }
+        if let Some(expected) = expected_chain.get(events.len()) {
+            return Err(ReplayDivergence {
+                index: events.len(),
+                event: None,
+                expected: Some(expected.clone()),
+                actual: None,
+            });
+        }
+        Ok(Self {
+            events: events.to_vec(),
+        })
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -109,3 +174,47 @@ impl Display for ReplayInvariantError {
 }
 
 impl Error for ReplayInvariantError {}
+
+/// First point at which a replayed event log departs from its expected chain.
+///
+/// `event` is `None` when the log ended before the chain did; `actual` is
+/// `None` in the same case, and `expected` is `None` when the log is longer
+/// than the chain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplayDivergence {
+    pub index: usize,
+    pub event: Option<DeterministicEvent>,
+    pub expected: Option<String>,
+    pub actual: Option<String>,
+}
+
+impl ReplayDivergence {
+    /// Sequence number of the diverging event, if the log reached that far.
+    #[must_use]
+    pub fn sequence(&self) -> Option<u64> {
+        self.event.as_ref().map(|event| event.sequence)
+    }
+}
+
+impl Display for ReplayDivergence {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.event {
+            Some(event) => write!(
+                f,
+                "replay diverged at event {} (sequence {}, type {}): expected {}, got {}",
+                self.index,
+                event.sequence,
+                event.event_type,
+                self.expected.as_deref().unwrap_or("<end of chain>"),
+                self.actual.as_deref().unwrap_or("<none>"),
+            ),
+            None => write!(
+                f,
+                "replay diverged at event {}: event log ended before expected chain",
+                self.index
+            ),
+        }
+    }
+}
+
+impl Error for ReplayDivergence {}
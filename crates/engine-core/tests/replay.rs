@@ -1,6 +1,21 @@
-use engine_core::invariants::canonical_hash;
+use engine_core::invariants::{canonical_hash, event_chain_link};
 use engine_core::{DeterministicEvent, ReplayInvariantError, ReplayState, SignedPack};
 
+fn sample_events() -> Vec<DeterministicEvent> {
+    ["run_started", "tool_called", "tool_result", "run_completed"]
+        .iter()
+        .zip(1_u64..)
+        .map(|(event_type, sequence)| DeterministicEvent {
+            sequence,
+            event_type: (*event_type).into(),
+        })
+        .collect()
+}
+
+fn chain_for(events: &[DeterministicEvent]) -> Vec<String> {
+    ReplayState::replay(events).event_chain()
+}
+
 #[test]
 fn replay_is_deterministic() {
     let source = vec![
@@ -50,3 +65,66 @@ fn signed_pack_runtime_guards_delegate_to_invariants() {
     assert!(pack.delegation_snapshot_matches("snapshot-a", "snapshot-a"));
     assert!(!pack.delegation_snapshot_matches("snapshot-a", "snapshot-b"));
 }
+
+#[test]
+fn replay_verified_accepts_identical_chain() {
+    let source = sample_events();
+    let chain = chain_for(&source);
+
+    let replayed = ReplayState::replay_verified(&source, &chain).expect("chain must match");
+    assert_eq!(replayed.events, source);
+}
+
+#[test]
+fn replay_verified_pinpoints_first_divergent_event() {
+    let recorded = sample_events();
+    let chain = chain_for(&recorded);
+
+    let mut replayed = recorded.clone();
+    replayed[2].event_type = "tool_failed".into();
+
+    let err = ReplayState::replay_verified(&replayed, &chain).expect_err("divergence must fail");
+    assert_eq!(err.index, 2);
+    assert_eq!(err.sequence(), Some(3));
+    assert_eq!(err.event, Some(replayed[2].clone()));
+    assert_eq!(err.expected.as_ref(), Some(&chain[2]));
+    assert_ne!(err.actual, err.expected);
+}
+
+#[test]
+fn replay_verified_rejects_truncated_log() {
+    let recorded = sample_events();
+    let chain = chain_for(&recorded);
+
+    let err =
+        ReplayState::replay_verified(&recorded[..3], &chain).expect_err("missing events must fail");
+    assert_eq!(err.index, 3);
+    assert_eq!(err.event, None);
+}
+
+#[test]
+fn chain_hash_is_stable_and_order_sensitive() {
+    let first = ReplayState::replay(&sample_events());
+    let second = ReplayState::replay(&sample_events());
+    assert_eq!(first.chain_hash(), second.chain_hash());
+    assert_eq!(first.event_chain(), second.event_chain());
+
+    let mut reordered = sample_events();
+    reordered.swap(1, 2);
+    let reordered = ReplayState::replay(&reordered);
+    assert_ne!(first.chain_hash(), reordered.chain_hash());
+    assert_eq!(first.event_chain()[0], reordered.event_chain()[0]);
+}
+
+#[test]
+fn event_chain_links_prefix_hashes() {
+    let events = sample_events();
+    let chain = ReplayState::replay(&events).event_chain();
+    assert_eq!(chain.len(), events.len());
+    assert_eq!(chain[0], event_chain_link("", &events[0]));
+    assert_eq!(chain[1], event_chain_link(&chain[0], &events[1]));
+    assert_eq!(
+        ReplayState::replay(&events).chain_hash(),
+        chain[chain.len() - 1]
+    );
+}
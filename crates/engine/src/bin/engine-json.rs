@@ -1,5 +1,11 @@
-use std::io::{self, Read};
+use std::collections::BTreeMap;
+use std::convert::Infallible;
+use std::io::{self, BufRead, Read, Write};
 
+use decision_engine::engine::{
+    compute_flip_distances, evaluate_decision, explain_decision_boundary, DecisionError,
+};
+use decision_engine::types::{DecisionBoundary, DecisionInput, DecisionOutput, FlipDistance};
 use engine::{
     policy::Policy, state::RunEvent, tools::ToolResult, Action, Engine, EngineConfig,
     ExecutionControls, RunHandle,
@@ -25,33 +31,44 @@ enum EngineRequest {
     },
     NextAction {
         run_id: String,
-        run_handle: RunHandle,
+        #[serde(default)]
+        run_handle: Option<RunHandle>,
     },
     ApplyToolResult {
         run_id: String,
-        run_handle: RunHandle,
+        #[serde(default)]
+        run_handle: Option<RunHandle>,
         tool_result: ToolResult,
     },
     PauseRun {
         run_id: String,
-        run_handle: RunHandle,
+        #[serde(default)]
+        run_handle: Option<RunHandle>,
         reason: String,
     },
     ResumeRun {
         run_id: String,
-        run_handle: RunHandle,
+        #[serde(default)]
+        run_handle: Option<RunHandle>,
     },
     CancelRun {
         run_id: String,
-        run_handle: RunHandle,
+        #[serde(default)]
+        run_handle: Option<RunHandle>,
         reason: String,
     },
     RecordCost {
         run_id: String,
-        run_handle: RunHandle,
+        #[serde(default)]
+        run_handle: Option<RunHandle>,
         step_id: String,
         cost_usd: f64,
     },
+    EvaluateDecision {
+        input: DecisionInput,
+        #[serde(default)]
+        explain: bool,
+    },
 }
 
 #[derive(Debug, Serialize)]
@@ -66,9 +83,22 @@ struct EngineResponse {
     #[serde(skip_serializing_if = "Option::is_none")]
     action: Option<Action>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    decision: Option<DecisionEnvelope>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     error: Option<String>,
 }
 
+#[derive(Debug, Serialize)]
+struct DecisionEnvelope {
+    #[serde(rename = "schemaVersion")]
+    schema_version: &'static str,
+    output: DecisionOutput,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    explanation: Option<DecisionBoundary>,
+    #[serde(rename = "flipDistances", skip_serializing_if = "Option::is_none")]
+    flip_distances: Option<Vec<FlipDistance>>,
+}
+
 #[derive(Debug, Serialize)]
 struct EventEnvelope {
     #[serde(rename = "schemaVersion")]
@@ -83,11 +113,57 @@ struct EventEnvelope {
     payload: Value,
 }
 
+/// Where run handles live between requests.
+///
+/// One-shot mode ships the handle in every request and response. In
+/// `--stream` mode the process keeps each run's handle keyed by `run_id`, so
+/// requests may omit it and responses leave it out.
+struct Session {
+    streaming: bool,
+    runs: BTreeMap<String, RunHandle>,
+}
+
+impl Session {
+    fn new(streaming: bool) -> Self {
+        Self {
+            streaming,
+            runs: BTreeMap::new(),
+        }
+    }
+
+    /// The handle to operate on: the one in the request, else the stored one.
+    fn checkout(&mut self, run_id: &str, provided: Option<RunHandle>) -> Result<RunHandle, String> {
+        match provided {
+            Some(run_handle) => Ok(run_handle),
+            None if self.streaming => self
+                .runs
+                .remove(run_id)
+                .ok_or_else(|| format!("unknown run_id: {run_id}")),
+            None => Err("run_handle is required".to_owned()),
+        }
+    }
+
+    /// Keep the handle for later requests, or hand it back to the caller.
+    fn checkin(&mut self, run_id: &str, run_handle: RunHandle) -> Option<RunHandle> {
+        if self.streaming {
+            self.runs.insert(run_id.to_owned(), run_handle);
+            None
+        } else {
+            Some(run_handle)
+        }
+    }
+}
+
 fn default_initiator() -> String {
     "runner".to_owned()
 }
 
 fn main() {
+    if std::env::args().skip(1).any(|arg| arg == "--stream") {
+        stream();
+        return;
+    }
+
     let mut input = String::new();
     if let Err(err) = io::stdin().read_to_string(&mut input) {
         emit_error(format!("failed to read stdin: {err}"));
@@ -103,8 +179,56 @@ fn main() {
     };
 
     let engine = Engine::new(EngineConfig::default());
+    let response = handle_request(&engine, &mut Session::new(false), request);
 
-    let response = match request {
+    if let Err(err) = serde_json::to_writer(io::stdout(), &response) {
+        emit_error(format!("failed to write response: {err}"));
+    }
+}
+
+/// Serve newline-delimited JSON requests until stdin closes.
+///
+/// Each non-blank line gets exactly one response line, in order. A line that
+/// fails to parse gets an error response and the session continues.
+fn stream() {
+    let engine = Engine::new(EngineConfig::default());
+    let mut session = Session::new(true);
+    let mut stdout = io::stdout().lock();
+
+    for line in io::stdin().lock().lines() {
+        let line = match line {
+            Ok(line) => line,
+            Err(err) => {
+                emit_error(format!("failed to read stdin: {err}"));
+                return;
+            }
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let response = match serde_json::from_str::<EngineRequest>(&line) {
+            Ok(request) => handle_request(&engine, &mut session, request),
+            Err(err) => error_response(format!("invalid request json: {err}")),
+        };
+
+        let written = serde_json::to_writer(&mut stdout, &response)
+            .map_err(io::Error::from)
+            .and_then(|()| stdout.write_all(b"\n"))
+            .and_then(|()| stdout.flush());
+        if written.is_err() {
+            // The reader went away; nobody is left to answer
+            return;
+        }
+    }
+}
+
+fn handle_request(
+    engine: &Engine,
+    session: &mut Session,
+    request: EngineRequest,
+) -> EngineResponse {
+    match request {
         EngineRequest::CompileWorkflow { workflow_json } => {
             match engine.compile(&workflow_json.to_string()) {
                 Ok(workflow) => EngineResponse {
@@ -113,6 +237,7 @@ fn main() {
                     run_handle: None,
                     events: vec![],
                     action: None,
+                    decision: None,
                     error: None,
                 },
                 Err(err) => error_response(err.to_string()),
@@ -130,142 +255,127 @@ fn main() {
             };
             match result {
                 Ok(mut run_handle) => {
-                    let events =
-                        drain_wrapped_events(&mut run_handle, &run_id, Some(initiator));
+                    let events = drain_wrapped_events(&mut run_handle, &run_id, Some(initiator));
                     EngineResponse {
                         ok: true,
                         workflow: None,
-                        run_handle: Some(run_handle),
+                        run_handle: session.checkin(&run_id, run_handle),
                         events,
                         action: None,
+                        decision: None,
                         error: None,
                     }
                 }
                 Err(err) => error_response(err.to_string()),
             }
         }
-        EngineRequest::NextAction {
-            run_id,
-            mut run_handle,
-        } => {
-            let action = run_handle.next_action();
-            let events = drain_wrapped_events(&mut run_handle, &run_id, None);
-            EngineResponse {
-                ok: true,
-                workflow: None,
-                run_handle: Some(run_handle),
-                events,
-                action: Some(action),
-                error: None,
-            }
+        EngineRequest::NextAction { run_id, run_handle } => {
+            run_step(session, &run_id, run_handle, |run_handle| {
+                Ok::<_, Infallible>(Some(run_handle.next_action()))
+            })
         }
         EngineRequest::ApplyToolResult {
             run_id,
-            mut run_handle,
+            run_handle,
             tool_result,
-        } => match run_handle.apply_tool_result(tool_result) {
-            Ok(()) => {
-                let events = drain_wrapped_events(&mut run_handle, &run_id, None);
-                EngineResponse {
-                    ok: true,
-                    workflow: None,
-                    run_handle: Some(run_handle),
-                    events,
-                    action: None,
-                    error: None,
-                }
-            }
-            Err(err) => error_response(err.to_string()),
-        },
+        } => run_step(session, &run_id, run_handle, |run_handle| {
+            run_handle.apply_tool_result(tool_result).map(|()| None)
+        }),
         EngineRequest::PauseRun {
             run_id,
-            mut run_handle,
+            run_handle,
             reason,
-        } => match run_handle.pause(&reason) {
-            Ok(()) => {
-                let events = drain_wrapped_events(&mut run_handle, &run_id, None);
-                EngineResponse {
-                    ok: true,
-                    workflow: None,
-                    run_handle: Some(run_handle),
-                    events,
-                    action: None,
-                    error: None,
-                }
-            }
-            Err(err) => error_response(err.to_string()),
-        },
-        EngineRequest::ResumeRun {
-            run_id,
-            mut run_handle,
-        } => match run_handle.resume() {
-            Ok(()) => {
-                let events = drain_wrapped_events(&mut run_handle, &run_id, None);
-                EngineResponse {
-                    ok: true,
-                    workflow: None,
-                    run_handle: Some(run_handle),
-                    events,
-                    action: None,
-                    error: None,
-                }
-            }
-            Err(err) => error_response(err.to_string()),
-        },
+        } => run_step(session, &run_id, run_handle, |run_handle| {
+            run_handle.pause(&reason).map(|()| None)
+        }),
+        EngineRequest::ResumeRun { run_id, run_handle } => {
+            run_step(session, &run_id, run_handle, |run_handle| {
+                run_handle.resume().map(|()| None)
+            })
+        }
         EngineRequest::CancelRun {
             run_id,
-            mut run_handle,
+            run_handle,
             reason,
-        } => match run_handle.cancel(&reason) {
-            Ok(()) => {
-                let events = drain_wrapped_events(&mut run_handle, &run_id, None);
-                EngineResponse {
-                    ok: true,
-                    workflow: None,
-                    run_handle: Some(run_handle),
-                    events,
-                    action: None,
-                    error: None,
-                }
-            }
-            Err(err) => error_response(err.to_string()),
-        },
+        } => run_step(session, &run_id, run_handle, |run_handle| {
+            run_handle.cancel(&reason).map(|()| None)
+        }),
         EngineRequest::RecordCost {
             run_id,
-            mut run_handle,
+            run_handle,
             step_id,
             cost_usd,
-        } => match run_handle.record_cost(step_id, cost_usd) {
-            Ok(()) => {
-                let events = drain_wrapped_events(&mut run_handle, &run_id, None);
-                EngineResponse {
-                    ok: true,
-                    workflow: None,
-                    run_handle: Some(run_handle),
-                    events,
-                    action: None,
-                    error: None,
-                }
-            }
-            Err(err) => {
-                // Budget exceeded still returns the handle so the caller can inspect it
-                EngineResponse {
-                    ok: false,
-                    workflow: None,
-                    run_handle: None,
-                    events: vec![],
-                    action: None,
-                    error: Some(err.to_string()),
-                }
-            }
+        } => run_step(session, &run_id, run_handle, |run_handle| {
+            run_handle.record_cost(step_id, cost_usd).map(|()| None)
+        }),
+        EngineRequest::EvaluateDecision { input, explain } => match evaluate(&input, explain) {
+            Ok(decision) => EngineResponse {
+                ok: true,
+                workflow: None,
+                run_handle: None,
+                events: vec![],
+                action: None,
+                decision: Some(decision),
+                error: None,
+            },
+            Err(err) => error_response(err.to_string()),
         },
-    };
+    }
+}
 
-    if let Err(err) = serde_json::to_writer(io::stdout(), &response) {
-        emit_error(format!("failed to write response: {err}"));
+/// Apply `step` to a run's handle and report the events it produced.
+///
+/// On failure the error is reported without the handle; in stream mode the
+/// handle is still kept, so a failed step (e.g. an exceeded budget) leaves the
+/// run inspectable by later requests.
+fn run_step<E: std::fmt::Display>(
+    session: &mut Session,
+    run_id: &str,
+    run_handle: Option<RunHandle>,
+    step: impl FnOnce(&mut RunHandle) -> Result<Option<Action>, E>,
+) -> EngineResponse {
+    let mut run_handle = match session.checkout(run_id, run_handle) {
+        Ok(run_handle) => run_handle,
+        Err(message) => return error_response(message),
+    };
+    match step(&mut run_handle) {
+        Ok(action) => {
+            let events = drain_wrapped_events(&mut run_handle, run_id, None);
+            EngineResponse {
+                ok: true,
+                workflow: None,
+                run_handle: session.checkin(run_id, run_handle),
+                events,
+                action,
+                decision: None,
+                error: None,
+            }
+        }
+        Err(err) => {
+            let _ = session.checkin(run_id, run_handle);
+            error_response(err.to_string())
+        }
     }
 }
 
+fn evaluate(input: &DecisionInput, explain: bool) -> Result<DecisionEnvelope, DecisionError> {
+    let output = evaluate_decision(input)?;
+    let (explanation, flip_distances) = if explain {
+        (
+            Some(explain_decision_boundary(input)?),
+            Some(compute_flip_distances(input)?),
+        )
+    } else {
+        (None, None)
+    };
+    Ok(DecisionEnvelope {
+        schema_version: SCHEMA_VERSION,
+        output,
+        explanation,
+        flip_distances,
+    })
+}
+
 fn error_response(message: String) -> EngineResponse {
     EngineResponse {
         ok: false,
@@ -273,6 +383,7 @@ fn error_response(message: String) -> EngineResponse {
         run_handle: None,
         events: vec![],
         action: None,
+        decision: None,
         error: Some(message),
     }
 }
@@ -335,13 +446,29 @@ fn wrap_event(
                 payload,
             }
         }
-        RunEvent::PolicyDenied { reason, .. } => EventEnvelope {
+        RunEvent::PolicyDenied {
+            reason,
+            rule_index,
+            rule_name,
+            ..
+        } => EventEnvelope {
             schema_version: SCHEMA_VERSION,
             event_id,
             run_id: run_id.to_owned(),
             event_type: "policy.denied".to_owned(),
             timestamp,
-            payload: serde_json::json!({"schemaVersion": SCHEMA_VERSION, "reason": reason}),
+            payload: serde_json::json!({"schemaVersion": SCHEMA_VERSION, "reason": reason, "ruleIndex": rule_index, "ruleName": rule_name}),
+        },
+        RunEvent::ApprovalRequested {
+            step_id,
+            capability,
+        } => EventEnvelope {
+            schema_version: SCHEMA_VERSION,
+            event_id,
+            run_id: run_id.to_owned(),
+            event_type: "policy.approval_requested".to_owned(),
+            timestamp,
+            payload: serde_json::json!({"schemaVersion": SCHEMA_VERSION, "callId": step_id, "capability": capability}),
         },
         RunEvent::ArtifactEmitted { patch, .. } => EventEnvelope {
             schema_version: SCHEMA_VERSION,
@@ -392,6 +519,17 @@ fn wrap_event(
             timestamp,
             payload: serde_json::json!({"schemaVersion": SCHEMA_VERSION, "status": "failed", "error": {"message": reason}}),
         },
+        RunEvent::ClockRead {
+            run_elapsed_ms,
+            step_elapsed_ms,
+        } => EventEnvelope {
+            schema_version: SCHEMA_VERSION,
+            event_id,
+            run_id: run_id.to_owned(),
+            event_type: "clock.read".to_owned(),
+            timestamp,
+            payload: serde_json::json!({"schemaVersion": SCHEMA_VERSION, "runElapsedMs": run_elapsed_ms, "stepElapsedMs": step_elapsed_ms}),
+        },
         RunEvent::RunCreated => EventEnvelope {
             schema_version: SCHEMA_VERSION,
             event_id,
@@ -0,0 +1,73 @@
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock, PoisonError};
+use std::time::{Duration, Instant};
+
+/// Monotonic time source for run deadlines.
+///
+/// Readings are offsets from an arbitrary origin; only differences between
+/// readings from the same clock are meaningful. Every step and run timeout
+/// check reads through a `Clock`, so injecting a [`MockClock`] makes timeout
+/// behaviour deterministic in tests and replays.
+pub trait Clock: Debug + Send + Sync {
+    fn now(&self) -> Duration;
+}
+
+/// Real monotonic time, measured from the first reading in this process.
+///
+/// Readings restart after a process restart, so runs persist the time they
+/// have used rather than readings.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Duration {
+        static ORIGIN: OnceLock<Instant> = OnceLock::new();
+        ORIGIN.get_or_init(Instant::now).elapsed()
+    }
+}
+
+/// A clock that only moves when told to.
+///
+/// Clones share the same reading, so a test can keep one clone and advance
+/// the time seen by the run handle that holds another.
+#[derive(Debug, Clone, Default)]
+pub struct MockClock {
+    now: Arc<Mutex<Duration>>,
+}
+
+impl MockClock {
+    #[must_use]
+    pub fn new(start: Duration) -> Self {
+        Self {
+            now: Arc::new(Mutex::new(start)),
+        }
+    }
+
+    /// Move the clock forward by `by`, saturating at `Duration::MAX`.
+    pub fn advance(&self, by: Duration) {
+        let mut now = self.reading();
+        *now = now.saturating_add(by);
+    }
+
+    /// Set the reading to `to`.
+    pub fn set(&self, to: Duration) {
+        *self.reading() = to;
+    }
+
+    fn reading(&self) -> MutexGuard<'_, Duration> {
+        // A panic while holding the lock cannot leave a Duration half-written
+        self.now.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> Duration {
+        *self.reading()
+    }
+}
+
+/// The clock runs use unless another is injected.
+#[must_use]
+pub fn system_clock() -> Arc<dyn Clock> {
+    Arc::new(SystemClock)
+}
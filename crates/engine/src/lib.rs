@@ -1,24 +1,31 @@
 pub mod artifacts;
 pub mod capsule;
+pub mod clock;
+pub mod lint;
 pub mod policy;
 pub mod state;
 pub mod tools;
 pub mod workflow;
 
 use std::collections::VecDeque;
+use std::sync::Arc;
 use std::time::Duration;
 
 use anyhow::Context;
+use decision_engine::determinism::{canonical_json, stable_hash};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+use crate::clock::Clock;
+use crate::lint::LintFinding;
 use crate::policy::{Capability, Decision, Policy};
 use crate::state::{RunEvent, RunStatus, StateTransitionError};
 use crate::tools::{ToolCall, ToolResult};
-use crate::workflow::{StepKind, Workflow};
+use crate::workflow::{StepKind, Workflow, WORKFLOW_SCHEMA_VERSION};
 
-/// Maximum number of pending events before we reject further actions.
-const MAX_PENDING_EVENTS: usize = 10_000;
+/// Maximum number of pending events; past it the oldest is evicted (see
+/// `RunHandle::set_event_sink`).
+pub const MAX_PENDING_EVENTS: usize = 10_000;
 
 /// Maximum workflow JSON payload size (16 MiB).
 const MAX_WORKFLOW_SIZE: usize = 16 * 1024 * 1024;
@@ -31,6 +38,7 @@ pub struct EngineConfig {
 #[derive(Debug, Clone)]
 pub struct Engine {
     config: EngineConfig,
+    clock: Arc<dyn Clock>,
 }
 
 #[derive(Debug, Error)]
@@ -41,19 +49,24 @@ pub enum EngineError {
     Transition(#[from] StateTransitionError),
     #[error("budget exceeded: spent {spent:.4} of {limit:.4} USD")]
     BudgetExceeded { spent: f64, limit: f64 },
+    #[error("budget infeasible: steps estimated at {estimated:.4} reach the {limit:.4} USD limit")]
+    BudgetInfeasible { estimated: f64, limit: f64 },
     #[error("step timeout: step {step_id} exceeded {timeout_ms}ms")]
     StepTimeout { step_id: String, timeout_ms: u64 },
     #[error("run timeout: elapsed {elapsed_ms}ms exceeds {limit_ms}ms")]
     RunTimeout { elapsed_ms: u64, limit_ms: u64 },
+    #[error("workflow schema {from} cannot be migrated to {to}")]
+    IncompatibleSchema { from: String, to: String },
 }
 
 /// Controls that govern execution behaviour for a run.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ExecutionControls {
     /// Maximum number of steps before the run is automatically stopped.
     #[serde(default)]
     pub max_steps: Option<usize>,
-    /// Per-step timeout. If a step exceeds this, the run fails.
+    /// Per-step timeout, from when a tool call is issued until its result is
+    /// applied. If a step exceeds this, the run fails.
     #[serde(default)]
     pub step_timeout: Option<Duration>,
     /// Total run timeout. If the run exceeds this, it is cancelled.
@@ -80,14 +93,14 @@ impl Default for ExecutionControls {
 }
 
 /// Tracks budget consumption for a run.
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
 pub struct BudgetTracker {
     pub spent_usd: f64,
     pub reserved_usd: f64,
     pub step_costs: Vec<StepCost>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct StepCost {
     pub step_id: String,
     pub cost_usd: f64,
@@ -117,6 +130,46 @@ impl BudgetTracker {
             cost_usd: actual_cost,
         });
     }
+
+    /// Total committed cost of `step_id`, summing repeated charges.
+    #[must_use]
+    pub fn cost_of(&self, step_id: &str) -> f64 {
+        self.step_costs
+            .iter()
+            .filter(|c| c.step_id == step_id)
+            .map(|c| c.cost_usd)
+            .sum()
+    }
+
+    /// The single most expensive charge; the earliest one on a tie.
+    #[must_use]
+    pub fn most_expensive_step(&self) -> Option<&StepCost> {
+        self.step_costs
+            .iter()
+            .reduce(|best, c| if c.cost_usd > best.cost_usd { c } else { best })
+    }
+
+    /// The first charge after which cumulative spend reached `limit`.
+    ///
+    /// Uses the same `>=` test as `RunHandle::record_cost`, so this is the
+    /// charge that paused the run on `BudgetExceeded`.
+    #[must_use]
+    pub fn overspend_step(&self, limit: f64) -> Option<&StepCost> {
+        let mut spent = 0.0;
+        self.step_costs.iter().find(|c| {
+            spent += c.cost_usd;
+            spent >= limit
+        })
+    }
+}
+
+/// Receives the events a run evicts from its full pending queue.
+///
+/// Without a sink evicted events are lost, though `RunHandle::events_dropped`
+/// still counts them.
+pub trait EventSink: std::fmt::Debug + Send + Sync {
+    /// Take one evicted event; called oldest first.
+    fn spill(&self, event: RunEvent);
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -129,6 +182,60 @@ pub struct RunHandle {
     controls: ExecutionControls,
     budget: BudgetTracker,
     steps_executed: usize,
+    #[serde(default)]
+    approved: Vec<Capability>,
+    /// Capability the run is waiting on, once its `ApprovalRequested` event
+    /// has been emitted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    awaiting_approval: Option<Capability>,
+    /// Time source for deadline checks; not serialized, so a deserialized
+    /// handle reads the system clock until `set_clock` says otherwise.
+    #[serde(skip, default = "clock::system_clock")]
+    clock: Arc<dyn Clock>,
+    /// Time the run has used, if it has a run timeout. Elapsed time rather
+    /// than a clock reading, which means nothing to another process or clock.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    run_elapsed: Option<Duration>,
+    /// Time the pending tool call has taken, if steps have a timeout.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    step_elapsed: Option<Duration>,
+    /// Reading of `clock` the elapsed times were last brought up to; not
+    /// serialized.
+    #[serde(skip)]
+    last_reading: Option<Duration>,
+    /// Where evicted events go; not serialized.
+    #[serde(skip)]
+    event_sink: Option<Arc<dyn EventSink>>,
+    /// Events evicted from the full pending queue, spilled or not.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    events_dropped: u64,
+}
+
+// serde's skip_serializing_if passes the field by reference
+#[allow(clippy::trivially_copy_pass_by_ref)]
+fn is_zero(count: &u64) -> bool {
+    *count == 0
+}
+
+/// Durable state of a run, without the transient event queue.
+///
+/// Two handles in the same logical state produce equal snapshots regardless of
+/// when their events were drained, so this is the form to persist.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunSnapshot {
+    pub workflow: Workflow,
+    pub policy: Policy,
+    pub status: RunStatus,
+    pub current_step: usize,
+    pub controls: ExecutionControls,
+    pub budget: BudgetTracker,
+    pub steps_executed: usize,
+    #[serde(default)]
+    pub approved: Vec<Capability>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_elapsed: Option<Duration>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub step_elapsed: Option<Duration>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -136,16 +243,45 @@ pub struct RunHandle {
 pub enum Action {
     ToolCall(ToolCall),
     EmitArtifact(crate::artifacts::Patch),
+    /// The policy prompts for this capability; call `RunHandle::approve` or `cancel`.
+    AwaitApproval {
+        capability: Capability,
+    },
     Done,
-    Paused { reason: String },
-    Cancelled { reason: String },
-    Error { message: String },
+    Paused {
+        reason: String,
+    },
+    Cancelled {
+        reason: String,
+    },
+    Error {
+        message: String,
+    },
+}
+
+/// `(major, minor, patch)` of a `MAJOR.MINOR.PATCH` schema version, if it is one.
+fn parse_schema_version(version: &str) -> Option<(u32, u32, u32)> {
+    let mut parts = version.split('.').map(|part| part.parse::<u32>().ok());
+    match (parts.next()?, parts.next()?, parts.next()?, parts.next()) {
+        (Some(major), Some(minor), Some(patch), None) => Some((major, minor, patch)),
+        _ => None,
+    }
 }
 
 impl Engine {
     #[must_use]
     pub fn new(config: EngineConfig) -> Self {
-        Self { config }
+        Self {
+            config,
+            clock: clock::system_clock(),
+        }
+    }
+
+    /// Use `clock` for the deadlines of every run this engine starts.
+    #[must_use]
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
     }
 
     pub fn compile(&self, workflow_dsl_or_json: &str) -> Result<Workflow, EngineError> {
@@ -155,7 +291,7 @@ impl Engine {
                 MAX_WORKFLOW_SIZE
             )));
         }
-        serde_json::from_str::<Workflow>(workflow_dsl_or_json)
+        let workflow = serde_json::from_str::<Workflow>(workflow_dsl_or_json)
             .with_context(|| {
                 if self.config.strict_schema {
                     "strict schema validation rejected workflow"
@@ -163,7 +299,61 @@ impl Engine {
                     "failed to parse workflow JSON"
                 }
             })
-            .map_err(|err| EngineError::Parse(err.to_string()))
+            .map_err(|err| EngineError::Parse(err.to_string()))?;
+        self.migrate_workflow(workflow, WORKFLOW_SCHEMA_VERSION)
+    }
+
+    /// Upgrade a workflow authored under an older schema to `to_version`.
+    ///
+    /// Minor- and patch-version bumps within the same major are accepted and
+    /// recorded in `migrated_from`; anything else (major change, downgrade of
+    /// any component, or a version that is not `MAJOR.MINOR.PATCH`) is rejected
+    /// so long-lived runs never execute under semantics they were not written for.
+    pub fn migrate_workflow(
+        &self,
+        mut workflow: Workflow,
+        to_version: &str,
+    ) -> Result<Workflow, EngineError> {
+        let from_version = workflow.schema_version.clone();
+        let forward = match (
+            parse_schema_version(&from_version),
+            parse_schema_version(to_version),
+        ) {
+            (Some(from), Some(to)) => from.0 == to.0 && from <= to,
+            _ => false,
+        };
+        if !forward {
+            return Err(EngineError::IncompatibleSchema {
+                from: from_version,
+                to: to_version.to_owned(),
+            });
+        }
+        if from_version == to_version {
+            return Ok(workflow);
+        }
+        workflow.migrated_from.get_or_insert(from_version);
+        to_version.clone_into(&mut workflow.schema_version);
+        Ok(workflow)
+    }
+
+    /// Statically check a workflow against a policy; see [`lint::lint_workflow`].
+    #[must_use]
+    pub fn lint(&self, workflow: &Workflow, policy: &Policy) -> Vec<LintFinding> {
+        lint::lint_workflow(workflow, policy)
+    }
+
+    /// Sum of the workflow's step cost estimates, in USD.
+    ///
+    /// Steps without an estimate count as free; estimates that are negative
+    /// or not finite are ignored, as `BudgetTracker::reserve` ignores them.
+    #[must_use]
+    pub fn estimate_total_cost(&self, workflow: &Workflow) -> f64 {
+        workflow
+            .steps
+            .iter()
+            .filter_map(|step| step.estimated_cost_usd)
+            .filter(|cost| cost.is_finite() && *cost >= 0.0)
+            .sum()
     }
 
     pub fn start_run(&self, workflow: Workflow, policy: Policy) -> Result<RunHandle, EngineError> {
@@ -176,6 +366,18 @@ impl Engine {
         policy: Policy,
         controls: ExecutionControls,
     ) -> Result<RunHandle, EngineError> {
+        // Refuse a run that cannot finish within budget before it burns steps;
+        // `record_cost` pauses once spending reaches the limit, so an estimate
+        // that merely reaches it is infeasible too
+        let estimated = self.estimate_total_cost(&workflow);
+        if let Some(limit) = controls.budget_limit_usd {
+            if estimated >= limit {
+                return Err(EngineError::BudgetInfeasible { estimated, limit });
+            }
+        }
+        let mut budget = BudgetTracker::default();
+        budget.reserve(estimated);
+        let run_elapsed = controls.run_timeout.map(|_| Duration::ZERO);
         let mut handle = RunHandle {
             workflow,
             policy,
@@ -183,15 +385,106 @@ impl Engine {
             current_step: 0,
             pending_events: VecDeque::new(),
             controls,
-            budget: BudgetTracker::default(),
+            budget,
             steps_executed: 0,
+            approved: Vec::new(),
+            awaiting_approval: None,
+            clock: Arc::clone(&self.clock),
+            run_elapsed,
+            step_elapsed: None,
+            last_reading: None,
+            event_sink: None,
+            events_dropped: 0,
         };
         handle.transition(RunStatus::Running)?;
+        if handle.has_deadlines() {
+            handle.read_clock();
+        }
         Ok(handle)
     }
 }
 
 impl RunHandle {
+    /// Capture the durable state of the run; pending events are not included.
+    #[must_use]
+    pub fn snapshot(&self) -> RunSnapshot {
+        RunSnapshot {
+            workflow: self.workflow.clone(),
+            policy: self.policy.clone(),
+            status: self.status.clone(),
+            current_step: self.current_step,
+            controls: self.controls.clone(),
+            budget: self.budget.clone(),
+            steps_executed: self.steps_executed,
+            approved: self.approved.clone(),
+            run_elapsed: self.run_elapsed,
+            step_elapsed: self.step_elapsed,
+        }
+    }
+
+    /// Rebuild a run from a snapshot, with an empty event queue.
+    ///
+    /// The run reads the system clock; call `set_clock` to replay it against
+    /// another one. A run with deadlines resumes counting from its first
+    /// reading after the restore (logged as its only pending event), so time
+    /// spent persisted never counts against it. It has no event sink and no
+    /// dropped events.
+    #[must_use]
+    pub fn restore(snapshot: RunSnapshot) -> Self {
+        let mut run = Self {
+            workflow: snapshot.workflow,
+            policy: snapshot.policy,
+            status: snapshot.status,
+            current_step: snapshot.current_step,
+            pending_events: VecDeque::new(),
+            controls: snapshot.controls,
+            budget: snapshot.budget,
+            steps_executed: snapshot.steps_executed,
+            approved: snapshot.approved,
+            awaiting_approval: None,
+            clock: clock::system_clock(),
+            run_elapsed: snapshot.run_elapsed,
+            step_elapsed: snapshot.step_elapsed,
+            last_reading: None,
+            event_sink: None,
+            events_dropped: 0,
+        };
+        if run.has_deadlines() {
+            run.read_clock();
+        }
+        run
+    }
+
+    /// Read deadlines from `clock` from now on.
+    ///
+    /// Time up to now is counted on the old clock, and counting continues
+    /// from a first reading of the new one.
+    pub fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        let timed = self.has_deadlines();
+        if timed {
+            self.read_clock();
+        }
+        self.clock = clock;
+        self.last_reading = None;
+        if timed {
+            self.read_clock();
+        }
+    }
+
+    /// Hand events evicted from the full pending queue to `sink` from now
+    /// on, instead of discarding them.
+    pub fn set_event_sink(&mut self, sink: Arc<dyn EventSink>) {
+        self.event_sink = Some(sink);
+    }
+
+    /// How many events have been evicted because more than
+    /// `MAX_PENDING_EVENTS` were pending; non-zero means a consumer that only
+    /// drains the queue has missed some.
+    #[must_use]
+    pub fn events_dropped(&self) -> u64 {
+        self.events_dropped
+    }
+
     #[must_use]
     pub fn status(&self) -> &RunStatus {
         &self.status
@@ -234,12 +527,18 @@ impl RunHandle {
         Ok(())
     }
 
+    /// Approve a capability the policy prompted for, for the rest of the run.
+    pub fn approve(&mut self, capability: Capability) {
+        if self.awaiting_approval.as_ref() == Some(&capability) {
+            self.awaiting_approval = None;
+        }
+        if !self.approved.contains(&capability) {
+            self.approved.push(capability);
+        }
+    }
+
     /// Record a cost against the run's budget and check the budget limit.
-    pub fn record_cost(
-        &mut self,
-        step_id: String,
-        cost_usd: f64,
-    ) -> Result<(), EngineError> {
+    pub fn record_cost(&mut self, step_id: String, cost_usd: f64) -> Result<(), EngineError> {
         self.budget.commit(step_id, cost_usd);
 
         if let Some(limit) = self.controls.budget_limit_usd {
@@ -290,7 +589,19 @@ impl RunHandle {
             }
         }
 
-        let Some(step) = self.workflow.steps.get(self.current_step) else {
+        if let Err(err) = self.check_deadlines() {
+            return if matches!(err, EngineError::RunTimeout { .. }) {
+                Action::Cancelled {
+                    reason: err.to_string(),
+                }
+            } else {
+                Action::Error {
+                    message: err.to_string(),
+                }
+            };
+        }
+
+        let Some(step) = self.workflow.steps.get(self.current_step).cloned() else {
             if self.transition(RunStatus::Completed).is_err() {
                 return Action::Error {
                     message: "unable to complete run".to_owned(),
@@ -304,7 +615,20 @@ impl RunHandle {
                 let required_capabilities = vec![Capability::ToolUse {
                     name: tool.name.clone(),
                 }];
-                if let Some(reason) = self.first_denied_reason(&required_capabilities) {
+                let decision = self.first_blocking_decision(&required_capabilities);
+                if let Some((capability, Decision::Prompt, _)) = decision {
+                    // Announce each pending approval once, however often the
+                    // caller polls before answering it
+                    if self.awaiting_approval.as_ref() != Some(&capability) {
+                        self.push_event(RunEvent::ApprovalRequested {
+                            step_id: step.id.clone(),
+                            capability: capability.clone(),
+                        });
+                        self.awaiting_approval = Some(capability.clone());
+                    }
+                    return Action::AwaitApproval { capability };
+                }
+                if let Some((_, Decision::Deny(reason), rule_index)) = decision {
                     let message = format!("policy denied tool call {}: {reason}", tool.name);
                     self.push_event(RunEvent::PolicyDenied {
                         step_id: step.id.clone(),
@@ -315,6 +639,9 @@ impl RunHandle {
                             input: input.clone(),
                         },
                         reason: reason.clone(),
+                        rule_index,
+                        rule_name: rule_index
+                            .and_then(|index| self.policy.rules[index].name.clone()),
                     });
                     let _ = self.transition(RunStatus::Failed {
                         reason: message.clone(),
@@ -322,6 +649,11 @@ impl RunHandle {
                     return Action::Error { message };
                 }
 
+                if self.controls.step_timeout.is_some() && self.step_elapsed.is_none() {
+                    // Re-issuing a pending call does not extend its deadline
+                    self.read_clock();
+                    self.step_elapsed = Some(Duration::ZERO);
+                }
                 self.push_event(RunEvent::ToolCallRequested {
                     step_id: step.id.clone(),
                     call: ToolCall {
@@ -357,7 +689,9 @@ impl RunHandle {
                 to: RunStatus::Running,
             }));
         }
+        self.check_deadlines()?;
 
+        self.step_elapsed = None;
         self.push_event(RunEvent::ToolCallCompleted {
             step_id: tool_result.step_id.clone(),
             result: tool_result,
@@ -372,19 +706,98 @@ impl RunHandle {
         self.pending_events.drain(..).collect()
     }
 
-    fn first_denied_reason(&self, required_capabilities: &[Capability]) -> Option<String> {
-        for capability in required_capabilities {
-            if let Decision::Deny(reason) = self.policy.evaluate(capability) {
-                return Some(reason);
+    /// Whether a run or step deadline is running, so the clock needs reading.
+    fn has_deadlines(&self) -> bool {
+        self.run_elapsed.is_some() || self.step_elapsed.is_some()
+    }
+
+    /// Read the clock, add the time since the previous reading to the run's
+    /// and the pending step's elapsed time, and log the result as a
+    /// `ClockRead` event so a replay sees the same deadlines.
+    fn read_clock(&mut self) {
+        let now = self.clock.now();
+        let since = self
+            .last_reading
+            .map_or(Duration::ZERO, |last| now.saturating_sub(last));
+        self.last_reading = Some(now);
+        for elapsed in [&mut self.run_elapsed, &mut self.step_elapsed]
+            .into_iter()
+            .flatten()
+        {
+            *elapsed = elapsed.saturating_add(since);
+        }
+        self.push_event(RunEvent::ClockRead {
+            run_elapsed_ms: self.run_elapsed.map(duration_ms),
+            step_elapsed_ms: self.step_elapsed.map(duration_ms),
+        });
+    }
+
+    /// Enforce the run and step timeouts against the clock.
+    ///
+    /// An expired run timeout cancels the run; an expired step timeout (the
+    /// pending tool call took too long) fails it.
+    fn check_deadlines(&mut self) -> Result<(), EngineError> {
+        if !self.has_deadlines() {
+            return Ok(());
+        }
+        self.read_clock();
+        if let (Some(limit), Some(elapsed)) = (self.controls.run_timeout, self.run_elapsed) {
+            if elapsed > limit {
+                let err = EngineError::RunTimeout {
+                    elapsed_ms: duration_ms(elapsed),
+                    limit_ms: duration_ms(limit),
+                };
+                let _ = self.transition(RunStatus::Cancelled {
+                    reason: err.to_string(),
+                });
+                return Err(err);
+            }
+        }
+        if let (Some(limit), Some(elapsed)) = (self.controls.step_timeout, self.step_elapsed) {
+            if elapsed > limit {
+                let step_id = self
+                    .workflow
+                    .steps
+                    .get(self.current_step)
+                    .map(|step| step.id.clone())
+                    .unwrap_or_default();
+                let err = EngineError::StepTimeout {
+                    step_id,
+                    timeout_ms: duration_ms(limit),
+                };
+                let _ = self.transition(RunStatus::Failed {
+                    reason: err.to_string(),
+                });
+                return Err(err);
             }
         }
-        None
+        Ok(())
+    }
+
+    /// First required capability the policy does not simply allow, in order.
+    /// Prompts for capabilities already approved count as allowed.
+    fn first_blocking_decision(
+        &self,
+        required_capabilities: &[Capability],
+    ) -> Option<(Capability, Decision, Option<usize>)> {
+        required_capabilities.iter().find_map(|capability| {
+            match self.policy.evaluate_traced(capability) {
+                (Decision::Allow, _) => None,
+                (Decision::Prompt, _) if self.approved.contains(capability) => None,
+                (decision, rule_index) => Some((capability.clone(), decision, rule_index)),
+            }
+        })
     }
 
     fn push_event(&mut self, event: RunEvent) {
         if self.pending_events.len() >= MAX_PENDING_EVENTS {
-            // Drop oldest events to stay within bounds — consumers should drain regularly.
-            self.pending_events.pop_front();
+            // Evict the oldest event to stay within bounds — consumers should drain regularly.
+            if let Some(evicted) = self.pending_events.pop_front() {
+                self.events_dropped += 1;
+                if let Some(sink) = &self.event_sink {
+                    sink.spill(evicted);
+                }
+            }
         }
         self.pending_events.push_back(event);
     }
@@ -396,3 +809,39 @@ impl RunHandle {
         Ok(())
     }
 }
+
+fn duration_ms(duration: Duration) -> u64 {
+    u64::try_from(duration.as_millis()).unwrap_or(u64::MAX)
+}
+
+#[derive(Serialize)]
+struct RunIdInputs<'a> {
+    workflow: &'a Workflow,
+    policy: &'a Policy,
+    initiator: &'a str,
+}
+
+/// Derive a stable, UUID-shaped run id from what the run will execute.
+///
+/// The canonical JSON of `(workflow, policy, initiator)` is hashed with
+/// BLAKE3, so the same inputs yield the same id on every platform and across
+/// restarts, which makes replays and duplicate submissions easy to detect.
+/// The id is laid out as a version 8 (custom) UUID.
+#[must_use]
+pub fn deterministic_run_id(workflow: &Workflow, policy: &Policy, initiator: &str) -> String {
+    let hash = stable_hash(&canonical_json(&RunIdInputs {
+        workflow,
+        policy,
+        initiator,
+    }));
+    let variant = u8::from_str_radix(&hash[16..17], 16).unwrap_or(0) & 0x3 | 0x8;
+    format!(
+        "{}-{}-8{}-{:x}{}-{}",
+        &hash[0..8],
+        &hash[8..12],
+        &hash[13..16],
+        variant,
+        &hash[17..20],
+        &hash[20..32]
+    )
+}
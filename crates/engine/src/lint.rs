@@ -0,0 +1,117 @@
+use serde::{Deserialize, Serialize};
+
+use crate::artifacts::Patch;
+use crate::policy::{Capability, Decision, Policy};
+use crate::workflow::{StepId, StepKind, Workflow};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LintSeverity {
+    /// The run cannot succeed as written.
+    Error,
+    /// The run works, but part of the workflow has no effect.
+    Warning,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LintKind {
+    /// A tool step whose capability the policy denies.
+    DeniedTool,
+    /// An artifact path no later step references.
+    UnusedArtifact,
+    /// A step the run can never reach (it follows a denied tool step).
+    UnreachableStep,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LintFinding {
+    pub step_id: StepId,
+    pub severity: LintSeverity,
+    pub kind: LintKind,
+    pub message: String,
+}
+
+/// Statically check `workflow` against `policy` without running it.
+///
+/// Reports tool steps the policy denies (error), artifact paths emitted but
+/// never referenced by a later step's tool input or patch (warning), and steps
+/// after the first denied tool step, which the run never reaches (warning).
+/// Findings are sorted by step id, then kind, so the output is reproducible.
+#[must_use]
+pub fn lint_workflow(workflow: &Workflow, policy: &Policy) -> Vec<LintFinding> {
+    let mut findings = Vec::new();
+    let mut blocked_by: Option<&StepId> = None;
+
+    for (index, step) in workflow.steps.iter().enumerate() {
+        if let Some(blocker) = blocked_by {
+            findings.push(LintFinding {
+                step_id: step.id.clone(),
+                severity: LintSeverity::Warning,
+                kind: LintKind::UnreachableStep,
+                message: format!("step is unreachable: step {blocker} is denied by policy"),
+            });
+        }
+
+        match &step.kind {
+            StepKind::ToolCall { tool, .. } => {
+                let capability = Capability::ToolUse {
+                    name: tool.name.clone(),
+                };
+                if let Decision::Deny(reason) = policy.evaluate(&capability) {
+                    findings.push(LintFinding {
+                        step_id: step.id.clone(),
+                        severity: LintSeverity::Error,
+                        kind: LintKind::DeniedTool,
+                        message: format!("policy denies tool {}: {reason}", tool.name),
+                    });
+                    blocked_by.get_or_insert(&step.id);
+                }
+            }
+            StepKind::EmitArtifact { patch } => {
+                let later = &workflow.steps[index + 1..];
+                for path in artifact_paths(patch) {
+                    if !later.iter().any(|s| references_path(&s.kind, path)) {
+                        findings.push(LintFinding {
+                            step_id: step.id.clone(),
+                            severity: LintSeverity::Warning,
+                            kind: LintKind::UnusedArtifact,
+                            message: format!("artifact {path} is never referenced by a later step"),
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    findings.sort_by(|a, b| (&a.step_id, a.kind).cmp(&(&b.step_id, b.kind)));
+    findings
+}
+
+/// Distinct paths a patch writes, in first-seen order.
+fn artifact_paths(patch: &Patch) -> Vec<&str> {
+    let mut paths: Vec<&str> = Vec::new();
+    for diff in &patch.diffs {
+        if !paths.contains(&diff.path.as_str()) {
+            paths.push(&diff.path);
+        }
+    }
+    paths
+}
+
+fn references_path(kind: &StepKind, path: &str) -> bool {
+    match kind {
+        StepKind::ToolCall { input, .. } => value_mentions(input, path),
+        StepKind::EmitArtifact { patch } => patch.diffs.iter().any(|diff| diff.path == path),
+    }
+}
+
+/// Whether any string in `value` (recursively) equals `path`.
+fn value_mentions(value: &serde_json::Value, path: &str) -> bool {
+    match value {
+        serde_json::Value::String(s) => s == path,
+        serde_json::Value::Array(items) => items.iter().any(|v| value_mentions(v, path)),
+        serde_json::Value::Object(map) => map.values().any(|v| value_mentions(v, path)),
+        _ => false,
+    }
+}
@@ -3,8 +3,26 @@ use serde::{Deserialize, Serialize};
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(tag = "capability", content = "value", rename_all = "snake_case")]
 pub enum Capability {
-    ToolUse { name: String },
+    ToolUse {
+        name: String,
+    },
     EmitArtifact,
+    /// Rule-only: matches every `ToolUse` whose name fits the glob `pattern`,
+    /// where `*` matches any run of characters (e.g. `fs.*`).
+    ToolUsePattern {
+        pattern: String,
+    },
+}
+
+impl Capability {
+    fn matches_pattern(&self, requested: &Capability) -> bool {
+        match (self, requested) {
+            (Capability::ToolUsePattern { pattern }, Capability::ToolUse { name }) => {
+                glob_matches(pattern, name)
+            }
+            _ => false,
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -13,38 +31,98 @@ pub struct PolicyRule {
     pub allow: bool,
     #[serde(default)]
     pub reason: Option<String>,
+    /// Readable rule name, reported alongside the rule index when it denies.
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Hold matching capabilities for the caller's approval instead of
+    /// applying `allow`.
+    #[serde(default)]
+    pub prompt: bool,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
 pub struct Policy {
     #[serde(default)]
     pub rules: Vec<PolicyRule>,
+    /// Outcome for capabilities no rule matches.
+    #[serde(default)]
+    pub default_decision: Decision,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
 pub enum Decision {
+    #[default]
     Allow,
     Deny(String),
+    /// Hold the run until the capability is approved by the caller.
+    Prompt,
 }
 
 impl Policy {
+    /// Decide whether `requested` is allowed.
+    ///
+    /// The first exact-match rule wins; failing that, the first matching
+    /// pattern rule. Capabilities matched by no rule get `default_decision`.
     #[must_use]
     pub fn evaluate(&self, requested: &Capability) -> Decision {
+        self.evaluate_traced(requested).0
+    }
+
+    /// Decide like [`Policy::evaluate`], also returning the index in `rules`
+    /// of the rule that decided, or `None` when `default_decision` applied.
+    #[must_use]
+    pub fn evaluate_traced(&self, requested: &Capability) -> (Decision, Option<usize>) {
         self.rules
             .iter()
-            .find(|rule| rule.capability == *requested)
-            .map_or(Decision::Allow, |rule| {
-                if rule.allow {
-                    Decision::Allow
-                } else {
-                    Decision::Deny(
-                        rule.reason
-                            .clone()
-                            .unwrap_or_else(|| "capability denied".to_owned()),
-                    )
-                }
+            .position(|rule| rule.capability == *requested)
+            .or_else(|| {
+                self.rules
+                    .iter()
+                    .position(|rule| rule.capability.matches_pattern(requested))
             })
+            .map_or_else(
+                || (self.default_decision.clone(), None),
+                |index| {
+                    let rule = &self.rules[index];
+                    let decision = if rule.prompt {
+                        Decision::Prompt
+                    } else if rule.allow {
+                        Decision::Allow
+                    } else {
+                        Decision::Deny(
+                            rule.reason
+                                .clone()
+                                .unwrap_or_else(|| "capability denied".to_owned()),
+                        )
+                    };
+                    (decision, Some(index))
+                },
+            )
+    }
+}
+
+/// Match `text` against a glob where `*` stands for any (possibly empty) run
+/// of characters and every other character matches itself.
+fn glob_matches(pattern: &str, text: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = text.strip_prefix(first) else {
+        return false;
+    };
+    let mut parts = parts.peekable();
+    while let Some(part) = parts.next() {
+        if parts.peek().is_none() {
+            // Last segment after a `*` anchors at the end
+            return rest.ends_with(part);
+        }
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
     }
+    // No `*` at all: exact match
+    rest.is_empty()
 }
 
 /// Execution-level policy constraints for the state machine.
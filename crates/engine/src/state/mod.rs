@@ -3,6 +3,7 @@ use thiserror::Error;
 
 use crate::{
     artifacts::Patch,
+    policy::Capability,
     tools::{ToolCall, ToolResult},
     workflow::StepId,
 };
@@ -41,6 +42,17 @@ pub enum RunEvent {
         step_id: StepId,
         call: ToolCall,
         reason: String,
+        /// Index in `Policy::rules` of the denying rule; `None` when the
+        /// policy's default decision denied.
+        #[serde(default)]
+        rule_index: Option<usize>,
+        /// Name of the denying rule, if it has one.
+        #[serde(default)]
+        rule_name: Option<String>,
+    },
+    ApprovalRequested {
+        step_id: StepId,
+        capability: Capability,
     },
     ArtifactEmitted {
         step_id: StepId,
@@ -57,6 +69,14 @@ pub enum RunEvent {
     RunFailed {
         reason: String,
     },
+    /// The run read its clock for its deadlines; the run's and the pending
+    /// step's elapsed time as of that reading.
+    ClockRead {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        run_elapsed_ms: Option<u64>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        step_elapsed_ms: Option<u64>,
+    },
 }
 
 impl RunStatus {
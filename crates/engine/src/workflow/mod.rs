@@ -2,17 +2,34 @@ use serde::{Deserialize, Serialize};
 
 use crate::{artifacts::Patch, tools::ToolSpec};
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// Workflow schema version understood by this engine.
+pub const WORKFLOW_SCHEMA_VERSION: &str = "1.0.0";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Workflow {
     pub id: String,
     pub version: String,
+    /// Schema version the workflow was authored against.
+    #[serde(default = "default_schema_version")]
+    pub schema_version: String,
+    /// Original schema version when the workflow was migrated on load.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub migrated_from: Option<String>,
     pub steps: Vec<Step>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+fn default_schema_version() -> String {
+    WORKFLOW_SCHEMA_VERSION.to_owned()
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Step {
     pub id: StepId,
     pub kind: StepKind,
+    /// Author's estimate of what the step costs, in USD; checked against the
+    /// budget before a run starts.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub estimated_cost_usd: Option<f64>,
 }
 
 pub type StepId = String;
@@ -0,0 +1,98 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use decision_engine::engine::evaluate_decision;
+use decision_engine::types::DecisionInput;
+use serde_json::{json, Value};
+
+fn decision_input() -> Value {
+    json!({
+        "id": "cli-decision",
+        "actions": [
+            {"id": "a1", "label": "Action 1"},
+            {"id": "a2", "label": "Action 2"}
+        ],
+        "scenarios": [
+            {"id": "s1", "probability": 0.5, "adversarial": false},
+            {"id": "s2", "probability": 0.5, "adversarial": true}
+        ],
+        "outcomes": [
+            ["a1", "s1", 100.0],
+            ["a1", "s2", 20.0],
+            ["a2", "s1", 80.0],
+            ["a2", "s2", 60.0]
+        ]
+    })
+}
+
+fn run_engine_json(request: &Value) -> Value {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_engine-json"))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("spawn engine-json");
+    child
+        .stdin
+        .take()
+        .expect("stdin")
+        .write_all(request.to_string().as_bytes())
+        .expect("write request");
+    let output = child.wait_with_output().expect("wait for engine-json");
+    assert!(output.status.success());
+    serde_json::from_slice(&output.stdout).expect("response json")
+}
+
+#[test]
+fn evaluate_decision_returns_output_and_fingerprint() {
+    let input: DecisionInput = serde_json::from_value(decision_input()).expect("input");
+    let expected = evaluate_decision(&input).expect("evaluate");
+
+    let response = run_engine_json(&json!({
+        "command": "evaluate_decision",
+        "input": decision_input()
+    }));
+
+    assert_eq!(response["ok"], json!(true));
+    let decision = &response["decision"];
+    assert_eq!(decision["schemaVersion"], json!("0.1.0"));
+    assert_eq!(
+        decision["output"]["ranked_actions"][0]["action_id"],
+        json!("a2")
+    );
+    assert_eq!(
+        decision["output"]["determinism_fingerprint"],
+        json!(expected.determinism_fingerprint)
+    );
+    assert!(decision["output"]["trace"]["utility_table"].is_object());
+    assert!(decision.get("explanation").is_none());
+    assert!(decision.get("flipDistances").is_none());
+}
+
+#[test]
+fn evaluate_decision_explain_includes_boundary_and_flips() {
+    let response = run_engine_json(&json!({
+        "command": "evaluate_decision",
+        "input": decision_input(),
+        "explain": true
+    }));
+
+    assert_eq!(response["ok"], json!(true));
+    let decision = &response["decision"];
+    assert_eq!(decision["explanation"]["top_action"], json!("a2"));
+    assert!(decision["flipDistances"].is_array());
+}
+
+#[test]
+fn evaluate_decision_rejects_invalid_input() {
+    let mut input = decision_input();
+    input["outcomes"] = json!([["a1", "s1", 100.0]]);
+
+    let response = run_engine_json(&json!({
+        "command": "evaluate_decision",
+        "input": input
+    }));
+
+    assert_eq!(response["ok"], json!(false));
+    assert!(response.get("decision").is_none());
+    assert!(response["error"].as_str().is_some());
+}
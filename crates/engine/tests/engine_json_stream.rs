@@ -0,0 +1,119 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use serde_json::{json, Value};
+
+fn echo_step(id: &str, msg: &str) -> Value {
+    json!({
+        "id": id,
+        "kind": {
+            "type": "tool_call",
+            "tool": {
+                "name": "echo",
+                "description": "echo input",
+                "input_schema": {"type": "object"},
+                "output_schema": {"type": "object"}
+            },
+            "input": {"msg": msg}
+        }
+    })
+}
+
+fn tool_result(step_id: &str) -> Value {
+    json!({
+        "step_id": step_id,
+        "tool_name": "echo",
+        "output": {"ok": true},
+        "success": true,
+        "error": null
+    })
+}
+
+/// Feed `requests` to one `engine-json --stream` process, one per line.
+fn run_stream(requests: &[Value]) -> Vec<Value> {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_engine-json"))
+        .arg("--stream")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("spawn engine-json");
+    {
+        let mut stdin = child.stdin.take().expect("stdin");
+        for request in requests {
+            writeln!(stdin, "{request}").expect("write request");
+        }
+    }
+    let output = child.wait_with_output().expect("wait for engine-json");
+    assert!(output.status.success());
+    String::from_utf8(output.stdout)
+        .expect("utf-8 output")
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("response json"))
+        .collect()
+}
+
+fn event_types(response: &Value) -> Vec<&str> {
+    response["events"]
+        .as_array()
+        .expect("events")
+        .iter()
+        .map(|e| e["type"].as_str().expect("event type"))
+        .collect()
+}
+
+#[test]
+fn streamed_session_drives_a_run_without_shipping_the_handle() {
+    let workflow = json!({
+        "id": "wf-stream",
+        "version": "v0",
+        "steps": [echo_step("step-1", "one"), echo_step("step-2", "two")]
+    });
+    let compiled = run_stream(&[json!({"command": "compile_workflow", "workflow_json": workflow})]);
+    let workflow = compiled[0]["workflow"].clone();
+
+    let responses = run_stream(&[
+        json!({"command": "start_run", "workflow": workflow, "run_id": "run-1"}),
+        json!({"command": "next_action", "run_id": "run-1"}),
+        json!({"command": "apply_tool_result", "run_id": "run-1", "tool_result": tool_result("step-1")}),
+        json!({"command": "next_action", "run_id": "run-1"}),
+        json!({"command": "apply_tool_result", "run_id": "run-1", "tool_result": tool_result("step-2")}),
+        json!({"command": "next_action", "run_id": "run-1"}),
+    ]);
+
+    assert_eq!(responses.len(), 6);
+    for response in &responses {
+        assert_eq!(response["ok"], json!(true), "{response}");
+        assert!(response.get("run_handle").is_none());
+        for event in response["events"].as_array().expect("events") {
+            assert_eq!(event["schemaVersion"], json!("0.1.0"));
+        }
+    }
+    assert_eq!(event_types(&responses[0]), vec!["run.started"]);
+    assert_eq!(responses[1]["action"]["type"], json!("tool_call"));
+    assert_eq!(responses[1]["action"]["step_id"], json!("step-1"));
+    assert_eq!(responses[3]["action"]["step_id"], json!("step-2"));
+    assert_eq!(responses[5]["action"]["type"], json!("done"));
+}
+
+#[test]
+fn stream_reports_errors_and_keeps_going() {
+    let responses = run_stream(&[
+        json!("not a request"),
+        json!({"command": "next_action", "run_id": "missing"}),
+        json!({"command": "evaluate_decision", "input": {
+            "actions": [{"id": "a1", "label": "Action 1"}],
+            "scenarios": [{"id": "s1", "adversarial": false}],
+            "outcomes": [["a1", "s1", 1.0]]
+        }}),
+    ]);
+
+    assert_eq!(responses.len(), 3);
+    assert_eq!(responses[0]["ok"], json!(false));
+    assert!(responses[0]["error"]
+        .as_str()
+        .unwrap()
+        .starts_with("invalid request json"));
+    assert_eq!(responses[1]["error"], json!("unknown run_id: missing"));
+    assert_eq!(responses[2]["ok"], json!(true));
+    assert_eq!(responses[2]["decision"]["schemaVersion"], json!("0.1.0"));
+}
@@ -0,0 +1,100 @@
+use std::sync::{Arc, Mutex};
+
+use engine::{
+    policy::Policy, state::RunEvent, Engine, EngineConfig, EventSink, RunHandle, MAX_PENDING_EVENTS,
+};
+
+const WORKFLOW: &str = r#"
+{
+  "id": "wf-events",
+  "version": "v0",
+  "steps": [
+    {
+      "id": "step-1",
+      "kind": {
+        "type": "tool_call",
+        "tool": {
+          "name": "echo",
+          "description": "echo input",
+          "input_schema": {"type": "object"},
+          "output_schema": {"type": "object"}
+        },
+        "input": {"msg": "one"}
+      }
+    }
+  ]
+}
+"#;
+
+#[derive(Debug, Default)]
+struct RecordingSink {
+    events: Mutex<Vec<RunEvent>>,
+}
+
+impl EventSink for RecordingSink {
+    fn spill(&self, event: RunEvent) {
+        self.events.lock().expect("sink lock").push(event);
+    }
+}
+
+fn start_run() -> RunHandle {
+    let engine = Engine::new(EngineConfig::default());
+    let workflow = engine.compile(WORKFLOW).expect("compile");
+    engine
+        .start_run(workflow, Policy::default())
+        .expect("start")
+}
+
+/// Pause and resume `cycles` times without draining: two events per cycle.
+fn churn(run: &mut RunHandle, cycles: usize) {
+    for i in 0..cycles {
+        run.pause(&format!("pause-{i}")).expect("pause");
+        run.resume().expect("resume");
+    }
+}
+
+#[test]
+fn overflow_without_sink_counts_dropped_events() {
+    let mut run = start_run();
+    let _ = run.drain_events();
+    assert_eq!(run.events_dropped(), 0);
+
+    churn(&mut run, MAX_PENDING_EVENTS / 2 + 3);
+
+    assert_eq!(run.events_dropped(), 6);
+    let events = run.drain_events();
+    assert_eq!(events.len(), MAX_PENDING_EVENTS);
+    assert_eq!(
+        events.first(),
+        Some(&RunEvent::RunPaused {
+            reason: "pause-3".to_owned()
+        })
+    );
+}
+
+#[test]
+fn overflow_with_sink_spills_evicted_events_in_order() {
+    let mut run = start_run();
+    let sink = Arc::new(RecordingSink::default());
+    run.set_event_sink(sink.clone());
+    let _ = run.drain_events();
+
+    churn(&mut run, MAX_PENDING_EVENTS / 2 + 2);
+
+    assert_eq!(run.events_dropped(), 4);
+    let spilled = sink.events.lock().expect("sink lock").clone();
+    assert_eq!(
+        spilled,
+        vec![
+            RunEvent::RunPaused {
+                reason: "pause-0".to_owned()
+            },
+            RunEvent::RunResumed,
+            RunEvent::RunPaused {
+                reason: "pause-1".to_owned()
+            },
+            RunEvent::RunResumed,
+        ]
+    );
+    assert_eq!(run.drain_events().len(), MAX_PENDING_EVENTS);
+}
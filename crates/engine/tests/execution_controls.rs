@@ -1,8 +1,12 @@
+use std::sync::Arc;
+use std::time::Duration;
+
 use engine::{
+    clock::MockClock,
     policy::Policy,
     state::{RunEvent, RunStatus},
     tools::ToolResult,
-    Action, Engine, EngineConfig, ExecutionControls,
+    Action, BudgetTracker, Engine, EngineConfig, EngineError, ExecutionControls, RunHandle,
 };
 
 fn simple_workflow_json() -> &'static str {
@@ -81,7 +85,8 @@ fn pause_and_resume_run() {
     run.apply_tool_result(tool_result("step-1")).expect("apply");
 
     // Pause the run
-    run.pause("user requested pause").expect("pause should succeed");
+    run.pause("user requested pause")
+        .expect("pause should succeed");
     assert!(matches!(run.status(), RunStatus::Paused { .. }));
 
     // next_action should return Paused while paused
@@ -99,7 +104,9 @@ fn pause_and_resume_run() {
 
     // Verify events include pause and resume
     let events = run.drain_events();
-    let has_paused = events.iter().any(|e| matches!(e, RunEvent::RunPaused { .. }));
+    let has_paused = events
+        .iter()
+        .any(|e| matches!(e, RunEvent::RunPaused { .. }));
     let has_resumed = events.iter().any(|e| matches!(e, RunEvent::RunResumed));
     assert!(has_paused, "events should include RunPaused");
     assert!(has_resumed, "events should include RunResumed");
@@ -118,7 +125,8 @@ fn cancel_running_run() {
     run.apply_tool_result(tool_result("step-1")).expect("apply");
 
     // Cancel the run
-    run.cancel("no longer needed").expect("cancel should succeed");
+    run.cancel("no longer needed")
+        .expect("cancel should succeed");
     assert!(matches!(run.status(), RunStatus::Cancelled { .. }));
 
     // next_action should return Cancelled
@@ -254,7 +262,8 @@ fn budget_exceeded_pauses_run() {
     // Step 1
     let _ = run.next_action();
     run.apply_tool_result(tool_result("step-1")).expect("apply");
-    run.record_cost("step-1".to_owned(), 0.03).expect("record cost within budget");
+    run.record_cost("step-1".to_owned(), 0.03)
+        .expect("record cost within budget");
 
     // Record more cost that exceeds budget
     let err = run.record_cost("step-1-extra".to_owned(), 0.03);
@@ -291,6 +300,254 @@ fn budget_tracking_accumulates() {
     assert_eq!(budget.step_costs.len(), 2);
 }
 
+fn estimated_run(estimates: [f64; 3], limit: f64) -> Result<RunHandle, EngineError> {
+    let engine = Engine::new(EngineConfig::default());
+    let mut workflow = engine.compile(simple_workflow_json()).expect("compile");
+    for (step, estimate) in workflow.steps.iter_mut().zip(estimates) {
+        step.estimated_cost_usd = Some(estimate);
+    }
+    assert!((engine.estimate_total_cost(&workflow) - estimates.iter().sum::<f64>()).abs() < 1e-12);
+    let controls = ExecutionControls {
+        budget_limit_usd: Some(limit),
+        ..Default::default()
+    };
+    engine.start_run_with_controls(workflow, Policy::default(), controls)
+}
+
+#[test]
+fn estimate_over_budget_is_rejected_at_start() {
+    let err = estimated_run([0.02, 0.05, 0.01], 0.05).expect_err("infeasible budget");
+    assert!(
+        matches!(err, EngineError::BudgetInfeasible { estimated, limit }
+            if (estimated - 0.08).abs() < 1e-12 && limit == 0.05),
+        "expected BudgetInfeasible, got {err:?}"
+    );
+}
+
+#[test]
+fn estimate_at_the_limit_is_rejected_like_spending_it() {
+    // Exactly representable, so the sum lands on the limit
+    let err = estimated_run([0.25, 0.25, 0.5], 1.0).expect_err("estimate reaches the limit");
+    assert!(
+        matches!(err, EngineError::BudgetInfeasible { estimated, limit }
+            if estimated == 1.0 && limit == 1.0),
+        "expected BudgetInfeasible, got {err:?}"
+    );
+
+    // Spending that much would pause the run at its last step
+    let mut run = estimated_run([0.25, 0.25, 0.25], 1.0).expect("below the limit");
+    run.record_cost("step-1".to_owned(), 1.0)
+        .expect_err("spent the limit");
+    assert!(matches!(run.status(), RunStatus::Paused { .. }));
+}
+
+#[test]
+fn estimate_within_budget_starts_and_reserves_it() {
+    let mut run = estimated_run([0.01, 0.01, 0.02], 0.05).expect("feasible budget");
+    assert!(matches!(run.status(), RunStatus::Running));
+    assert!((run.budget().reserved_usd - 0.04).abs() < 1e-12);
+    assert!(matches!(run.next_action(), Action::ToolCall(_)));
+
+    // Actual costs draw down the reservation
+    run.record_cost("step-1".to_owned(), 0.01)
+        .expect("within budget");
+    assert!((run.budget().reserved_usd - 0.03).abs() < 1e-12);
+}
+
+fn charged(costs: &[(&str, f64)]) -> BudgetTracker {
+    let mut budget = BudgetTracker::default();
+    for &(step_id, cost) in costs {
+        budget.commit(step_id.to_owned(), cost);
+    }
+    budget
+}
+
+#[test]
+fn budget_cost_of_sums_repeated_charges() {
+    let budget = charged(&[("step-1", 0.25), ("step-2", 1.0), ("step-1", 0.5)]);
+    assert!((budget.cost_of("step-1") - 0.75).abs() < f64::EPSILON);
+    assert!((budget.cost_of("step-2") - 1.0).abs() < f64::EPSILON);
+    assert_eq!(budget.cost_of("step-3"), 0.0);
+}
+
+#[test]
+fn budget_most_expensive_step() {
+    let budget = charged(&[
+        ("step-1", 0.5),
+        ("step-2", 2.0),
+        ("step-3", 1.0),
+        ("step-4", 2.0),
+    ]);
+    let top = budget.most_expensive_step().expect("charges");
+    assert_eq!(top.step_id, "step-2");
+    assert!(BudgetTracker::default().most_expensive_step().is_none());
+}
+
+#[test]
+fn budget_overspend_step_finds_threshold_crossing() {
+    let budget = charged(&[
+        ("step-1", 0.25),
+        ("step-2", 0.5),
+        ("step-3", 0.5),
+        ("step-4", 5.0),
+    ]);
+    assert_eq!(
+        budget.overspend_step(1.0).expect("crossed").step_id,
+        "step-3"
+    );
+    // Reaching the limit exactly counts, as it does when pausing the run
+    assert_eq!(
+        budget.overspend_step(0.75).expect("crossed").step_id,
+        "step-2"
+    );
+    assert!(budget.overspend_step(100.0).is_none());
+
+    // Matches the charge that paused a real run
+    let engine = Engine::new(EngineConfig::default());
+    let workflow = engine.compile(simple_workflow_json()).expect("compile");
+    let controls = ExecutionControls {
+        budget_limit_usd: Some(0.05),
+        ..Default::default()
+    };
+    let mut run = engine
+        .start_run_with_controls(workflow, Policy::default(), controls)
+        .expect("start");
+    run.record_cost("step-1".to_owned(), 0.03)
+        .expect("within budget");
+    assert!(run.record_cost("step-2".to_owned(), 0.03).is_err());
+    let overspend = run.budget().overspend_step(0.05).expect("crossed");
+    assert_eq!(overspend.step_id, "step-2");
+}
+
+// --- Timeouts ---
+
+fn run_with_clock(controls: ExecutionControls) -> (RunHandle, MockClock) {
+    let clock = MockClock::new(Duration::from_secs(1_000));
+    let engine = Engine::new(EngineConfig::default()).with_clock(Arc::new(clock.clone()));
+    let workflow = engine.compile(simple_workflow_json()).expect("compile");
+    let run = engine
+        .start_run_with_controls(workflow, Policy::default(), controls)
+        .expect("start");
+    (run, clock)
+}
+
+#[test]
+fn step_over_its_deadline_fails_the_run() {
+    let (mut run, clock) = run_with_clock(ExecutionControls {
+        step_timeout: Some(Duration::from_secs(5)),
+        ..Default::default()
+    });
+
+    // Within the deadline
+    let _ = run.next_action();
+    clock.advance(Duration::from_secs(5));
+    run.apply_tool_result(tool_result("step-1"))
+        .expect("on time");
+
+    // Asking again does not restart the step's clock
+    let _ = run.next_action();
+    clock.advance(Duration::from_secs(3));
+    let _ = run.next_action();
+    clock.advance(Duration::from_secs(3));
+    let err = run
+        .apply_tool_result(tool_result("step-2"))
+        .expect_err("late");
+    assert!(matches!(
+        err,
+        EngineError::StepTimeout { ref step_id, timeout_ms: 5_000 } if step_id == "step-2"
+    ));
+    assert!(matches!(run.status(), RunStatus::Failed { .. }));
+    assert!(matches!(run.next_action(), Action::Error { .. }));
+}
+
+#[test]
+fn run_over_its_deadline_is_cancelled() {
+    let (mut run, clock) = run_with_clock(ExecutionControls {
+        run_timeout: Some(Duration::from_secs(60)),
+        ..Default::default()
+    });
+
+    let _ = run.next_action();
+    clock.advance(Duration::from_secs(30));
+    run.apply_tool_result(tool_result("step-1"))
+        .expect("on time");
+
+    clock.advance(Duration::from_secs(31));
+    let action = run.next_action();
+    assert!(
+        matches!(action, Action::Cancelled { ref reason } if reason.contains("61000ms")),
+        "expected Cancelled, got {action:?}"
+    );
+    assert!(matches!(run.status(), RunStatus::Cancelled { .. }));
+}
+
+#[test]
+fn restored_run_keeps_deadlines_under_injected_clock() {
+    let (mut run, clock) = run_with_clock(ExecutionControls {
+        step_timeout: Some(Duration::from_secs(5)),
+        ..Default::default()
+    });
+    let _ = run.next_action();
+    clock.advance(Duration::from_secs(3));
+    let _ = run.next_action();
+
+    // The snapshot holds the time the step has taken, not a clock reading
+    let snapshot = run.snapshot();
+    assert_eq!(snapshot.step_elapsed, Some(Duration::from_secs(3)));
+    let json = serde_json::to_string(&snapshot).expect("serialize");
+    assert!(!json.contains("1000"), "{json}");
+
+    // Time spent persisted does not count; the step's 3s carry over
+    clock.advance(Duration::from_secs(60));
+    let mut restored = RunHandle::restore(serde_json::from_str(&json).expect("deserialize"));
+    restored.set_clock(Arc::new(clock.clone()));
+    clock.advance(Duration::from_secs(3));
+    assert!(restored.apply_tool_result(tool_result("step-1")).is_err());
+    assert!(matches!(restored.status(), RunStatus::Failed { .. }));
+}
+
+#[test]
+fn clock_reads_are_logged_as_events() {
+    let (mut run, clock) = run_with_clock(ExecutionControls {
+        run_timeout: Some(Duration::from_secs(60)),
+        step_timeout: Some(Duration::from_secs(5)),
+        ..Default::default()
+    });
+    let _ = run.next_action();
+    clock.advance(Duration::from_secs(2));
+    run.apply_tool_result(tool_result("step-1"))
+        .expect("on time");
+
+    let reads: Vec<_> = run
+        .drain_events()
+        .into_iter()
+        .filter_map(|event| match event {
+            RunEvent::ClockRead {
+                run_elapsed_ms,
+                step_elapsed_ms,
+            } => Some((run_elapsed_ms, step_elapsed_ms)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        reads,
+        vec![
+            (Some(0), None),
+            (Some(0), None),
+            (Some(0), None),
+            (Some(2_000), Some(2_000)),
+        ]
+    );
+
+    // A run without deadlines never reads its clock
+    let (mut untimed, _) = run_with_clock(ExecutionControls::default());
+    let _ = untimed.next_action();
+    assert!(!untimed
+        .drain_events()
+        .iter()
+        .any(|event| matches!(event, RunEvent::ClockRead { .. })));
+}
+
 // --- Steps Executed Counter ---
 
 #[test]
@@ -437,3 +694,59 @@ fn transition_cancelled_to_running_fails() {
     let result = status.transition(&RunStatus::Running);
     assert!(result.is_err());
 }
+
+// --- Snapshot / Restore ---
+
+#[test]
+fn snapshot_ignores_pending_events() {
+    let engine = Engine::new(EngineConfig::default());
+    let workflow = engine.compile(simple_workflow_json()).expect("compile");
+    let mut run = engine
+        .start_run(workflow, Policy::default())
+        .expect("start");
+    let _ = run.next_action();
+    run.apply_tool_result(tool_result("step-1")).expect("apply");
+
+    let before = run.snapshot();
+    assert!(!run.drain_events().is_empty());
+    let after = run.snapshot();
+
+    assert_eq!(before, after);
+    assert_eq!(
+        serde_json::to_string(&before).expect("serialize"),
+        serde_json::to_string(&after).expect("serialize")
+    );
+}
+
+#[test]
+fn restore_reproduces_next_actions() {
+    let engine = Engine::new(EngineConfig::default());
+    let workflow = engine.compile(simple_workflow_json()).expect("compile");
+    let mut run = engine
+        .start_run_with_controls(
+            workflow,
+            Policy::default(),
+            ExecutionControls {
+                max_steps: Some(2),
+                ..Default::default()
+            },
+        )
+        .expect("start");
+    let _ = run.next_action();
+    run.apply_tool_result(tool_result("step-1")).expect("apply");
+
+    let json = serde_json::to_string(&run.snapshot()).expect("serialize");
+    let mut restored = RunHandle::restore(serde_json::from_str(&json).expect("deserialize"));
+    assert!(restored.drain_events().is_empty());
+    assert_eq!(restored.snapshot(), run.snapshot());
+
+    for _ in 0..2 {
+        let expected = serde_json::to_value(run.next_action()).expect("serialize");
+        let actual = serde_json::to_value(restored.next_action()).expect("serialize");
+        assert_eq!(expected, actual);
+        let _ = run.apply_tool_result(tool_result("step-2"));
+        let _ = restored.apply_tool_result(tool_result("step-2"));
+    }
+    assert_eq!(restored.snapshot(), run.snapshot());
+    assert!(matches!(restored.status(), RunStatus::Cancelled { .. }));
+}
@@ -1,6 +1,6 @@
 use engine::{
-    policy::{Capability, Policy, PolicyRule},
-    state::RunEvent,
+    policy::{Capability, Decision, Policy, PolicyRule},
+    state::{RunEvent, RunStatus},
     Action, Engine, EngineConfig,
 };
 
@@ -37,7 +37,10 @@ fn denied_capability_stops_run_deterministically() {
             },
             allow: false,
             reason: Some("tool blocked by policy".to_owned()),
+            name: Some("no-dangerous".to_owned()),
+            prompt: false,
         }],
+        ..Policy::default()
     };
 
     let mut run = engine.start_run(workflow, policy).expect("start run");
@@ -66,6 +69,8 @@ fn denied_capability_stops_run_deterministically() {
                 input: serde_json::json!({}),
             },
             reason: "tool blocked by policy".to_owned(),
+            rule_index: Some(0),
+            rule_name: Some("no-dangerous".to_owned()),
         }
     );
     assert_eq!(
@@ -75,3 +80,220 @@ fn denied_capability_stops_run_deterministically() {
         }
     );
 }
+
+fn single_tool_workflow(engine: &Engine, tool: &str) -> engine::workflow::Workflow {
+    let workflow_json = serde_json::json!({
+        "id": "wf-3",
+        "version": "v0",
+        "steps": [{
+            "id": "step-1",
+            "kind": {
+                "type": "tool_call",
+                "tool": {
+                    "name": tool,
+                    "description": tool,
+                    "input_schema": {"type": "object"},
+                    "output_schema": {"type": "object"}
+                },
+                "input": {}
+            }
+        }]
+    });
+    engine
+        .compile(&workflow_json.to_string())
+        .expect("compile workflow")
+}
+
+#[test]
+fn default_deny_policy_blocks_unlisted_tools() {
+    let engine = Engine::new(EngineConfig::default());
+    let policy: Policy = serde_json::from_value(serde_json::json!({
+        "rules": [{
+            "capability": {"capability": "tool_use", "value": {"name": "safe"}},
+            "allow": true
+        }],
+        "default_decision": {"deny": "not on the allow list"}
+    }))
+    .expect("parse policy");
+
+    assert_eq!(
+        policy.evaluate(&Capability::ToolUse {
+            name: "safe".to_owned()
+        }),
+        Decision::Allow
+    );
+
+    let mut allowed = engine
+        .start_run(single_tool_workflow(&engine, "safe"), policy.clone())
+        .expect("start run");
+    assert!(matches!(allowed.next_action(), Action::ToolCall(_)));
+
+    let mut denied = engine
+        .start_run(single_tool_workflow(&engine, "other"), policy)
+        .expect("start run");
+    assert!(matches!(
+        denied.next_action(),
+        Action::Error { message } if message == "policy denied tool call other: not on the allow list"
+    ));
+}
+
+#[test]
+fn prompt_policy_awaits_approval_before_tool_call() {
+    let engine = Engine::new(EngineConfig::default());
+    let policy = Policy {
+        default_decision: Decision::Prompt,
+        ..Policy::default()
+    };
+    let capability = Capability::ToolUse {
+        name: "deploy".to_owned(),
+    };
+
+    let mut run = engine
+        .start_run(single_tool_workflow(&engine, "deploy"), policy)
+        .expect("start run");
+    let action = run.next_action();
+    assert!(matches!(
+        action,
+        Action::AwaitApproval { capability: ref c } if *c == capability
+    ));
+    assert_eq!(run.status(), &RunStatus::Running);
+    // Polling again before answering does not request approval again
+    assert!(matches!(run.next_action(), Action::AwaitApproval { .. }));
+
+    let events = run.drain_events();
+    assert_eq!(
+        events,
+        vec![
+            RunEvent::RunStarted,
+            RunEvent::ApprovalRequested {
+                step_id: "step-1".to_owned(),
+                capability: capability.clone(),
+            },
+        ]
+    );
+
+    run.approve(capability);
+    assert!(matches!(run.next_action(), Action::ToolCall(_)));
+    assert!(matches!(
+        run.drain_events().as_slice(),
+        [RunEvent::ToolCallRequested { .. }]
+    ));
+}
+
+#[test]
+fn prompt_rule_awaits_approval_for_matching_tools() {
+    let engine = Engine::new(EngineConfig::default());
+    let policy: Policy = serde_json::from_value(serde_json::json!({
+        "rules": [{
+            "capability": {"capability": "tool_use_pattern", "value": {"pattern": "deploy.*"}},
+            "allow": true,
+            "prompt": true
+        }]
+    }))
+    .expect("parse policy");
+    let capability = Capability::ToolUse {
+        name: "deploy.prod".to_owned(),
+    };
+    assert_eq!(policy.evaluate(&capability), Decision::Prompt);
+    assert_eq!(
+        policy.evaluate(&Capability::ToolUse {
+            name: "search".to_owned()
+        }),
+        Decision::Allow
+    );
+
+    let mut run = engine
+        .start_run(single_tool_workflow(&engine, "deploy.prod"), policy)
+        .expect("start run");
+    for _ in 0..2 {
+        assert!(matches!(
+            run.next_action(),
+            Action::AwaitApproval { capability: ref c } if *c == capability
+        ));
+    }
+    let requested = run
+        .drain_events()
+        .into_iter()
+        .filter(|event| matches!(event, RunEvent::ApprovalRequested { .. }))
+        .count();
+    assert_eq!(requested, 1);
+
+    run.approve(capability);
+    assert!(matches!(run.next_action(), Action::ToolCall(_)));
+}
+
+#[test]
+fn evaluate_traced_reports_the_deciding_rule() {
+    let policy: Policy = serde_json::from_value(serde_json::json!({
+        "rules": [
+            {
+                "capability": {"capability": "tool_use", "value": {"name": "safe"}},
+                "allow": true
+            },
+            {
+                "capability": {"capability": "tool_use_pattern", "value": {"pattern": "fs.*"}},
+                "allow": false,
+                "reason": "filesystem blocked",
+                "name": "no-fs"
+            },
+            {
+                "capability": {"capability": "tool_use", "value": {"name": "fs.read"}},
+                "allow": false
+            }
+        ]
+    }))
+    .expect("parse policy");
+    let tool = |name: &str| Capability::ToolUse {
+        name: name.to_owned(),
+    };
+
+    // Exact rules win over earlier pattern rules
+    assert_eq!(
+        policy.evaluate_traced(&tool("fs.read")),
+        (Decision::Deny("capability denied".to_owned()), Some(2))
+    );
+    assert_eq!(
+        policy.evaluate_traced(&tool("fs.write")),
+        (Decision::Deny("filesystem blocked".to_owned()), Some(1))
+    );
+    assert_eq!(
+        policy.evaluate_traced(&tool("safe")),
+        (Decision::Allow, Some(0))
+    );
+    assert_eq!(
+        policy.evaluate_traced(&tool("other")),
+        (Decision::Allow, None)
+    );
+
+    let engine = Engine::new(EngineConfig::default());
+    let mut run = engine
+        .start_run(single_tool_workflow(&engine, "fs.write"), policy)
+        .expect("start run");
+    assert!(matches!(run.next_action(), Action::Error { .. }));
+    assert!(matches!(
+        &run.drain_events()[1],
+        RunEvent::PolicyDenied { rule_index: Some(1), rule_name: Some(name), .. } if name == "no-fs"
+    ));
+}
+
+#[test]
+fn default_deny_reports_no_rule() {
+    let engine = Engine::new(EngineConfig::default());
+    let policy = Policy {
+        default_decision: Decision::Deny("not on the allow list".to_owned()),
+        ..Policy::default()
+    };
+
+    let mut run = engine
+        .start_run(single_tool_workflow(&engine, "other"), policy)
+        .expect("start run");
+    assert!(matches!(run.next_action(), Action::Error { .. }));
+    assert!(matches!(
+        &run.drain_events()[1],
+        RunEvent::PolicyDenied {
+            rule_index: None,
+            rule_name: None,
+            ..
+        }
+    ));
+}
@@ -0,0 +1,111 @@
+use engine::policy::{Capability, Decision, Policy, PolicyRule};
+
+fn tool(name: &str) -> Capability {
+    Capability::ToolUse {
+        name: name.to_owned(),
+    }
+}
+
+fn rule(capability: Capability, allow: bool, reason: &str) -> PolicyRule {
+    PolicyRule {
+        capability,
+        allow,
+        reason: Some(reason.to_owned()),
+        name: None,
+        prompt: false,
+    }
+}
+
+fn fs_deny() -> PolicyRule {
+    rule(
+        Capability::ToolUsePattern {
+            pattern: "fs.*".to_owned(),
+        },
+        false,
+        "filesystem tools blocked",
+    )
+}
+
+#[test]
+fn prefix_pattern_denies_tool_family() {
+    let policy = Policy {
+        rules: vec![fs_deny()],
+        ..Policy::default()
+    };
+
+    for name in ["fs.read", "fs.write", "fs.delete"] {
+        assert_eq!(
+            policy.evaluate(&tool(name)),
+            Decision::Deny("filesystem tools blocked".to_owned())
+        );
+    }
+    assert_eq!(policy.evaluate(&tool("fsck")), Decision::Allow);
+}
+
+#[test]
+fn exact_allow_overrides_broader_pattern_deny() {
+    // The exact rule wins even though the pattern rule comes first
+    let policy = Policy {
+        rules: vec![fs_deny(), rule(tool("fs.read"), true, "reads are fine")],
+        ..Policy::default()
+    };
+
+    assert_eq!(policy.evaluate(&tool("fs.read")), Decision::Allow);
+    assert_eq!(
+        policy.evaluate(&tool("fs.write")),
+        Decision::Deny("filesystem tools blocked".to_owned())
+    );
+}
+
+#[test]
+fn first_matching_pattern_wins() {
+    let policy = Policy {
+        rules: vec![
+            rule(
+                Capability::ToolUsePattern {
+                    pattern: "net.*.get".to_owned(),
+                },
+                true,
+                "reads allowed",
+            ),
+            rule(
+                Capability::ToolUsePattern {
+                    pattern: "net.*".to_owned(),
+                },
+                false,
+                "network blocked",
+            ),
+        ],
+        ..Policy::default()
+    };
+
+    assert_eq!(policy.evaluate(&tool("net.http.get")), Decision::Allow);
+    assert_eq!(
+        policy.evaluate(&tool("net.http.post")),
+        Decision::Deny("network blocked".to_owned())
+    );
+}
+
+#[test]
+fn non_matching_tool_defaults_to_allow() {
+    let policy = Policy {
+        rules: vec![fs_deny()],
+        ..Policy::default()
+    };
+
+    assert_eq!(policy.evaluate(&tool("http.get")), Decision::Allow);
+    assert_eq!(policy.evaluate(&Capability::EmitArtifact), Decision::Allow);
+}
+
+#[test]
+fn pattern_rules_round_trip_through_json() {
+    let policy: Policy = serde_json::from_str(
+        r#"{"rules":[{"capability":{"capability":"tool_use_pattern","value":{"pattern":"fs.*"}},"allow":false}]}"#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        policy.evaluate(&tool("fs.read")),
+        Decision::Deny("capability denied".to_owned())
+    );
+}
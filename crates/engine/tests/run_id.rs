@@ -0,0 +1,97 @@
+use engine::{
+    deterministic_run_id,
+    policy::{Capability, Decision, Policy, PolicyRule},
+    Engine, EngineConfig,
+};
+
+const WORKFLOW: &str = r#"
+{
+  "id": "wf-run-id",
+  "version": "v0",
+  "steps": [
+    {
+      "id": "step-1",
+      "kind": {
+        "type": "tool_call",
+        "tool": {
+          "name": "search",
+          "description": "search",
+          "input_schema": {"type": "object"},
+          "output_schema": {"type": "object"}
+        },
+        "input": {"query": "reach", "limit": 10}
+      }
+    }
+  ]
+}
+"#;
+
+#[test]
+fn identical_inputs_share_a_run_id() {
+    let engine = Engine::new(EngineConfig::default());
+    let workflow = engine.compile(WORKFLOW).expect("compile workflow");
+    let policy = Policy::default();
+
+    let id = deterministic_run_id(&workflow, &policy, "alice");
+    assert_eq!(
+        id,
+        deterministic_run_id(&workflow.clone(), &policy.clone(), "alice")
+    );
+
+    // UUID layout: 8-4-4-4-12 hex digits, version 8, RFC 4122 variant
+    let groups: Vec<&str> = id.split('-').collect();
+    assert_eq!(
+        groups.iter().map(|g| g.len()).collect::<Vec<_>>(),
+        vec![8, 4, 4, 4, 12]
+    );
+    assert!(id.chars().all(|c| c == '-' || c.is_ascii_hexdigit()));
+    assert!(groups[2].starts_with('8'));
+    assert!(matches!(
+        groups[3].chars().next(),
+        Some('8' | '9' | 'a' | 'b')
+    ));
+}
+
+#[test]
+fn any_input_change_changes_the_run_id() {
+    let engine = Engine::new(EngineConfig::default());
+    let workflow = engine.compile(WORKFLOW).expect("compile workflow");
+    let policy = Policy::default();
+    let base = deterministic_run_id(&workflow, &policy, "alice");
+
+    let mut renamed = workflow.clone();
+    renamed.id = "wf-other".to_owned();
+    let reworded = engine
+        .compile(&WORKFLOW.replace("\"reach\"", "\"reach!\""))
+        .expect("compile workflow");
+    let strict = Policy {
+        default_decision: Decision::Prompt,
+        ..Policy::default()
+    };
+    let ruled = Policy {
+        rules: vec![PolicyRule {
+            capability: Capability::ToolUse {
+                name: "search".to_owned(),
+            },
+            allow: true,
+            reason: None,
+            name: None,
+            prompt: false,
+        }],
+        ..Policy::default()
+    };
+
+    let ids = [
+        base.clone(),
+        deterministic_run_id(&renamed, &policy, "alice"),
+        deterministic_run_id(&reworded, &policy, "alice"),
+        deterministic_run_id(&workflow, &strict, "alice"),
+        deterministic_run_id(&workflow, &ruled, "alice"),
+        deterministic_run_id(&workflow, &policy, "bob"),
+    ];
+    for (i, a) in ids.iter().enumerate() {
+        for b in &ids[i + 1..] {
+            assert_ne!(a, b);
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use engine::{
+    workflow::{Workflow, WORKFLOW_SCHEMA_VERSION},
+    Engine, EngineConfig, EngineError,
+};
+
+fn workflow_json(schema_version: &str) -> String {
+    format!(
+        r#"
+    {{
+      "id": "wf-migrate",
+      "version": "v0",
+      "schema_version": "{schema_version}",
+      "steps": []
+    }}
+    "#
+    )
+}
+
+#[test]
+fn missing_schema_version_defaults_to_current() {
+    let engine = Engine::new(EngineConfig::default());
+    let workflow = engine
+        .compile(r#"{"id": "wf", "version": "v0", "steps": []}"#)
+        .expect("compile");
+
+    assert_eq!(workflow.schema_version, WORKFLOW_SCHEMA_VERSION);
+    assert_eq!(workflow.migrated_from, None);
+}
+
+#[test]
+fn minor_bump_is_migrated_and_recorded() {
+    let engine = Engine::new(EngineConfig::default());
+    let workflow: Workflow = serde_json::from_str(&workflow_json("1.2.0")).expect("parse");
+
+    let migrated = engine
+        .migrate_workflow(workflow, "1.4.0")
+        .expect("minor bump must be accepted");
+
+    assert_eq!(migrated.schema_version, "1.4.0");
+    assert_eq!(migrated.migrated_from.as_deref(), Some("1.2.0"));
+}
+
+#[test]
+fn major_bump_is_rejected() {
+    let engine = Engine::new(EngineConfig::default());
+    let workflow: Workflow = serde_json::from_str(&workflow_json("1.2.0")).expect("parse");
+
+    let err = engine
+        .migrate_workflow(workflow, "2.0.0")
+        .expect_err("major bump must be rejected");
+
+    assert!(matches!(
+        err,
+        EngineError::IncompatibleSchema { ref from, ref to } if from == "1.2.0" && to == "2.0.0"
+    ));
+}
+
+#[test]
+fn compile_rejects_workflow_from_newer_major() {
+    let engine = Engine::new(EngineConfig::default());
+    let err = engine
+        .compile(&workflow_json("9.0.0"))
+        .expect_err("newer major must be rejected");
+
+    assert!(matches!(err, EngineError::IncompatibleSchema { .. }));
+}
+
+#[test]
+fn downgrade_is_rejected_down_to_the_patch() {
+    let engine = Engine::new(EngineConfig::default());
+    for target in ["1.2.0", "1.1.9"] {
+        let workflow: Workflow = serde_json::from_str(&workflow_json("1.2.3")).expect("parse");
+        let err = engine
+            .migrate_workflow(workflow, target)
+            .expect_err("downgrade must be rejected");
+        assert!(matches!(err, EngineError::IncompatibleSchema { ref to, .. } if to == target));
+    }
+
+    let workflow: Workflow = serde_json::from_str(&workflow_json("1.2.3")).expect("parse");
+    let migrated = engine
+        .migrate_workflow(workflow, "1.2.4")
+        .expect("patch bump must be accepted");
+    assert_eq!(migrated.migrated_from.as_deref(), Some("1.2.3"));
+}
+
+#[test]
+fn unparseable_versions_are_rejected() {
+    let engine = Engine::new(EngineConfig::default());
+    for (from, to) in [
+        ("1.2", "1.4.0"),
+        ("1.2.0", "1.x.0"),
+        ("v1.2.0", "v1.2.0"),
+        ("1.2.0", "1.2.0.1"),
+    ] {
+        let workflow: Workflow = serde_json::from_str(&workflow_json(from)).expect("parse");
+        let err = engine
+            .migrate_workflow(workflow, to)
+            .expect_err("unparseable version must be rejected");
+        assert!(
+            matches!(err, EngineError::IncompatibleSchema { .. }),
+            "{from} -> {to}"
+        );
+    }
+}
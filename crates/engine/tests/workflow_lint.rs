@@ -0,0 +1,136 @@
+use engine::{
+    artifacts::{Diff, Patch},
+    lint::{LintKind, LintSeverity},
+    policy::{Capability, Policy, PolicyRule},
+    tools::ToolSpec,
+    workflow::{Step, StepKind, Workflow},
+    Engine, EngineConfig,
+};
+
+fn tool_step(id: &str, tool: &str, input: serde_json::Value) -> Step {
+    Step {
+        id: id.to_owned(),
+        kind: StepKind::ToolCall {
+            tool: ToolSpec {
+                name: tool.to_owned(),
+                description: tool.to_owned(),
+                input_schema: serde_json::json!({"type": "object"}),
+                output_schema: serde_json::json!({"type": "object"}),
+            },
+            input,
+        },
+        estimated_cost_usd: None,
+    }
+}
+
+fn artifact_step(id: &str, path: &str) -> Step {
+    Step {
+        id: id.to_owned(),
+        kind: StepKind::EmitArtifact {
+            patch: Patch {
+                diffs: vec![Diff {
+                    path: path.to_owned(),
+                    before: String::new(),
+                    after: "content".to_owned(),
+                }],
+            },
+        },
+        estimated_cost_usd: None,
+    }
+}
+
+fn workflow(steps: Vec<Step>) -> Workflow {
+    Workflow {
+        id: "wf-lint".to_owned(),
+        version: "v0".to_owned(),
+        schema_version: engine::workflow::WORKFLOW_SCHEMA_VERSION.to_owned(),
+        migrated_from: None,
+        steps,
+    }
+}
+
+fn deny(tool: &str) -> Policy {
+    Policy {
+        rules: vec![PolicyRule {
+            capability: Capability::ToolUse {
+                name: tool.to_owned(),
+            },
+            allow: false,
+            reason: Some("blocked".to_owned()),
+            name: None,
+            prompt: false,
+        }],
+        ..Policy::default()
+    }
+}
+
+#[test]
+fn clean_workflow_has_no_findings() {
+    let workflow = workflow(vec![
+        artifact_step("step-1", "out/report.md"),
+        tool_step(
+            "step-2",
+            "publish",
+            serde_json::json!({"files": ["out/report.md"]}),
+        ),
+    ]);
+
+    let engine = Engine::new(EngineConfig::default());
+    assert!(engine.lint(&workflow, &Policy::default()).is_empty());
+}
+
+#[test]
+fn denied_tool_is_an_error() {
+    let workflow = workflow(vec![tool_step("step-1", "shell", serde_json::json!({}))]);
+
+    let findings = Engine::new(EngineConfig::default()).lint(&workflow, &deny("shell"));
+    assert_eq!(findings.len(), 1);
+    assert_eq!(findings[0].step_id, "step-1");
+    assert_eq!(findings[0].kind, LintKind::DeniedTool);
+    assert_eq!(findings[0].severity, LintSeverity::Error);
+    assert_eq!(findings[0].message, "policy denies tool shell: blocked");
+}
+
+#[test]
+fn unreferenced_artifact_is_a_warning() {
+    let workflow = workflow(vec![
+        artifact_step("step-1", "out/used.md"),
+        artifact_step("step-2", "out/orphan.md"),
+        tool_step(
+            "step-3",
+            "publish",
+            serde_json::json!({"file": "out/used.md"}),
+        ),
+    ]);
+
+    let findings = Engine::new(EngineConfig::default()).lint(&workflow, &Policy::default());
+    assert_eq!(findings.len(), 1);
+    assert_eq!(findings[0].step_id, "step-2");
+    assert_eq!(findings[0].kind, LintKind::UnusedArtifact);
+    assert_eq!(findings[0].severity, LintSeverity::Warning);
+}
+
+#[test]
+fn steps_after_denied_tool_are_unreachable_and_sorted() {
+    let workflow = workflow(vec![
+        tool_step("c-deploy", "deploy", serde_json::json!({})),
+        artifact_step("b-notes", "notes.md"),
+        tool_step("a-read", "read", serde_json::json!({"path": "notes.md"})),
+    ]);
+
+    let engine = Engine::new(EngineConfig::default());
+    let findings = engine.lint(&workflow, &deny("deploy"));
+    let summary: Vec<(&str, LintKind)> = findings
+        .iter()
+        .map(|f| (f.step_id.as_str(), f.kind))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            ("a-read", LintKind::UnreachableStep),
+            ("b-notes", LintKind::UnreachableStep),
+            ("c-deploy", LintKind::DeniedTool),
+        ]
+    );
+    assert_eq!(findings, engine.lint(&workflow, &deny("deploy")));
+}
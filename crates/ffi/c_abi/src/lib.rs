@@ -184,6 +184,50 @@ pub extern "C" fn reach_next_action(run_id: u64) -> *mut c_char {
     )
 }
 
+/// Read-only run summary: the serialized `RunStatus`, steps executed, and
+/// budget spent, reserved and limit. Does not advance the run.
+///
+/// The returned string is owned by the caller and must be released with
+/// `reach_string_free`.
+#[no_mangle]
+pub extern "C" fn reach_run_status(run_id: u64) -> *mut c_char {
+    let runs = match RUNS.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    let Some(run) = runs.get(&run_id) else {
+        return into_c_string("{\"error\":\"unknown run\"}".to_owned());
+    };
+    let status = serde_json::json!({
+        "status": run.status(),
+        "steps_executed": run.steps_executed(),
+        "budget": {
+            "spent_usd": run.budget().spent_usd,
+            "reserved_usd": run.budget().reserved_usd,
+            "limit_usd": run.controls().budget_limit_usd,
+        },
+    });
+    into_c_string(status.to_string())
+}
+
+/// Build information for this crate (`requiem_version::version_info`), so
+/// a host can check compatibility before issuing commands. Pure; touches no
+/// engine or run state.
+///
+/// The returned string is owned by the caller and must be released with
+/// `reach_string_free`.
+#[no_mangle]
+pub extern "C" fn reach_version() -> *mut c_char {
+    into_c_string(requiem_version::version_info(env!("CARGO_PKG_VERSION")))
+}
+
+/// Whether this build supports the Reach protocol
+/// (`requiem_version::is_supported`).
+#[no_mangle]
+pub extern "C" fn reach_supported() -> bool {
+    requiem_version::is_supported()
+}
+
 #[no_mangle]
 /// # Safety
 /// The caller must pass valid NUL-terminated pointers owned according to the C ABI and uphold lifetime guarantees.
@@ -226,3 +270,77 @@ pub unsafe extern "C" fn reach_string_free(ptr: *mut c_char) {
         let _ = CString::from_raw(ptr);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Take ownership of a string returned across the C ABI.
+    fn take_json(ptr: *mut c_char) -> serde_json::Value {
+        // SAFETY: `ptr` was just returned by a `reach_*` function
+        let text = unsafe { CStr::from_ptr(ptr) }.to_str().unwrap().to_owned();
+        unsafe { reach_string_free(ptr) };
+        serde_json::from_str(&text).unwrap()
+    }
+
+    #[test]
+    fn test_run_status_reports_paused_run_without_advancing_it() {
+        let workflow = CString::new(
+            serde_json::json!({
+                "id": "wf",
+                "version": "1.0.0",
+                "steps": [
+                    {"id": "draft", "kind": {"type": "emit_artifact", "patch": {"diffs": []}}},
+                    {"id": "publish", "kind": {"type": "emit_artifact", "patch": {"diffs": []}}}
+                ]
+            })
+            .to_string(),
+        )
+        .unwrap();
+        let policy = CString::new("{}").unwrap();
+
+        let engine_id = reach_engine_create();
+        // SAFETY: both pointers are valid NUL-terminated strings
+        let run_id = unsafe { reach_start_run(engine_id, workflow.as_ptr(), policy.as_ptr()) };
+        assert_ne!(run_id, 0);
+
+        take_json(reach_next_action(run_id));
+        RUNS.lock()
+            .unwrap()
+            .get_mut(&run_id)
+            .unwrap()
+            .pause("operator hold")
+            .unwrap();
+
+        for _ in 0..2 {
+            let status = take_json(reach_run_status(run_id));
+            assert_eq!(
+                status["status"],
+                serde_json::json!({"status": "paused", "reason": "operator hold"})
+            );
+            assert_eq!(status["steps_executed"], 1);
+            assert_eq!(status["budget"]["spent_usd"], 0.0);
+            assert!(status["budget"]["limit_usd"].is_null());
+        }
+        assert_eq!(RUNS.lock().unwrap()[&run_id].steps_executed(), 1);
+
+        reach_run_free(run_id);
+        reach_engine_free(engine_id);
+        assert_eq!(
+            take_json(reach_run_status(run_id)),
+            serde_json::json!({"error": "unknown run"})
+        );
+    }
+
+    #[test]
+    fn test_version_reports_build_and_default_capabilities() {
+        let version = take_json(reach_version());
+        assert_eq!(version["crate_version"], env!("CARGO_PKG_VERSION"));
+        let shared = requiem_version::version_info(env!("CARGO_PKG_VERSION"));
+        assert_eq!(
+            version,
+            serde_json::from_str::<serde_json::Value>(&shared).unwrap()
+        );
+        assert!(reach_supported());
+    }
+}
@@ -91,4 +91,37 @@ pub fn apply_tool_result(run_id: u64, tool_result_json: String) -> Result<String
     serde_json::to_string(&run.drain_events()).map_err(|_| FfiError::Serialization)
 }
 
+/// Build information for this crate as JSON
+/// (`requiem_version::version_info`). Touches no engine or run state.
+#[uniffi::export]
+#[must_use]
+pub fn version() -> String {
+    requiem_version::version_info(env!("CARGO_PKG_VERSION"))
+}
+
+/// Whether this build supports the Reach protocol
+/// (`requiem_version::is_supported`).
+#[uniffi::export]
+#[must_use]
+pub fn supported() -> bool {
+    requiem_version::is_supported()
+}
+
 uniffi::setup_scaffolding!();
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_version_reports_build_and_default_capabilities() {
+        let version: serde_json::Value = serde_json::from_str(&version()).unwrap();
+        assert_eq!(version["crate_version"], env!("CARGO_PKG_VERSION"));
+        let shared = requiem_version::version_info(env!("CARGO_PKG_VERSION"));
+        assert_eq!(
+            version,
+            serde_json::from_str::<serde_json::Value>(&shared).unwrap()
+        );
+        assert!(supported());
+    }
+}
@@ -0,0 +1,61 @@
+//! Reach protocol version and default capabilities
+//!
+//! The values `requiem` negotiates with, in a crate with no runtime
+//! dependencies, so hosts such as the FFI layers can report compatibility
+//! without linking the protocol server.
+
+/// Protocol major version
+pub const PROTOCOL_VERSION_MAJOR: u16 = 1;
+
+/// Protocol minor version
+pub const PROTOCOL_VERSION_MINOR: u16 = 0;
+
+/// Payload encoding of a session that does not negotiate another
+pub const DEFAULT_ENCODING: &str = "cbor";
+
+/// Whether payload compression is on by default
+pub const DEFAULT_COMPRESSION: bool = false;
+
+/// Whether numeric payloads use fixed-point values by default
+pub const DEFAULT_FIXED_POINT: bool = true;
+
+/// Whether this build supports the Reach protocol
+#[must_use]
+pub fn is_supported() -> bool {
+    true
+}
+
+/// Build information as JSON, so a host can check compatibility before
+/// issuing commands: `crate_version` (the caller's own), the protocol
+/// version, and the default capabilities.
+#[must_use]
+pub fn version_info(crate_version: &str) -> String {
+    serde_json::json!({
+        "crate_version": crate_version,
+        "protocol_version": format!("{PROTOCOL_VERSION_MAJOR}.{PROTOCOL_VERSION_MINOR}"),
+        "capabilities": {
+            "encoding": DEFAULT_ENCODING,
+            "compression": DEFAULT_COMPRESSION,
+            "fixed_point": DEFAULT_FIXED_POINT,
+        },
+    })
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_version_info_reports_caller_version_and_defaults() {
+        let info: serde_json::Value = serde_json::from_str(&version_info("9.9.9")).unwrap();
+        assert_eq!(
+            info,
+            serde_json::json!({
+                "crate_version": "9.9.9",
+                "protocol_version": "1.0",
+                "capabilities": {"encoding": "cbor", "compression": false, "fixed_point": true},
+            })
+        );
+    }
+}
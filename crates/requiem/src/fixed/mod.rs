@@ -9,9 +9,38 @@
 //! - Serialization uses little-endian byte order
 //! - No floating-point in protocol fields (allowed only in UI pretty-print)
 
+pub mod stats;
+
 use serde::{Deserialize, Serialize};
 use std::fmt;
 use std::ops::{Add, Div, Mul, Sub};
+use thiserror::Error;
+
+/// Errors from parsing a fixed-point decimal string
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ParseFixedError {
+    #[error("empty fixed-point string")]
+    Empty,
+
+    #[error("invalid character {found:?} at index {index} in fixed-point string")]
+    InvalidCharacter { index: usize, found: char },
+
+    #[error("fixed-point string has no digits")]
+    NoDigits,
+
+    #[error("value out of range for fixed-point type")]
+    OutOfRange,
+}
+
+/// Errors from converting between `FixedDuration` and `std::time::Duration`
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum DurationConversionError {
+    #[error("negative duration cannot be represented as std::time::Duration")]
+    Negative,
+
+    #[error("duration out of range for FixedDuration")]
+    OutOfRange,
+}
 
 /// Fixed-point Q32.32 format (signed 64-bit)
 /// Range: ~-2.1 billion to +2.1 billion
@@ -41,7 +70,7 @@ impl FixedQ32_32 {
     }
 
     /// Create from f64 value (for construction only, not in hot paths)
-    /// 
+    ///
     /// # Panics
     /// Panics in debug mode if value is out of range
     pub fn from_f64(value: f64) -> Option<Self> {
@@ -63,8 +92,9 @@ impl FixedQ32_32 {
     /// Create from integer
     pub const fn from_i64(value: i64) -> Option<Self> {
         // Check for overflow before shifting
-        if value > (i64::MAX >> Self::FRACTIONAL_BITS) 
-            || value < (i64::MIN >> Self::FRACTIONAL_BITS) {
+        if value > (i64::MAX >> Self::FRACTIONAL_BITS)
+            || value < (i64::MIN >> Self::FRACTIONAL_BITS)
+        {
             return None;
         }
         Some(Self(value << Self::FRACTIONAL_BITS))
@@ -112,6 +142,14 @@ impl FixedQ32_32 {
         Some(Self(quotient as i64))
     }
 
+    /// Checked square root
+    /// Exact integer square root of the 128-bit scaled value, truncated to the
+    /// nearest raw unit below; None for negative input
+    pub fn checked_sqrt(self) -> Option<Self> {
+        let scaled = u128::try_from(self.0).ok()? << Self::FRACTIONAL_BITS;
+        i64::try_from(scaled.isqrt()).ok().map(Self)
+    }
+
     /// Saturating addition (deterministic overflow handling)
     pub fn saturating_add(self, rhs: Self) -> Self {
         Self(self.0.saturating_add(rhs.0))
@@ -124,15 +162,146 @@ impl FixedQ32_32 {
             None => {
                 let sign = (self.0 < 0) ^ (rhs.0 < 0);
                 if sign {
-                    Self(i64::MIN)  // Negative overflow
+                    Self(i64::MIN) // Negative overflow
                 } else {
-                    Self(i64::MAX)  // Positive overflow
+                    Self(i64::MAX) // Positive overflow
                 }
             }
         }
     }
 }
 
+impl FixedQ32_32 {
+    /// Parse a decimal string such as "10.12345" or "-0.5"
+    ///
+    /// The result is the nearest raw value (ties round away from zero),
+    /// computed with integer arithmetic only so identical strings always yield
+    /// identical bits. Accepts an optional sign, leading/trailing zeros and
+    /// forms like "5." or ".5"; surrounding whitespace is ignored.
+    pub fn from_decimal_str(s: &str) -> Result<Self, ParseFixedError> {
+        let trimmed = s.trim();
+        if trimmed.is_empty() {
+            return Err(ParseFixedError::Empty);
+        }
+        let offset = s.len() - s.trim_start().len();
+
+        let (negative, body, body_start) = match trimmed.as_bytes()[0] {
+            b'-' => (true, &trimmed[1..], 1),
+            b'+' => (false, &trimmed[1..], 1),
+            _ => (false, trimmed, 0),
+        };
+
+        let mut integer: u128 = 0;
+        let mut fraction: Vec<u8> = Vec::new();
+        let mut seen_point = false;
+        let mut seen_digit = false;
+        for (i, c) in body.char_indices() {
+            match c {
+                '0'..='9' => {
+                    let digit = c as u8 - b'0';
+                    seen_digit = true;
+                    if seen_point {
+                        fraction.push(digit);
+                    } else {
+                        integer = integer * 10 + u128::from(digit);
+                        if integer > 1u128 << 31 {
+                            return Err(ParseFixedError::OutOfRange);
+                        }
+                    }
+                }
+                '.' if !seen_point => seen_point = true,
+                _ => {
+                    return Err(ParseFixedError::InvalidCharacter {
+                        index: offset + body_start + i,
+                        found: c,
+                    })
+                }
+            }
+        }
+        if !seen_digit {
+            return Err(ParseFixedError::NoDigits);
+        }
+
+        // Binary long division of the decimal fraction: each doubling shifts
+        // out the next fractional bit; one extra bit decides the rounding.
+        let mut bits: u128 = 0;
+        for _ in 0..Self::FRACTIONAL_BITS {
+            bits = (bits << 1) | u128::from(double_decimal_fraction(&mut fraction));
+        }
+        let round_up = double_decimal_fraction(&mut fraction) == 1;
+
+        let magnitude = (integer << Self::FRACTIONAL_BITS) + bits + u128::from(round_up);
+        let magnitude = i128::try_from(magnitude).map_err(|_| ParseFixedError::OutOfRange)?;
+        let raw = if negative { -magnitude } else { magnitude };
+        i64::try_from(raw)
+            .map(Self)
+            .map_err(|_| ParseFixedError::OutOfRange)
+    }
+
+    /// Format as a decimal string with exactly `places` fractional digits
+    ///
+    /// Uses integer arithmetic only (ties round away from zero). With 32 places
+    /// the output is exact and parses back to the same raw value.
+    #[must_use]
+    pub fn to_decimal_str(self, places: u32) -> String {
+        const MASK: u128 = (1u128 << 32) - 1;
+        let magnitude = u128::from(self.0.unsigned_abs());
+        let mut integer = magnitude >> Self::FRACTIONAL_BITS;
+        let mut fraction = magnitude & MASK;
+
+        let mut digits: Vec<u8> = Vec::with_capacity(places as usize);
+        for _ in 0..places {
+            fraction *= 10;
+            // `fraction` is below 2^32, so this is a single decimal digit
+            #[allow(clippy::cast_possible_truncation)]
+            let digit = (fraction >> Self::FRACTIONAL_BITS) as u8;
+            digits.push(digit);
+            fraction &= MASK;
+        }
+
+        // Round the remainder: carry through the digits into the integer part
+        if fraction << 1 > MASK {
+            let mut carry = true;
+            for digit in digits.iter_mut().rev() {
+                if *digit == 9 {
+                    *digit = 0;
+                } else {
+                    *digit += 1;
+                    carry = false;
+                    break;
+                }
+            }
+            if carry {
+                integer += 1;
+            }
+        }
+
+        let is_zero = integer == 0 && digits.iter().all(|&d| d == 0);
+        let mut out = String::new();
+        if self.0 < 0 && !is_zero {
+            out.push('-');
+        }
+        out.push_str(&integer.to_string());
+        if places > 0 {
+            out.push('.');
+            out.extend(digits.iter().map(|&d| char::from(b'0' + d)));
+        }
+        out
+    }
+}
+
+/// Double a fractional decimal number given as digits after the point,
+/// returning the integer carry (0 or 1)
+fn double_decimal_fraction(digits: &mut [u8]) -> u8 {
+    let mut carry = 0;
+    for digit in digits.iter_mut().rev() {
+        let doubled = *digit * 2 + carry;
+        *digit = doubled % 10;
+        carry = doubled / 10;
+    }
+    carry
+}
+
 impl fmt::Display for FixedQ32_32 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{:.10}", self.to_f64())
@@ -184,6 +353,12 @@ impl FixedBps {
     pub const fn from_raw(raw: i16) -> Self {
         Self(raw)
     }
+
+    /// Convert from ppm (100 ppm = 1 bps), truncating toward zero
+    /// Returns None if the result is outside the i16 range
+    pub fn try_from_ppm(ppm: FixedPpm) -> Option<Self> {
+        i16::try_from(ppm.0 / 100).ok().map(Self)
+    }
 }
 
 impl fmt::Display for FixedBps {
@@ -214,6 +389,12 @@ impl FixedPpm {
         Self(ppm)
     }
 
+    /// Convert from basis points (1 bps = 100 ppm); always exact
+    #[must_use]
+    pub const fn from_bps(bps: FixedBps) -> Self {
+        Self(bps.0 as i32 * 100)
+    }
+
     /// Create from ratio [0, 1] -> [0, 1_000_000]
     pub fn from_ratio(ratio: f64) -> Option<Self> {
         if ratio < 0.0 || ratio > 1.0 {
@@ -250,6 +431,27 @@ impl FixedPpm {
     pub fn saturating_add(self, rhs: Self) -> Self {
         Self(self.0.saturating_add(rhs.0))
     }
+
+    /// Checked subtraction
+    #[must_use]
+    pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+        self.0.checked_sub(rhs.0).map(Self)
+    }
+
+    /// Saturating subtraction
+    #[must_use]
+    pub fn saturating_sub(self, rhs: Self) -> Self {
+        Self(self.0.saturating_sub(rhs.0))
+    }
+
+    /// Checked scaling by a ratio expressed in ppm (`other` / 1,000,000)
+    /// Uses 64-bit intermediate to prevent overflow; truncates toward zero
+    #[must_use]
+    pub fn checked_mul_ratio(self, other: Self) -> Option<Self> {
+        let product =
+            i64::from(self.0) * i64::from(other.0) / i64::from(Self::ONE_HUNDRED_PERCENT.0);
+        i32::try_from(product).ok().map(Self)
+    }
 }
 
 impl fmt::Display for FixedPpm {
@@ -260,7 +462,7 @@ impl fmt::Display for FixedPpm {
 
 /// Duration in microseconds
 /// Used for: timeouts, histogram bucket boundaries, latency measurements
-/// 
+///
 /// This is i64 to allow for negative durations (useful for relative time)
 /// Range: +/- 292,471 years (sufficient for all practical purposes)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
@@ -328,15 +530,72 @@ impl FixedDuration {
     pub fn checked_sub(self, rhs: Self) -> Option<Self> {
         self.0.checked_sub(rhs.0).map(Self)
     }
+
+    /// Saturating addition
+    #[must_use]
+    pub fn saturating_add(self, rhs: Self) -> Self {
+        Self(self.0.saturating_add(rhs.0))
+    }
+
+    /// Saturating subtraction
+    #[must_use]
+    pub fn saturating_sub(self, rhs: Self) -> Self {
+        Self(self.0.saturating_sub(rhs.0))
+    }
+}
+
+/// Saturates at the i64 range (same as `saturating_add`)
+impl Add for FixedDuration {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        self.saturating_add(rhs)
+    }
+}
+
+/// Saturates at the i64 range (same as `saturating_sub`)
+impl Sub for FixedDuration {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        self.saturating_sub(rhs)
+    }
+}
+
+/// Truncates sub-microsecond precision
+impl TryFrom<std::time::Duration> for FixedDuration {
+    type Error = DurationConversionError;
+
+    fn try_from(duration: std::time::Duration) -> Result<Self, Self::Error> {
+        i64::try_from(duration.as_micros())
+            .map(Self)
+            .map_err(|_| DurationConversionError::OutOfRange)
+    }
+}
+
+impl TryFrom<FixedDuration> for std::time::Duration {
+    type Error = DurationConversionError;
+
+    fn try_from(duration: FixedDuration) -> Result<Self, Self::Error> {
+        u64::try_from(duration.0)
+            .map(std::time::Duration::from_micros)
+            .map_err(|_| DurationConversionError::Negative)
+    }
 }
 
 impl fmt::Display for FixedDuration {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let micros = self.0.abs();
         let sign = if self.0 < 0 { "-" } else { "" };
-        
+
         if micros >= 1_000_000 {
-            write!(f, "{}{}.{:06}s", sign, micros / 1_000_000, micros % 1_000_000)
+            write!(
+                f,
+                "{}{}.{:06}s",
+                sign,
+                micros / 1_000_000,
+                micros % 1_000_000
+            )
         } else if micros >= 1000 {
             write!(f, "{}{}.{:03}ms", sign, micros / 1000, micros % 1000)
         } else {
@@ -347,7 +606,7 @@ impl fmt::Display for FixedDuration {
 
 /// Throughput in micro-operations per second
 /// Used for: ops/sec rates with 6 decimal precision
-/// 
+///
 /// Example: 1,000,000 = 1 op/sec, 2,500,000 = 2.5 ops/sec
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 #[serde(transparent)]
@@ -437,6 +696,24 @@ mod tests {
         assert!((three.to_f64() - 3.0).abs() < 1e-9);
     }
 
+    #[test]
+    fn test_fixed_q32_32_sqrt() {
+        let four = FixedQ32_32::from_i64(4).unwrap();
+        assert_eq!(four.checked_sqrt(), FixedQ32_32::from_i64(2));
+        assert_eq!(FixedQ32_32::ZERO.checked_sqrt(), Some(FixedQ32_32::ZERO));
+
+        let two = FixedQ32_32::from_i64(2).unwrap();
+        assert_eq!(
+            two.checked_sqrt(),
+            Some(FixedQ32_32::from_raw(6_074_000_999))
+        );
+
+        let largest = FixedQ32_32::from_raw(i64::MAX).checked_sqrt().unwrap();
+        assert!((largest.to_f64() - (i64::MAX as f64 / 4294967296.0).sqrt()).abs() < 1e-6);
+
+        assert_eq!(FixedQ32_32::from_raw(-1).checked_sqrt(), None);
+    }
+
     #[test]
     fn test_fixed_bps() {
         let bps = FixedBps::from_percent(5.5).unwrap();
@@ -450,6 +727,43 @@ mod tests {
         assert_eq!(ppm.to_raw(), 999900);
     }
 
+    #[test]
+    fn test_fixed_bps_ppm_conversion() {
+        let bps = FixedBps::from_bps(550);
+        let ppm = FixedPpm::from_bps(bps);
+        assert_eq!(ppm, FixedPpm::from_ppm(55_000));
+        assert_eq!(FixedBps::try_from_ppm(ppm), Some(bps));
+
+        let floor = FixedBps::from_raw(i16::MIN);
+        assert_eq!(
+            FixedBps::try_from_ppm(FixedPpm::from_bps(floor)),
+            Some(floor)
+        );
+
+        assert_eq!(FixedBps::try_from_ppm(FixedPpm::from_ppm(3_276_800)), None);
+        assert_eq!(FixedBps::try_from_ppm(FixedPpm::from_ppm(-3_276_900)), None);
+    }
+
+    #[test]
+    fn test_fixed_ppm_sub() {
+        let delta = FixedPpm::from_ppm(950_000).checked_sub(FixedPpm::from_ppm(900_000));
+        assert_eq!(delta, Some(FixedPpm::from_ppm(50_000)));
+
+        let floor = FixedPpm::from_raw(i32::MIN);
+        assert_eq!(floor.checked_sub(FixedPpm::ONE), None);
+        assert_eq!(floor.saturating_sub(FixedPpm::ONE), floor);
+    }
+
+    #[test]
+    fn test_fixed_ppm_mul_ratio() {
+        let half = FixedPpm::from_ratio(0.5).unwrap();
+        let scaled = FixedPpm::from_ppm(950_000).checked_mul_ratio(half);
+        assert_eq!(scaled, Some(FixedPpm::from_ppm(475_000)));
+
+        let large = FixedPpm::from_raw(i32::MAX);
+        assert_eq!(large.checked_mul_ratio(FixedPpm::from_ppm(2_000_000)), None);
+    }
+
     #[test]
     fn test_fixed_duration() {
         let dur = FixedDuration::from_seconds(5).unwrap();
@@ -457,12 +771,184 @@ mod tests {
         assert_eq!(dur.to_seconds(), 5);
     }
 
+    #[test]
+    fn test_fixed_duration_std_roundtrip() {
+        use std::time::Duration;
+
+        let std_dur = Duration::from_millis(1_500);
+        let fixed = FixedDuration::try_from(std_dur).unwrap();
+        assert_eq!(fixed.to_micros(), 1_500_000);
+        assert_eq!(Duration::try_from(fixed).unwrap(), std_dur);
+
+        // Sub-microsecond precision is truncated
+        let fixed = FixedDuration::try_from(Duration::from_nanos(2_999)).unwrap();
+        assert_eq!(fixed, FixedDuration::from_micros(2));
+
+        assert_eq!(
+            Duration::try_from(FixedDuration::from_micros(-1)),
+            Err(DurationConversionError::Negative)
+        );
+        assert_eq!(
+            FixedDuration::try_from(Duration::MAX),
+            Err(DurationConversionError::OutOfRange)
+        );
+    }
+
+    #[test]
+    fn test_fixed_duration_saturating_ops() {
+        let max = FixedDuration::from_raw(i64::MAX);
+        let min = FixedDuration::from_raw(i64::MIN);
+        let sec = FixedDuration::ONE_SECOND;
+
+        assert_eq!(max.saturating_add(sec), max);
+        assert_eq!(min.saturating_sub(sec), min);
+        assert_eq!(max + sec, max);
+        assert_eq!(min - sec, min);
+        assert_eq!(sec + sec, FixedDuration::from_seconds(2).unwrap());
+        assert_eq!(
+            sec - FixedDuration::ONE_MILLISECOND,
+            FixedDuration::from_millis(999).unwrap()
+        );
+    }
+
     #[test]
     fn test_fixed_throughput() {
         let tp = FixedThroughput::from_ops_per_sec(1234.567).unwrap();
         assert!((tp.to_ops_per_sec() - 1234.567).abs() < 0.0001);
     }
 
+    #[test]
+    fn test_fixed_q32_32_decimal_str_parse() {
+        assert_eq!(FixedQ32_32::from_decimal_str("1"), Ok(FixedQ32_32::ONE));
+        assert_eq!(
+            FixedQ32_32::from_decimal_str("-0010.500").unwrap().to_raw(),
+            -(21i64 << 31)
+        );
+        assert_eq!(
+            FixedQ32_32::from_decimal_str("+.25").unwrap().to_raw(),
+            1i64 << 30
+        );
+        assert_eq!(
+            FixedQ32_32::from_decimal_str("5.").unwrap(),
+            FixedQ32_32::from_i64(5).unwrap()
+        );
+        assert_eq!(
+            FixedQ32_32::from_decimal_str("-0.0").unwrap(),
+            FixedQ32_32::ZERO
+        );
+        // 0.1 is 429496729.6 raw units: nearest is 429496730
+        assert_eq!(
+            FixedQ32_32::from_decimal_str("0.1").unwrap().to_raw(),
+            429_496_730
+        );
+        // Extreme representable values
+        assert_eq!(
+            FixedQ32_32::from_decimal_str("-2147483648")
+                .unwrap()
+                .to_raw(),
+            i64::MIN
+        );
+        assert_eq!(
+            FixedQ32_32::from_decimal_str("2147483647.99999999976716935634613037109375")
+                .unwrap()
+                .to_raw(),
+            i64::MAX
+        );
+    }
+
+    #[test]
+    fn test_fixed_q32_32_decimal_str_errors() {
+        assert_eq!(
+            FixedQ32_32::from_decimal_str(""),
+            Err(ParseFixedError::Empty)
+        );
+        assert_eq!(
+            FixedQ32_32::from_decimal_str("  "),
+            Err(ParseFixedError::Empty)
+        );
+        assert_eq!(
+            FixedQ32_32::from_decimal_str("-."),
+            Err(ParseFixedError::NoDigits)
+        );
+        assert_eq!(
+            FixedQ32_32::from_decimal_str("1.2.3"),
+            Err(ParseFixedError::InvalidCharacter {
+                index: 3,
+                found: '.'
+            })
+        );
+        assert_eq!(
+            FixedQ32_32::from_decimal_str("10usd"),
+            Err(ParseFixedError::InvalidCharacter {
+                index: 2,
+                found: 'u'
+            })
+        );
+        assert_eq!(
+            FixedQ32_32::from_decimal_str("1e5"),
+            Err(ParseFixedError::InvalidCharacter {
+                index: 1,
+                found: 'e'
+            })
+        );
+        assert_eq!(
+            FixedQ32_32::from_decimal_str("2147483648"),
+            Err(ParseFixedError::OutOfRange)
+        );
+        assert_eq!(
+            FixedQ32_32::from_decimal_str("99999999999999"),
+            Err(ParseFixedError::OutOfRange)
+        );
+    }
+
+    #[test]
+    fn test_fixed_q32_32_decimal_str_roundtrip() {
+        let parsed = FixedQ32_32::from_decimal_str("10.12345").unwrap();
+        assert_eq!(parsed.to_decimal_str(5), "10.12345");
+        assert_eq!(
+            FixedQ32_32::from_decimal_str("-2.5")
+                .unwrap()
+                .to_decimal_str(0),
+            "-3"
+        );
+        assert_eq!(
+            FixedQ32_32::from_decimal_str("-0.0000001")
+                .unwrap()
+                .to_decimal_str(2),
+            "0.00"
+        );
+        assert_eq!(
+            FixedQ32_32::from_decimal_str("9.9999")
+                .unwrap()
+                .to_decimal_str(2),
+            "10.00"
+        );
+
+        // 32 places is exact, so every raw value survives the round trip
+        for raw in [
+            0,
+            1,
+            -1,
+            429_496_730,
+            123_456_789_012_345,
+            i64::MIN,
+            i64::MAX,
+        ] {
+            let value = FixedQ32_32::from_raw(raw);
+            let text = value.to_decimal_str(32);
+            assert_eq!(FixedQ32_32::from_decimal_str(&text), Ok(value), "{text}");
+        }
+    }
+
+    #[test]
+    fn test_fixed_q32_32_decimal_str_deterministic() {
+        let a = FixedQ32_32::from_decimal_str("1.2345678901").unwrap();
+        let b = FixedQ32_32::from_decimal_str("1.2345678901").unwrap();
+        let c = FixedQ32_32::from_decimal_str("001.23456789010000").unwrap();
+        assert_eq!(a.to_raw(), b.to_raw());
+        assert_eq!(a.to_raw(), c.to_raw());
+    }
+
     #[test]
     fn test_determinism() {
         // Same input should always produce same raw output
@@ -0,0 +1,93 @@
+//! Deterministic statistics over fixed-point values
+//!
+//! Integer arithmetic only, with 128-bit intermediates as in
+//! [`FixedQ32_32::checked_mul`], so results are bit-identical on every
+//! platform. Every function returns `None` for empty input or when an
+//! intermediate or the result does not fit.
+
+use super::FixedQ32_32;
+
+/// Arithmetic mean, truncated toward zero to the nearest raw unit
+#[must_use]
+pub fn mean(values: &[FixedQ32_32]) -> Option<FixedQ32_32> {
+    let count = i128::try_from(values.len()).ok().filter(|&n| n > 0)?;
+    let sum = values.iter().try_fold(0i128, |sum, value| {
+        sum.checked_add(i128::from(value.to_raw()))
+    })?;
+    i64::try_from(sum / count).ok().map(FixedQ32_32::from_raw)
+}
+
+/// Population variance: the mean squared deviation from [`mean`]
+#[must_use]
+pub fn variance(values: &[FixedQ32_32]) -> Option<FixedQ32_32> {
+    let center = i128::from(mean(values)?.to_raw());
+    let squares = values.iter().try_fold(0i128, |sum, value| {
+        let deviation = i128::from(value.to_raw()) - center;
+        sum.checked_add(deviation.checked_mul(deviation)?)
+    })?;
+    // Squared raw values carry twice the fractional bits
+    let count = i128::try_from(values.len()).ok()?;
+    let raw = (squares / count) >> FixedQ32_32::FRACTIONAL_BITS;
+    i64::try_from(raw).ok().map(FixedQ32_32::from_raw)
+}
+
+/// Population standard deviation: [`FixedQ32_32::checked_sqrt`] of [`variance`]
+#[must_use]
+pub fn std_dev(values: &[FixedQ32_32]) -> Option<FixedQ32_32> {
+    variance(values)?.checked_sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixed(values: &[i64]) -> Vec<FixedQ32_32> {
+        values
+            .iter()
+            .map(|&v| FixedQ32_32::from_i64(v).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_known_values() {
+        let values = fixed(&[2, 4, 4, 4, 5, 5, 7, 9]);
+        assert_eq!(mean(&values), FixedQ32_32::from_i64(5));
+        assert_eq!(variance(&values), FixedQ32_32::from_i64(4));
+        assert_eq!(std_dev(&values), FixedQ32_32::from_i64(2));
+
+        let values = fixed(&[1, 2, 3, 4]);
+        assert_eq!(mean(&values), FixedQ32_32::from_decimal_str("2.5").ok());
+        assert_eq!(
+            variance(&values),
+            FixedQ32_32::from_decimal_str("1.25").ok()
+        );
+        // floor(sqrt(1.25) * 2^32)
+        assert_eq!(std_dev(&values), Some(FixedQ32_32::from_raw(4_801_919_417)));
+
+        let single = fixed(&[-3]);
+        assert_eq!(mean(&single), FixedQ32_32::from_i64(-3));
+        assert_eq!(variance(&single), Some(FixedQ32_32::ZERO));
+    }
+
+    #[test]
+    fn test_mean_truncates_toward_zero() {
+        let raw = |r: i64| FixedQ32_32::from_raw(r);
+        assert_eq!(mean(&[raw(1), raw(2)]), Some(raw(1)));
+        assert_eq!(mean(&[raw(-1), raw(-2)]), Some(raw(-1)));
+    }
+
+    #[test]
+    fn test_empty_and_overflow_return_none() {
+        assert_eq!(mean(&[]), None);
+        assert_eq!(variance(&[]), None);
+        assert_eq!(std_dev(&[]), None);
+
+        let extremes = [
+            FixedQ32_32::from_raw(i64::MIN),
+            FixedQ32_32::from_raw(i64::MAX),
+        ];
+        assert_eq!(mean(&extremes), Some(FixedQ32_32::ZERO));
+        assert_eq!(variance(&extremes), None);
+        assert_eq!(std_dev(&extremes), None);
+    }
+}
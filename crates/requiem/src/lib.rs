@@ -28,20 +28,24 @@
 
 pub mod fixed;
 pub mod protocol;
+mod runs;
 pub mod server;
 
 // Re-export commonly used types
 pub use fixed::{
-    FixedBps, FixedDuration, FixedPpm, FixedQ32_32, FixedThroughput,
+    DurationConversionError, FixedBps, FixedDuration, FixedPpm, FixedQ32_32, FixedThroughput,
+    ParseFixedError,
 };
 pub use protocol::{
-    CapabilityFlags, Encoding, ErrorCode, ErrorPayload, ExecRequestPayload, ExecResultPayload,
-    ExecutionControls, ExecutionMetrics, Frame, FrameError, FrameFlags, HealthRequestPayload,
-    HealthResultPayload, HelloAckPayload, HelloPayload, Histogram, MessageType, ProtocolCapabilities,
+    decode_cbor, encode_cbor, engine_to_protocol, frame_message, frame_message_as, parse_frame,
+    parse_frame_as, protocol_to_engine, validate_steps, CancelRunPayload, CapabilityFlags,
+    CompressionCodec, ConnectionStatsPayload, ConversionError, Encoding, ErrorCode, ErrorPayload,
+    ExecRequestPayload, ExecResultPayload, ExecutionControls, ExecutionMetrics, Frame,
+    FrameAssembler, FrameError, FrameFlags, FrameReader, HealthRequestPayload, HealthResultPayload,
+    HelloAckPayload, HelloPayload, Histogram, HistogramError, MessageType, ProtocolCapabilities,
     ProtocolError, ProtocolState, ProtocolStats, ProtocolVersion, RunStatus, Workflow,
-    decode_cbor, encode_cbor, frame_message, parse_frame,
 };
-pub use server::{Server, ServerConfig};
+pub use server::{ConnectionLimitMode, Server, ServerConfig, TransportError};
 
 /// Protocol version information
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -51,9 +55,11 @@ pub const PROTOCOL_NAME: &str = "reach-binary-protocol";
 
 /// Check if the protocol is supported
 pub fn is_supported() -> bool {
-    true
+    requiem_version::is_supported()
 }
 
+pub use requiem_version::version_info;
+
 /// Get protocol capabilities
 pub fn capabilities() -> ProtocolCapabilities {
     ProtocolCapabilities::default()
@@ -0,0 +1,176 @@
+//! Multi-frame message reassembly
+//!
+//! A logical message may be split across several frames sharing a correlation
+//! id; the last one carries `FrameFlags::EOS`. `FrameAssembler` joins their
+//! payloads so the result can be handed to `parse_frame` in one piece.
+
+use super::frame::{Frame, FrameError, FrameFlags, MAX_PAYLOAD_BYTES};
+
+/// Reassembles EOS-terminated multi-frame messages
+///
+/// Frames are fed in arrival order with [`FrameAssembler::push`]. The total
+/// payload of one message is bounded by `MAX_PAYLOAD_BYTES`; on any error the
+/// partial message is discarded and the assembler is ready for the next one.
+#[derive(Debug, Default)]
+pub struct FrameAssembler {
+    pending: Option<Frame>,
+}
+
+impl FrameAssembler {
+    /// Create an empty assembler
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a frame, returning the complete message once its EOS frame arrives
+    ///
+    /// The returned frame has the message type and correlation id of the first
+    /// frame, the flags of the EOS frame and the concatenated payload.
+    pub fn push(&mut self, frame: Frame) -> Result<Option<Frame>, FrameError> {
+        let eos = frame.flags.contains(FrameFlags::EOS);
+
+        let message = match self.pending.take() {
+            None => frame,
+            Some(mut message) => {
+                if frame.correlation_id != message.correlation_id {
+                    return Err(FrameError::CorrelationMismatch {
+                        expected: message.correlation_id,
+                        got: frame.correlation_id,
+                    });
+                }
+                let size = message.payload.len() + frame.payload.len();
+                if size > MAX_PAYLOAD_BYTES as usize {
+                    return Err(FrameError::PayloadTooLarge {
+                        size: u32::try_from(size).unwrap_or(u32::MAX),
+                        max: MAX_PAYLOAD_BYTES,
+                    });
+                }
+                message.payload.extend_from_slice(&frame.payload);
+                message.flags = frame.flags;
+                message
+            }
+        };
+
+        if eos {
+            Ok(Some(message))
+        } else {
+            self.pending = Some(message);
+            Ok(None)
+        }
+    }
+
+    /// Whether a partial message is waiting for more frames
+    #[must_use]
+    pub fn is_pending(&self) -> bool {
+        self.pending.is_some()
+    }
+
+    /// Payload bytes accumulated for the partial message
+    #[must_use]
+    pub fn pending_len(&self) -> usize {
+        self.pending.as_ref().map_or(0, |frame| frame.payload.len())
+    }
+
+    /// Discard any partial message
+    pub fn reset(&mut self) {
+        self.pending = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::{parse_frame, serialize_message, MessageType};
+
+    fn part(correlation_id: u32, payload: Vec<u8>, eos: bool) -> Frame {
+        let flags = if eos {
+            FrameFlags::EOS
+        } else {
+            FrameFlags::NONE
+        };
+        Frame::new(MessageType::ExecResult, payload)
+            .unwrap()
+            .with_correlation_id(correlation_id)
+            .with_flags(flags)
+    }
+
+    #[test]
+    fn test_two_frame_message_reassembled() {
+        let message = vec!["alpha".to_string(), "beta".to_string(), "gamma".to_string()];
+        let bytes = serialize_message(&message).unwrap();
+        let (head, tail) = bytes.split_at(bytes.len() / 2);
+
+        let mut assembler = FrameAssembler::new();
+        assert!(assembler
+            .push(part(5, head.to_vec(), false))
+            .unwrap()
+            .is_none());
+        assert!(assembler.is_pending());
+        assert_eq!(assembler.pending_len(), head.len());
+
+        let frame = assembler
+            .push(part(5, tail.to_vec(), true))
+            .unwrap()
+            .unwrap();
+        assert_eq!(frame.correlation_id, 5);
+        assert_eq!(frame.msg_type, MessageType::ExecResult);
+        assert!(frame.flags.contains(FrameFlags::EOS));
+        assert!(!assembler.is_pending());
+
+        let decoded: Vec<String> = parse_frame(&frame).unwrap();
+        assert_eq!(decoded, message);
+    }
+
+    #[test]
+    fn test_single_eos_frame_passes_through() {
+        let mut assembler = FrameAssembler::new();
+        let frame = part(1, b"whole".to_vec(), true);
+        assert_eq!(assembler.push(frame.clone()).unwrap(), Some(frame));
+        assert!(!assembler.is_pending());
+    }
+
+    #[test]
+    fn test_oversize_accumulation_rejected() {
+        let half = (MAX_PAYLOAD_BYTES / 2) as usize;
+        let mut assembler = FrameAssembler::new();
+        assert!(assembler
+            .push(part(9, vec![0; half], false))
+            .unwrap()
+            .is_none());
+        assert!(assembler
+            .push(part(9, vec![0; half], false))
+            .unwrap()
+            .is_none());
+
+        let err = assembler.push(part(9, vec![0; 1], true)).unwrap_err();
+        assert!(matches!(
+            err,
+            FrameError::PayloadTooLarge { size, max: MAX_PAYLOAD_BYTES } if size == MAX_PAYLOAD_BYTES + 1
+        ));
+        // The partial message is dropped and the assembler is reusable
+        assert!(!assembler.is_pending());
+        assert!(assembler
+            .push(part(10, b"next".to_vec(), true))
+            .unwrap()
+            .is_some());
+    }
+
+    #[test]
+    fn test_mixed_correlation_ids_rejected() {
+        let mut assembler = FrameAssembler::new();
+        assembler.push(part(1, b"first".to_vec(), false)).unwrap();
+
+        let err = assembler
+            .push(part(2, b"second".to_vec(), true))
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            FrameError::CorrelationMismatch {
+                expected: 1,
+                got: 2
+            }
+        ));
+        assert!(!assembler.is_pending());
+    }
+}
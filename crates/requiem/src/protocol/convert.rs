@@ -0,0 +1,522 @@
+//! Conversion between protocol and engine workflows
+//!
+//! A protocol workflow is a step list whose order is given by `depends_on`;
+//! an engine workflow is a step list the engine runs strictly in order. Going
+//! to the engine, steps are topologically sorted (ties keep their listed
+//! order, so the result is deterministic). Coming back, each step depends on
+//! the one before it.
+//!
+//! Step configuration keys, checked by [`validate_steps`] before conversion:
+//! - `tool_call`: `tool` (required string), `description` (string), `input`,
+//!   `input_schema` and `output_schema` (objects), `estimated_cost_usd`
+//!   (non-negative number)
+//! - `emit_artifact`: `patch` (required, `{"diffs": [...]}`),
+//!   `estimated_cost_usd` (non-negative number)
+//! - `decision`: `expression` (required string)
+//! - `pause`: `reason` (string)
+//!
+//! Keys not listed are ignored. `decision` and `pause` steps have no engine
+//! equivalent.
+
+use super::message::{StepType, Workflow, WorkflowStep};
+use std::collections::{BTreeMap, BTreeSet};
+use thiserror::Error;
+
+/// Workflow conversion errors
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ConversionError {
+    #[error("step {0} is defined more than once")]
+    DuplicateStep(String),
+
+    #[error("step {step} depends on unknown step {dependency}")]
+    UnknownDependency { step: String, dependency: String },
+
+    #[error("dependency cycle among steps {}", .0.join(", "))]
+    Cycle(Vec<String>),
+
+    #[error("step {step} has type {step_type:?}, which the engine cannot run")]
+    UnsupportedStep { step: String, step_type: StepType },
+
+    #[error("step {step} has invalid config: {reason}")]
+    InvalidConfig { step: String, reason: String },
+
+    #[error("step {step} config is missing required key {key}")]
+    MissingConfigKey { step: String, key: String },
+
+    #[error("step {step} config key {key} is invalid: {reason}")]
+    InvalidConfigKey {
+        step: String,
+        key: String,
+        reason: String,
+    },
+}
+
+/// Convert a protocol workflow into the engine's form
+///
+/// The workflow name becomes the engine workflow id. Step configs are
+/// checked with [`validate_steps`] first.
+pub fn protocol_to_engine(
+    workflow: &Workflow,
+) -> Result<engine::workflow::Workflow, ConversionError> {
+    validate_steps(workflow)?;
+    let steps = topological_order(&workflow.steps)?
+        .into_iter()
+        .map(|step| {
+            Ok(engine::workflow::Step {
+                id: step.id.clone(),
+                kind: step_kind(step)?,
+                estimated_cost_usd: step
+                    .config
+                    .get("estimated_cost_usd")
+                    .and_then(serde_json::Value::as_f64),
+            })
+        })
+        .collect::<Result<_, ConversionError>>()?;
+
+    Ok(engine::workflow::Workflow {
+        id: workflow.name.clone(),
+        version: workflow.version.clone(),
+        schema_version: engine::workflow::WORKFLOW_SCHEMA_VERSION.to_string(),
+        migrated_from: None,
+        steps,
+    })
+}
+
+/// Convert an engine workflow into the protocol's form
+///
+/// The engine's step order becomes a dependency chain. Every engine step has
+/// a protocol equivalent, so this cannot fail.
+#[must_use]
+pub fn engine_to_protocol(workflow: &engine::workflow::Workflow) -> Workflow {
+    let steps = workflow
+        .steps
+        .iter()
+        .enumerate()
+        .map(|(index, step)| {
+            let (step_type, mut config) = match &step.kind {
+                engine::workflow::StepKind::ToolCall { tool, input } => {
+                    let mut config = BTreeMap::new();
+                    config.insert("tool".to_string(), serde_json::json!(tool.name));
+                    config.insert(
+                        "description".to_string(),
+                        serde_json::json!(tool.description),
+                    );
+                    config.insert("input".to_string(), input.clone());
+                    config.insert("input_schema".to_string(), tool.input_schema.clone());
+                    config.insert("output_schema".to_string(), tool.output_schema.clone());
+                    (StepType::ToolCall, config)
+                }
+                engine::workflow::StepKind::EmitArtifact { patch } => {
+                    let mut config = BTreeMap::new();
+                    config.insert("patch".to_string(), serde_json::json!(patch));
+                    (StepType::EmitArtifact, config)
+                }
+            };
+            if let Some(cost) = step.estimated_cost_usd {
+                config.insert("estimated_cost_usd".to_string(), serde_json::json!(cost));
+            }
+            WorkflowStep {
+                id: step.id.clone(),
+                step_type,
+                config,
+                depends_on: index
+                    .checked_sub(1)
+                    .map(|previous| vec![workflow.steps[previous].id.clone()])
+                    .unwrap_or_default(),
+            }
+        })
+        .collect();
+
+    Workflow {
+        name: workflow.id.clone(),
+        version: workflow.version.clone(),
+        steps,
+    }
+}
+
+/// Check every step's config against the keys its `StepType` requires
+///
+/// Steps are checked in declared order and keys in the order listed in the
+/// module docs; the first problem found is returned.
+pub fn validate_steps(workflow: &Workflow) -> Result<(), ConversionError> {
+    workflow.steps.iter().try_for_each(validate_step)
+}
+
+fn validate_step(step: &WorkflowStep) -> Result<(), ConversionError> {
+    let invalid = |key: &str, reason: &str| ConversionError::InvalidConfigKey {
+        step: step.id.clone(),
+        key: key.to_string(),
+        reason: reason.to_string(),
+    };
+    let required = |key: &str| {
+        step.config
+            .get(key)
+            .ok_or_else(|| ConversionError::MissingConfigKey {
+                step: step.id.clone(),
+                key: key.to_string(),
+            })
+    };
+    let text = |key: &str, value: &serde_json::Value| match value.as_str() {
+        Some("") => Err(invalid(key, "must not be empty")),
+        Some(_) => Ok(()),
+        None => Err(invalid(key, "must be a string")),
+    };
+    let optional =
+        |key: &str, check: &dyn Fn(&str, &serde_json::Value) -> Result<(), ConversionError>| {
+            step.config
+                .get(key)
+                .map_or(Ok(()), |value| check(key, value))
+        };
+    let object = |key: &str, value: &serde_json::Value| {
+        if value.is_object() {
+            Ok(())
+        } else {
+            Err(invalid(key, "must be an object"))
+        }
+    };
+    let cost = |key: &str, value: &serde_json::Value| match value.as_f64() {
+        Some(cost) if cost >= 0.0 => Ok(()),
+        _ => Err(invalid(key, "must be a non-negative number")),
+    };
+
+    match step.step_type {
+        StepType::ToolCall => {
+            text("tool", required("tool")?)?;
+            optional("description", &|key, value| {
+                value
+                    .as_str()
+                    .map(|_| ())
+                    .ok_or_else(|| invalid(key, "must be a string"))
+            })?;
+            optional("input_schema", &object)?;
+            optional("output_schema", &object)?;
+            optional("estimated_cost_usd", &cost)
+        }
+        StepType::EmitArtifact => {
+            serde_json::from_value::<engine::artifacts::Patch>(required("patch")?.clone())
+                .map_err(|e| invalid("patch", &e.to_string()))?;
+            optional("estimated_cost_usd", &cost)
+        }
+        StepType::Decision => text("expression", required("expression")?),
+        StepType::Pause => optional("reason", &text),
+    }
+}
+
+/// Order steps so every step follows its dependencies
+///
+/// Kahn's algorithm, always taking the earliest listed ready step.
+fn topological_order(steps: &[WorkflowStep]) -> Result<Vec<&WorkflowStep>, ConversionError> {
+    let mut index_of = BTreeMap::new();
+    for (index, step) in steps.iter().enumerate() {
+        if index_of.insert(step.id.as_str(), index).is_some() {
+            return Err(ConversionError::DuplicateStep(step.id.clone()));
+        }
+    }
+
+    let mut waiting_on = vec![0usize; steps.len()];
+    let mut dependents: Vec<Vec<usize>> = vec![Vec::new(); steps.len()];
+    for (index, step) in steps.iter().enumerate() {
+        // A dependency listed twice is still one edge
+        let dependencies: BTreeSet<&str> = step.depends_on.iter().map(String::as_str).collect();
+        for dependency in dependencies {
+            let Some(&before) = index_of.get(dependency) else {
+                return Err(ConversionError::UnknownDependency {
+                    step: step.id.clone(),
+                    dependency: dependency.to_string(),
+                });
+            };
+            waiting_on[index] += 1;
+            dependents[before].push(index);
+        }
+    }
+
+    let mut ready: BTreeSet<usize> = (0..steps.len()).filter(|&i| waiting_on[i] == 0).collect();
+    let mut order = Vec::with_capacity(steps.len());
+    while let Some(index) = ready.pop_first() {
+        order.push(&steps[index]);
+        for &dependent in &dependents[index] {
+            waiting_on[dependent] -= 1;
+            if waiting_on[dependent] == 0 {
+                ready.insert(dependent);
+            }
+        }
+    }
+
+    if order.len() < steps.len() {
+        return Err(ConversionError::Cycle(
+            steps
+                .iter()
+                .zip(&waiting_on)
+                .filter(|(_, &waiting)| waiting > 0)
+                .map(|(step, _)| step.id.clone())
+                .collect(),
+        ));
+    }
+    Ok(order)
+}
+
+fn step_kind(step: &WorkflowStep) -> Result<engine::workflow::StepKind, ConversionError> {
+    let invalid = |reason: String| ConversionError::InvalidConfig {
+        step: step.id.clone(),
+        reason,
+    };
+    let text = |key: &str| step.config.get(key).and_then(serde_json::Value::as_str);
+    let schema = |key: &str| {
+        step.config
+            .get(key)
+            .cloned()
+            .unwrap_or_else(|| serde_json::json!({"type": "object"}))
+    };
+
+    match step.step_type {
+        StepType::ToolCall => {
+            let name = text("tool").ok_or_else(|| invalid("missing tool name".to_string()))?;
+            Ok(engine::workflow::StepKind::ToolCall {
+                tool: engine::tools::ToolSpec {
+                    name: name.to_string(),
+                    description: text("description").unwrap_or_default().to_string(),
+                    input_schema: schema("input_schema"),
+                    output_schema: schema("output_schema"),
+                },
+                input: step.config.get("input").cloned().unwrap_or_default(),
+            })
+        }
+        StepType::EmitArtifact => {
+            let patch = step
+                .config
+                .get("patch")
+                .ok_or_else(|| invalid("missing patch".to_string()))?;
+            Ok(engine::workflow::StepKind::EmitArtifact {
+                patch: serde_json::from_value(patch.clone())
+                    .map_err(|e| invalid(format!("invalid patch: {e}")))?,
+            })
+        }
+        StepType::Decision | StepType::Pause => Err(ConversionError::UnsupportedStep {
+            step: step.id.clone(),
+            step_type: step.step_type.clone(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn artifact(id: &str, depends_on: &[&str]) -> WorkflowStep {
+        let mut config = BTreeMap::new();
+        config.insert(
+            "patch".to_string(),
+            serde_json::json!({"diffs": [{"path": format!("{id}.md"), "before": "", "after": id}]}),
+        );
+        WorkflowStep {
+            id: id.to_string(),
+            step_type: StepType::EmitArtifact,
+            config,
+            depends_on: depends_on.iter().map(|d| d.to_string()).collect(),
+        }
+    }
+
+    fn tool(id: &str, depends_on: &[&str]) -> WorkflowStep {
+        let mut config = BTreeMap::new();
+        config.insert("tool".to_string(), serde_json::json!("search"));
+        config.insert("input".to_string(), serde_json::json!({"query": id}));
+        WorkflowStep {
+            id: id.to_string(),
+            step_type: StepType::ToolCall,
+            config,
+            depends_on: depends_on.iter().map(|d| d.to_string()).collect(),
+        }
+    }
+
+    fn workflow(steps: Vec<WorkflowStep>) -> Workflow {
+        Workflow {
+            name: "wf".to_string(),
+            version: "1.0.0".to_string(),
+            steps,
+        }
+    }
+
+    fn step_ids(workflow: &engine::workflow::Workflow) -> Vec<&str> {
+        workflow.steps.iter().map(|s| s.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_linear_workflow_roundtrips() {
+        let linear = workflow(vec![
+            tool("fetch", &[]),
+            artifact("draft", &["fetch"]),
+            tool("publish", &["draft"]),
+        ]);
+
+        let converted = protocol_to_engine(&linear).unwrap();
+        assert_eq!(converted.id, "wf");
+        assert_eq!(step_ids(&converted), vec!["fetch", "draft", "publish"]);
+        match &converted.steps[0].kind {
+            engine::workflow::StepKind::ToolCall { tool, input } => {
+                assert_eq!(tool.name, "search");
+                assert_eq!(input["query"], serde_json::json!("fetch"));
+            }
+            other => panic!("expected a tool call, got {other:?}"),
+        }
+
+        let back = engine_to_protocol(&converted);
+        assert_eq!(back.steps[2].depends_on, vec!["draft".to_string()]);
+        assert_eq!(protocol_to_engine(&back).unwrap(), converted);
+    }
+
+    #[test]
+    fn test_estimated_cost_roundtrips() {
+        let mut fetch = tool("fetch", &[]);
+        fetch
+            .config
+            .insert("estimated_cost_usd".to_string(), serde_json::json!(0.25));
+        let mut draft = artifact("draft", &["fetch"]);
+        draft
+            .config
+            .insert("estimated_cost_usd".to_string(), serde_json::json!(1));
+        let costed = workflow(vec![fetch, draft, tool("publish", &["draft"])]);
+
+        let converted = protocol_to_engine(&costed).unwrap();
+        let costs: Vec<_> = converted
+            .steps
+            .iter()
+            .map(|s| s.estimated_cost_usd)
+            .collect();
+        assert_eq!(costs, vec![Some(0.25), Some(1.0), None]);
+
+        let back = engine_to_protocol(&converted);
+        assert_eq!(
+            back.steps[0].config["estimated_cost_usd"],
+            serde_json::json!(0.25)
+        );
+        assert!(!back.steps[2].config.contains_key("estimated_cost_usd"));
+        assert_eq!(protocol_to_engine(&back).unwrap(), converted);
+
+        let mut negative = tool("fetch", &[]);
+        negative
+            .config
+            .insert("estimated_cost_usd".to_string(), serde_json::json!(-1.0));
+        assert_eq!(
+            validate_steps(&workflow(vec![negative])),
+            Err(ConversionError::InvalidConfigKey {
+                step: "fetch".to_string(),
+                key: "estimated_cost_usd".to_string(),
+                reason: "must be a non-negative number".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_diamond_dependencies_are_ordered() {
+        // Listed out of order: the join first, the root last
+        let diamond = workflow(vec![
+            artifact("join", &["right", "left"]),
+            artifact("right", &["root"]),
+            artifact("left", &["root"]),
+            artifact("root", &[]),
+        ]);
+
+        let converted = protocol_to_engine(&diamond).unwrap();
+        assert_eq!(step_ids(&converted), vec!["root", "right", "left", "join"]);
+    }
+
+    #[test]
+    fn test_cycles_and_unmappable_steps_are_rejected() {
+        let cyclic = workflow(vec![
+            artifact("start", &[]),
+            artifact("a", &["start", "b"]),
+            artifact("b", &["a"]),
+        ]);
+        assert_eq!(
+            protocol_to_engine(&cyclic),
+            Err(ConversionError::Cycle(vec![
+                "a".to_string(),
+                "b".to_string()
+            ]))
+        );
+
+        let unknown = workflow(vec![artifact("a", &["missing"])]);
+        assert_eq!(
+            protocol_to_engine(&unknown),
+            Err(ConversionError::UnknownDependency {
+                step: "a".to_string(),
+                dependency: "missing".to_string(),
+            })
+        );
+
+        let mut decision = artifact("choose", &[]);
+        decision.step_type = StepType::Decision;
+        decision
+            .config
+            .insert("expression".to_string(), serde_json::json!("score > 0"));
+        assert_eq!(
+            protocol_to_engine(&workflow(vec![decision])),
+            Err(ConversionError::UnsupportedStep {
+                step: "choose".to_string(),
+                step_type: StepType::Decision,
+            })
+        );
+    }
+
+    #[test]
+    fn test_valid_step_configs_pass_validation() {
+        let mut decision = artifact("choose", &["fetch"]);
+        decision.step_type = StepType::Decision;
+        decision.config =
+            BTreeMap::from([("expression".to_string(), serde_json::json!("score > 0"))]);
+        let mut pause = artifact("wait", &["choose"]);
+        pause.step_type = StepType::Pause;
+        pause.config = BTreeMap::new();
+
+        let steps = workflow(vec![
+            tool("fetch", &[]),
+            artifact("draft", &["fetch"]),
+            decision,
+            pause,
+        ]);
+        assert_eq!(validate_steps(&steps), Ok(()));
+    }
+
+    #[test]
+    fn test_step_config_errors_name_step_and_key() {
+        let mut nameless = tool("fetch", &[]);
+        nameless.config.remove("tool");
+        let mut decision = artifact("choose", &[]);
+        decision.step_type = StepType::Decision;
+        decision.config = BTreeMap::new();
+
+        // The first step in declared order is reported
+        let steps = workflow(vec![artifact("draft", &[]), nameless, decision.clone()]);
+        assert_eq!(
+            validate_steps(&steps),
+            Err(ConversionError::MissingConfigKey {
+                step: "fetch".to_string(),
+                key: "tool".to_string(),
+            })
+        );
+        assert!(matches!(
+            protocol_to_engine(&steps),
+            Err(ConversionError::MissingConfigKey { step, .. }) if step == "fetch"
+        ));
+
+        assert_eq!(
+            validate_steps(&workflow(vec![decision.clone()])),
+            Err(ConversionError::MissingConfigKey {
+                step: "choose".to_string(),
+                key: "expression".to_string(),
+            })
+        );
+
+        decision
+            .config
+            .insert("expression".to_string(), serde_json::json!(42));
+        assert_eq!(
+            validate_steps(&workflow(vec![decision])),
+            Err(ConversionError::InvalidConfigKey {
+                step: "choose".to_string(),
+                key: "expression".to_string(),
+                reason: "must be a string".to_string(),
+            })
+        );
+    }
+}
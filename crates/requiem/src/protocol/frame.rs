@@ -26,7 +26,7 @@
 use bytes::{Buf, BufMut, BytesMut};
 use crc32c::crc32c;
 use serde::{Deserialize, Serialize};
-use std::io;
+use std::io::{self, Read, Write};
 use thiserror::Error;
 use tokio_util::codec::{Decoder, Encoder};
 
@@ -38,6 +38,12 @@ pub const MAGIC: u32 = 0x52454348;
 /// This prevents memory exhaustion attacks
 pub const MAX_PAYLOAD_BYTES: u32 = 64 * 1024 * 1024;
 
+/// Payload cap for handshake and heartbeat frames (64 KiB)
+pub const MAX_CONTROL_PAYLOAD_BYTES: u32 = 64 * 1024;
+
+/// Payload cap for health results and error frames (1 MiB)
+pub const MAX_REPORT_PAYLOAD_BYTES: u32 = 1024 * 1024;
+
 /// Header size: Magic(4) + Version(4) + MsgType(4) + Flags(4) + CorrelationID(4) + PayloadLen(4) = 24
 pub const HEADER_SIZE: usize = 24;
 
@@ -51,8 +57,8 @@ pub const FRAME_OVERHEAD: usize = HEADER_SIZE + FOOTER_SIZE;
 pub const MAX_UNTRUSTED_ALLOCATION: u32 = 1024 * 1024;
 
 /// Protocol version (major, minor)
-pub const PROTOCOL_VERSION_MAJOR: u16 = 1;
-pub const PROTOCOL_VERSION_MINOR: u16 = 0;
+pub const PROTOCOL_VERSION_MAJOR: u16 = requiem_version::PROTOCOL_VERSION_MAJOR;
+pub const PROTOCOL_VERSION_MINOR: u16 = requiem_version::PROTOCOL_VERSION_MINOR;
 
 /// Frame flags
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -61,7 +67,7 @@ pub struct FrameFlags(pub u32);
 impl FrameFlags {
     /// No special flags
     pub const NONE: Self = Self(0);
-    /// Payload is compressed (zlib)
+    /// Payload is compressed with the connection's negotiated codec
     pub const COMPRESSED: Self = Self(1 << 0);
     /// End of stream indicator
     pub const EOS: Self = Self(1 << 1);
@@ -77,6 +83,92 @@ impl FrameFlags {
     }
 }
 
+/// Payload compression codec negotiated in the hello exchange
+///
+/// Applies to frames carrying `FrameFlags::COMPRESSED`. With `None`, frames
+/// are never compressed and the flag is cleared on encode.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CompressionCodec {
+    #[default]
+    None,
+    Zlib,
+    Zstd,
+}
+
+impl CompressionCodec {
+    /// Codecs a server supports unless configured otherwise, most preferred first
+    pub const SUPPORTED: [Self; 2] = [Self::Zstd, Self::Zlib];
+
+    /// First codec in the client's `offered` list that is also `supported`
+    ///
+    /// Falls back to `None` when the two lists share no codec.
+    #[must_use]
+    pub fn negotiate(offered: &[Self], supported: &[Self]) -> Self {
+        offered
+            .iter()
+            .copied()
+            .find(|codec| *codec != Self::None && supported.contains(codec))
+            .unwrap_or(Self::None)
+    }
+
+    /// Compress `data`; `None` returns it unchanged
+    pub fn compress(self, data: &[u8]) -> Result<Vec<u8>, FrameError> {
+        match self {
+            Self::None => Ok(data.to_vec()),
+            Self::Zlib => {
+                let mut encoder =
+                    flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(data)?;
+                Ok(encoder.finish()?)
+            }
+            // Single-shot compression records the content size, so the frame's
+            // window never exceeds the payload and fits the receiver's cap
+            Self::Zstd => Ok(zstd::bulk::compress(data, 0)?),
+        }
+    }
+
+    /// Decompress `data`, refusing output longer than `max` bytes
+    pub fn decompress(self, data: &[u8], max: u32) -> Result<Vec<u8>, FrameError> {
+        let mut reader: Box<dyn Read + '_> = match self {
+            Self::None => {
+                return Err(FrameError::Compression(
+                    "compressed payload but no codec negotiated".to_string(),
+                ))
+            }
+            Self::Zlib => Box::new(flate2::read::ZlibDecoder::new(data)),
+            Self::Zstd => {
+                // ADVERSARIAL: Bound the back-reference window by the cap so a
+                // frame header cannot make the decoder allocate past it
+                let mut decoder = zstd::Decoder::new(data)?;
+                decoder.window_log_max(zstd_window_log(max))?;
+                Box::new(decoder)
+            }
+        };
+
+        // ADVERSARIAL: Read at most one byte past the cap so a compression
+        // bomb cannot expand beyond the payload limit
+        let mut payload =
+            Vec::with_capacity(std::cmp::min(data.len(), MAX_UNTRUSTED_ALLOCATION as usize));
+        reader
+            .by_ref()
+            .take(u64::from(max) + 1)
+            .read_to_end(&mut payload)?;
+        let size = u32::try_from(payload.len()).unwrap_or(u32::MAX);
+        if size > max {
+            return Err(FrameError::PayloadTooLarge { size, max });
+        }
+        Ok(payload)
+    }
+}
+
+/// Smallest zstd window log whose window covers `max` bytes
+///
+/// Clamped to zstd's minimum window log of 10 (1 KiB).
+fn zstd_window_log(max: u32) -> u32 {
+    max.saturating_sub(1).bit_width().max(10)
+}
+
 /// Message types for the protocol
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[repr(u32)]
@@ -91,6 +183,10 @@ pub enum MessageType {
     ExecRequest = 0x10,
     /// Execution result
     ExecResult = 0x11,
+    /// Cancel a run that is still active
+    CancelRun = 0x12,
+    /// Resume a paused run with what it is waiting for
+    ResumeRun = 0x13,
     /// Health check request
     HealthRequest = 0x20,
     /// Health check result
@@ -108,6 +204,8 @@ impl MessageType {
             0x02 => Some(Self::HelloAck),
             0x10 => Some(Self::ExecRequest),
             0x11 => Some(Self::ExecResult),
+            0x12 => Some(Self::CancelRun),
+            0x13 => Some(Self::ResumeRun),
             0x20 => Some(Self::HealthRequest),
             0x21 => Some(Self::HealthResult),
             0xFF => Some(Self::Error),
@@ -119,6 +217,24 @@ impl MessageType {
     pub fn to_u32(self) -> u32 {
         self as u32
     }
+
+    /// Largest payload accepted for this message type
+    ///
+    /// Only execution requests, results and resumes (which carry tool
+    /// output) may use the full `MAX_PAYLOAD_BYTES`; every cap is at most
+    /// that global ceiling.
+    #[must_use]
+    pub fn max_payload_bytes(self) -> u32 {
+        match self {
+            Self::Heartbeat
+            | Self::Hello
+            | Self::HelloAck
+            | Self::CancelRun
+            | Self::HealthRequest => MAX_CONTROL_PAYLOAD_BYTES,
+            Self::HealthResult | Self::Error => MAX_REPORT_PAYLOAD_BYTES,
+            Self::ExecRequest | Self::ExecResult | Self::ResumeRun => MAX_PAYLOAD_BYTES,
+        }
+    }
 }
 
 /// Frame parsing/serialization errors
@@ -126,25 +242,31 @@ impl MessageType {
 pub enum FrameError {
     #[error("invalid magic number: expected {expected:08X}, got {got:08X}")]
     InvalidMagic { expected: u32, got: u32 },
-    
+
     #[error("unsupported protocol version: major={major}, minor={minor}")]
     UnsupportedVersion { major: u16, minor: u16 },
-    
+
     #[error("unknown message type: {0:#08X}")]
     UnknownMessageType(u32),
-    
+
     #[error("payload too large: {size} bytes (max {max})")]
     PayloadTooLarge { size: u32, max: u32 },
-    
+
     #[error("payload length mismatch: header says {expected}, got {actual}")]
     PayloadLengthMismatch { expected: usize, actual: usize },
-    
+
     #[error("CRC32C mismatch: expected {expected:08X}, calculated {calculated:08X}")]
     CrcMismatch { expected: u32, calculated: u32 },
-    
+
+    #[error("correlation id mismatch in multi-frame message: expected {expected}, got {got}")]
+    CorrelationMismatch { expected: u32, got: u32 },
+
+    #[error("compression error: {0}")]
+    Compression(String),
+
     #[error("incomplete frame: need {needed} more bytes")]
     Incomplete { needed: usize },
-    
+
     #[error("IO error: {0}")]
     Io(#[from] io::Error),
 }
@@ -163,11 +285,12 @@ pub struct Frame {
 impl Frame {
     /// Create a new frame with current protocol version
     pub fn new(msg_type: MessageType, payload: Vec<u8>) -> Result<Self, FrameError> {
-        let payload_len = payload.len() as u32;
-        if payload_len > MAX_PAYLOAD_BYTES {
+        let payload_len = u32::try_from(payload.len()).unwrap_or(u32::MAX);
+        let max = msg_type.max_payload_bytes();
+        if payload_len > max {
             return Err(FrameError::PayloadTooLarge {
                 size: payload_len,
-                max: MAX_PAYLOAD_BYTES,
+                max,
             });
         }
 
@@ -196,7 +319,7 @@ impl Frame {
     /// Calculate CRC32C over the frame content (excluding the CRC field itself)
     fn calculate_crc(&self) -> u32 {
         let mut hasher = crc32c::Hasher::new();
-        
+
         // Hash magic
         hasher.update(&MAGIC.to_le_bytes());
         // Hash version
@@ -212,7 +335,7 @@ impl Frame {
         hasher.update(&(self.payload.len() as u32).to_le_bytes());
         // Hash payload
         hasher.update(&self.payload);
-        
+
         hasher.finalize()
     }
 
@@ -220,9 +343,9 @@ impl Frame {
     pub fn encode(&self, dst: &mut BytesMut) -> Result<(), FrameError> {
         let payload_len = self.payload.len();
         let total_len = FRAME_OVERHEAD + payload_len;
-        
+
         dst.reserve(total_len);
-        
+
         // Magic
         dst.put_u32_le(MAGIC);
         // Version
@@ -241,7 +364,7 @@ impl Frame {
         // CRC32C
         let crc = self.calculate_crc();
         dst.put_u32_le(crc);
-        
+
         Ok(())
     }
 
@@ -254,7 +377,7 @@ impl Frame {
 
         // Peek at header without consuming
         let mut peek = src.as_ref();
-        
+
         // Check magic
         let magic = peek.get_u32_le();
         if magic != MAGIC {
@@ -282,11 +405,12 @@ impl Frame {
         // Parse payload length
         let payload_len = peek.get_u32_le();
 
-        // Validate payload length
-        if payload_len > MAX_PAYLOAD_BYTES {
+        // Validate payload length against the cap for this message type
+        let max = msg_type.max_payload_bytes();
+        if payload_len > max {
             return Err(FrameError::PayloadTooLarge {
                 size: payload_len,
-                max: MAX_PAYLOAD_BYTES,
+                max,
             });
         }
 
@@ -301,14 +425,15 @@ impl Frame {
 
         // Extract payload with guarded allocation
         // ADVERSARIAL: Cap pre-allocation to prevent memory-based DoS
-        let mut payload = Vec::with_capacity(std::cmp::min(payload_len, MAX_UNTRUSTED_ALLOCATION) as usize);
-        
+        let mut payload =
+            Vec::with_capacity(std::cmp::min(payload_len, MAX_UNTRUSTED_ALLOCATION) as usize);
+
         payload.extend_from_slice(&src[..payload_len as usize]);
         src.advance(payload_len as usize);
 
         // Verify CRC
         let expected_crc = src.get_u32_le();
-        
+
         // Calculate CRC over what we just decoded
         let frame = Self {
             version_major,
@@ -318,7 +443,7 @@ impl Frame {
             correlation_id,
             payload,
         };
-        
+
         let calculated_crc = frame.calculate_crc();
         if expected_crc != calculated_crc {
             return Err(FrameError::CrcMismatch {
@@ -334,17 +459,80 @@ impl Frame {
     pub fn payload(&self) -> &[u8] {
         &self.payload
     }
+
+    /// Prepare a frame for the wire
+    ///
+    /// A `COMPRESSED` frame has its payload compressed with `codec`; with
+    /// `CompressionCodec::None` the payload passes through and the flag is
+    /// cleared. The CRC is calculated later, over the compressed bytes.
+    pub fn compress(mut self, codec: CompressionCodec) -> Result<Self, FrameError> {
+        if !self.flags.contains(FrameFlags::COMPRESSED) {
+            return Ok(self);
+        }
+        if codec == CompressionCodec::None {
+            self.flags.0 &= !FrameFlags::COMPRESSED.0;
+            return Ok(self);
+        }
+
+        self.payload = codec.compress(&self.payload)?;
+        let size = u32::try_from(self.payload.len()).unwrap_or(u32::MAX);
+        let max = self.msg_type.max_payload_bytes();
+        if size > max {
+            return Err(FrameError::PayloadTooLarge { size, max });
+        }
+        Ok(self)
+    }
+
+    /// Undo [`Frame::compress`] on a frame read from the wire
+    ///
+    /// The decompressed payload is held to the message type's payload cap,
+    /// and the `COMPRESSED` flag is cleared.
+    pub fn decompress(mut self, codec: CompressionCodec) -> Result<Self, FrameError> {
+        if !self.flags.contains(FrameFlags::COMPRESSED) {
+            return Ok(self);
+        }
+        self.payload = codec.decompress(&self.payload, self.msg_type.max_payload_bytes())?;
+        self.flags.0 &= !FrameFlags::COMPRESSED.0;
+        Ok(self)
+    }
 }
 
 /// Tokio codec for framing
-pub struct FrameCodec;
+///
+/// Compresses and decompresses `COMPRESSED` frames with the codec it holds,
+/// which is `CompressionCodec::None` until a hello exchange agrees on one.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FrameCodec {
+    compression: CompressionCodec,
+}
+
+impl FrameCodec {
+    /// Codec applying `compression` to `COMPRESSED` frames
+    #[must_use]
+    pub fn with_compression(compression: CompressionCodec) -> Self {
+        Self { compression }
+    }
+
+    /// Codec currently applied to `COMPRESSED` frames
+    #[must_use]
+    pub fn compression(&self) -> CompressionCodec {
+        self.compression
+    }
+
+    /// Switch codecs, e.g. once the hello exchange has completed
+    pub fn set_compression(&mut self, compression: CompressionCodec) {
+        self.compression = compression;
+    }
+}
 
 impl Decoder for FrameCodec {
     type Item = Frame;
     type Error = FrameError;
 
     fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
-        Frame::decode(src)
+        Frame::decode(src)?
+            .map(|frame| frame.decompress(self.compression))
+            .transpose()
     }
 }
 
@@ -352,21 +540,24 @@ impl Encoder<Frame> for FrameCodec {
     type Error = FrameError;
 
     fn encode(&mut self, item: Frame, dst: &mut BytesMut) -> Result<(), Self::Error> {
-        item.encode(dst)
+        item.compress(self.compression)?.encode(dst)
     }
 }
 
 /// Frame parser with recovery capabilities
-/// 
-/// When a parse error occurs, attempts to resynchronize by scanning for magic bytes
+///
+/// When a parse error occurs, attempts to resynchronize by skipping the corrupt
+/// frame (if its header is intact) or scanning for the next magic bytes
 pub struct ResilientFrameParser {
     max_resync_attempts: usize,
+    resync_events: u64,
 }
 
 impl Default for ResilientFrameParser {
     fn default() -> Self {
         Self {
             max_resync_attempts: 3,
+            resync_events: 0,
         }
     }
 }
@@ -376,11 +567,18 @@ impl ResilientFrameParser {
     pub fn with_max_resync(max: usize) -> Self {
         Self {
             max_resync_attempts: max,
+            resync_events: 0,
         }
     }
 
+    /// Number of times the parser has skipped corrupt data to resynchronize
+    #[must_use]
+    pub fn resync_events(&self) -> u64 {
+        self.resync_events
+    }
+
     /// Parse with automatic resynchronization on error
-    /// 
+    ///
     /// Returns Ok(None) if more data needed
     /// Returns Ok(Some(frame)) on success
     /// Returns Err(_) only on unrecoverable errors
@@ -388,6 +586,7 @@ impl ResilientFrameParser {
         let mut attempts = 0;
 
         loop {
+            let buffered = src.len();
             match Frame::decode(src) {
                 Ok(frame) => return Ok(frame),
                 Err(e) => {
@@ -396,39 +595,68 @@ impl ResilientFrameParser {
                         return Err(e);
                     }
 
-                    // Try to resync by finding next magic bytes
-                    if let Some(pos) = find_magic(src) {
-                        if pos > 0 {
-                            src.advance(pos);
-                            continue;
+                    if src.len() < buffered {
+                        // Decoder already consumed the corrupt frame (CRC mismatch)
+                        self.resync_events += 1;
+                        continue;
+                    }
+
+                    match find_magic(src) {
+                        Some(0) => {
+                            // Magic is valid but the frame is not: skip the claimed frame
+                            match claimed_frame_len(src) {
+                                Some(len) if len > src.len() => return Ok(None),
+                                Some(len) => src.advance(len),
+                                // Bogus length: step past the magic and rescan
+                                None => src.advance(4),
+                            }
                         }
-                    } else {
-                        // No magic found, clear buffer if it's getting large
-                        if src.len() > 4096 {
-                            src.clear();
+                        Some(pos) => src.advance(pos),
+                        None => {
+                            // No magic found, clear buffer if it's getting large
+                            if src.len() > 4096 {
+                                src.clear();
+                            }
+                            return Ok(None);
                         }
-                        return Ok(None);
                     }
-
-                    return Err(e);
+                    self.resync_events += 1;
                 }
             }
         }
     }
 }
 
+/// Total length of the frame whose header starts at offset 0, as claimed by its
+/// payload length field. Returns None if that length exceeds `MAX_PAYLOAD_BYTES`.
+fn claimed_frame_len(src: &BytesMut) -> Option<usize> {
+    let mut length_field = &src[HEADER_SIZE - 4..HEADER_SIZE];
+    let payload_len = length_field.get_u32_le();
+    if payload_len > MAX_PAYLOAD_BYTES {
+        return None;
+    }
+    Some(FRAME_OVERHEAD + payload_len as usize)
+}
+
 /// Find magic bytes in buffer, returning offset or None
-fn find_magic(src: &BytesMut) -> Option<usize> {
+pub(crate) fn find_magic(src: &BytesMut) -> Option<usize> {
     let magic_bytes = MAGIC.to_le_bytes();
-    src.windows(4)
-        .position(|window| window == magic_bytes)
+    src.windows(4).position(|window| window == magic_bytes)
 }
 
 // Compile-time assertions for protocol alignment
 const _ASSERT_HEADER_SIZE: () = assert!(HEADER_SIZE == 24, "Header size must be 24 bytes");
 const _ASSERT_FRAME_OVERHEAD: () = assert!(FRAME_OVERHEAD == 28, "Frame overhead must be 28 bytes");
 const _ASSERT_MAGIC_VALUE: () = assert!(MAGIC == 0x52454348, "Magic must be 'RECH' (0x52454348)");
-const _ASSERT_MAX_PAYLOAD: () = assert!(MAX_PAYLOAD_BYTES == 64 * 1024 * 1024, "Max payload must be 64 MiB");
+const _ASSERT_MAX_PAYLOAD: () = assert!(
+    MAX_PAYLOAD_BYTES == 64 * 1024 * 1024,
+    "Max payload must be 64 MiB"
+);
+const _ASSERT_TYPE_CAPS: () = assert!(
+    MAX_CONTROL_PAYLOAD_BYTES <= MAX_REPORT_PAYLOAD_BYTES
+        && MAX_REPORT_PAYLOAD_BYTES <= MAX_PAYLOAD_BYTES,
+    "Per-type payload caps must not exceed the global cap"
+);
 
 #[cfg(test)]
 mod tests {
@@ -471,6 +699,187 @@ mod tests {
         assert!(matches!(result, Err(FrameError::PayloadTooLarge { .. })));
     }
 
+    #[test]
+    fn test_zstd_compressed_frame_roundtrip() {
+        let payload = b"repetitive payload ".repeat(64);
+        let frame = Frame::new(MessageType::ExecRequest, payload.clone())
+            .unwrap()
+            .with_flags(FrameFlags::COMPRESSED);
+        let mut codec = FrameCodec::with_compression(CompressionCodec::Zstd);
+
+        let mut buf = BytesMut::new();
+        codec.encode(frame, &mut buf).unwrap();
+        assert!(buf.len() < FRAME_OVERHEAD + payload.len());
+
+        // The CRC covers the compressed bytes on the wire
+        let on_wire = Frame::decode(&mut buf.clone()).unwrap().unwrap();
+        assert!(on_wire.flags.contains(FrameFlags::COMPRESSED));
+        assert_ne!(on_wire.payload, payload);
+
+        let decoded = codec.decode(&mut buf).unwrap().unwrap();
+        assert_eq!(decoded.payload, payload);
+        assert!(!decoded.flags.contains(FrameFlags::COMPRESSED));
+    }
+
+    #[test]
+    fn test_zstd_window_is_bounded_by_payload_cap() {
+        assert_eq!(zstd_window_log(1), 10);
+        assert_eq!(zstd_window_log(MAX_CONTROL_PAYLOAD_BYTES), 16);
+        assert_eq!(zstd_window_log(MAX_CONTROL_PAYLOAD_BYTES + 1), 17);
+        assert_eq!(zstd_window_log(MAX_PAYLOAD_BYTES), 26);
+
+        // A frame whose header asks for a window beyond the cap is refused
+        // before the decoder allocates it
+        let payload = b"small but wide".repeat(16);
+        let mut encoder = zstd::stream::Encoder::new(Vec::new(), 0).unwrap();
+        encoder.include_contentsize(false).unwrap();
+        encoder.window_log(24).unwrap();
+        encoder.write_all(&payload).unwrap();
+        let wide = encoder.finish().unwrap();
+        assert!(matches!(
+            CompressionCodec::Zstd.decompress(&wide, MAX_CONTROL_PAYLOAD_BYTES),
+            Err(FrameError::Io(_))
+        ));
+        assert_eq!(
+            CompressionCodec::Zstd
+                .decompress(&wide, MAX_PAYLOAD_BYTES)
+                .unwrap(),
+            payload
+        );
+
+        // Our own encoder sizes the window to the payload
+        let compressed = CompressionCodec::Zstd.compress(&payload).unwrap();
+        let cap = u32::try_from(payload.len()).unwrap();
+        assert_eq!(
+            CompressionCodec::Zstd.decompress(&compressed, cap).unwrap(),
+            payload
+        );
+    }
+
+    #[test]
+    fn test_uncompressed_codec_passes_payload_through() {
+        let payload = b"plain payload".to_vec();
+        let frame = Frame::new(MessageType::ExecRequest, payload.clone())
+            .unwrap()
+            .with_flags(FrameFlags::COMPRESSED);
+        let mut codec = FrameCodec::default();
+
+        let mut buf = BytesMut::new();
+        codec.encode(frame, &mut buf).unwrap();
+        assert_eq!(buf.len(), FRAME_OVERHEAD + payload.len());
+
+        let decoded = codec.decode(&mut buf).unwrap().unwrap();
+        assert_eq!(decoded.payload, payload);
+        assert_eq!(decoded.flags, FrameFlags::NONE);
+    }
+
+    #[test]
+    fn test_compression_negotiation_falls_back_to_none() {
+        use CompressionCodec::{Zlib, Zstd};
+
+        assert_eq!(
+            CompressionCodec::negotiate(&[Zlib, Zstd], &CompressionCodec::SUPPORTED),
+            Zlib
+        );
+        assert_eq!(
+            CompressionCodec::negotiate(&[Zstd], &[Zlib]),
+            CompressionCodec::None
+        );
+        assert_eq!(
+            CompressionCodec::negotiate(&[], &CompressionCodec::SUPPORTED),
+            CompressionCodec::None
+        );
+
+        // A compressed frame cannot be read without an agreed codec
+        let frame = Frame::new(MessageType::ExecRequest, b"payload".to_vec())
+            .unwrap()
+            .with_flags(FrameFlags::COMPRESSED);
+        let mut buf = BytesMut::new();
+        FrameCodec::with_compression(Zlib)
+            .encode(frame, &mut buf)
+            .unwrap();
+        assert!(matches!(
+            FrameCodec::default().decode(&mut buf),
+            Err(FrameError::Compression(_))
+        ));
+    }
+
+    #[test]
+    fn test_decompression_is_held_to_payload_cap() {
+        // A streamed frame with a small window still expands without bound,
+        // so the output cap has to hold on its own
+        let mut encoder = zstd::stream::Encoder::new(Vec::new(), 0).unwrap();
+        encoder.include_contentsize(false).unwrap();
+        encoder.window_log(10).unwrap();
+        encoder
+            .write_all(&vec![0; MAX_CONTROL_PAYLOAD_BYTES as usize + 1])
+            .unwrap();
+        let bomb = encoder.finish().unwrap();
+        let frame = Frame::new(MessageType::Heartbeat, bomb)
+            .unwrap()
+            .with_flags(FrameFlags::COMPRESSED);
+        assert!(matches!(
+            frame.decompress(CompressionCodec::Zstd),
+            Err(FrameError::PayloadTooLarge {
+                max: MAX_CONTROL_PAYLOAD_BYTES,
+                ..
+            })
+        ));
+    }
+
+    /// Encode a frame without the size check in `Frame::new`, as a hostile peer could
+    fn encode_unchecked(msg_type: MessageType, payload_len: usize) -> BytesMut {
+        let frame = Frame {
+            version_major: PROTOCOL_VERSION_MAJOR,
+            version_minor: PROTOCOL_VERSION_MINOR,
+            msg_type,
+            flags: FrameFlags::NONE,
+            correlation_id: 1,
+            payload: vec![0xAB; payload_len],
+        };
+        let mut buf = BytesMut::new();
+        frame.encode(&mut buf).unwrap();
+        buf
+    }
+
+    #[test]
+    fn test_payload_cap_per_message_type() {
+        let one_mib = 1024 * 1024;
+
+        let mut hello = encode_unchecked(MessageType::Hello, one_mib);
+        assert!(matches!(
+            Frame::decode(&mut hello),
+            Err(FrameError::PayloadTooLarge { size, max: MAX_CONTROL_PAYLOAD_BYTES }) if size as usize == one_mib
+        ));
+        assert!(matches!(
+            Frame::new(MessageType::Hello, vec![0; one_mib]),
+            Err(FrameError::PayloadTooLarge {
+                max: MAX_CONTROL_PAYLOAD_BYTES,
+                ..
+            })
+        ));
+
+        let mut exec = encode_unchecked(MessageType::ExecRequest, one_mib);
+        let frame = Frame::decode(&mut exec).unwrap().unwrap();
+        assert_eq!(frame.msg_type, MessageType::ExecRequest);
+        assert_eq!(frame.payload().len(), one_mib);
+
+        // The global cap still bounds the largest types
+        assert_eq!(
+            MessageType::ExecRequest.max_payload_bytes(),
+            MAX_PAYLOAD_BYTES
+        );
+        let mut header = encode_unchecked(MessageType::ExecRequest, 0);
+        header[20..24].copy_from_slice(&(MAX_PAYLOAD_BYTES + 1).to_le_bytes());
+        assert!(matches!(
+            Frame::decode(&mut header),
+            Err(FrameError::PayloadTooLarge {
+                max: MAX_PAYLOAD_BYTES,
+                ..
+            })
+        ));
+    }
+
     #[test]
     fn test_unknown_message_type() {
         let mut buf = BytesMut::new();
@@ -483,7 +892,10 @@ mod tests {
         buf.put_u32_le(crc32c(&[]));
 
         let result = Frame::decode(&mut buf);
-        assert!(matches!(result, Err(FrameError::UnknownMessageType(0x9999))));
+        assert!(matches!(
+            result,
+            Err(FrameError::UnknownMessageType(0x9999))
+        ));
     }
 
     #[test]
@@ -521,6 +933,8 @@ mod tests {
             MessageType::HelloAck,
             MessageType::ExecRequest,
             MessageType::ExecResult,
+            MessageType::CancelRun,
+            MessageType::ResumeRun,
             MessageType::HealthRequest,
             MessageType::HealthResult,
             MessageType::Error,
@@ -549,6 +963,73 @@ mod tests {
         assert_eq!(pos, 7);
     }
 
+    #[test]
+    fn test_resilient_parser_skips_corrupt_frame() {
+        let mut buf = BytesMut::new();
+        for payload in [&b"first"[..], b"corrupt", b"second"] {
+            Frame::new(MessageType::ExecRequest, payload.to_vec())
+                .unwrap()
+                .encode(&mut buf)
+                .unwrap();
+        }
+        // Flip a payload byte in the middle frame so its CRC no longer matches
+        let corrupt_offset = FRAME_OVERHEAD + "first".len() + HEADER_SIZE;
+        buf[corrupt_offset] ^= 0xFF;
+
+        let mut parser = ResilientFrameParser::default();
+        let first = parser.parse_resilient(&mut buf).unwrap().unwrap();
+        assert_eq!(first.payload, b"first");
+
+        let second = parser.parse_resilient(&mut buf).unwrap().unwrap();
+        assert_eq!(second.payload, b"second");
+        assert_eq!(parser.resync_events(), 1);
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn test_resilient_parser_skips_unknown_message_type() {
+        let mut buf = BytesMut::new();
+        buf.put_u32_le(MAGIC);
+        buf.put_u16_le(1);
+        buf.put_u16_le(0);
+        buf.put_u32_le(0x9999); // Unknown type
+        buf.put_u32_le(0);
+        buf.put_u32_le(0);
+        buf.put_u32_le(3);
+        buf.extend_from_slice(b"bad");
+        buf.put_u32_le(0);
+        Frame::new(MessageType::Heartbeat, vec![])
+            .unwrap()
+            .encode(&mut buf)
+            .unwrap();
+
+        let mut parser = ResilientFrameParser::default();
+        let frame = parser.parse_resilient(&mut buf).unwrap().unwrap();
+        assert_eq!(frame.msg_type, MessageType::Heartbeat);
+        assert_eq!(parser.resync_events(), 1);
+    }
+
+    #[test]
+    fn test_resilient_parser_bounds_bogus_length() {
+        let mut buf = BytesMut::new();
+        buf.put_u32_le(MAGIC);
+        buf.put_u16_le(1);
+        buf.put_u16_le(0);
+        buf.put_u32_le(0x10);
+        buf.put_u32_le(0);
+        buf.put_u32_le(0);
+        buf.put_u32_le(u32::MAX); // Claims far more than MAX_PAYLOAD_BYTES
+        Frame::new(MessageType::Heartbeat, vec![])
+            .unwrap()
+            .encode(&mut buf)
+            .unwrap();
+
+        let mut parser = ResilientFrameParser::default();
+        let frame = parser.parse_resilient(&mut buf).unwrap().unwrap();
+        assert_eq!(frame.msg_type, MessageType::Heartbeat);
+        assert_eq!(parser.resync_events(), 2);
+    }
+
     #[test]
     fn test_find_magic_not_found() {
         let buf = BytesMut::from(&b"no magic here"[..]);
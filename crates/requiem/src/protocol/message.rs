@@ -10,9 +10,11 @@
 //! - Smallest representable encoding used
 //! - Floating-point values are avoided (use fixed-point types instead)
 
+use super::frame::CompressionCodec;
 use crate::fixed::{FixedBps, FixedDuration, FixedPpm, FixedQ32_32, FixedThroughput};
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
+use thiserror::Error;
 
 /// Capability flags for feature negotiation
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
@@ -34,6 +36,8 @@ impl CapabilityFlags {
     pub const FIXED_POINT: Self = Self(1 << 5);
     /// Streaming responses supported
     pub const STREAMING: Self = Self(1 << 6);
+    /// JSON encoding supported
+    pub const JSON_ENCODING: Self = Self(1 << 7);
 
     pub fn contains(self, other: Self) -> bool {
         self.0 & other.0 != 0
@@ -42,6 +46,26 @@ impl CapabilityFlags {
     pub fn insert(&mut self, other: Self) {
         self.0 |= other.0;
     }
+
+    /// Flags set in both `self` and `other`
+    #[must_use]
+    pub fn intersection(self, other: Self) -> Self {
+        Self(self.0 & other.0)
+    }
+}
+
+impl std::ops::BitOr for CapabilityFlags {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+}
+
+impl std::ops::BitOrAssign for CapabilityFlags {
+    fn bitor_assign(&mut self, other: Self) {
+        self.insert(other);
+    }
 }
 
 /// Client hello message (first message from client)
@@ -59,6 +83,9 @@ pub struct HelloPayload {
     pub capabilities: CapabilityFlags,
     /// Preferred payload encoding
     pub preferred_encoding: Encoding,
+    /// Compression codecs the client supports, most preferred first
+    #[serde(default)]
+    pub compression_codecs: Vec<CompressionCodec>,
 }
 
 impl HelloPayload {
@@ -70,8 +97,10 @@ impl HelloPayload {
             max_version: (1, 0),
             capabilities: CapabilityFlags::BINARY_PROTOCOL
                 | CapabilityFlags::CBOR_ENCODING
+                | CapabilityFlags::JSON_ENCODING
                 | CapabilityFlags::FIXED_POINT,
             preferred_encoding: Encoding::Cbor,
+            compression_codecs: Vec::new(),
         }
     }
 
@@ -110,6 +139,12 @@ pub struct HelloAckPayload {
     pub cas_version: String,
     /// Server-assigned session ID
     pub session_id: String,
+    /// Payload encoding for every frame after this ack
+    #[serde(default)]
+    pub selected_encoding: Encoding,
+    /// Codec for `COMPRESSED` frames after this ack (`None` = no compression)
+    #[serde(default)]
+    pub selected_compression: CompressionCodec,
 }
 
 impl HelloAckPayload {
@@ -124,14 +159,20 @@ impl HelloAckPayload {
             hash_version: "blake3".to_string(),
             cas_version: "1".to_string(),
             session_id: session_id.to_string(),
+            selected_encoding: Encoding::Cbor,
+            selected_compression: CompressionCodec::None,
         }
     }
 }
 
 /// Payload encoding options
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+///
+/// `Hello` and `HelloAck` are always CBOR; the encoding named in the ack applies
+/// to every later frame on the connection.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum Encoding {
+    #[default]
     Cbor,
     Json,
 }
@@ -151,6 +192,48 @@ pub struct ExecRequestPayload {
     pub metadata: BTreeMap<String, String>,
 }
 
+/// Cancel run payload
+///
+/// Cancels a run the same session started that is still active (paused at a
+/// tool call or approval). Answered with the run's final `ExecResult`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CancelRunPayload {
+    /// Run to cancel
+    pub run_id: String,
+    /// Reason recorded on the cancelled run
+    pub reason: String,
+}
+
+/// Resume run payload
+///
+/// Gives a run the same session started, paused at a tool call or approval,
+/// what it is waiting for. Answered with an `ExecResult` whose events continue
+/// from the ones already reported.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ResumeRunPayload {
+    /// Run to resume
+    pub run_id: String,
+    /// What the run is waiting for
+    pub input: ResumeInput,
+}
+
+/// What a paused run is given to continue
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ResumeInput {
+    /// Result of the tool call the run paused on
+    ToolResult {
+        step_id: String,
+        success: bool,
+        #[serde(default)]
+        output: BTreeMap<String, serde_json::Value>,
+        #[serde(default)]
+        error: Option<String>,
+    },
+    /// Approval of the capability the run paused on, for the rest of the run
+    Approve,
+}
+
 /// Workflow definition (simplified for protocol)
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
 pub struct Workflow {
@@ -175,8 +258,19 @@ pub struct WorkflowStep {
     pub depends_on: Vec<String>,
 }
 
+impl WorkflowStep {
+    /// Name of the tool a `tool_call` step calls, from its `tool` config key
+    #[must_use]
+    pub fn tool_name(&self) -> Option<&str> {
+        match self.step_type {
+            StepType::ToolCall => self.config.get("tool").and_then(|t| t.as_str()),
+            _ => None,
+        }
+    }
+}
+
 /// Step types
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub enum StepType {
     ToolCall,
@@ -295,6 +389,16 @@ pub struct RunEvent {
     pub payload: BTreeMap<String, serde_json::Value>,
 }
 
+impl RunEvent {
+    /// Sort the keys of every object nested in the payload (see
+    /// [`encoding::canonicalize_value`])
+    pub fn canonicalize(&mut self) {
+        self.payload
+            .values_mut()
+            .for_each(encoding::canonicalize_value);
+    }
+}
+
 /// Action
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(tag = "type", rename_all = "snake_case")]
@@ -334,6 +438,37 @@ pub struct ExecutionMetrics {
     pub latency_histogram: Histogram,
 }
 
+impl From<&engine::RunHandle> for ExecutionMetrics {
+    /// Report the steps executed and budget spent by an engine run
+    ///
+    /// Spend that cannot be represented in Q32.32 saturates to the largest Q32.32 value.
+    fn from(run: &engine::RunHandle) -> Self {
+        Self {
+            steps_executed: u32::try_from(run.steps_executed()).unwrap_or(u32::MAX),
+            budget_spent_usd: FixedQ32_32::from_f64(run.budget().spent_usd)
+                .unwrap_or(FixedQ32_32::from_raw(i64::MAX)),
+            ..Self::default()
+        }
+    }
+}
+
+impl ExecutionMetrics {
+    /// Set `latency_histogram` and the p50/p95/p99 latencies estimated from it
+    ///
+    /// Quantiles of an empty histogram are reported as zero.
+    #[must_use]
+    pub fn with_latency_histogram(self, histogram: Histogram) -> Self {
+        let quantile = |q| histogram.quantile(q).unwrap_or(FixedDuration::ZERO);
+        Self {
+            latency_p50_us: quantile(0.5),
+            latency_p95_us: quantile(0.95),
+            latency_p99_us: quantile(0.99),
+            latency_histogram: histogram,
+            ..self
+        }
+    }
+}
+
 /// Histogram with fixed-point bucket boundaries
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
 pub struct Histogram {
@@ -343,11 +478,162 @@ pub struct Histogram {
     pub counts: Vec<u64>,
 }
 
+/// Histogram validation and aggregation errors
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum HistogramError {
+    #[error("histogram has {counts} counts for {boundaries} boundaries (expected boundaries + 1)")]
+    CountsLengthMismatch { boundaries: usize, counts: usize },
+
+    #[error("histogram boundaries are not sorted at index {index}")]
+    UnsortedBoundaries { index: usize },
+
+    #[error("histogram boundaries differ, cannot merge")]
+    BoundaryMismatch,
+
+    #[error("histogram count overflow in bucket {index}")]
+    CountOverflow { index: usize },
+}
+
+impl Histogram {
+    /// Quantile resolution: `q` is rounded to parts per million before use
+    const QUANTILE_SCALE: u32 = 1_000_000;
+
+    /// Check the structural invariants: one overflow bucket and sorted boundaries
+    pub fn validate(&self) -> Result<(), HistogramError> {
+        if self.counts.len() != self.boundaries.len() + 1 {
+            return Err(HistogramError::CountsLengthMismatch {
+                boundaries: self.boundaries.len(),
+                counts: self.counts.len(),
+            });
+        }
+        if let Some(index) = self
+            .boundaries
+            .windows(2)
+            .position(|pair| pair[0] > pair[1])
+        {
+            return Err(HistogramError::UnsortedBoundaries { index: index + 1 });
+        }
+        Ok(())
+    }
+
+    /// Count raw samples into the buckets bounded by `boundaries`
+    ///
+    /// A sample lands in the first bucket whose upper boundary it does not
+    /// exceed, so a sample equal to a boundary counts towards the bucket below
+    /// it; samples above the last boundary go to the overflow bucket. Counts
+    /// do not depend on the order of `samples`.
+    pub fn from_samples(
+        boundaries: Vec<FixedDuration>,
+        samples: &[FixedDuration],
+    ) -> Result<Histogram, HistogramError> {
+        let mut histogram = Histogram {
+            counts: vec![0; boundaries.len() + 1],
+            boundaries,
+        };
+        histogram.validate()?;
+        for sample in samples {
+            let index = histogram
+                .boundaries
+                .partition_point(|boundary| boundary < sample);
+            histogram.counts[index] += 1;
+        }
+        Ok(histogram)
+    }
+
+    /// Total number of samples across all buckets
+    #[must_use]
+    pub fn total(&self) -> u64 {
+        self.counts
+            .iter()
+            .fold(0u64, |acc, c| acc.saturating_add(*c))
+    }
+
+    /// Estimate the `q`th quantile (`q` in [0, 1])
+    ///
+    /// Interpolates linearly inside the bucket holding the target rank. The
+    /// first bucket starts at zero (or its boundary, if negative) and the
+    /// overflow bucket has no upper edge, so ranks landing there report the
+    /// last boundary. `q` is quantized to ppm and the interpolation is done in
+    /// integer arithmetic so results are identical across platforms.
+    ///
+    /// Returns `None` for an invalid or empty histogram, or `q` outside [0, 1].
+    #[must_use]
+    pub fn quantile(&self, q: f64) -> Option<FixedDuration> {
+        if self.validate().is_err() || !(0.0..=1.0).contains(&q) {
+            return None;
+        }
+        let total = u128::from(self.total());
+        if total == 0 {
+            return None;
+        }
+
+        // `q` is in [0, 1], so the rounded ppm value is in [0, QUANTILE_SCALE]
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let q_ppm = (q * f64::from(Self::QUANTILE_SCALE)).round() as u32;
+        let scale = u128::from(Self::QUANTILE_SCALE);
+        let rank = u128::from(q_ppm) * total;
+        let mut cumulative: u128 = 0;
+
+        for (index, count) in self.counts.iter().enumerate() {
+            let count = u128::from(*count);
+            let bucket_end = (cumulative + count) * scale;
+            if count == 0 || bucket_end < rank {
+                cumulative += count;
+                continue;
+            }
+
+            let Some(upper) = self.boundaries.get(index) else {
+                // Overflow bucket: no upper edge to interpolate towards
+                return self.boundaries.last().copied();
+            };
+            let lower = match index {
+                0 => upper.to_micros().min(0),
+                _ => self.boundaries[index - 1].to_micros(),
+            };
+            let width = i128::from(upper.to_micros()) - i128::from(lower);
+            let offset = i128::try_from(rank - cumulative * scale).ok()?;
+            let denominator = i128::try_from(count * scale).ok()?;
+            let value = i128::from(lower) + width * offset / denominator;
+            return i64::try_from(value).ok().map(FixedDuration::from_micros);
+        }
+
+        None
+    }
+
+    /// Merge two histograms with identical boundaries by summing counts
+    pub fn merge(&self, other: &Histogram) -> Result<Histogram, HistogramError> {
+        self.validate()?;
+        other.validate()?;
+        if self.boundaries != other.boundaries {
+            return Err(HistogramError::BoundaryMismatch);
+        }
+
+        let counts = self
+            .counts
+            .iter()
+            .zip(&other.counts)
+            .enumerate()
+            .map(|(index, (a, b))| {
+                a.checked_add(*b)
+                    .ok_or(HistogramError::CountOverflow { index })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Histogram {
+            boundaries: self.boundaries.clone(),
+            counts,
+        })
+    }
+}
+
 /// Health check request
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
 pub struct HealthRequestPayload {
     /// Request detailed metrics
     pub detailed: bool,
+    /// Report the protocol stats of this session only
+    #[serde(default)]
+    pub session_id: Option<String>,
 }
 
 /// Health check result
@@ -361,6 +647,20 @@ pub struct HealthResultPayload {
     pub uptime_us: FixedDuration,
     /// Current load metrics (if detailed)
     pub load: Option<LoadMetrics>,
+    /// Stats of the requested session (None if no session was requested or it is unknown)
+    #[serde(default)]
+    pub connection_stats: Option<ConnectionStatsPayload>,
+}
+
+/// Protocol counters of a single connection
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct ConnectionStatsPayload {
+    pub frames_sent: u64,
+    pub frames_received: u64,
+    pub bytes_sent: u64,
+    pub bytes_received: u64,
+    pub crc_errors: u64,
+    pub resync_events: u64,
 }
 
 /// Health status
@@ -406,13 +706,13 @@ pub enum ErrorCode {
     InvalidMessage = 100,
     UnsupportedVersion = 101,
     EncodingError = 102,
-    
+
     // Execution errors (2xx)
     ExecutionFailed = 200,
     BudgetExceeded = 201,
     Timeout = 202,
     PolicyDenied = 203,
-    
+
     // System errors (3xx)
     InternalError = 300,
     ResourceExhausted = 301,
@@ -421,8 +721,18 @@ pub enum ErrorCode {
 
 /// Payload encoding/decoding
 pub mod encoding {
-    use super::*;
-    use ciborium::{de::from_reader, ser::into_writer};
+    use super::{Deserialize, Serialize};
+    use ciborium::{de::from_reader_with_recursion_limit, ser::into_writer};
+
+    /// Deepest nesting of arrays, maps and tags `decode_cbor` accepts
+    ///
+    /// Matches `serde_json`'s recursion limit, so CBOR and JSON sessions accept
+    /// the same documents.
+    pub const MAX_CBOR_DEPTH: usize = 128;
+
+    /// Most data items (every scalar, string, array, map key and map value)
+    /// `decode_cbor` accepts in one payload
+    pub const MAX_CBOR_ITEMS: usize = 1 << 20;
 
     /// Encode payload to CBOR bytes (canonical)
     pub fn encode_cbor<T: Serialize>(value: &T) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
@@ -431,9 +741,110 @@ pub mod encoding {
         Ok(buf)
     }
 
-    /// Decode payload from CBOR bytes
-    pub fn decode_cbor<T: for<'de> Deserialize<'de>>(bytes: &[u8]) -> Result<T, Box<dyn std::error::Error>> {
-        Ok(from_reader(bytes)?)
+    /// Decode payload from CBOR bytes, within `MAX_CBOR_DEPTH` and `MAX_CBOR_ITEMS`
+    pub fn decode_cbor<T: for<'de> Deserialize<'de>>(
+        bytes: &[u8],
+    ) -> Result<T, Box<dyn std::error::Error>> {
+        decode_cbor_limited(bytes, MAX_CBOR_DEPTH, MAX_CBOR_ITEMS)
+    }
+
+    /// Decode payload from CBOR bytes, rejecting deep or large documents
+    ///
+    /// The item headers are walked before anything is deserialized, so a
+    /// payload nested deeper than `max_depth`, or holding more than
+    /// `max_items` data items, is rejected without recursing or allocating
+    /// for it. Declared lengths are never trusted: an array claiming 2^60
+    /// elements fails when the bytes run out.
+    pub fn decode_cbor_limited<T: for<'de> Deserialize<'de>>(
+        bytes: &[u8],
+        max_depth: usize,
+        max_items: usize,
+    ) -> Result<T, Box<dyn std::error::Error>> {
+        check_cbor_limits(bytes, max_depth, max_items)?;
+        Ok(from_reader_with_recursion_limit(bytes, max_depth)?)
+    }
+
+    /// Walk the CBOR item headers in `bytes`, enforcing the decode limits
+    fn check_cbor_limits(bytes: &[u8], max_depth: usize, max_items: usize) -> Result<(), String> {
+        let truncated = || "truncated CBOR payload".to_string();
+        // Items still expected by each open array, map, tag or indefinite
+        // string; None until a break byte
+        let mut open: Vec<Option<u64>> = Vec::new();
+        let mut items = 0usize;
+        let mut pos = 0usize;
+
+        loop {
+            let initial = *bytes.get(pos).ok_or_else(truncated)?;
+            pos += 1;
+
+            let mut completed = true;
+            if initial == 0xff {
+                if open.pop() != Some(None) {
+                    return Err("unexpected CBOR break".to_string());
+                }
+            } else {
+                items += 1;
+                if items > max_items {
+                    return Err(format!("CBOR payload exceeds {max_items} items"));
+                }
+
+                let major = initial >> 5;
+                let info = initial & 0x1f;
+                let argument = match info {
+                    0..=23 => Some(u64::from(info)),
+                    24..=27 => {
+                        let width = 1usize << (info - 24);
+                        let field = bytes.get(pos..pos + width).ok_or_else(truncated)?;
+                        pos += width;
+                        Some(field.iter().fold(0u64, |acc, &b| (acc << 8) | u64::from(b)))
+                    }
+                    31 if (2..=5).contains(&major) => None,
+                    _ => return Err(format!("invalid CBOR initial byte 0x{initial:02x}")),
+                };
+
+                let expected = match (major, argument) {
+                    // Definite strings: skip the content
+                    (2 | 3, Some(len)) => {
+                        pos = usize::try_from(len)
+                            .ok()
+                            .and_then(|len| pos.checked_add(len))
+                            .filter(|&end| end <= bytes.len())
+                            .ok_or_else(truncated)?;
+                        Some(0)
+                    }
+                    (4, Some(len)) => Some(len),
+                    (5, Some(len)) => Some(len.checked_mul(2).ok_or_else(truncated)?),
+                    // Indefinite strings, arrays and maps run to a break
+                    (2..=5, None) => None,
+                    // A tag wraps exactly one item
+                    (6, _) => Some(1),
+                    _ => Some(0),
+                };
+
+                if expected != Some(0) {
+                    open.push(expected);
+                    if open.len() > max_depth {
+                        return Err(format!("CBOR nesting exceeds depth limit of {max_depth}"));
+                    }
+                    completed = false;
+                }
+            }
+
+            // A finished item counts toward its parent, which may finish too
+            while completed {
+                match open.last_mut() {
+                    None => return Ok(()),
+                    Some(Some(remaining)) => {
+                        *remaining -= 1;
+                        completed = *remaining == 0;
+                        if completed {
+                            open.pop();
+                        }
+                    }
+                    Some(None) => completed = false,
+                }
+            }
+        }
     }
 
     /// Encode to JSON (for debugging/fallback)
@@ -442,15 +853,146 @@ pub mod encoding {
     }
 
     /// Decode from JSON
-    pub fn decode_json<T: for<'de> Deserialize<'de>>(bytes: &[u8]) -> Result<T, Box<dyn std::error::Error>> {
+    pub fn decode_json<T: for<'de> Deserialize<'de>>(
+        bytes: &[u8],
+    ) -> Result<T, Box<dyn std::error::Error>> {
         Ok(serde_json::from_slice(bytes)?)
     }
+
+    /// Sort object keys in place at every level of `value`
+    ///
+    /// With `preserve_order`, objects keep the key order they were parsed or
+    /// built in, so logically equal values can encode differently until
+    /// canonicalized.
+    pub fn canonicalize_value(value: &mut serde_json::Value) {
+        match value {
+            serde_json::Value::Object(map) => {
+                let mut entries: Vec<_> = std::mem::take(map).into_iter().collect();
+                entries.sort_by(|a, b| a.0.cmp(&b.0));
+                for (key, mut item) in entries {
+                    canonicalize_value(&mut item);
+                    map.insert(key, item);
+                }
+            }
+            serde_json::Value::Array(items) => items.iter_mut().for_each(canonicalize_value),
+            _ => {}
+        }
+    }
+
+    /// Encode to canonical JSON: object keys sorted at every level, no whitespace
+    ///
+    /// Independent of field declaration order and map insertion order, so it
+    /// is safe to hash.
+    pub fn encode_canonical_json<T: Serialize>(
+        value: &T,
+    ) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        let mut value = serde_json::to_value(value)?;
+        canonicalize_value(&mut value);
+        Ok(serde_json::to_vec(&value)?)
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::*;
     use super::encoding::*;
+    use super::*;
+
+    #[test]
+    fn test_cbor_decode_limits() {
+        let nested = |depth: usize| {
+            let mut bytes = vec![0x81; depth];
+            bytes.push(0x00);
+            bytes
+        };
+
+        let value: serde_json::Value = decode_cbor_limited(&nested(8), 8, 100).unwrap();
+        assert_eq!(value.to_string(), "[[[[[[[[0]]]]]]]]");
+        let err = decode_cbor_limited::<serde_json::Value>(&nested(9), 8, 100).unwrap_err();
+        assert!(err.to_string().contains("depth limit of 8"), "{err}");
+        assert!(decode_cbor::<serde_json::Value>(&nested(MAX_CBOR_DEPTH + 1)).is_err());
+
+        // An array of five integers is six items
+        let five = encode_cbor(&vec![1u8, 2, 3, 4, 5]).unwrap();
+        assert!(decode_cbor_limited::<Vec<u8>>(&five, 8, 6).is_ok());
+        let err = decode_cbor_limited::<Vec<u8>>(&five, 8, 5).unwrap_err();
+        assert!(err.to_string().contains("exceeds 5 items"), "{err}");
+
+        // A declared length is not trusted: 2^60 elements, then nothing
+        let huge = [0x9b, 0x10, 0, 0, 0, 0, 0, 0, 0];
+        assert!(decode_cbor::<Vec<u8>>(&huge).is_err());
+
+        // Ordinary payloads, including indefinite-length ones, still decode
+        let request = ExecRequestPayload {
+            run_id: "run-1".to_string(),
+            workflow: Workflow {
+                name: "wf".to_string(),
+                version: "1.0.0".to_string(),
+                steps: vec![],
+            },
+            controls: ExecutionControls::default(),
+            policy: Policy::default(),
+            metadata: BTreeMap::from([("k".to_string(), "v".to_string())]),
+        };
+        let decoded: ExecRequestPayload = decode_cbor(&encode_cbor(&request).unwrap()).unwrap();
+        assert_eq!(decoded.run_id, "run-1");
+        let indefinite = [0x9f, 0x01, 0x9f, 0xff, 0xff];
+        let value: serde_json::Value = decode_cbor(&indefinite).unwrap();
+        assert_eq!(value.to_string(), "[1,[]]");
+    }
+
+    #[test]
+    fn test_exec_result_reports_engine_budget() {
+        let engine = engine::Engine::new(engine::EngineConfig::default());
+        let workflow = engine
+            .compile(
+                r#"{
+                "id": "wf-cost",
+                "version": "v0",
+                "steps": [{
+                    "id": "step-1",
+                    "kind": {
+                        "type": "tool_call",
+                        "tool": {
+                            "name": "echo",
+                            "description": "echo input",
+                            "input_schema": {"type": "object"},
+                            "output_schema": {"type": "object"}
+                        }
+                    }
+                }]
+            }"#,
+            )
+            .unwrap();
+        let mut run = engine
+            .start_run(workflow, engine::policy::Policy::default())
+            .unwrap();
+        assert!(matches!(run.next_action(), engine::Action::ToolCall(_)));
+        run.apply_tool_result(engine::tools::ToolResult {
+            step_id: "step-1".to_string(),
+            tool_name: "echo".to_string(),
+            output: serde_json::json!({"ok": true}),
+            success: true,
+            error: None,
+        })
+        .unwrap();
+        run.record_cost("step-1".to_string(), 0.125).unwrap();
+        run.record_cost("step-1".to_string(), 0.0375).unwrap();
+
+        let result = ExecResultPayload {
+            run_id: "run-cost".to_string(),
+            status: RunStatus::Completed,
+            result_digest: String::new(),
+            events: Vec::new(),
+            final_action: Some(Action::Done),
+            metrics: ExecutionMetrics::from(&run),
+            session_id: "sess-1".to_string(),
+        };
+        let decoded: ExecResultPayload = decode_cbor(&encode_cbor(&result).unwrap()).unwrap();
+
+        assert_eq!(decoded.metrics.steps_executed, 1);
+        let spent = decoded.metrics.budget_spent_usd.to_f64();
+        assert!((spent - 0.1625).abs() < 1.0 / (1u64 << 32) as f64);
+    }
 
     #[test]
     fn test_hello_roundtrip() {
@@ -526,13 +1068,130 @@ mod tests {
 
         let encoded = encode_cbor(&metrics).unwrap();
         let decoded: ExecutionMetrics = decode_cbor(&encoded).unwrap();
-        
+
         assert_eq!(metrics.steps_executed, decoded.steps_executed);
         assert_eq!(metrics.elapsed_us.to_raw(), decoded.elapsed_us.to_raw());
-        assert_eq!(metrics.budget_spent_usd.to_raw(), decoded.budget_spent_usd.to_raw());
+        assert_eq!(
+            metrics.budget_spent_usd.to_raw(),
+            decoded.budget_spent_usd.to_raw()
+        );
         assert_eq!(metrics.cas_hit_rate.to_raw(), decoded.cas_hit_rate.to_raw());
     }
 
+    fn latency_histogram() -> Histogram {
+        Histogram {
+            boundaries: vec![
+                FixedDuration::from_millis(10).unwrap(),
+                FixedDuration::from_millis(20).unwrap(),
+                FixedDuration::from_millis(50).unwrap(),
+            ],
+            counts: vec![10, 50, 30, 10],
+        }
+    }
+
+    #[test]
+    fn test_histogram_quantiles() {
+        let histogram = latency_histogram();
+
+        // Rank 50 is 40/50 of the way through the 10ms..20ms bucket
+        assert_eq!(
+            histogram.quantile(0.5),
+            Some(FixedDuration::from_millis(18).unwrap())
+        );
+        // Rank 99 lands in the overflow bucket, reported at the last boundary
+        assert_eq!(
+            histogram.quantile(0.99),
+            Some(FixedDuration::from_millis(50).unwrap())
+        );
+        assert_eq!(histogram.quantile(0.0), Some(FixedDuration::ZERO));
+        assert_eq!(histogram.quantile(1.5), None);
+        assert_eq!(Histogram::default().quantile(0.5), None);
+    }
+
+    #[test]
+    fn test_histogram_from_samples() {
+        let ms = |millis: &[i64]| -> Vec<FixedDuration> {
+            millis
+                .iter()
+                .map(|m| FixedDuration::from_millis(*m).unwrap())
+                .collect()
+        };
+        let boundaries = ms(&[10, 20, 50]);
+        let samples = ms(&[0, 5, 10, 15, 20, 25, 50, 60, 100]);
+
+        // Samples on a boundary count towards the bucket below it
+        let histogram = Histogram::from_samples(boundaries.clone(), &samples).unwrap();
+        assert_eq!(histogram.counts, vec![3, 2, 2, 2]);
+        let mut reversed = samples.clone();
+        reversed.reverse();
+        assert_eq!(
+            Histogram::from_samples(boundaries.clone(), &reversed),
+            Ok(histogram.clone())
+        );
+
+        // Rank 4.5 is 1.5/2 of the way through the 10ms..20ms bucket
+        let metrics = ExecutionMetrics::default().with_latency_histogram(histogram.clone());
+        assert_eq!(metrics.latency_p50_us, FixedDuration::from_micros(17_500));
+        assert_eq!(
+            metrics.latency_p95_us,
+            FixedDuration::from_millis(50).unwrap()
+        );
+        assert_eq!(
+            metrics.latency_p99_us,
+            FixedDuration::from_millis(50).unwrap()
+        );
+        assert_eq!(metrics.latency_histogram, histogram);
+
+        assert_eq!(
+            Histogram::from_samples(ms(&[20, 10, 50]), &samples),
+            Err(HistogramError::UnsortedBoundaries { index: 1 })
+        );
+        assert_eq!(
+            Histogram::from_samples(Vec::new(), &samples)
+                .unwrap()
+                .counts,
+            vec![9]
+        );
+    }
+
+    #[test]
+    fn test_histogram_merge() {
+        let merged = latency_histogram().merge(&latency_histogram()).unwrap();
+        assert_eq!(merged.counts, vec![20, 100, 60, 20]);
+        assert_eq!(merged.quantile(0.5), latency_histogram().quantile(0.5));
+    }
+
+    #[test]
+    fn test_histogram_merge_boundary_mismatch() {
+        let mut other = latency_histogram();
+        other.boundaries[2] = FixedDuration::from_millis(100).unwrap();
+
+        assert_eq!(
+            latency_histogram().merge(&other),
+            Err(HistogramError::BoundaryMismatch)
+        );
+    }
+
+    #[test]
+    fn test_histogram_validation() {
+        let mut short = latency_histogram();
+        short.counts.pop();
+        assert_eq!(
+            short.validate(),
+            Err(HistogramError::CountsLengthMismatch {
+                boundaries: 3,
+                counts: 3
+            })
+        );
+
+        let mut unsorted = latency_histogram();
+        unsorted.boundaries.swap(0, 1);
+        assert_eq!(
+            unsorted.validate(),
+            Err(HistogramError::UnsortedBoundaries { index: 1 })
+        );
+    }
+
     #[test]
     fn test_determinism() {
         // Same input should produce same bytes
@@ -7,25 +7,35 @@
 //! - Streaming frames with length prefix
 //! - CRC32C integrity checking
 //! - Protocol version negotiation
-//! - Deterministic CBOR encoding
+//! - Deterministic CBOR encoding, with JSON as a negotiated alternative
 //! - Fixed-point numeric types for cross-platform determinism
 //! - Automatic resynchronization on parse errors
 
+pub mod assembler;
+pub mod convert;
 pub mod frame;
 pub mod message;
+pub mod reader;
 
+pub use assembler::FrameAssembler;
+pub use convert::{engine_to_protocol, protocol_to_engine, validate_steps, ConversionError};
 pub use frame::{
-    Frame, FrameCodec, FrameError, FrameFlags, MessageType, ResilientFrameParser,
-    FRAME_OVERHEAD, HEADER_SIZE, MAGIC, MAX_PAYLOAD_BYTES, PROTOCOL_VERSION_MAJOR,
-    PROTOCOL_VERSION_MINOR,
+    CompressionCodec, Frame, FrameCodec, FrameError, FrameFlags, MessageType, ResilientFrameParser,
+    FRAME_OVERHEAD, HEADER_SIZE, MAGIC, MAX_CONTROL_PAYLOAD_BYTES, MAX_PAYLOAD_BYTES,
+    MAX_REPORT_PAYLOAD_BYTES, PROTOCOL_VERSION_MAJOR, PROTOCOL_VERSION_MINOR,
 };
 pub use message::{
-    Action, CapabilityFlags, Decision, Encoding, ErrorCode, ErrorPayload, ExecRequestPayload,
-    ExecResultPayload, ExecutionControls, ExecutionMetrics, HealthRequestPayload,
-    HealthResultPayload, HealthStatus, HelloAckPayload, HelloPayload, Histogram, LoadMetrics,
-    Policy, PolicyCondition, PolicyRule, RunEvent, RunStatus, StepType, Workflow, WorkflowStep,
-    encoding::{decode_cbor, decode_json, encode_cbor, encode_json},
+    encoding::{
+        canonicalize_value, decode_cbor, decode_cbor_limited, decode_json, encode_canonical_json,
+        encode_cbor, encode_json, MAX_CBOR_DEPTH, MAX_CBOR_ITEMS,
+    },
+    Action, CancelRunPayload, CapabilityFlags, ConnectionStatsPayload, Decision, Encoding,
+    ErrorCode, ErrorPayload, ExecRequestPayload, ExecResultPayload, ExecutionControls,
+    ExecutionMetrics, HealthRequestPayload, HealthResultPayload, HealthStatus, HelloAckPayload,
+    HelloPayload, Histogram, HistogramError, LoadMetrics, Policy, PolicyCondition, PolicyRule,
+    ResumeInput, ResumeRunPayload, RunEvent, RunStatus, StepType, Workflow, WorkflowStep,
 };
+pub use reader::FrameReader;
 
 use crate::fixed::{FixedBps, FixedDuration, FixedPpm, FixedQ32_32, FixedThroughput};
 use bytes::BytesMut;
@@ -36,31 +46,40 @@ use thiserror::Error;
 pub enum ProtocolError {
     #[error("frame error: {0}")]
     Frame(#[from] FrameError),
-    
+
     #[error("encoding error: {0}")]
     Encoding(String),
-    
+
     #[error("version negotiation failed: client supports {client:?}, server supports {server:?}")]
     VersionNegotiationFailed {
         client: (u16, u16),
         server: (u16, u16),
     },
-    
+
     #[error("capability mismatch: required {required:?}, have {have:?}")]
     CapabilityMismatch {
         required: CapabilityFlags,
         have: CapabilityFlags,
     },
-    
+
     #[error("unexpected message type: expected {expected:?}, got {got:?}")]
     UnexpectedMessageType {
         expected: MessageType,
         got: MessageType,
     },
-    
+
     #[error("session not established")]
     NoSession,
-    
+
+    #[error("execution failed: {0}")]
+    Execution(String),
+
+    #[error("resource exhausted: {0}")]
+    ResourceExhausted(String),
+
+    #[error("policy denied: {0}")]
+    PolicyDenied(String),
+
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 }
@@ -107,12 +126,38 @@ impl std::fmt::Display for ProtocolVersion {
 
 /// Serialize a message to CBOR payload
 pub fn serialize_message<T: serde::Serialize>(msg: &T) -> Result<Vec<u8>, ProtocolError> {
-    encode_cbor(msg).map_err(|e| ProtocolError::Encoding(e.to_string()))
+    serialize_message_as(Encoding::Cbor, msg)
+}
+
+/// Serialize a message in the given encoding
+pub fn serialize_message_as<T: serde::Serialize>(
+    encoding: Encoding,
+    msg: &T,
+) -> Result<Vec<u8>, ProtocolError> {
+    match encoding {
+        Encoding::Cbor => encode_cbor(msg),
+        Encoding::Json => encode_json(msg),
+    }
+    .map_err(|e| ProtocolError::Encoding(e.to_string()))
 }
 
 /// Deserialize a message from CBOR payload
-pub fn deserialize_message<T: for<'de> serde::Deserialize<'de>>(bytes: &[u8]) -> Result<T, ProtocolError> {
-    decode_cbor(bytes).map_err(|e| ProtocolError::Encoding(e.to_string()))
+pub fn deserialize_message<T: for<'de> serde::Deserialize<'de>>(
+    bytes: &[u8],
+) -> Result<T, ProtocolError> {
+    deserialize_message_as(Encoding::Cbor, bytes)
+}
+
+/// Deserialize a message from a payload in the given encoding
+pub fn deserialize_message_as<T: for<'de> serde::Deserialize<'de>>(
+    encoding: Encoding,
+    bytes: &[u8],
+) -> Result<T, ProtocolError> {
+    match encoding {
+        Encoding::Cbor => decode_cbor(bytes),
+        Encoding::Json => decode_json(bytes),
+    }
+    .map_err(|e| ProtocolError::Encoding(e.to_string()))
 }
 
 /// Build a frame from a message
@@ -121,7 +166,17 @@ pub fn frame_message<T: serde::Serialize>(
     msg: &T,
     correlation_id: u32,
 ) -> Result<Frame, ProtocolError> {
-    let payload = serialize_message(msg)?;
+    frame_message_as(Encoding::Cbor, msg_type, msg, correlation_id)
+}
+
+/// Build a frame from a message in the given encoding
+pub fn frame_message_as<T: serde::Serialize>(
+    encoding: Encoding,
+    msg_type: MessageType,
+    msg: &T,
+    correlation_id: u32,
+) -> Result<Frame, ProtocolError> {
+    let payload = serialize_message_as(encoding, msg)?;
     Ok(Frame::new(msg_type, payload)?.with_correlation_id(correlation_id))
 }
 
@@ -130,6 +185,14 @@ pub fn parse_frame<T: for<'de> serde::Deserialize<'de>>(frame: &Frame) -> Result
     deserialize_message(frame.payload())
 }
 
+/// Parse a frame payload in the given encoding into a message
+pub fn parse_frame_as<T: for<'de> serde::Deserialize<'de>>(
+    encoding: Encoding,
+    frame: &Frame,
+) -> Result<T, ProtocolError> {
+    deserialize_message_as(encoding, frame.payload())
+}
+
 /// Protocol statistics (for monitoring)
 #[derive(Debug, Clone, Default)]
 pub struct ProtocolStats {
@@ -149,6 +212,34 @@ pub struct ProtocolStats {
     pub version: Option<ProtocolVersion>,
 }
 
+impl ProtocolStats {
+    /// Add the counters from `delta` (e.g. collected by a `FrameReader`)
+    pub fn absorb(&mut self, delta: &ProtocolStats) {
+        self.frames_sent += delta.frames_sent;
+        self.frames_received += delta.frames_received;
+        self.bytes_sent += delta.bytes_sent;
+        self.bytes_received += delta.bytes_received;
+        self.crc_errors += delta.crc_errors;
+        self.resync_events += delta.resync_events;
+        if delta.version.is_some() {
+            self.version = delta.version;
+        }
+    }
+}
+
+impl From<&ProtocolStats> for ConnectionStatsPayload {
+    fn from(stats: &ProtocolStats) -> Self {
+        Self {
+            frames_sent: stats.frames_sent,
+            frames_received: stats.frames_received,
+            bytes_sent: stats.bytes_sent,
+            bytes_received: stats.bytes_received,
+            crc_errors: stats.crc_errors,
+            resync_events: stats.resync_events,
+        }
+    }
+}
+
 /// Protocol capabilities for a connection
 #[derive(Debug, Clone)]
 pub struct ProtocolCapabilities {
@@ -163,8 +254,8 @@ impl Default for ProtocolCapabilities {
         Self {
             version: ProtocolVersion::V1_0,
             encoding: Encoding::Cbor,
-            compression: false,
-            fixed_point: true,
+            compression: requiem_version::DEFAULT_COMPRESSION,
+            fixed_point: requiem_version::DEFAULT_FIXED_POINT,
         }
     }
 }
@@ -184,17 +275,48 @@ mod tests {
         assert!(!v1.compatible_with(v3));
     }
 
+    #[test]
+    fn test_default_capabilities_match_version_info() {
+        let defaults = ProtocolCapabilities::default();
+        let info: serde_json::Value =
+            serde_json::from_str(&crate::version_info(crate::VERSION)).unwrap();
+        assert_eq!(info["protocol_version"], defaults.version.to_string());
+        assert_eq!(
+            info["capabilities"],
+            serde_json::json!({
+                "encoding": defaults.encoding,
+                "compression": defaults.compression,
+                "fixed_point": defaults.fixed_point,
+            })
+        );
+    }
+
     #[test]
     fn test_frame_message_roundtrip() {
         let hello = HelloPayload::new("test-cli", "1.0.0");
         let frame = frame_message(MessageType::Hello, &hello).unwrap();
-        
+
         assert_eq!(frame.msg_type, MessageType::Hello);
-        
+
         let decoded: HelloPayload = parse_frame(&frame).unwrap();
         assert_eq!(hello.client_name, decoded.client_name);
     }
 
+    #[test]
+    fn test_parse_frame_rejects_deeply_nested_payload() {
+        // One array per level around a single integer
+        let mut nested = vec![0x81; MAX_CBOR_DEPTH + 1];
+        nested.push(0x00);
+        let frame = Frame::new(MessageType::ExecRequest, nested).unwrap();
+
+        match parse_frame::<serde_json::Value>(&frame) {
+            Err(ProtocolError::Encoding(message)) => {
+                assert!(message.contains("depth"), "{message}")
+            }
+            other => panic!("expected an encoding error, got {other:?}"),
+        }
+    }
+
     #[test]
     fn test_version_ordering() {
         let v1 = ProtocolVersion::new(1, 0);
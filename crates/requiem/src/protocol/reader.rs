@@ -0,0 +1,199 @@
+//! Streaming frame reader
+//!
+//! Pulls bytes from any `AsyncRead` and yields complete frames, recovering from
+//! corrupt input the same way the server does: invalid magic triggers a scan for
+//! the next magic, and frames failing their CRC are dropped. `COMPRESSED`
+//! frames are decompressed with the codec set by [`FrameReader::set_compression`].
+
+use super::frame::{find_magic, CompressionCodec, Frame, FrameError};
+use super::ProtocolStats;
+use bytes::{Buf, BytesMut};
+use tokio::io::{AsyncRead, AsyncReadExt};
+use tracing::warn;
+
+/// Initial capacity of the read buffer
+const READ_BUFFER_CAPACITY: usize = 4096;
+
+/// Yields frames from an async byte stream
+///
+/// Counters for bytes and frames received, CRC errors and resync events are
+/// accumulated until collected with [`FrameReader::take_stats`].
+///
+/// `next_frame` is cancel safe: if its future is dropped (e.g. by a timeout)
+/// no buffered data is lost and the next call resumes where it left off.
+pub struct FrameReader<R> {
+    reader: R,
+    buf: BytesMut,
+    stats: ProtocolStats,
+    compression: CompressionCodec,
+}
+
+impl<R: AsyncRead + Unpin> FrameReader<R> {
+    /// Wrap a reader
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            buf: BytesMut::with_capacity(READ_BUFFER_CAPACITY),
+            stats: ProtocolStats::default(),
+            compression: CompressionCodec::None,
+        }
+    }
+
+    /// Decompress `COMPRESSED` frames with `compression` from now on
+    pub fn set_compression(&mut self, compression: CompressionCodec) {
+        self.compression = compression;
+    }
+
+    /// Read the next valid frame
+    ///
+    /// Returns Ok(None) once the stream reaches EOF; bytes of a trailing
+    /// partial frame are discarded. Returns Err(_) on I/O errors and on frame
+    /// errors that cannot be recovered from by resynchronizing.
+    pub async fn next_frame(&mut self) -> Result<Option<Frame>, FrameError> {
+        loop {
+            if let Some(frame) = self.decode_buffered()? {
+                return Ok(Some(frame));
+            }
+
+            let n = self.reader.read_buf(&mut self.buf).await?;
+            if n == 0 {
+                return Ok(None);
+            }
+            self.stats.bytes_received += n as u64;
+        }
+    }
+
+    /// Counters accumulated since the last call to [`FrameReader::take_stats`]
+    pub fn stats(&self) -> &ProtocolStats {
+        &self.stats
+    }
+
+    /// Collect and reset the accumulated counters
+    pub fn take_stats(&mut self) -> ProtocolStats {
+        std::mem::take(&mut self.stats)
+    }
+
+    /// Number of bytes buffered but not yet decoded
+    pub fn buffered(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Unwrap the underlying reader, dropping any buffered bytes
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// Decode one frame from the buffer, skipping over corrupt data
+    fn decode_buffered(&mut self) -> Result<Option<Frame>, FrameError> {
+        loop {
+            match Frame::decode(&mut self.buf) {
+                Ok(Some(frame)) => {
+                    self.stats.frames_received += 1;
+                    return frame.decompress(self.compression).map(Some);
+                }
+                Ok(None) => return Ok(None),
+                Err(FrameError::InvalidMagic { .. }) => {
+                    let Some(pos) = find_magic(&self.buf) else {
+                        // Keep a possible partial magic at the tail for the next read
+                        let keep = std::cmp::min(self.buf.len(), 3);
+                        self.buf.advance(self.buf.len() - keep);
+                        return Ok(None);
+                    };
+                    warn!("Resyncing after invalid magic, skipping {pos} bytes");
+                    self.buf.advance(pos);
+                    self.stats.resync_events += 1;
+                }
+                Err(FrameError::CrcMismatch { .. }) => {
+                    // The decoder has already consumed the corrupt frame
+                    warn!("CRC mismatch, dropping frame");
+                    self.stats.crc_errors += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::{MessageType, FRAME_OVERHEAD, HEADER_SIZE};
+    use bytes::BufMut;
+
+    fn encoded(correlation_id: u32, payload: &[u8]) -> Vec<u8> {
+        let mut frame = Frame::new(MessageType::Hello, payload.to_vec()).unwrap();
+        frame.correlation_id = correlation_id;
+        let mut buf = BytesMut::new();
+        frame.encode(&mut buf).unwrap();
+        buf.to_vec()
+    }
+
+    #[tokio::test]
+    async fn test_reads_frame_delivered_in_fragments() {
+        let bytes = encoded(7, b"fragmented payload");
+        let mut mock = tokio_test::io::Builder::new();
+        for chunk in bytes.chunks(5) {
+            mock.read(chunk);
+        }
+        let mut reader = FrameReader::new(mock.build());
+
+        let frame = reader.next_frame().await.unwrap().unwrap();
+        assert_eq!(frame.correlation_id, 7);
+        assert_eq!(frame.payload(), b"fragmented payload");
+        assert!(reader.next_frame().await.unwrap().is_none());
+
+        let stats = reader.take_stats();
+        assert_eq!(stats.frames_received, 1);
+        assert_eq!(stats.bytes_received, bytes.len() as u64);
+        assert_eq!(stats.resync_events, 0);
+        assert_eq!(reader.stats().bytes_received, 0);
+    }
+
+    #[tokio::test]
+    async fn test_skips_interleaved_garbage_and_corrupt_frames() {
+        let mut corrupt = encoded(2, b"corrupt");
+        let last = corrupt.len() - 1;
+        corrupt[last] ^= 0xFF;
+
+        let mut stream = BytesMut::new();
+        stream.put_slice(b"garbage");
+        stream.put_slice(&encoded(1, b"first"));
+        stream.put_slice(&[0xDE, 0xAD, 0xBE, 0xEF, 0x00]);
+        stream.put_slice(&corrupt);
+        stream.put_slice(&encoded(3, b"third"));
+
+        // Split mid-frame so resync has to survive across reads
+        let (head, tail) = stream.split_at(FRAME_OVERHEAD + 3);
+        let mock = tokio_test::io::Builder::new().read(head).read(tail).build();
+        let mut reader = FrameReader::new(mock);
+
+        let first = reader.next_frame().await.unwrap().unwrap();
+        assert_eq!(first.correlation_id, 1);
+        let third = reader.next_frame().await.unwrap().unwrap();
+        assert_eq!(third.correlation_id, 3);
+        assert!(reader.next_frame().await.unwrap().is_none());
+
+        let stats = reader.stats();
+        assert_eq!(stats.frames_received, 2);
+        assert_eq!(stats.crc_errors, 1);
+        assert_eq!(stats.resync_events, 2);
+        assert_eq!(stats.bytes_received, stream.len() as u64);
+    }
+
+    #[tokio::test]
+    async fn test_magic_split_across_reads_is_not_lost() {
+        let frame = encoded(9, b"split magic");
+        let mut head = vec![0x55; HEADER_SIZE + 6];
+        head.extend_from_slice(&frame[..2]);
+        let mock = tokio_test::io::Builder::new()
+            .read(&head)
+            .read(&frame[2..])
+            .build();
+        let mut reader = FrameReader::new(mock);
+
+        let decoded = reader.next_frame().await.unwrap().unwrap();
+        assert_eq!(decoded.correlation_id, 9);
+        assert_eq!(reader.buffered(), 0);
+        assert_eq!(reader.stats().resync_events, 1);
+    }
+}
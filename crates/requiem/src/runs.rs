@@ -0,0 +1,583 @@
+//! Run registry
+//!
+//! Executes `ExecRequest`s through the `engine` crate. Each request's workflow
+//! (see [`crate::protocol::convert`]), controls and policy are compiled into
+//! their engine equivalents, and a run is started and driven until it
+//! completes, fails, or stops at something the server cannot do by itself (a
+//! tool call or an approval prompt). Runs that stop early stay registered
+//! under their run id until [`RunRegistry::resume`] gives them what they are
+//! waiting for or they are cancelled; finished runs are dropped.
+//!
+//! The registry is shared by every connection. Its map of run ids is locked
+//! only to look up, add or remove a run; driving a run holds that run's own
+//! lock, so runs with different ids are driven in parallel. The engine is
+//! synchronous, and callers on an async runtime should call in from a
+//! blocking task.
+//!
+//! Every run holds a `CancellationToken`, normally a child of its
+//! connection's token. The driving loop checks it between steps, and
+//! registered runs whose token has been cancelled are cancelled in the engine
+//! and dropped by [`RunRegistry::reap_cancelled`].
+
+use crate::protocol::{
+    protocol_to_engine, Action, Decision, ExecRequestPayload, ExecutionControls, ExecutionMetrics,
+    Policy, PolicyCondition, ProtocolError, ResumeInput, RunEvent, RunStatus,
+};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use tokio_util::sync::CancellationToken;
+
+/// Reason given to a run whose token is cancelled while it is being driven
+const CANCELLED_REASON: &str = "execution cancelled";
+
+/// Outcome of driving a run as far as the server can take it
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct RunOutcome {
+    pub status: RunStatus,
+    pub events: Vec<RunEvent>,
+    pub final_action: Option<Action>,
+    pub metrics: ExecutionMetrics,
+}
+
+/// Engine plus the runs that are still live
+#[derive(Debug)]
+pub(crate) struct RunRegistry {
+    engine: engine::Engine,
+    runs: Mutex<HashMap<String, Arc<LiveRun>>>,
+}
+
+/// A registered run, being driven or paused
+#[derive(Debug)]
+struct LiveRun {
+    session_id: String,
+    token: CancellationToken,
+    /// The run's own lock, held while it is driven. Empty while the run is
+    /// first driven and once it has been taken to finish or cancel.
+    paused: Mutex<Option<PausedRun>>,
+}
+
+/// A paused run and what is needed to report on it later
+#[derive(Debug)]
+struct PausedRun {
+    request: ExecRequestPayload,
+    run: engine::RunHandle,
+    /// Events already reported, so later event ids continue the sequence
+    events_reported: usize,
+    awaiting: Awaiting,
+}
+
+/// What a paused run needs before it can continue
+#[derive(Debug, Clone, PartialEq)]
+enum Awaiting {
+    ToolResult { step_id: String, tool_name: String },
+    Approval(engine::policy::Capability),
+}
+
+/// Where driving a run stopped
+struct Stop {
+    /// Action the client should see, if any
+    action: Option<Action>,
+    /// The engine's error message, if it reported one
+    error: Option<String>,
+    /// What the run paused on, if it paused
+    awaiting: Option<Awaiting>,
+}
+
+impl Default for RunRegistry {
+    fn default() -> Self {
+        Self::new(engine::Engine::new(engine::EngineConfig::default()))
+    }
+}
+
+/// Lock `mutex`, carrying on past a panic in another holder
+///
+/// A panic while driving leaves at worst a run that fails its next step, so
+/// the registry stays usable.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+impl RunRegistry {
+    pub fn new(engine: engine::Engine) -> Self {
+        Self {
+            engine,
+            runs: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Whether `run_id` is registered (being driven or paused, and not yet
+    /// cancelled)
+    #[cfg(test)]
+    pub fn is_active(&self, run_id: &str) -> bool {
+        lock(&self.runs).contains_key(run_id)
+    }
+
+    /// Compile, start and drive the run described by `request`
+    ///
+    /// A tool call or approval prompt pauses the run, since the server has no
+    /// tool executor of its own, and the run stays registered for
+    /// `session_id` until it is resumed past the pause or `token` is
+    /// cancelled. A run id that is still registered, including by a run
+    /// still being driven, cannot be reused.
+    pub fn execute(
+        &self,
+        request: &ExecRequestPayload,
+        session_id: &str,
+        token: CancellationToken,
+    ) -> Result<RunOutcome, ProtocolError> {
+        self.reap_cancelled("client disconnected");
+
+        let live = Arc::new(LiveRun {
+            session_id: session_id.to_string(),
+            token,
+            paused: Mutex::new(None),
+        });
+        let mut paused = lock(&live.paused);
+        {
+            let mut runs = lock(&self.runs);
+            if runs.contains_key(&request.run_id) {
+                return Err(ProtocolError::Execution(format!(
+                    "run {} is already active",
+                    request.run_id
+                )));
+            }
+            runs.insert(request.run_id.clone(), Arc::clone(&live));
+        }
+
+        match self.start(request, &live.token) {
+            Ok((outcome, Some(run))) => {
+                *paused = Some(run);
+                Ok(outcome)
+            }
+            result => {
+                self.unregister(&request.run_id, &live);
+                result.map(|(outcome, _)| outcome)
+            }
+        }
+    }
+
+    /// Start the run `request` describes and drive it, returning the run
+    /// too if it paused on something it can be resumed past
+    fn start(
+        &self,
+        request: &ExecRequestPayload,
+        token: &CancellationToken,
+    ) -> Result<(RunOutcome, Option<PausedRun>), ProtocolError> {
+        let mut run = self
+            .engine
+            .start_run_with_controls(
+                protocol_to_engine(&request.workflow)
+                    .map_err(|e| ProtocolError::Execution(e.to_string()))?,
+                compile_policy(&request.policy)?,
+                compile_controls(&request.controls)?,
+            )
+            .map_err(|e| ProtocolError::Execution(e.to_string()))?;
+
+        let stop = drive(&mut run, token)?;
+        let outcome = outcome(&request.run_id, 0, &mut run, stop.action, stop.error)?;
+
+        let paused = match (&outcome.status, stop.awaiting) {
+            (RunStatus::Paused { .. }, Some(awaiting)) => Some(PausedRun {
+                request: request.clone(),
+                run,
+                events_reported: outcome.events.len(),
+                awaiting,
+            }),
+            _ => None,
+        };
+        Ok((outcome, paused))
+    }
+
+    /// Give a registered run what it paused for and drive it on
+    ///
+    /// Only the session that started the run may resume it, and `input` must
+    /// match what the run is waiting for: the result of its pending tool
+    /// call, or approval of the capability it prompted for. A run that pauses
+    /// again stays registered; otherwise it is dropped. Returns the request
+    /// that started the run, for digesting, along with the new outcome.
+    /// Waits for the run if it is still being driven.
+    pub fn resume(
+        &self,
+        run_id: &str,
+        session_id: &str,
+        input: ResumeInput,
+    ) -> Result<(ExecRequestPayload, RunOutcome), ProtocolError> {
+        let live = self.owned(run_id, session_id)?;
+        let mut paused = lock(&live.paused);
+        let mut entry = paused.take().ok_or_else(|| not_active(run_id))?;
+
+        match (&entry.awaiting, input) {
+            (
+                Awaiting::ToolResult { step_id, tool_name },
+                ResumeInput::ToolResult {
+                    step_id: result_step,
+                    success,
+                    output,
+                    error,
+                },
+            ) if *step_id == result_step => {
+                let result = engine::tools::ToolResult {
+                    step_id: result_step,
+                    tool_name: tool_name.clone(),
+                    output: serde_json::Value::Object(output.into_iter().collect()),
+                    success,
+                    error,
+                };
+                if let Err(e) = entry.run.resume() {
+                    self.unregister(run_id, &live);
+                    return Err(ProtocolError::Execution(e.to_string()));
+                }
+                // A late result fails the run; the outcome reports it
+                let _ = entry.run.apply_tool_result(result);
+            }
+            (Awaiting::Approval(capability), ResumeInput::Approve) => {
+                entry.run.approve(capability.clone());
+                if let Err(e) = entry.run.resume() {
+                    self.unregister(run_id, &live);
+                    return Err(ProtocolError::Execution(e.to_string()));
+                }
+            }
+            (awaiting, _) => {
+                let message = match awaiting {
+                    Awaiting::ToolResult { step_id, .. } => {
+                        format!("run {run_id} is awaiting the tool result for step {step_id}")
+                    }
+                    Awaiting::Approval(_) => format!("run {run_id} is awaiting approval"),
+                };
+                *paused = Some(entry);
+                return Err(ProtocolError::Execution(message));
+            }
+        }
+
+        let driven = drive(&mut entry.run, &live.token).and_then(|stop| {
+            let outcome = outcome(
+                run_id,
+                entry.events_reported,
+                &mut entry.run,
+                stop.action,
+                stop.error,
+            )?;
+            Ok((outcome, stop.awaiting))
+        });
+        match driven {
+            Ok((outcome, Some(awaiting))) if matches!(outcome.status, RunStatus::Paused { .. }) => {
+                entry.events_reported += outcome.events.len();
+                entry.awaiting = awaiting;
+                let request = entry.request.clone();
+                *paused = Some(entry);
+                Ok((request, outcome))
+            }
+            driven => {
+                self.unregister(run_id, &live);
+                driven.map(|(outcome, _)| (entry.request, outcome))
+            }
+        }
+    }
+
+    /// Cancel a registered run on behalf of the session that started it
+    ///
+    /// A run still being driven is stopped before its next step instead, and
+    /// the request driving it reports the cancellation. Returns the request
+    /// that started the run, for digesting, along with the cancelled outcome.
+    pub fn cancel(
+        &self,
+        run_id: &str,
+        session_id: &str,
+        reason: &str,
+    ) -> Result<(ExecRequestPayload, RunOutcome), ProtocolError> {
+        let live = self.owned(run_id, session_id)?;
+        live.token.cancel();
+        let mut entry = lock(&live.paused)
+            .take()
+            .ok_or_else(|| not_active(run_id))?;
+        self.unregister(run_id, &live);
+
+        entry
+            .run
+            .cancel(reason)
+            .map_err(|e| ProtocolError::Execution(e.to_string()))?;
+        let outcome = outcome(run_id, entry.events_reported, &mut entry.run, None, None)?;
+        Ok((entry.request, outcome))
+    }
+
+    /// The registered run `run_id`, if the session that started it asks
+    fn owned(&self, run_id: &str, session_id: &str) -> Result<Arc<LiveRun>, ProtocolError> {
+        let live = lock(&self.runs)
+            .get(run_id)
+            .cloned()
+            .ok_or_else(|| not_active(run_id))?;
+        if live.session_id != session_id {
+            return Err(ProtocolError::Execution(format!(
+                "run {run_id} belongs to another session"
+            )));
+        }
+        Ok(live)
+    }
+
+    /// Drop `run_id` from the registry if it is still registered as `live`
+    fn unregister(&self, run_id: &str, live: &Arc<LiveRun>) {
+        let mut runs = lock(&self.runs);
+        if runs
+            .get(run_id)
+            .is_some_and(|entry| Arc::ptr_eq(entry, live))
+        {
+            runs.remove(run_id);
+        }
+    }
+
+    /// Cancel and drop every registered run whose token has been cancelled
+    ///
+    /// Runs still being driven stop at their next step; this waits for them.
+    /// Returns the ids of the runs cancelled, in sorted order.
+    pub fn reap_cancelled(&self, reason: &str) -> Vec<String> {
+        let mut cancelled: Vec<(String, Arc<LiveRun>)> = {
+            let mut runs = lock(&self.runs);
+            let ids: Vec<String> = runs
+                .iter()
+                .filter(|(_, live)| live.token.is_cancelled())
+                .map(|(run_id, _)| run_id.clone())
+                .collect();
+            ids.into_iter()
+                .filter_map(|run_id| runs.remove(&run_id).map(|live| (run_id, live)))
+                .collect()
+        };
+        cancelled.sort_by(|(a, _), (b, _)| a.cmp(b));
+        for (_, live) in &cancelled {
+            if let Some(mut entry) = lock(&live.paused).take() {
+                // Registered runs are paused, and a paused run can always be cancelled
+                let _ = entry.run.cancel(reason);
+            }
+        }
+        cancelled.into_iter().map(|(run_id, _)| run_id).collect()
+    }
+}
+
+/// Error for a run id with nothing registered to act on
+fn not_active(run_id: &str) -> ProtocolError {
+    ProtocolError::Execution(format!("run {run_id} is not active"))
+}
+
+/// Report the state of `run`, draining its events
+fn outcome(
+    run_id: &str,
+    first_event: usize,
+    run: &mut engine::RunHandle,
+    final_action: Option<Action>,
+    error: Option<String>,
+) -> Result<RunOutcome, ProtocolError> {
+    Ok(RunOutcome {
+        status: run_status(run.status(), error),
+        events: run
+            .drain_events()
+            .iter()
+            .enumerate()
+            .map(|(index, event)| run_event(run_id, first_event + index, event))
+            .collect::<Result<_, _>>()?,
+        final_action,
+        metrics: ExecutionMetrics::from(&*run),
+    })
+}
+
+impl Stop {
+    /// A stop that leaves nothing to resume
+    fn at(action: Option<Action>, error: Option<String>) -> Self {
+        Self {
+            action,
+            error,
+            awaiting: None,
+        }
+    }
+}
+
+/// Take actions until the run needs something from outside or stops
+///
+/// `token` is checked before every step; once it is cancelled the run is
+/// cancelled and no further step is taken.
+fn drive(run: &mut engine::RunHandle, token: &CancellationToken) -> Result<Stop, ProtocolError> {
+    let pause = |run: &mut engine::RunHandle, reason: String| {
+        run.pause(&reason)
+            .map_err(|e| ProtocolError::Execution(e.to_string()))
+    };
+
+    loop {
+        if token.is_cancelled() {
+            run.cancel(CANCELLED_REASON)
+                .map_err(|e| ProtocolError::Execution(e.to_string()))?;
+            return Ok(Stop::at(None, None));
+        }
+        match run.next_action() {
+            engine::Action::EmitArtifact(_) => {}
+            engine::Action::ToolCall(call) => {
+                pause(
+                    run,
+                    format!("awaiting tool result for step {}", call.step_id),
+                )?;
+                let input = match call.input {
+                    serde_json::Value::Object(map) => map.into_iter().collect(),
+                    serde_json::Value::Null => BTreeMap::new(),
+                    other => BTreeMap::from([("input".to_string(), other)]),
+                };
+                return Ok(Stop {
+                    awaiting: Some(Awaiting::ToolResult {
+                        step_id: call.step_id.clone(),
+                        tool_name: call.tool_name.clone(),
+                    }),
+                    action: Some(Action::ToolCall {
+                        step_id: call.step_id,
+                        tool_name: call.tool_name,
+                        input,
+                    }),
+                    error: None,
+                });
+            }
+            engine::Action::AwaitApproval { capability } => {
+                let described = match &capability {
+                    engine::policy::Capability::ToolUse { name } => format!("tool {name}"),
+                    engine::policy::Capability::EmitArtifact => "artifact emission".to_string(),
+                    engine::policy::Capability::ToolUsePattern { pattern } => {
+                        format!("tools {pattern}")
+                    }
+                };
+                pause(run, format!("awaiting approval for {described}"))?;
+                return Ok(Stop {
+                    action: None,
+                    error: None,
+                    awaiting: Some(Awaiting::Approval(capability)),
+                });
+            }
+            engine::Action::Done => return Ok(Stop::at(Some(Action::Done), None)),
+            engine::Action::Paused { .. } | engine::Action::Cancelled { .. } => {
+                return Ok(Stop::at(None, None));
+            }
+            engine::Action::Error { message } => return Ok(Stop::at(None, Some(message))),
+        }
+    }
+}
+
+fn run_status(status: &engine::state::RunStatus, error: Option<String>) -> RunStatus {
+    use engine::state::RunStatus as Engine;
+    match status {
+        Engine::Completed => RunStatus::Completed,
+        Engine::Paused { reason } => RunStatus::Paused {
+            reason: reason.clone(),
+        },
+        Engine::Cancelled { reason } => RunStatus::Cancelled {
+            reason: reason.clone(),
+        },
+        Engine::Failed { reason } => RunStatus::Failed {
+            reason: reason.clone(),
+        },
+        Engine::Created | Engine::Running => RunStatus::Failed {
+            reason: error.unwrap_or_else(|| "run stopped while still running".to_string()),
+        },
+    }
+}
+
+/// Protocol form of an engine event
+///
+/// Events carry no wall-clock time (`timestamp_us` is 0) so that the result
+/// digest, which covers them, is reproducible.
+fn run_event(
+    run_id: &str,
+    index: usize,
+    event: &engine::state::RunEvent,
+) -> Result<RunEvent, ProtocolError> {
+    let serde_json::Value::Object(mut fields) =
+        serde_json::to_value(event).map_err(|e| ProtocolError::Encoding(e.to_string()))?
+    else {
+        return Err(ProtocolError::Encoding(
+            "run event is not an object".to_string(),
+        ));
+    };
+    let Some(serde_json::Value::String(event_type)) = fields.remove("type") else {
+        return Err(ProtocolError::Encoding("run event has no type".to_string()));
+    };
+    Ok(RunEvent {
+        event_id: format!("{run_id}-{index}"),
+        event_type,
+        timestamp_us: 0,
+        payload: fields.into_iter().collect(),
+    })
+}
+
+/// Compile protocol execution controls; zero means "no limit"
+fn compile_controls(
+    controls: &ExecutionControls,
+) -> Result<engine::ExecutionControls, ProtocolError> {
+    let duration = |value: crate::fixed::FixedDuration, name: &str| {
+        if value == crate::fixed::FixedDuration::ZERO {
+            return Ok(None);
+        }
+        std::time::Duration::try_from(value)
+            .map(Some)
+            .map_err(|e| ProtocolError::Execution(format!("{name}: {e}")))
+    };
+
+    Ok(engine::ExecutionControls {
+        max_steps: controls
+            .max_steps
+            .map(|max| usize::try_from(max).unwrap_or(usize::MAX)),
+        step_timeout: duration(controls.step_timeout_us, "step_timeout_us")?,
+        run_timeout: duration(controls.run_timeout_us, "run_timeout_us")?,
+        budget_limit_usd: (controls.budget_limit_usd != crate::fixed::FixedQ32_32::ZERO)
+            .then(|| controls.budget_limit_usd.to_f64()),
+        min_step_interval: duration(controls.min_step_interval_us, "min_step_interval_us")?,
+    })
+}
+
+/// Compile a protocol policy
+///
+/// Only tool and artifact capabilities map onto engine rules: `ToolAllowed`
+/// (a `*` in the name makes it a pattern) and `Capability { name:
+/// "emit_artifact" }`.
+fn compile_policy(policy: &Policy) -> Result<engine::policy::Policy, ProtocolError> {
+    use engine::policy::{Capability, PolicyRule};
+
+    let rules = policy
+        .rules
+        .iter()
+        .map(|rule| {
+            let unsupported = |what: &str| {
+                ProtocolError::Execution(format!("policy rule {} {}", rule.name, what))
+            };
+            let capability = match &rule.condition {
+                PolicyCondition::ToolAllowed { tool_name } if tool_name.contains('*') => {
+                    Capability::ToolUsePattern {
+                        pattern: tool_name.clone(),
+                    }
+                }
+                PolicyCondition::ToolAllowed { tool_name } => Capability::ToolUse {
+                    name: tool_name.clone(),
+                },
+                PolicyCondition::Capability { name } if name == "emit_artifact" => {
+                    Capability::EmitArtifact
+                }
+                condition => {
+                    return Err(unsupported(&format!(
+                        "has unsupported condition {condition:?}"
+                    )));
+                }
+            };
+            let (allow, reason) = match &rule.decision {
+                Decision::Allow | Decision::Prompt => (true, None),
+                Decision::Deny { reason } => (false, Some(reason.clone())),
+            };
+            Ok(PolicyRule {
+                capability,
+                allow,
+                reason,
+                name: Some(rule.name.clone()),
+                prompt: matches!(rule.decision, Decision::Prompt),
+            })
+        })
+        .collect::<Result<_, _>>()?;
+
+    Ok(engine::policy::Policy {
+        rules,
+        default_decision: match &policy.default_decision {
+            Decision::Allow => engine::policy::Decision::Allow,
+            Decision::Deny { reason } => engine::policy::Decision::Deny(reason.clone()),
+            Decision::Prompt => engine::policy::Decision::Prompt,
+        },
+    })
+}
@@ -6,23 +6,29 @@
 //! - TCP sockets (optional, for debugging)
 
 use crate::protocol::{
-    CapabilityFlags, ErrorCode, ErrorPayload, ExecRequestPayload, ExecResultPayload,
-    Frame, FrameCodec, FrameError, FrameFlags, HealthRequestPayload, HealthResultPayload,
-    HealthStatus, HelloAckPayload, HelloPayload, MessageType, ProtocolCapabilities,
-    ProtocolError, ProtocolState, ProtocolStats, ProtocolVersion, deserialize_message,
-    encode_cbor, frame_message, parse_frame, serialize_message,
+    canonicalize_value, encode_canonical_json, frame_message, frame_message_as, parse_frame,
+    parse_frame_as, Action, CancelRunPayload, CapabilityFlags, CompressionCodec,
+    ConnectionStatsPayload, Encoding, ErrorCode, ErrorPayload, ExecRequestPayload,
+    ExecResultPayload, Frame, FrameCodec, FrameError, FrameFlags, FrameReader,
+    HealthRequestPayload, HealthResultPayload, HealthStatus, HelloAckPayload, HelloPayload,
+    MessageType, ProtocolError, ProtocolState, ProtocolStats, ProtocolVersion, ResumeRunPayload,
+    RunEvent, Workflow,
 };
+use crate::runs::{RunOutcome, RunRegistry};
 use bytes::BytesMut;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
+use thiserror::Error;
 use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
 use tokio::net::{TcpListener, TcpStream};
-use tokio::sync::{Mutex, RwLock};
-use tokio_util::codec::{Decoder, Encoder};
+use tokio::sync::{Mutex, OwnedSemaphorePermit, RwLock, Semaphore};
+use tokio::task::{JoinHandle, JoinSet};
+use tokio_util::codec::Encoder;
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, info, warn};
 
 #[cfg(windows)]
-use tokio::net::windows::named_pipe::{ServerOptions};
+use tokio::net::windows::named_pipe::ServerOptions;
 
 /// Server configuration
 #[derive(Debug, Clone)]
@@ -33,14 +39,29 @@ pub struct ServerConfig {
     pub socket_path: Option<String>,
     /// Maximum concurrent connections
     pub max_connections: usize,
+    /// What to do with a new connection once `max_connections` are open
+    pub connection_limit_mode: ConnectionLimitMode,
     /// Connection timeout
     pub connection_timeout_secs: u64,
+    /// Interval between heartbeats sent to each connection (0 = disabled)
+    pub heartbeat_interval_secs: u64,
+    /// Heartbeat intervals without any inbound frame before a connection is closed
+    pub heartbeat_max_missed: u32,
     /// Maximum request size
     pub max_request_size: usize,
     /// Require CRC verification
     pub require_crc: bool,
     /// Parent process ID (for watchdog)
     pub parent_pid: Option<u32>,
+    /// Compression codecs offered to clients, most preferred first (empty = never compress)
+    pub compression_codecs: Vec<CompressionCodec>,
+    /// `ExecRequest`s a session may have in flight; further ones get a `ResourceExhausted` error
+    pub max_in_flight_requests: usize,
+    /// Tool capabilities (`LLM`, `SANDBOX`) offered to clients; a session gets those its hello also claims
+    pub tool_capabilities: CapabilityFlags,
+    /// Tools workflows may call, by name, with the capabilities each needs from the session
+    /// (`NONE` for none). Steps calling a tool not listed here are denied.
+    pub tools: BTreeMap<String, CapabilityFlags>,
 }
 
 impl Default for ServerConfig {
@@ -53,14 +74,129 @@ impl Default for ServerConfig {
                 "/tmp/requiem.sock".to_string()
             }),
             max_connections: 100,
+            connection_limit_mode: ConnectionLimitMode::Reject,
             connection_timeout_secs: 300,
+            heartbeat_interval_secs: 30,
+            heartbeat_max_missed: 3,
             max_request_size: 64 * 1024 * 1024,
             require_crc: true,
             parent_pid: None,
+            compression_codecs: CompressionCodec::SUPPORTED.to_vec(),
+            max_in_flight_requests: 8,
+            tool_capabilities: CapabilityFlags::LLM | CapabilityFlags::SANDBOX,
+            tools: BTreeMap::new(),
         }
     }
 }
 
+/// A `ServerConfig` that cannot start any usable listener
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum TransportError {
+    #[error("no transport configured: set tcp_bind or socket_path")]
+    NoneConfigured,
+
+    #[error("socket_path set but {transport} unavailable on this target")]
+    Unavailable { transport: &'static str },
+}
+
+impl ServerConfig {
+    /// Check that every configured transport exists on this platform and at
+    /// least one is configured
+    ///
+    /// `socket_path` is a Unix socket on POSIX and a named pipe on Windows;
+    /// other targets only have TCP.
+    pub fn validate_transports(&self) -> Result<(), TransportError> {
+        #[cfg(not(any(unix, windows)))]
+        if self.socket_path.is_some() {
+            return Err(TransportError::Unavailable {
+                transport: "Unix sockets and named pipes",
+            });
+        }
+        if self.tcp_bind.is_none() && self.socket_path.is_none() {
+            return Err(TransportError::NoneConfigured);
+        }
+        Ok(())
+    }
+}
+
+/// Behaviour when the server is at `max_connections`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ConnectionLimitMode {
+    /// Accept the connection, send a `ResourceExhausted` error frame and close it
+    #[default]
+    Reject,
+    /// Stop accepting until an existing connection closes (clients queue in the backlog)
+    Backpressure,
+}
+
+/// Caps concurrent connections; each accepted connection holds a permit until it closes
+#[derive(Clone)]
+struct ConnectionLimiter {
+    permits: Arc<Semaphore>,
+    max_connections: usize,
+    mode: ConnectionLimitMode,
+}
+
+impl ConnectionLimiter {
+    fn new(max_connections: usize, mode: ConnectionLimitMode) -> Self {
+        Self {
+            permits: Arc::new(Semaphore::new(max_connections.min(Semaphore::MAX_PERMITS))),
+            max_connections,
+            mode,
+        }
+    }
+
+    /// In backpressure mode, wait for capacity before accepting
+    async fn reserve(&self) -> Option<OwnedSemaphorePermit> {
+        match self.mode {
+            ConnectionLimitMode::Reject => None,
+            ConnectionLimitMode::Backpressure => self.permits.clone().acquire_owned().await.ok(),
+        }
+    }
+
+    /// Admit an accepted connection, or return None if it must be rejected
+    fn admit(&self, reserved: Option<OwnedSemaphorePermit>) -> Option<OwnedSemaphorePermit> {
+        reserved.or_else(|| self.permits.clone().try_acquire_owned().ok())
+    }
+}
+
+/// Per-connection liveness, compression and concurrency settings derived from `ServerConfig`
+#[derive(Debug, Clone)]
+struct ConnectionSettings {
+    /// Close the connection after this long without a complete frame
+    idle_timeout: std::time::Duration,
+    /// Send a heartbeat this often (None = disabled)
+    heartbeat_interval: Option<std::time::Duration>,
+    /// Close the connection after this many heartbeat intervals without inbound frames
+    heartbeat_max_missed: u32,
+    /// Codecs this server can agree to, most preferred first
+    compression_codecs: Vec<CompressionCodec>,
+    /// `ExecRequest`s processed at once before further ones are rejected
+    max_in_flight_requests: usize,
+    /// Tool capabilities this server can agree to
+    tool_capabilities: CapabilityFlags,
+    /// Tools workflows may call, with the capabilities each needs
+    tools: Arc<BTreeMap<String, CapabilityFlags>>,
+}
+
+impl ConnectionSettings {
+    fn from_config(config: &ServerConfig) -> Self {
+        Self {
+            idle_timeout: std::time::Duration::from_secs(config.connection_timeout_secs),
+            heartbeat_interval: (config.heartbeat_interval_secs > 0)
+                .then(|| std::time::Duration::from_secs(config.heartbeat_interval_secs)),
+            heartbeat_max_missed: config.heartbeat_max_missed,
+            compression_codecs: config.compression_codecs.clone(),
+            max_in_flight_requests: config.max_in_flight_requests,
+            tool_capabilities: config.tool_capabilities,
+            tools: Arc::new(config.tools.clone()),
+        }
+    }
+}
+
+/// Spawned connection tasks, tracked so shutdown can drain them instead of aborting
+type ConnectionTasks = Arc<Mutex<Vec<JoinHandle<()>>>>;
+
 /// Server handle
 #[derive(Clone)]
 pub struct Server {
@@ -68,12 +204,17 @@ pub struct Server {
     state: Arc<RwLock<ServerState>>,
     stats: Arc<RwLock<ProtocolStats>>,
     shutdown: tokio::sync::broadcast::Sender<()>,
+    connection_tasks: ConnectionTasks,
 }
 
 #[derive(Debug)]
 struct ServerState {
     connections: HashMap<String, ConnectionInfo>,
     next_session_id: u64,
+    /// Engine and the runs it has paused, shared by every connection. The
+    /// registry locks each run on its own, so handlers clone it out rather
+    /// than drive runs under this state's lock.
+    runs: Arc<RunRegistry>,
 }
 
 #[derive(Debug, Clone)]
@@ -83,6 +224,10 @@ struct ConnectionInfo {
     client_version: String,
     protocol_version: ProtocolVersion,
     connected_at: std::time::Instant,
+    /// Counters for this connection only; the server-wide stats are their
+    /// sum. Shared with the connection task, which updates them without
+    /// locking the server state.
+    stats: Arc<RwLock<ProtocolStats>>,
 }
 
 impl Server {
@@ -94,31 +239,43 @@ impl Server {
             state: Arc::new(RwLock::new(ServerState {
                 connections: HashMap::new(),
                 next_session_id: 1,
+                runs: Arc::default(),
             })),
             stats: Arc::new(RwLock::new(ProtocolStats::default())),
             shutdown,
+            connection_tasks: Arc::new(Mutex::new(Vec::new())),
         }
     }
 
     /// Run the server (blocking)
+    ///
+    /// Fails straight away with a [`TransportError`] if the config would
+    /// start no listener, instead of waiting for a shutdown that never comes.
     pub async fn run(&self) -> Result<(), Box<dyn std::error::Error>> {
+        self.config.validate_transports()?;
         info!("Starting Requiem server");
-        info!("Protocol version: {}.{}", 
+        info!(
+            "Protocol version: {}.{}",
             crate::protocol::PROTOCOL_VERSION_MAJOR,
             crate::protocol::PROTOCOL_VERSION_MINOR
         );
 
         let mut handles = vec![];
+        let limiter = ConnectionLimiter::new(
+            self.config.max_connections,
+            self.config.connection_limit_mode,
+        );
+        let settings = ConnectionSettings::from_config(&self.config);
 
         // Start Parent Watchdog (5s heartbeat / death signal)
         let shutdown_watchdog = self.shutdown.subscribe();
         let parent_pid = self.config.parent_pid;
-        
+
         let watchdog_handle = tokio::spawn(async move {
             let mut interval = tokio::time::interval(std::time::Duration::from_secs(5));
             loop {
                 interval.tick().await;
-                
+
                 if let Some(pid) = parent_pid {
                     if !is_parent_alive(pid) {
                         warn!("Parent process {} is gone, shutting down", pid);
@@ -132,7 +289,7 @@ impl Server {
                     warn!("Parent process died (reparented to 1), shutting down");
                     break;
                 }
-                
+
                 // If shutdown signaled
                 if !shutdown_watchdog.is_empty() {
                     break;
@@ -145,10 +302,15 @@ impl Server {
             let state = self.state.clone();
             let stats = self.stats.clone();
             let shutdown = self.shutdown.subscribe();
-            
+            let tasks = self.connection_tasks.clone();
+            let limiter = limiter.clone();
+            let settings = settings.clone();
+
             info!("Starting TCP listener on {}", addr);
             let handle = tokio::spawn(async move {
-                if let Err(e) = run_tcp_server(&addr, state, stats, shutdown).await {
+                if let Err(e) =
+                    run_tcp_server(&addr, state, stats, tasks, limiter, settings, shutdown).await
+                {
                     error!("TCP server error: {}", e);
                 }
             });
@@ -162,10 +324,15 @@ impl Server {
             let state = self.state.clone();
             let stats = self.stats.clone();
             let shutdown = self.shutdown.subscribe();
-            
+            let tasks = self.connection_tasks.clone();
+            let limiter = limiter.clone();
+            let settings = settings.clone();
+
             info!("Starting Unix socket server at {}", path);
             let handle = tokio::spawn(async move {
-                if let Err(e) = run_unix_server(&path, state, stats, shutdown).await {
+                if let Err(e) =
+                    run_unix_server(&path, state, stats, tasks, limiter, settings, shutdown).await
+                {
                     error!("Unix server error: {}", e);
                 }
             });
@@ -179,10 +346,16 @@ impl Server {
             let state = self.state.clone();
             let stats = self.stats.clone();
             let shutdown = self.shutdown.subscribe();
-            
+            let tasks = self.connection_tasks.clone();
+            let limiter = limiter.clone();
+            let settings = settings.clone();
+
             info!("Starting named pipe server at {}", name);
             let handle = tokio::spawn(async move {
-                if let Err(e) = run_named_pipe_server(&name, state, stats, shutdown).await {
+                if let Err(e) =
+                    run_named_pipe_server(&name, state, stats, tasks, limiter, settings, shutdown)
+                        .await
+                {
                     error!("Named pipe server error: {}", e);
                 }
             });
@@ -194,7 +367,7 @@ impl Server {
         let _ = shutdown_rx.recv().await;
 
         info!("Shutting down server");
-        
+
         // Cancel all tasks
         for handle in handles {
             handle.abort();
@@ -208,11 +381,74 @@ impl Server {
         let _ = self.shutdown.send(());
     }
 
+    /// Shutdown the server, letting in-flight connections finish
+    ///
+    /// Stops accepting new connections, then waits up to `timeout` for open
+    /// connections to finish their current frame and close. Connections still
+    /// running when the timeout expires are aborted. Returns the number of
+    /// connections that had to be aborted.
+    pub async fn shutdown_graceful(&self, timeout: std::time::Duration) -> usize {
+        self.shutdown();
+        let deadline = tokio::time::Instant::now() + timeout;
+        let mut pending = Vec::new();
+
+        loop {
+            // Re-check after each drain: a connection may have been accepted
+            // just before the listeners observed the shutdown signal.
+            let batch = std::mem::take(&mut *self.connection_tasks.lock().await);
+            if batch.is_empty() {
+                break;
+            }
+            let aborts: Vec<_> = batch.iter().map(JoinHandle::abort_handle).collect();
+            pending.extend(aborts);
+
+            let drained = tokio::time::timeout_at(deadline, async {
+                for handle in batch {
+                    let _ = handle.await;
+                }
+            })
+            .await;
+            if drained.is_err() {
+                break;
+            }
+        }
+
+        let mut aborted = 0;
+        for handle in pending {
+            if !handle.is_finished() {
+                handle.abort();
+                aborted += 1;
+            }
+        }
+        if aborted > 0 {
+            warn!(
+                "Graceful shutdown timed out, aborted {} connection(s)",
+                aborted
+            );
+        } else {
+            info!("Graceful shutdown drained all connections");
+        }
+        aborted
+    }
+
     /// Get current statistics
     pub async fn stats(&self) -> ProtocolStats {
         self.stats.read().await.clone()
     }
 
+    /// Get statistics for a single connection, if the session is still open
+    pub async fn connection_stats(&self, session_id: &str) -> Option<ProtocolStats> {
+        let stats = self
+            .state
+            .read()
+            .await
+            .connections
+            .get(session_id)
+            .map(|info| Arc::clone(&info.stats))?;
+        let stats = stats.read().await.clone();
+        Some(stats)
+    }
+
     /// Get active connections count
     pub async fn active_connections(&self) -> usize {
         self.state.read().await.connections.len()
@@ -224,26 +460,47 @@ async fn run_tcp_server(
     addr: &str,
     state: Arc<RwLock<ServerState>>,
     stats: Arc<RwLock<ProtocolStats>>,
+    tasks: ConnectionTasks,
+    limiter: ConnectionLimiter,
+    settings: ConnectionSettings,
     mut shutdown: tokio::sync::broadcast::Receiver<()>,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let listener = TcpListener::bind(addr).await?;
     info!("TCP server listening on {}", addr);
 
     loop {
+        let reserved = tokio::select! {
+            biased;
+            _ = shutdown.recv() => break,
+            permit = limiter.reserve() => permit,
+        };
         tokio::select! {
+            biased;
+            _ = shutdown.recv() => {
+                break;
+            }
             result = listener.accept() => {
                 match result {
                     Ok((stream, peer_addr)) => {
+                        let Some(permit) = limiter.admit(reserved) else {
+                            warn!("Connection limit reached, rejecting {}", peer_addr);
+                            reject_connection(stream, limiter.max_connections).await;
+                            continue;
+                        };
                         let state = state.clone();
                         let stats = stats.clone();
-                        
-                        tokio::spawn(async move {
+                        let conn_shutdown = shutdown.resubscribe();
+                        let settings = settings.clone();
+
+                        let handle = tokio::spawn(async move {
+                            let _permit = permit;
                             info!("New connection from {}", peer_addr);
-                            if let Err(e) = handle_connection(stream, state, stats).await {
+                            if let Err(e) = handle_connection(stream, state, stats, settings, conn_shutdown).await {
                                 warn!("Connection from {} error: {}", peer_addr, e);
                             }
                             info!("Connection from {} closed", peer_addr);
                         });
+                        track_connection(&tasks, handle).await;
                     }
                     Err(e) => {
                         error!("Accept error: {}", e);
@@ -251,9 +508,6 @@ async fn run_tcp_server(
                     }
                 }
             }
-            _ = shutdown.recv() => {
-                break;
-            }
         }
     }
 
@@ -266,29 +520,50 @@ async fn run_unix_server(
     path: &str,
     state: Arc<RwLock<ServerState>>,
     stats: Arc<RwLock<ProtocolStats>>,
+    tasks: ConnectionTasks,
+    limiter: ConnectionLimiter,
+    settings: ConnectionSettings,
     mut shutdown: tokio::sync::broadcast::Receiver<()>,
 ) -> Result<(), Box<dyn std::error::Error>> {
     use tokio::net::UnixListener;
-    
+
     // Remove existing socket file if it exists
     let _ = std::fs::remove_file(path);
-    
+
     let listener = UnixListener::bind(path)?;
     info!("Unix server listening on {}", path);
 
     loop {
+        let reserved = tokio::select! {
+            biased;
+            _ = shutdown.recv() => break,
+            permit = limiter.reserve() => permit,
+        };
         tokio::select! {
+            biased;
+            _ = shutdown.recv() => {
+                break;
+            }
             result = listener.accept() => {
                 match result {
                     Ok((stream, _)) => {
+                        let Some(permit) = limiter.admit(reserved) else {
+                            warn!("Connection limit reached, rejecting Unix connection");
+                            reject_connection(stream, limiter.max_connections).await;
+                            continue;
+                        };
                         let state = state.clone();
                         let stats = stats.clone();
-                        
-                        tokio::spawn(async move {
-                            if let Err(e) = handle_connection(stream, state, stats).await {
+                        let conn_shutdown = shutdown.resubscribe();
+                        let settings = settings.clone();
+
+                        let handle = tokio::spawn(async move {
+                            let _permit = permit;
+                            if let Err(e) = handle_connection(stream, state, stats, settings, conn_shutdown).await {
                                 warn!("Unix connection error: {}", e);
                             }
                         });
+                        track_connection(&tasks, handle).await;
                     }
                     Err(e) => {
                         error!("Accept error: {}", e);
@@ -296,9 +571,6 @@ async fn run_unix_server(
                     }
                 }
             }
-            _ = shutdown.recv() => {
-                break;
-            }
         }
     }
 
@@ -311,6 +583,9 @@ async fn run_named_pipe_server(
     pipe_name: &str,
     state: Arc<RwLock<ServerState>>,
     stats: Arc<RwLock<ProtocolStats>>,
+    tasks: ConnectionTasks,
+    limiter: ConnectionLimiter,
+    settings: ConnectionSettings,
     mut shutdown: tokio::sync::broadcast::Receiver<()>,
 ) -> Result<(), Box<dyn std::error::Error>> {
     info!("Named pipe server listening on {}", pipe_name);
@@ -322,17 +597,35 @@ async fn run_named_pipe_server(
             .create(pipe_name)?;
         first_instance = false;
 
+        let reserved = tokio::select! {
+            biased;
+            _ = shutdown.recv() => break,
+            permit = limiter.reserve() => permit,
+        };
         tokio::select! {
+            biased;
+            _ = shutdown.recv() => {
+                break;
+            }
             res = server.connect() => {
                 match res {
                     Ok(_) => {
+                        let Some(permit) = limiter.admit(reserved) else {
+                            warn!("Connection limit reached, rejecting named pipe client");
+                            reject_connection(server, limiter.max_connections).await;
+                            continue;
+                        };
                         let state = state.clone();
                         let stats = stats.clone();
-                        tokio::spawn(async move {
-                            if let Err(e) = handle_connection(server, state, stats).await {
+                        let conn_shutdown = shutdown.resubscribe();
+                        let settings = settings.clone();
+                        let handle = tokio::spawn(async move {
+                            let _permit = permit;
+                            if let Err(e) = handle_connection(server, state, stats, settings, conn_shutdown).await {
                                 warn!("Named pipe connection error: {}", e);
                             }
                         });
+                        track_connection(&tasks, handle).await;
                     }
                     Err(e) => {
                         error!("Named pipe connect error: {}", e);
@@ -340,159 +633,373 @@ async fn run_named_pipe_server(
                     }
                 }
             }
-            _ = shutdown.recv() => {
-                break;
-            }
         }
     }
 
     Ok(())
 }
 
+/// Tell a client the server is full, then close the connection
+async fn reject_connection<S>(mut stream: S, max_connections: usize)
+where
+    S: AsyncWrite + Unpin,
+{
+    let error_payload = ErrorPayload {
+        code: ErrorCode::ResourceExhausted,
+        message: "Connection limit reached".to_string(),
+        details: {
+            let mut m = std::collections::BTreeMap::new();
+            m.insert("max_connections".to_string(), max_connections.to_string());
+            m
+        },
+        correlation_id: String::new(),
+    };
+    let mut buf = BytesMut::new();
+    let written = match frame_message(MessageType::Error, &error_payload, 0) {
+        Ok(frame) => match FrameCodec::default().encode(frame, &mut buf) {
+            Ok(()) => stream.write_all(&buf).await.map_err(ProtocolError::Io),
+            Err(e) => Err(ProtocolError::Frame(e)),
+        },
+        Err(e) => Err(e),
+    };
+    if let Err(e) = written {
+        debug!("Failed to send connection limit error: {}", e);
+    }
+    let _ = stream.shutdown().await;
+}
+
+/// Record a spawned connection task, pruning ones that already finished
+async fn track_connection(tasks: &ConnectionTasks, handle: JoinHandle<()>) {
+    let mut tasks = tasks.lock().await;
+    tasks.retain(|task| !task.is_finished());
+    tasks.push(handle);
+}
+
+/// Add `delta` to the server-wide stats and to this connection's stats
+async fn record_stats(
+    stats: &RwLock<ProtocolStats>,
+    connection_stats: &RwLock<ProtocolStats>,
+    delta: &ProtocolStats,
+) {
+    stats.write().await.absorb(delta);
+    connection_stats.write().await.absorb(delta);
+}
+
+/// Stats delta for one frame of `len` bytes written to the peer
+fn sent_frame_stats(len: usize) -> ProtocolStats {
+    ProtocolStats {
+        frames_sent: 1,
+        bytes_sent: len as u64,
+        ..Default::default()
+    }
+}
+
+/// How long a connection may sit idle after shutdown before it is closed
+const SHUTDOWN_DRAIN_IDLE: std::time::Duration = std::time::Duration::from_millis(250);
+
 /// Handle a single connection (generic over stream type)
+///
+/// A connection that sends no complete frame for `idle_timeout` is sent a
+/// `Timeout` error and closed, releasing its connection slot.
+///
+/// When heartbeats are enabled an empty `Heartbeat` frame is sent every
+/// `heartbeat_interval`; a peer that sends nothing (not even a heartbeat
+/// reply) for `heartbeat_max_missed` intervals is treated as dead and closed.
+///
+/// `ExecRequest`s run as separate tasks, so a session can have up to
+/// `max_in_flight_requests` of them in flight; each result is sent as soon as
+/// its run finishes, tagged with its request's correlation ID, so results may
+/// arrive out of order. A request beyond the limit is answered with a
+/// `ResourceExhausted` error.
+///
+/// On shutdown the connection switches to draining: it keeps answering frames
+/// until the peer has been idle for `SHUTDOWN_DRAIN_IDLE` and no request is
+/// in flight, so requests already sent still get their response.
 async fn handle_connection<S>(
     stream: S,
     state: Arc<RwLock<ServerState>>,
     stats: Arc<RwLock<ProtocolStats>>,
-) -> Result<(), ProtocolError> 
+    settings: ConnectionSettings,
+    mut shutdown: tokio::sync::broadcast::Receiver<()>,
+) -> Result<(), ProtocolError>
 where
     S: AsyncRead + AsyncWrite + Unpin,
 {
-    let (mut read_half, mut write_half) = tokio::io::split(stream);
-    let mut codec = FrameCodec;
+    let (read_half, mut write_half) = tokio::io::split(stream);
+    let mut reader = FrameReader::new(read_half);
+    let mut codec = FrameCodec::default();
 
-    let mut buf = BytesMut::with_capacity(4096);
     let mut connection_state = ProtocolState::Disconnected;
     let mut session_id = String::new();
+    let mut encoding = Encoding::Cbor;
+    let mut compression = CompressionCodec::None;
+    let mut capabilities = CapabilityFlags::NONE;
+    let session_stats = Arc::new(RwLock::new(ProtocolStats::default()));
 
-    let read_timeout = std::time::Duration::from_secs(60);
+    // Runs started here hold child tokens; leaving by any path cancels them
+    let cancel = CancellationToken::new();
+    let _cancel_on_exit = cancel.clone().drop_guard();
 
-    loop {
-        // Read data with timeout to prevent idle connection hanging
-        let read_result = tokio::time::timeout(
-            read_timeout,
-            read_half.read_buf(&mut buf)
-        ).await;
-
-        match read_result {
-            Ok(Ok(0)) => {
-                // Connection closed
-                break;
-            }
-            Ok(Ok(n)) => {
-                let mut s = stats.write().await;
-                s.bytes_received += n as u64;
-            }
-            Ok(Err(e)) => {
-                return Err(ProtocolError::Io(e));
-            }
-            Err(_) => {
-                warn!("Connection timed out after being idle for {}s", read_timeout.as_secs());
-                break;
-            }
-        }
+    let idle_timeout = settings.idle_timeout;
+    let mut draining = false;
+    let mut idle_deadline = tokio::time::Instant::now() + idle_timeout;
 
-        // Parse frames
-        loop {
-            match codec.decode(&mut buf) {
-                Ok(Some(frame)) => {
-                    let mut s = stats.write().await;
-                    s.frames_received += 1;
-                    drop(s);
-
-                    match handle_frame(
-                        frame.clone(),
-                        &mut connection_state,
-                        &mut session_id,
-                        &state,
-                    ).await {
-                        Ok(Some(mut response)) => {
-                            // Propagate correlation ID
-                            response.correlation_id = frame.correlation_id;
-                            
-                            let mut response_buf = BytesMut::new();
-                            codec.encode(response, &mut response_buf)?;
-                            
-                            write_half.write_all(&response_buf).await?;
-                            write_half.flush().await?;
-
-                            let mut s = stats.write().await;
-                            s.frames_sent += 1;
-                            s.bytes_sent += response_buf.len() as u64;
-                        }
-                        Ok(None) => {
-                            // No response needed
-                        }
-                        Err(e) => {
-                            // Send error response
-                            let error_frame = create_error_frame(&e, &session_id, frame.correlation_id)?;
-                            let mut error_buf = BytesMut::new();
-                            codec.encode(error_frame, &mut error_buf)?;
-                            
-                            write_half.write_all(&error_buf).await?;
-                            write_half.flush().await?;
-
-                            // Log error and continue
-                            error!("Frame handling error: {}", e);
-                        }
+    let heartbeat_period = settings.heartbeat_interval.unwrap_or(idle_timeout);
+    let mut heartbeat = tokio::time::interval_at(
+        tokio::time::Instant::now() + heartbeat_period,
+        heartbeat_period,
+    );
+    heartbeat.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    let liveness_limit = heartbeat_period.saturating_mul(settings.heartbeat_max_missed);
+    let mut last_inbound = tokio::time::Instant::now();
+
+    // Exec requests in flight, each yielding its correlation ID and response
+    let mut in_flight = JoinSet::new();
+
+    'connection: loop {
+        let (correlation_id, handled) = 'handled: {
+            // Read the next frame with a deadline to prevent idle connection hanging
+            let read_result = tokio::select! {
+                biased;
+                Some(joined) = in_flight.join_next() => match joined {
+                    Ok(completed) => break 'handled completed,
+                    Err(e) => {
+                        error!("Exec request task failed: {}", e);
+                        continue 'connection;
                     }
+                },
+                result = tokio::time::timeout_at(idle_deadline, reader.next_frame()) => result,
+                _ = shutdown.recv(), if !draining => {
+                    debug!("Shutdown requested, draining connection");
+                    draining = true;
+                    idle_deadline = tokio::time::Instant::now() + SHUTDOWN_DRAIN_IDLE;
+                    continue 'connection;
                 }
-                Ok(None) => {
-                    // Need more data
-                    break;
+                _ = heartbeat.tick(), if settings.heartbeat_interval.is_some() && !draining => {
+                    if last_inbound.elapsed() >= liveness_limit {
+                        warn!(
+                            "Peer missed {} heartbeats, closing connection",
+                            settings.heartbeat_max_missed
+                        );
+                        let _ = write_half.shutdown().await;
+                        break 'connection;
+                    }
+                    let mut heartbeat_buf = BytesMut::new();
+                    codec.encode(Frame::new(MessageType::Heartbeat, Vec::new())?, &mut heartbeat_buf)?;
+                    write_half.write_all(&heartbeat_buf).await?;
+                    write_half.flush().await?;
+
+                    let delta = sent_frame_stats(heartbeat_buf.len());
+                    record_stats(&stats, &session_stats, &delta).await;
+                    continue 'connection;
                 }
-                Err(FrameError::InvalidMagic { .. }) => {
-                    // Try to resync
-                    if let Some(pos) = find_magic(&buf) {
-                        if pos > 0 {
-                            warn!("Resyncing after invalid magic, skipping {} bytes", pos);
-                            buf.advance(pos);
-                            let mut s = stats.write().await;
-                            s.resync_events += 1;
-                            continue;
-                        }
-                    } else {
-                        // No magic found, clear buffer
-                        buf.clear();
+            };
+            let delta = reader.take_stats();
+            record_stats(&stats, &session_stats, &delta).await;
+
+            let frame = match read_result {
+                Ok(Ok(Some(frame))) => {
+                    last_inbound = tokio::time::Instant::now();
+                    idle_deadline = last_inbound
+                        + if draining {
+                            SHUTDOWN_DRAIN_IDLE
+                        } else {
+                            idle_timeout
+                        };
+                    frame
+                }
+                Ok(Ok(None)) => {
+                    // Connection closed
+                    break 'connection;
+                }
+                Ok(Err(e)) => {
+                    // Tell the client why before dropping it, when the header says
+                    // enough to explain; the stream cannot be trusted past it
+                    let Some(rejection) = rejected_frame(&e, &session_id, encoding)? else {
+                        return Err(ProtocolError::Frame(e));
+                    };
+                    warn!("Closing connection after unreadable frame: {}", e);
+                    let mut rejection_buf = BytesMut::new();
+                    let sent = match codec.encode(rejection, &mut rejection_buf) {
+                        Ok(()) => write_half
+                            .write_all(&rejection_buf)
+                            .await
+                            .map_err(ProtocolError::Io),
+                        Err(e) => Err(ProtocolError::Frame(e)),
+                    };
+                    if let Err(e) = sent {
+                        debug!("Failed to send frame rejection: {}", e);
                     }
-                    break;
+                    let _ = write_half.shutdown().await;
+                    break 'connection;
                 }
-                Err(FrameError::CrcMismatch { .. }) => {
-                    let mut s = stats.write().await;
-                    s.crc_errors += 1;
-                    warn!("CRC mismatch, dropping frame");
-                    // Try to recover by looking for next magic
-                    if buf.len() > 4 {
-                        buf.advance(1);
+                Err(_) if draining && !in_flight.is_empty() => {
+                    // Keep waiting for the requests still running
+                    idle_deadline = tokio::time::Instant::now() + SHUTDOWN_DRAIN_IDLE;
+                    continue 'connection;
+                }
+                Err(_) if draining => {
+                    debug!("Connection drained, closing");
+                    break 'connection;
+                }
+                Err(_) => {
+                    warn!(
+                        "Connection timed out after being idle for {}s",
+                        idle_timeout.as_secs()
+                    );
+                    let mut timeout_buf = BytesMut::new();
+                    let sent = match codec.encode(
+                        idle_timeout_frame(&session_id, idle_timeout, encoding)?,
+                        &mut timeout_buf,
+                    ) {
+                        Ok(()) => write_half
+                            .write_all(&timeout_buf)
+                            .await
+                            .map_err(ProtocolError::Io),
+                        Err(e) => Err(ProtocolError::Frame(e)),
+                    };
+                    if let Err(e) = sent {
+                        debug!("Failed to send idle timeout error: {}", e);
                     }
+                    let _ = write_half.shutdown().await;
+                    break 'connection;
                 }
-                Err(e) => {
-                    return Err(ProtocolError::Frame(e));
+            };
+
+            if frame.msg_type == MessageType::ExecRequest
+                && connection_state == ProtocolState::Ready
+            {
+                if in_flight.len() >= settings.max_in_flight_requests {
+                    break 'handled (
+                        frame.correlation_id,
+                        Err(ProtocolError::ResourceExhausted(format!(
+                            "{} exec requests already in flight",
+                            in_flight.len()
+                        ))),
+                    );
                 }
+                let (session_id, state, cancel) =
+                    (session_id.clone(), state.clone(), cancel.child_token());
+                let tools = Arc::clone(&settings.tools);
+                in_flight.spawn(async move {
+                    let correlation_id = frame.correlation_id;
+                    let response = exec_response(
+                        &frame,
+                        encoding,
+                        &session_id,
+                        capabilities,
+                        &tools,
+                        &state,
+                        cancel,
+                    )
+                    .await;
+                    (correlation_id, response)
+                });
+                continue 'connection;
+            }
+
+            let correlation_id = frame.correlation_id;
+            (
+                correlation_id,
+                handle_frame(
+                    frame,
+                    &mut connection_state,
+                    &mut session_id,
+                    &mut encoding,
+                    &mut compression,
+                    &mut capabilities,
+                    &settings,
+                    &state,
+                    &session_stats,
+                    &cancel,
+                )
+                .await,
+            )
+        };
+
+        match handled {
+            Ok(Some(mut response)) => {
+                // Propagate correlation ID
+                response.correlation_id = correlation_id;
+
+                // The ack itself goes out uncompressed; everything after it
+                // uses the codec it announced
+                if response.msg_type == MessageType::HelloAck {
+                    codec.set_compression(compression);
+                    reader.set_compression(compression);
+                } else if compression != CompressionCodec::None {
+                    response.flags.insert(FrameFlags::COMPRESSED);
+                }
+
+                let mut response_buf = BytesMut::new();
+                codec.encode(response, &mut response_buf)?;
+
+                write_half.write_all(&response_buf).await?;
+                write_half.flush().await?;
+
+                let delta = sent_frame_stats(response_buf.len());
+                record_stats(&stats, &session_stats, &delta).await;
+            }
+            Ok(None) => {
+                // No response needed
+            }
+            Err(e) => {
+                // Send error response
+                let error_frame = create_error_frame(&e, &session_id, correlation_id, encoding)?;
+                let mut error_buf = BytesMut::new();
+                codec.encode(error_frame, &mut error_buf)?;
+
+                write_half.write_all(&error_buf).await?;
+                write_half.flush().await?;
+
+                // Log error and continue
+                error!("Frame handling error: {}", e);
             }
         }
     }
 
-    // Clean up connection state
+    // Clean up connection state and cancel the runs this connection left active
+    cancel.cancel();
+    while in_flight.join_next().await.is_some() {}
     if !session_id.is_empty() {
-        let mut s = state.write().await;
-        s.connections.remove(&session_id);
+        state.write().await.connections.remove(&session_id);
+    }
+    for run_id in with_runs(&state, |runs| runs.reap_cancelled("client disconnected")).await? {
+        info!("Cancelled run {} after its client disconnected", run_id);
     }
 
     Ok(())
 }
 
 /// Handle a single frame
+///
+/// Hello is always CBOR. Every other frame is parsed and answered in the
+/// encoding negotiated by the hello, which is stored in `encoding`. The hello
+/// also settles on the first codec the client offers that is in
+/// `settings.compression_codecs`, stored in `compression`, and grants the
+/// tool capabilities both sides claim, stored in `capabilities`. Runs are
+/// started with a child of the connection's `cancel` token. Health requests
+/// report `connection_stats` only when they name this connection's session.
+#[allow(clippy::too_many_arguments)]
 async fn handle_frame(
     frame: Frame,
     state: &mut ProtocolState,
     session_id: &mut String,
+    encoding: &mut Encoding,
+    compression: &mut CompressionCodec,
+    capabilities: &mut CapabilityFlags,
+    settings: &ConnectionSettings,
     server_state: &Arc<RwLock<ServerState>>,
+    connection_stats: &Arc<RwLock<ProtocolStats>>,
+    cancel: &CancellationToken,
 ) -> Result<Option<Frame>, ProtocolError> {
     match frame.msg_type {
         MessageType::Hello => {
             let hello: HelloPayload = parse_frame(&frame)?;
-            debug!("Received hello from {} {}", hello.client_name, hello.client_version);
+            debug!(
+                "Received hello from {} {}",
+                hello.client_name, hello.client_version
+            );
 
             // Generate session ID
             let new_session_id = format!("sess-{}", {
@@ -505,25 +1012,51 @@ async fn handle_frame(
             // Store connection info
             {
                 let mut s = server_state.write().await;
-                s.connections.insert(new_session_id.clone(), ConnectionInfo {
-                    session_id: new_session_id.clone(),
-                    client_name: hello.client_name.clone(),
-                    client_version: hello.client_version.clone(),
-                    protocol_version: crate::protocol::ProtocolVersion::V1_0,
-                    connected_at: std::time::Instant::now(),
-                });
+                s.connections.insert(
+                    new_session_id.clone(),
+                    ConnectionInfo {
+                        session_id: new_session_id.clone(),
+                        client_name: hello.client_name.clone(),
+                        client_version: hello.client_version.clone(),
+                        protocol_version: crate::protocol::ProtocolVersion::V1_0,
+                        connected_at: std::time::Instant::now(),
+                        stats: Arc::clone(connection_stats),
+                    },
+                );
             }
 
             *session_id = new_session_id.clone();
             *state = ProtocolState::Ready;
 
+            // Both encodings are supported, so the client's preference wins
+            *encoding = hello.preferred_encoding;
+
+            // Without a codec in common, frames are sent uncompressed
+            *compression = CompressionCodec::negotiate(
+                &hello.compression_codecs,
+                &settings.compression_codecs,
+            );
+
+            // Tool capabilities are granted only when both sides claim them
+            *capabilities = hello.capabilities.intersection(settings.tool_capabilities);
+
             // Build response
-            let ack = HelloAckPayload::new(&new_session_id);
+            let mut ack = HelloAckPayload {
+                selected_encoding: *encoding,
+                selected_compression: *compression,
+                ..HelloAckPayload::new(&new_session_id)
+            };
+            if *compression != CompressionCodec::None {
+                ack.capabilities.insert(CapabilityFlags::COMPRESSION);
+            }
+            ack.capabilities.insert(*capabilities);
             let response = frame_message(MessageType::HelloAck, &ack, frame.correlation_id)?;
-            
-            info!("Session {} established for client {} {}", 
-                new_session_id, hello.client_name, hello.client_version);
-            
+
+            info!(
+                "Session {} established for client {} {}",
+                new_session_id, hello.client_name, hello.client_version
+            );
+
             Ok(Some(response))
         }
         MessageType::ExecRequest => {
@@ -531,26 +1064,88 @@ async fn handle_frame(
                 return Err(ProtocolError::NoSession);
             }
 
-            let request: ExecRequestPayload = parse_frame(&frame)?;
-            debug!("Received exec request for run {}", request.run_id);
+            exec_response(
+                &frame,
+                *encoding,
+                session_id,
+                *capabilities,
+                &settings.tools,
+                server_state,
+                cancel.child_token(),
+            )
+            .await
+        }
+        MessageType::CancelRun => {
+            if *state != ProtocolState::Ready {
+                return Err(ProtocolError::NoSession);
+            }
+
+            let request: CancelRunPayload = parse_frame_as(*encoding, &frame)?;
+            debug!("Received cancel request for run {}", request.run_id);
+
+            let owner = session_id.clone();
+            let (exec_request, outcome) = with_runs(server_state, move |runs| {
+                runs.cancel(&request.run_id, &owner, &request.reason)
+            })
+            .await??;
+            let result = exec_result(&exec_request, session_id, outcome)?;
+            let response = frame_message_as(
+                *encoding,
+                MessageType::ExecResult,
+                &result,
+                frame.correlation_id,
+            )?;
+
+            Ok(Some(response))
+        }
+        MessageType::ResumeRun => {
+            if *state != ProtocolState::Ready {
+                return Err(ProtocolError::NoSession);
+            }
+
+            let request: ResumeRunPayload = parse_frame_as(*encoding, &frame)?;
+            debug!("Received resume request for run {}", request.run_id);
 
-            // Process execution
-            let result = process_execution(&request, session_id).await?;
-            let response = frame_message(MessageType::ExecResult, &result, frame.correlation_id)?;
+            let owner = session_id.clone();
+            let (exec_request, outcome) = with_runs(server_state, move |runs| {
+                runs.resume(&request.run_id, &owner, request.input)
+            })
+            .await??;
+            let result = exec_result(&exec_request, session_id, outcome)?;
+            let response = frame_message_as(
+                *encoding,
+                MessageType::ExecResult,
+                &result,
+                frame.correlation_id,
+            )?;
 
             Ok(Some(response))
         }
         MessageType::HealthRequest => {
-            let _request: HealthRequestPayload = parse_frame(&frame)?;
-            
+            let request: HealthRequestPayload = parse_frame_as(*encoding, &frame)?;
+
+            // Another connection's stats are not this client's to read
+            let connection_stats = match &request.session_id {
+                Some(id) if id == session_id => Some(ConnectionStatsPayload::from(
+                    &*connection_stats.read().await,
+                )),
+                _ => None,
+            };
+
             let result = HealthResultPayload {
                 status: HealthStatus::Healthy,
                 version: env!("CARGO_PKG_VERSION").to_string(),
                 uptime_us: crate::fixed::FixedDuration::from_micros(0), // TODO: track actual uptime
                 load: None,
+                connection_stats,
             };
-            
-            let response = frame_message(MessageType::HealthResult, &result, frame.correlation_id)?;
+
+            let response = frame_message_as(
+                *encoding,
+                MessageType::HealthResult,
+                &result,
+                frame.correlation_id,
+            )?;
             Ok(Some(response))
         }
         MessageType::Heartbeat => {
@@ -567,66 +1162,259 @@ async fn handle_frame(
     }
 }
 
+/// Answer an `ExecRequest` frame with its `ExecResult`
+///
+/// Workflows calling a tool that is not in `tools`, or one needing a
+/// capability outside the session's `capabilities`, are denied before
+/// anything runs. The run is driven on the blocking pool, in parallel with
+/// runs on other ids.
+async fn exec_response(
+    frame: &Frame,
+    encoding: Encoding,
+    session_id: &str,
+    capabilities: CapabilityFlags,
+    tools: &BTreeMap<String, CapabilityFlags>,
+    server_state: &RwLock<ServerState>,
+    cancel: CancellationToken,
+) -> Result<Option<Frame>, ProtocolError> {
+    let request: ExecRequestPayload = parse_frame_as(encoding, frame)?;
+    debug!("Received exec request for run {}", request.run_id);
+    check_tool_capabilities(&request.workflow, capabilities, tools)?;
+
+    // Process execution
+    let owner = session_id.to_string();
+    let result = with_runs(server_state, move |runs| {
+        process_execution(&request, &owner, runs, cancel)
+    })
+    .await??;
+    let response = frame_message_as(
+        encoding,
+        MessageType::ExecResult,
+        &result,
+        frame.correlation_id,
+    )?;
+
+    Ok(Some(response))
+}
+
+/// Call `f` with the shared run registry from the blocking pool
+///
+/// The engine drives runs synchronously, so this keeps a long run from
+/// stalling the runtime, and the server state is only read long enough to
+/// clone the registry out.
+async fn with_runs<T, F>(server_state: &RwLock<ServerState>, f: F) -> Result<T, ProtocolError>
+where
+    T: Send + 'static,
+    F: FnOnce(&RunRegistry) -> T + Send + 'static,
+{
+    let runs = Arc::clone(&server_state.read().await.runs);
+    tokio::task::spawn_blocking(move || f(&runs))
+        .await
+        .map_err(|e| ProtocolError::Execution(format!("run task failed: {e}")))
+}
+
+/// Deny a workflow with a tool step calling a tool missing from `tools`, or
+/// one needing a capability the session did not negotiate
+///
+/// What a tool needs comes from the server's own `tools`, never from the
+/// step's config, so a client cannot talk its way past the check.
+fn check_tool_capabilities(
+    workflow: &Workflow,
+    capabilities: CapabilityFlags,
+    tools: &BTreeMap<String, CapabilityFlags>,
+) -> Result<(), ProtocolError> {
+    for step in &workflow.steps {
+        let Some(tool) = step.tool_name() else {
+            continue;
+        };
+        let Some(&required) = tools.get(tool) else {
+            return Err(ProtocolError::PolicyDenied(format!(
+                "step {} calls tool {}, which this server does not provide",
+                step.id, tool
+            )));
+        };
+        if capabilities.intersection(required) != required {
+            return Err(ProtocolError::PolicyDenied(format!(
+                "step {} calls tool {}, which needs a capability the session did not negotiate",
+                step.id, tool
+            )));
+        }
+    }
+    Ok(())
+}
+
 /// Process an execution request
-async fn process_execution(
+///
+/// Runs the workflow through the engine (see [`RunRegistry::execute`]) and
+/// digests what it did. Cancelling `cancel` stops the run before its next step.
+fn process_execution(
+    request: &ExecRequestPayload,
+    session_id: &str,
+    runs: &RunRegistry,
+    cancel: CancellationToken,
+) -> Result<ExecResultPayload, ProtocolError> {
+    let outcome = runs.execute(request, session_id, cancel)?;
+    exec_result(request, session_id, outcome)
+}
+
+/// Result payload for an outcome of the run `request` started
+///
+/// Event payloads and the final action's input are canonicalized first, so
+/// the returned values encode the same way the digest saw them.
+fn exec_result(
     request: &ExecRequestPayload,
     session_id: &str,
+    mut outcome: RunOutcome,
 ) -> Result<ExecResultPayload, ProtocolError> {
-    // This is a placeholder - actual implementation would:
-    // 1. Validate the workflow
-    // 2. Execute through the engine
-    // 3. Collect events and results
-    // 4. Calculate deterministic result digest
-    
     // ACTIONID SORT ENFORCEMENT
-    // In a real implementation, any rankings or action lists MUST be pre-sorted
-    // here before the digest    // 4. Calculate deterministic result digest
-    
-    // Calculate deterministic result digest using BLAKE3
-    let mut hasher = blake3::Hasher::new();
-    // Hash relevant fields for deterministic fingerprint
-    hasher.update(request.run_id.as_bytes());
-    
-    // Canonical metadata hashing
-    for (key, value) in &request.metadata {
-        hasher.update(key.as_bytes());
-        hasher.update(value.as_bytes());
+    // Any rankings or action lists MUST be pre-sorted here before the digest
+    outcome.events.iter_mut().for_each(RunEvent::canonicalize);
+    if let Some(Action::ToolCall { input, .. }) = &mut outcome.final_action {
+        input.values_mut().for_each(canonicalize_value);
     }
-
-    // In a real implementation, we'd hash the workflow output and artifacts
-    hasher.update(b"requiem-v1");
-    let result_digest = hasher.finalize().to_string();
+    let result_digest =
+        compute_result_digest(request, &outcome.events, outcome.final_action.as_ref())?;
 
     Ok(ExecResultPayload {
         run_id: request.run_id.clone(),
-        status: RunStatus::Completed,
+        status: outcome.status,
         result_digest,
-        events: Vec::new(),
-        final_action: Some(Action::Done),
-        metrics: ExecutionMetrics::default(),
+        events: outcome.events,
+        final_action: outcome.final_action,
+        metrics: outcome.metrics,
         session_id: session_id.to_string(),
     })
 }
 
-/// Create an error response frame
-fn create_error_frame(error: &ProtocolError, session_id: &str, correlation_id: u32) -> Result<Frame, ProtocolError> {
+/// Deterministic BLAKE3 fingerprint of an execution's inputs and outputs.
+///
+/// Covers the run id, metadata, and the canonical JSON encoding (sorted
+/// keys) of the workflow, the ordered events and the final action. Every
+/// section is length-prefixed so adjacent fields cannot be shifted into each
+/// other. The digest does not depend on the session's wire encoding, so CBOR
+/// and JSON clients get the same value for the same request.
+fn compute_result_digest(
+    request: &ExecRequestPayload,
+    events: &[RunEvent],
+    final_action: Option<&Action>,
+) -> Result<String, ProtocolError> {
+    fn update_section(hasher: &mut blake3::Hasher, bytes: &[u8]) {
+        hasher.update(&(bytes.len() as u64).to_le_bytes());
+        hasher.update(bytes);
+    }
+
+    fn canonical<T: serde::Serialize>(value: &T) -> Result<Vec<u8>, ProtocolError> {
+        encode_canonical_json(value).map_err(|e| ProtocolError::Encoding(e.to_string()))
+    }
+
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(b"requiem-v3");
+    update_section(&mut hasher, request.run_id.as_bytes());
+    for (key, value) in &request.metadata {
+        update_section(&mut hasher, key.as_bytes());
+        update_section(&mut hasher, value.as_bytes());
+    }
+    update_section(&mut hasher, &canonical(&request.workflow)?);
+    update_section(&mut hasher, &canonical(&events)?);
+    update_section(&mut hasher, &canonical(&final_action)?);
+    Ok(hasher.finalize().to_string())
+}
+
+/// Create the error frame sent before closing an idle connection
+fn idle_timeout_frame(
+    session_id: &str,
+    idle_timeout: std::time::Duration,
+    encoding: Encoding,
+) -> Result<Frame, ProtocolError> {
+    let error_payload = ErrorPayload {
+        code: ErrorCode::Timeout,
+        message: "Connection idle timeout".to_string(),
+        details: {
+            let mut m = std::collections::BTreeMap::new();
+            m.insert("session_id".to_string(), session_id.to_string());
+            m.insert(
+                "idle_timeout_secs".to_string(),
+                idle_timeout.as_secs().to_string(),
+            );
+            m
+        },
+        correlation_id: session_id.to_string(),
+    };
+
+    frame_message_as(encoding, MessageType::Error, &error_payload, 0)
+}
+
+/// Create the error frame sent before closing a connection whose inbound
+/// frame header was rejected
+///
+/// Covers oversize payload claims (`ResourceExhausted`) and unknown message
+/// types (`InvalidMessage`); other frame errors get no frame (None).
+fn rejected_frame(
+    error: &FrameError,
+    session_id: &str,
+    encoding: Encoding,
+) -> Result<Option<Frame>, ProtocolError> {
+    let mut details = std::collections::BTreeMap::new();
+    details.insert("session_id".to_string(), session_id.to_string());
     let (code, message) = match error {
-        ProtocolError::VersionNegotiationFailed { .. } => {
-            (ErrorCode::UnsupportedVersion, "Version negotiation failed".to_string())
-        }
-        ProtocolError::CapabilityMismatch { .. } => {
-            (ErrorCode::UnsupportedVersion, "Capability mismatch".to_string())
-        }
-        ProtocolError::NoSession => {
-            (ErrorCode::InvalidMessage, "No session established".to_string())
+        FrameError::PayloadTooLarge { size, max } => {
+            details.insert("payload_len".to_string(), size.to_string());
+            details.insert("max_payload_len".to_string(), max.to_string());
+            (
+                ErrorCode::ResourceExhausted,
+                "Frame payload too large".to_string(),
+            )
         }
-        ProtocolError::UnexpectedMessageType { expected, got } => {
-            (ErrorCode::InvalidMessage, 
-             format!("Expected {:?}, got {:?}", expected, got))
+        FrameError::UnknownMessageType(msg_type) => {
+            details.insert("msg_type".to_string(), format!("{msg_type:#06X}"));
+            (
+                ErrorCode::InvalidMessage,
+                "Unknown message type".to_string(),
+            )
         }
-        _ => {
-            (ErrorCode::InternalError, "Internal error".to_string())
+        _ => return Ok(None),
+    };
+
+    let error_payload = ErrorPayload {
+        code,
+        message,
+        details,
+        correlation_id: session_id.to_string(),
+    };
+
+    frame_message_as(encoding, MessageType::Error, &error_payload, 0).map(Some)
+}
+
+/// Create an error response frame
+fn create_error_frame(
+    error: &ProtocolError,
+    session_id: &str,
+    correlation_id: u32,
+    encoding: Encoding,
+) -> Result<Frame, ProtocolError> {
+    let (code, message) = match error {
+        ProtocolError::VersionNegotiationFailed { .. } => (
+            ErrorCode::UnsupportedVersion,
+            "Version negotiation failed".to_string(),
+        ),
+        ProtocolError::CapabilityMismatch { .. } => (
+            ErrorCode::UnsupportedVersion,
+            "Capability mismatch".to_string(),
+        ),
+        ProtocolError::NoSession => (
+            ErrorCode::InvalidMessage,
+            "No session established".to_string(),
+        ),
+        ProtocolError::Execution(message) => (ErrorCode::ExecutionFailed, message.clone()),
+        ProtocolError::ResourceExhausted(message) => {
+            (ErrorCode::ResourceExhausted, message.clone())
         }
+        ProtocolError::PolicyDenied(message) => (ErrorCode::PolicyDenied, message.clone()),
+        ProtocolError::UnexpectedMessageType { expected, got } => (
+            ErrorCode::InvalidMessage,
+            format!("Expected {expected:?}, got {got:?}"),
+        ),
+        _ => (ErrorCode::InternalError, "Internal error".to_string()),
     };
 
     let error_payload = ErrorPayload {
@@ -640,14 +1428,16 @@ fn create_error_frame(error: &ProtocolError, session_id: &str, correlation_id: u
         correlation_id: session_id.to_string(),
     };
 
-    frame_message(MessageType::Error, &error_payload, correlation_id)
+    frame_message_as(encoding, MessageType::Error, &error_payload, correlation_id)
 }
 
 #[cfg(windows)]
 fn is_parent_alive(parent_pid: u32) -> bool {
-    use windows::Win32::System::Threading::{OpenProcess, PROCESS_QUERY_LIMITED_INFORMATION, GetExitCodeProcess};
     use windows::Win32::Foundation::{CloseHandle, STILL_ACTIVE};
-    
+    use windows::Win32::System::Threading::{
+        GetExitCodeProcess, OpenProcess, PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+
     unsafe {
         let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, parent_pid);
         match handle {
@@ -670,45 +1460,1276 @@ fn is_parent_alive(parent_pid: u32) -> bool {
 fn is_parent_alive(parent_pid: u32) -> bool {
     unsafe {
         // kill with signal 0 checks for process existence without sending signal
-        libc::kill(parent_pid as libc::pid_t, 0) == 0 || 
-        std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
-    }
-}
-
-/// Find magic bytes in buffer
-fn find_magic(buf: &BytesMut) -> Option<usize> {
-    let magic_bytes = crate::protocol::MAGIC.to_le_bytes();
-    for i in 0..buf.len().saturating_sub(4) {
-        if buf[i..i+4] == magic_bytes {
-            return Some(i);
-        }
+        libc::kill(parent_pid as libc::pid_t, 0) == 0
+            || std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
     }
-    None
 }
-/// Use FrameCodec from frame module
-use crate::protocol::frame::{FrameCodec, find_magic as _find_magic};
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::protocol::ResumeInput;
 
     #[test]
     fn test_server_config_default() {
         let config = ServerConfig::default();
         assert_eq!(config.max_connections, 100);
         assert!(config.tcp_bind.is_none());
+        assert_eq!(config.validate_transports(), Ok(()));
+    }
+
+    #[tokio::test]
+    async fn test_run_without_viable_transport_fails_fast() {
+        let server = Server::new(ServerConfig {
+            tcp_bind: None,
+            socket_path: None,
+            ..Default::default()
+        });
+        let result = tokio::time::timeout(std::time::Duration::from_secs(5), server.run())
+            .await
+            .expect("run should fail instead of waiting for shutdown");
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "no transport configured: set tcp_bind or socket_path"
+        );
+    }
+
+    #[cfg(not(any(unix, windows)))]
+    #[test]
+    fn test_socket_path_unavailable_off_unix_and_windows() {
+        let config = ServerConfig {
+            tcp_bind: Some("127.0.0.1:0".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            config.validate_transports().unwrap_err().to_string(),
+            "socket_path set but Unix sockets and named pipes unavailable on this target"
+        );
+    }
+
+    /// Bind to an ephemeral port and release it for the server under test
+    fn free_tcp_addr() -> String {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        listener.local_addr().unwrap().to_string()
+    }
+
+    async fn connect_with_retry(addr: &str) -> TcpStream {
+        for _ in 0..50 {
+            if let Ok(stream) = TcpStream::connect(addr).await {
+                return stream;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+        panic!("server did not start listening on {addr}");
+    }
+
+    async fn write_frame(stream: &mut TcpStream, frame: Frame) {
+        let mut buf = BytesMut::new();
+        frame.encode(&mut buf).unwrap();
+        stream.write_all(&buf).await.unwrap();
+    }
+
+    async fn read_frame(stream: &mut TcpStream, buf: &mut BytesMut) -> Frame {
+        loop {
+            if let Some(frame) = Frame::decode(buf).unwrap() {
+                return frame;
+            }
+            let n = stream.read_buf(buf).await.unwrap();
+            assert!(n > 0, "connection closed before a full frame arrived");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_graceful_shutdown_delivers_in_flight_response() {
+        let addr = free_tcp_addr();
+        let server = Server::new(ServerConfig {
+            tcp_bind: Some(addr.clone()),
+            socket_path: None,
+            ..Default::default()
+        });
+        let runner = {
+            let server = server.clone();
+            tokio::spawn(async move { server.run().await.map_err(|e| e.to_string()) })
+        };
+
+        let mut stream = connect_with_retry(&addr).await;
+        let mut buf = BytesMut::new();
+        let hello = HelloPayload::new("test-cli", "1.0.0");
+        write_frame(
+            &mut stream,
+            frame_message(MessageType::Hello, &hello, 1).unwrap(),
+        )
+        .await;
+        let ack = read_frame(&mut stream, &mut buf).await;
+        assert_eq!(ack.msg_type, MessageType::HelloAck);
+
+        let request = ExecRequestPayload {
+            run_id: "run-drain".to_string(),
+            workflow: crate::protocol::Workflow::default(),
+            controls: crate::protocol::ExecutionControls::default(),
+            policy: crate::protocol::Policy::default(),
+            metadata: std::collections::BTreeMap::new(),
+        };
+        write_frame(
+            &mut stream,
+            frame_message(MessageType::ExecRequest, &request, 2).unwrap(),
+        )
+        .await;
+
+        let shutdown = {
+            let server = server.clone();
+            tokio::spawn(async move {
+                server
+                    .shutdown_graceful(std::time::Duration::from_secs(5))
+                    .await
+            })
+        };
+
+        let response = read_frame(&mut stream, &mut buf).await;
+        assert_eq!(response.msg_type, MessageType::ExecResult);
+        assert_eq!(response.correlation_id, 2);
+        let result: ExecResultPayload = parse_frame(&response).unwrap();
+        assert_eq!(result.run_id, "run-drain");
+
+        assert_eq!(shutdown.await.unwrap(), 0);
+        runner.await.unwrap().unwrap();
+    }
+
+    /// Step that emits `path` with `content` as an artifact
+    fn artifact_step(id: &str, path: &str) -> crate::protocol::WorkflowStep {
+        let mut config = std::collections::BTreeMap::new();
+        config.insert(
+            "patch".to_string(),
+            serde_json::json!({"diffs": [{"path": path, "before": "", "after": "content"}]}),
+        );
+        crate::protocol::WorkflowStep {
+            id: id.to_string(),
+            step_type: crate::protocol::StepType::EmitArtifact,
+            config,
+            depends_on: Vec::new(),
+        }
+    }
+
+    fn exec_request(run_id: &str, steps: Vec<crate::protocol::WorkflowStep>) -> ExecRequestPayload {
+        ExecRequestPayload {
+            run_id: run_id.to_string(),
+            workflow: crate::protocol::Workflow {
+                name: run_id.to_string(),
+                version: "1.0.0".to_string(),
+                steps,
+            },
+            controls: crate::protocol::ExecutionControls::default(),
+            policy: crate::protocol::Policy::default(),
+            metadata: std::collections::BTreeMap::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_result_digest_covers_workflow() {
+        let request = exec_request(
+            "run-digest",
+            vec![
+                artifact_step("fetch", "fetch.md"),
+                artifact_step("summarize", "summary.md"),
+            ],
+        );
+        let runs = RunRegistry::default();
+
+        let first =
+            process_execution(&request, "session", &runs, CancellationToken::new()).unwrap();
+        let again =
+            process_execution(&request, "session", &runs, CancellationToken::new()).unwrap();
+        assert_eq!(first.result_digest, again.result_digest);
+
+        let mut changed = request.clone();
+        changed.workflow.steps[1] = artifact_step("translate", "summary.md");
+        let other =
+            process_execution(&changed, "session", &runs, CancellationToken::new()).unwrap();
+        assert_ne!(first.result_digest, other.result_digest);
+    }
+
+    #[test]
+    fn test_canonicalized_events_share_digest() {
+        let event = |payload: &str| RunEvent {
+            event_id: "run-canon-1".to_string(),
+            event_type: "artifact_emitted".to_string(),
+            timestamp_us: 0,
+            payload: serde_json::from_str(payload).unwrap(),
+        };
+        let json = |event: &RunEvent| crate::protocol::encode_json(event).unwrap();
+        let mut first = event(r#"{"patch": {"b": 1, "a": {"y": [{"k": 2, "j": 1}], "x": null}}}"#);
+        let mut second = event(r#"{"patch": {"a": {"x": null, "y": [{"j": 1, "k": 2}]}, "b": 1}}"#);
+        assert_ne!(json(&first), json(&second));
+
+        first.canonicalize();
+        second.canonicalize();
+        assert_eq!(json(&first), json(&second));
+        let request = exec_request("run-canon", Vec::new());
+        assert_eq!(
+            compute_result_digest(&request, &[first], Some(&Action::Done)).unwrap(),
+            compute_result_digest(&request, &[second], Some(&Action::Done)).unwrap(),
+        );
+    }
+
+    #[tokio::test]
+    async fn test_exec_request_runs_workflow_through_engine() {
+        let addr = free_tcp_addr();
+        let server = Server::new(ServerConfig {
+            tcp_bind: Some(addr.clone()),
+            socket_path: None,
+            ..Default::default()
+        });
+        let runner = {
+            let server = server.clone();
+            tokio::spawn(async move { server.run().await.map_err(|e| e.to_string()) })
+        };
+
+        let mut buf = BytesMut::new();
+        let (mut stream, session_id) = open_session(&addr, &mut buf).await;
+        let request = exec_request(
+            "run-artifact",
+            vec![artifact_step("report", "out/report.md")],
+        );
+        write_frame(
+            &mut stream,
+            frame_message(MessageType::ExecRequest, &request, 2).unwrap(),
+        )
+        .await;
+        let result: ExecResultPayload =
+            parse_frame(&read_frame(&mut stream, &mut buf).await).unwrap();
+
+        assert_eq!(result.status, crate::protocol::RunStatus::Completed);
+        assert_eq!(result.final_action, Some(Action::Done));
+        assert_eq!(result.session_id, session_id);
+        assert_eq!(result.metrics.steps_executed, 1);
+        let event_types: Vec<&str> = result
+            .events
+            .iter()
+            .map(|e| e.event_type.as_str())
+            .collect();
+        assert_eq!(
+            event_types,
+            vec!["run_started", "artifact_emitted", "run_completed"]
+        );
+        let artifact = &result.events[1];
+        assert_eq!(artifact.event_id, "run-artifact-1");
+        assert_eq!(artifact.payload["step_id"], serde_json::json!("report"));
+        assert_eq!(
+            artifact.payload["patch"]["diffs"][0]["path"],
+            serde_json::json!("out/report.md")
+        );
+
+        drop(stream);
+        server
+            .shutdown_graceful(std::time::Duration::from_secs(1))
+            .await;
+        runner.await.unwrap().unwrap();
+    }
+
+    /// Step that calls the `search` tool, which the server pauses on
+    fn search_step(id: &str, depends_on: &[&str]) -> crate::protocol::WorkflowStep {
+        let mut config = std::collections::BTreeMap::new();
+        config.insert("tool".to_string(), serde_json::json!("search"));
+        config.insert("input".to_string(), serde_json::json!({"query": "reach"}));
+        crate::protocol::WorkflowStep {
+            id: id.to_string(),
+            step_type: crate::protocol::StepType::ToolCall,
+            config,
+            depends_on: depends_on.iter().map(|d| d.to_string()).collect(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_tool_call_pauses_run_and_keeps_it_registered() {
+        let request = exec_request(
+            "run-tool",
+            vec![
+                artifact_step("notes", "notes.md"),
+                search_step("search", &["notes"]),
+            ],
+        );
+        let runs = RunRegistry::default();
+
+        let result =
+            process_execution(&request, "session", &runs, CancellationToken::new()).unwrap();
+        assert_eq!(
+            result.status,
+            crate::protocol::RunStatus::Paused {
+                reason: "awaiting tool result for step search".to_string()
+            }
+        );
+        match result.final_action {
+            Some(Action::ToolCall {
+                step_id,
+                tool_name,
+                input,
+            }) => {
+                assert_eq!(step_id, "search");
+                assert_eq!(tool_name, "search");
+                assert_eq!(input["query"], serde_json::json!("reach"));
+            }
+            other => panic!("expected a tool call, got {:?}", other),
+        }
+
+        let duplicate =
+            process_execution(&request, "session", &runs, CancellationToken::new()).unwrap_err();
+        assert_eq!(
+            duplicate.to_string(),
+            "execution failed: run run-tool is already active"
+        );
+
+        let mut unsupported = request.clone();
+        unsupported.run_id = "run-unsupported".to_string();
+        unsupported.workflow.steps[1].depends_on = vec!["later".to_string()];
+        let error = process_execution(&unsupported, "session", &runs, CancellationToken::new())
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "execution failed: step search depends on unknown step later"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_resume_drives_paused_run_on() {
+        let request = exec_request(
+            "run-resume",
+            vec![
+                search_step("search", &[]),
+                artifact_step("report", "report.md"),
+            ],
+        );
+        let runs = RunRegistry::default();
+        let paused =
+            process_execution(&request, "session", &runs, CancellationToken::new()).unwrap();
+        assert!(matches!(
+            paused.status,
+            crate::protocol::RunStatus::Paused { .. }
+        ));
+
+        let tool_result = |step_id: &str| ResumeInput::ToolResult {
+            step_id: step_id.to_string(),
+            success: true,
+            output: std::collections::BTreeMap::from([("hits".to_string(), serde_json::json!(3))]),
+            error: None,
+        };
+
+        // Only the owning session, with the result the run is waiting for
+        let error = runs
+            .resume("run-resume", "other", tool_result("search"))
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "execution failed: run run-resume belongs to another session"
+        );
+        let error = runs
+            .resume("run-resume", "session", ResumeInput::Approve)
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "execution failed: run run-resume is awaiting the tool result for step search"
+        );
+        let error = runs
+            .resume("run-resume", "session", tool_result("report"))
+            .unwrap_err();
+        assert!(error.to_string().contains("awaiting the tool result"));
+        assert!(runs.is_active("run-resume"));
+
+        let (_, outcome) = runs
+            .resume("run-resume", "session", tool_result("search"))
+            .unwrap();
+        assert_eq!(outcome.status, crate::protocol::RunStatus::Completed);
+        assert_eq!(outcome.final_action, Some(Action::Done));
+        assert_eq!(outcome.metrics.steps_executed, 2);
+        let event_types: Vec<&str> = outcome
+            .events
+            .iter()
+            .map(|e| e.event_type.as_str())
+            .collect();
+        assert_eq!(
+            event_types,
+            vec![
+                "run_resumed",
+                "tool_call_completed",
+                "artifact_emitted",
+                "run_completed"
+            ]
+        );
+        assert_eq!(
+            outcome.events[0].event_id,
+            format!("run-resume-{}", paused.events.len())
+        );
+        assert!(!runs.is_active("run-resume"));
+    }
+
+    #[tokio::test]
+    async fn test_resume_with_approval_continues_prompted_run() {
+        let mut request = exec_request("run-approve", vec![search_step("search", &[])]);
+        request.policy.default_decision = crate::protocol::Decision::Prompt;
+        let runs = RunRegistry::default();
+        let paused =
+            process_execution(&request, "session", &runs, CancellationToken::new()).unwrap();
+        assert_eq!(
+            paused.status,
+            crate::protocol::RunStatus::Paused {
+                reason: "awaiting approval for tool search".to_string()
+            }
+        );
+
+        // Approved, the run goes on to the tool call itself
+        let (_, outcome) = runs
+            .resume("run-approve", "session", ResumeInput::Approve)
+            .unwrap();
+        assert_eq!(
+            outcome.status,
+            crate::protocol::RunStatus::Paused {
+                reason: "awaiting tool result for step search".to_string()
+            }
+        );
+        assert!(matches!(
+            outcome.final_action,
+            Some(Action::ToolCall { .. })
+        ));
+        assert!(runs.is_active("run-approve"));
+    }
+
+    #[test]
+    fn test_runs_on_different_ids_are_driven_in_parallel() {
+        let runs = Arc::new(RunRegistry::default());
+        let threads: Vec<_> = (0..8)
+            .map(|index| {
+                let runs = Arc::clone(&runs);
+                std::thread::spawn(move || {
+                    let run_id = format!("run-{index}");
+                    let request = exec_request(&run_id, vec![search_step("search", &[])]);
+                    let paused =
+                        process_execution(&request, "session", &runs, CancellationToken::new())
+                            .unwrap();
+                    assert!(matches!(
+                        paused.status,
+                        crate::protocol::RunStatus::Paused { .. }
+                    ));
+                    let result = ResumeInput::ToolResult {
+                        step_id: "search".to_string(),
+                        success: true,
+                        output: std::collections::BTreeMap::new(),
+                        error: None,
+                    };
+                    runs.resume(&run_id, "session", result).unwrap().1.status
+                })
+            })
+            .collect();
+
+        for thread in threads {
+            assert_eq!(
+                thread.join().unwrap(),
+                crate::protocol::RunStatus::Completed
+            );
+        }
+        assert!((0..8).all(|index| !runs.is_active(&format!("run-{index}"))));
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_token_stops_run_before_next_step() {
+        let request = exec_request(
+            "run-cancelled",
+            vec![
+                artifact_step("a", "a.md"),
+                artifact_step("b", "b.md"),
+                artifact_step("c", "c.md"),
+            ],
+        );
+        let token = CancellationToken::new();
+        token.cancel();
+
+        let runs = RunRegistry::default();
+        let result = process_execution(&request, "session", &runs, token).unwrap();
+        assert_eq!(
+            result.status,
+            crate::protocol::RunStatus::Cancelled {
+                reason: "execution cancelled".to_string()
+            }
+        );
+        assert_eq!(result.metrics.steps_executed, 0);
+        let event_types: Vec<&str> = result
+            .events
+            .iter()
+            .map(|e| e.event_type.as_str())
+            .collect();
+        assert_eq!(event_types, vec!["run_started", "run_cancelled"]);
+    }
+
+    #[tokio::test]
+    async fn test_disconnect_and_cancel_frame_cancel_active_runs() {
+        let addr = free_tcp_addr();
+        let server = Server::new(ServerConfig {
+            tcp_bind: Some(addr.clone()),
+            socket_path: None,
+            tools: BTreeMap::from([("search".to_string(), CapabilityFlags::NONE)]),
+            ..Default::default()
+        });
+        let runner = {
+            let server = server.clone();
+            tokio::spawn(async move { server.run().await.map_err(|e| e.to_string()) })
+        };
+        let request = exec_request("run-orphan", vec![search_step("search", &[])]);
+
+        // Paused at its tool call, then abandoned by its client
+        let mut buf = BytesMut::new();
+        let (mut stream, _) = open_session(&addr, &mut buf).await;
+        write_frame(
+            &mut stream,
+            frame_message(MessageType::ExecRequest, &request, 2).unwrap(),
+        )
+        .await;
+        let result: ExecResultPayload =
+            parse_frame(&read_frame(&mut stream, &mut buf).await).unwrap();
+        assert!(matches!(
+            result.status,
+            crate::protocol::RunStatus::Paused { .. }
+        ));
+        assert!(server.state.read().await.runs.is_active("run-orphan"));
+        drop(stream);
+
+        let mut released = false;
+        for _ in 0..50 {
+            if !server.state.read().await.runs.is_active("run-orphan") {
+                released = true;
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+        assert!(released, "run still active after its client disconnected");
+
+        // The id is free again; this time cancel the run explicitly
+        let mut buf = BytesMut::new();
+        let (mut stream, _) = open_session(&addr, &mut buf).await;
+        write_frame(
+            &mut stream,
+            frame_message(MessageType::ExecRequest, &request, 2).unwrap(),
+        )
+        .await;
+        let paused: ExecResultPayload =
+            parse_frame(&read_frame(&mut stream, &mut buf).await).unwrap();
+        assert!(matches!(
+            paused.status,
+            crate::protocol::RunStatus::Paused { .. }
+        ));
+
+        let cancel = CancelRunPayload {
+            run_id: "run-orphan".to_string(),
+            reason: "user abort".to_string(),
+        };
+        write_frame(
+            &mut stream,
+            frame_message(MessageType::CancelRun, &cancel, 3).unwrap(),
+        )
+        .await;
+        let response = read_frame(&mut stream, &mut buf).await;
+        assert_eq!(response.msg_type, MessageType::ExecResult);
+        let cancelled: ExecResultPayload = parse_frame(&response).unwrap();
+        assert_eq!(
+            cancelled.status,
+            crate::protocol::RunStatus::Cancelled {
+                reason: "user abort".to_string()
+            }
+        );
+        let last = cancelled.events.last().unwrap();
+        assert_eq!(last.event_type, "run_cancelled");
+        assert_eq!(last.event_id, format!("run-orphan-{}", paused.events.len()));
+        assert!(!server.state.read().await.runs.is_active("run-orphan"));
+
+        drop(stream);
+        server
+            .shutdown_graceful(std::time::Duration::from_secs(1))
+            .await;
+        runner.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_json_and_cbor_sessions_share_result_digest() {
+        let addr = free_tcp_addr();
+        let server = Server::new(ServerConfig {
+            tcp_bind: Some(addr.clone()),
+            socket_path: None,
+            ..Default::default()
+        });
+        let runner = {
+            let server = server.clone();
+            tokio::spawn(async move { server.run().await.map_err(|e| e.to_string()) })
+        };
+
+        let mut step = artifact_step("fetch", "fetch.md");
+        step.config.insert(
+            "options".to_string(),
+            serde_json::json!({"zeta": 1, "alpha": {"b": true, "a": [1, 2]}}),
+        );
+        let mut request = exec_request("run-encodings", vec![step]);
+        request
+            .metadata
+            .insert("origin".to_string(), "test".to_string());
+
+        let mut digests = Vec::new();
+        for encoding in [Encoding::Cbor, Encoding::Json] {
+            let mut buf = BytesMut::new();
+            let (mut stream, ack) = negotiate(&addr, &mut buf, encoding).await;
+            assert_eq!(ack.selected_encoding, encoding);
+
+            let frame = frame_message_as(encoding, MessageType::ExecRequest, &request, 2).unwrap();
+            write_frame(&mut stream, frame).await;
+            let response = read_frame(&mut stream, &mut buf).await;
+            assert_eq!(response.msg_type, MessageType::ExecResult);
+            if encoding == Encoding::Json {
+                assert!(serde_json::from_slice::<serde_json::Value>(response.payload()).is_ok());
+            }
+            let result: ExecResultPayload = parse_frame_as(encoding, &response).unwrap();
+            digests.push(result.result_digest);
+        }
+        assert_eq!(digests[0].as_bytes(), digests[1].as_bytes());
+
+        server
+            .shutdown_graceful(std::time::Duration::from_secs(1))
+            .await;
+        runner.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_exec_requests_on_one_session() {
+        let addr = free_tcp_addr();
+        let server = Server::new(ServerConfig {
+            tcp_bind: Some(addr.clone()),
+            socket_path: None,
+            max_in_flight_requests: 3,
+            ..Default::default()
+        });
+        let runner = {
+            let server = server.clone();
+            tokio::spawn(async move { server.run().await.map_err(|e| e.to_string()) })
+        };
+
+        // All three are sent before reading any result
+        let mut buf = BytesMut::new();
+        let (mut stream, _) = open_session(&addr, &mut buf).await;
+        for correlation_id in [10, 11, 12] {
+            let run_id = format!("run-{correlation_id}");
+            let request = exec_request(&run_id, vec![artifact_step("emit", "out.md")]);
+            write_frame(
+                &mut stream,
+                frame_message(MessageType::ExecRequest, &request, correlation_id).unwrap(),
+            )
+            .await;
+        }
+
+        // Results may come back in any order, each under its request's id
+        let mut results = std::collections::BTreeMap::new();
+        for _ in 0..3 {
+            let response = read_frame(&mut stream, &mut buf).await;
+            assert_eq!(response.msg_type, MessageType::ExecResult);
+            let result: ExecResultPayload = parse_frame(&response).unwrap();
+            results.insert(response.correlation_id, result.run_id);
+        }
+        assert_eq!(
+            results,
+            std::collections::BTreeMap::from([
+                (10, "run-10".to_string()),
+                (11, "run-11".to_string()),
+                (12, "run-12".to_string()),
+            ])
+        );
+
+        drop(stream);
+        server
+            .shutdown_graceful(std::time::Duration::from_secs(1))
+            .await;
+        runner.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_llm_tool_requires_negotiated_capability() {
+        let addr = free_tcp_addr();
+        let server = Server::new(ServerConfig {
+            tcp_bind: Some(addr.clone()),
+            socket_path: None,
+            tools: BTreeMap::from([
+                ("search".to_string(), CapabilityFlags::NONE),
+                ("summarize".to_string(), CapabilityFlags::LLM),
+            ]),
+            ..Default::default()
+        });
+        let runner = {
+            let server = server.clone();
+            tokio::spawn(async move { server.run().await.map_err(|e| e.to_string()) })
+        };
+        let summarize = |id: &str| {
+            let mut step = search_step(id, &[]);
+            step.config
+                .insert("tool".to_string(), serde_json::json!("summarize"));
+            step
+        };
+        let request = exec_request("run-llm", vec![summarize("summarize")]);
+
+        // The default hello does not claim the LLM capability, and the step
+        // needs it whether or not it names a category of its own
+        let mut buf = BytesMut::new();
+        let (mut stream, ack) = negotiate(&addr, &mut buf, Encoding::Cbor).await;
+        assert!(!ack.capabilities.contains(CapabilityFlags::LLM));
+        let mut disguised = summarize("disguised");
+        disguised
+            .config
+            .insert("category".to_string(), serde_json::json!("search"));
+        let mut unknown = search_step("unknown", &[]);
+        unknown
+            .config
+            .insert("tool".to_string(), serde_json::json!("shell"));
+        let unnegotiated = "needs a capability the session did not negotiate";
+        let denials = [
+            (
+                request.clone(),
+                format!("step summarize calls tool summarize, which {unnegotiated}"),
+            ),
+            (
+                exec_request("run-disguised", vec![disguised]),
+                format!("step disguised calls tool summarize, which {unnegotiated}"),
+            ),
+            (
+                exec_request("run-unknown", vec![unknown]),
+                "step unknown calls tool shell, which this server does not provide".to_string(),
+            ),
+        ];
+        for (denied, message) in denials {
+            let frame = frame_message(MessageType::ExecRequest, &denied, 2).unwrap();
+            write_frame(&mut stream, frame).await;
+            let response = read_frame(&mut stream, &mut buf).await;
+            assert_eq!(response.msg_type, MessageType::Error);
+            let error: ErrorPayload = parse_frame(&response).unwrap();
+            assert_eq!(error.code, ErrorCode::PolicyDenied);
+            assert_eq!(error.message, message);
+        }
+
+        // A session that claims it is granted it, and the run reaches the tool call
+        let mut llm_buf = BytesMut::new();
+        let mut llm_stream = connect_with_retry(&addr).await;
+        let mut hello = HelloPayload::new("test-cli", "1.0.0");
+        hello.capabilities.insert(CapabilityFlags::LLM);
+        write_frame(
+            &mut llm_stream,
+            frame_message(MessageType::Hello, &hello, 1).unwrap(),
+        )
+        .await;
+        let ack: HelloAckPayload =
+            parse_frame(&read_frame(&mut llm_stream, &mut llm_buf).await).unwrap();
+        assert!(ack.capabilities.contains(CapabilityFlags::LLM));
+        write_frame(
+            &mut llm_stream,
+            frame_message(MessageType::ExecRequest, &request, 2).unwrap(),
+        )
+        .await;
+        let response = read_frame(&mut llm_stream, &mut llm_buf).await;
+        assert_eq!(response.msg_type, MessageType::ExecResult);
+        let result: ExecResultPayload = parse_frame(&response).unwrap();
+        assert_eq!(
+            result.status,
+            crate::protocol::RunStatus::Paused {
+                reason: "awaiting tool result for step summarize".to_string()
+            }
+        );
+
+        drop(stream);
+        drop(llm_stream);
+        server
+            .shutdown_graceful(std::time::Duration::from_secs(1))
+            .await;
+        runner.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_negotiated_compression_applies_to_later_frames() {
+        let addr = free_tcp_addr();
+        let server = Server::new(ServerConfig {
+            tcp_bind: Some(addr.clone()),
+            socket_path: None,
+            compression_codecs: vec![CompressionCodec::Zstd],
+            ..Default::default()
+        });
+        let runner = {
+            let server = server.clone();
+            tokio::spawn(async move { server.run().await.map_err(|e| e.to_string()) })
+        };
+
+        let request = exec_request("run-compressed", vec![artifact_step("fetch", "fetch.md")]);
+        let cases = [
+            (vec![CompressionCodec::Zstd], CompressionCodec::Zstd),
+            (Vec::new(), CompressionCodec::None),
+            // The server does not offer zlib, so nothing is compressed
+            (vec![CompressionCodec::Zlib], CompressionCodec::None),
+        ];
+        for (offered, expected) in cases {
+            let mut buf = BytesMut::new();
+            let mut stream = connect_with_retry(&addr).await;
+            let hello = HelloPayload {
+                compression_codecs: offered,
+                ..HelloPayload::new("test-cli", "1.0.0")
+            };
+            write_frame(
+                &mut stream,
+                frame_message(MessageType::Hello, &hello, 1).unwrap(),
+            )
+            .await;
+            let ack: HelloAckPayload =
+                parse_frame(&read_frame(&mut stream, &mut buf).await).unwrap();
+            assert_eq!(ack.selected_compression, expected);
+            assert_eq!(
+                ack.capabilities.contains(CapabilityFlags::COMPRESSION),
+                expected != CompressionCodec::None
+            );
+
+            let mut codec = FrameCodec::with_compression(expected);
+            let frame = frame_message(MessageType::ExecRequest, &request, 2)
+                .unwrap()
+                .with_flags(FrameFlags::COMPRESSED);
+            let mut out = BytesMut::new();
+            codec.encode(frame, &mut out).unwrap();
+            stream.write_all(&out).await.unwrap();
+
+            let response = read_frame(&mut stream, &mut buf).await;
+            assert_eq!(response.msg_type, MessageType::ExecResult);
+            assert_eq!(
+                response.flags.contains(FrameFlags::COMPRESSED),
+                expected != CompressionCodec::None
+            );
+            let result: ExecResultPayload =
+                parse_frame(&response.decompress(expected).unwrap()).unwrap();
+            assert_eq!(result.run_id, "run-compressed");
+            assert_eq!(result.status, crate::protocol::RunStatus::Completed);
+        }
+
+        server
+            .shutdown_graceful(std::time::Duration::from_secs(1))
+            .await;
+        runner.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_max_connections_rejects_excess_connection() {
+        let addr = free_tcp_addr();
+        let server = Server::new(ServerConfig {
+            tcp_bind: Some(addr.clone()),
+            socket_path: None,
+            max_connections: 1,
+            ..Default::default()
+        });
+        let runner = {
+            let server = server.clone();
+            tokio::spawn(async move { server.run().await.map_err(|e| e.to_string()) })
+        };
+
+        // First connection is admitted and holds the only slot
+        let mut first = connect_with_retry(&addr).await;
+        let mut first_buf = BytesMut::new();
+        let hello = HelloPayload::new("test-cli", "1.0.0");
+        write_frame(
+            &mut first,
+            frame_message(MessageType::Hello, &hello, 1).unwrap(),
+        )
+        .await;
+        let ack = read_frame(&mut first, &mut first_buf).await;
+        assert_eq!(ack.msg_type, MessageType::HelloAck);
+
+        // Second concurrent connection is told the server is full
+        let mut second = TcpStream::connect(&addr).await.unwrap();
+        let mut second_buf = BytesMut::new();
+        let rejection = read_frame(&mut second, &mut second_buf).await;
+        assert_eq!(rejection.msg_type, MessageType::Error);
+        let error: ErrorPayload = parse_frame(&rejection).unwrap();
+        assert_eq!(error.code, ErrorCode::ResourceExhausted);
+        assert_eq!(
+            error.details.get("max_connections").map(String::as_str),
+            Some("1")
+        );
+
+        drop(first);
+        server
+            .shutdown_graceful(std::time::Duration::from_secs(1))
+            .await;
+        runner.await.unwrap().unwrap();
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_idle_connection_times_out() {
+        let state = Arc::new(RwLock::new(ServerState {
+            connections: HashMap::new(),
+            next_session_id: 1,
+            runs: Arc::default(),
+        }));
+        let stats = Arc::new(RwLock::new(ProtocolStats::default()));
+        let (_shutdown_tx, shutdown_rx) = tokio::sync::broadcast::channel(1);
+        let (mut client, server_end) = tokio::io::duplex(4096);
+        let idle_timeout = std::time::Duration::from_secs(30);
+        let settings = ConnectionSettings {
+            idle_timeout,
+            heartbeat_interval: None,
+            heartbeat_max_missed: 3,
+            compression_codecs: Vec::new(),
+            max_in_flight_requests: 1,
+            tool_capabilities: CapabilityFlags::NONE,
+            tools: Arc::default(),
+        };
+
+        let started = tokio::time::Instant::now();
+        let connection = tokio::spawn(handle_connection(
+            server_end,
+            state.clone(),
+            stats,
+            settings,
+            shutdown_rx,
+        ));
+
+        let mut buf = BytesMut::new();
+        frame_message(
+            MessageType::Hello,
+            &HelloPayload::new("test-cli", "1.0.0"),
+            1,
+        )
+        .unwrap()
+        .encode(&mut buf)
+        .unwrap();
+        client.write_all(&buf).await.unwrap();
+        let mut reader = FrameReader::new(&mut client);
+        let ack = reader.next_frame().await.unwrap().unwrap();
+        assert_eq!(ack.msg_type, MessageType::HelloAck);
+        assert_eq!(state.read().await.connections.len(), 1);
+
+        // The client goes quiet; the server gives up once the timeout elapses
+        let error = reader.next_frame().await.unwrap().unwrap();
+        assert!(started.elapsed() >= idle_timeout);
+        assert_eq!(error.msg_type, MessageType::Error);
+        let payload: ErrorPayload = parse_frame(&error).unwrap();
+        assert_eq!(payload.code, ErrorCode::Timeout);
+        assert_eq!(
+            payload.details.get("idle_timeout_secs").map(String::as_str),
+            Some("30")
+        );
+        assert!(reader.next_frame().await.unwrap().is_none());
+
+        connection.await.unwrap().unwrap();
+        assert!(state.read().await.connections.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_rejected_frame_header_gets_error_before_close() {
+        use crate::protocol::{
+            MAGIC, MAX_PAYLOAD_BYTES, PROTOCOL_VERSION_MAJOR, PROTOCOL_VERSION_MINOR,
+        };
+        use bytes::BufMut;
+
+        let oversize = MAX_PAYLOAD_BYTES + 1;
+        let cases = [
+            (
+                MessageType::ExecRequest.to_u32(),
+                oversize,
+                ErrorCode::ResourceExhausted,
+                ("payload_len", oversize.to_string()),
+            ),
+            (
+                0x9999,
+                0,
+                ErrorCode::InvalidMessage,
+                ("msg_type", "0x9999".to_string()),
+            ),
+        ];
+        for (msg_type, payload_len, code, (detail, value)) in cases {
+            let state = Arc::new(RwLock::new(ServerState {
+                connections: HashMap::new(),
+                next_session_id: 1,
+                runs: Arc::default(),
+            }));
+            let stats = Arc::new(RwLock::new(ProtocolStats::default()));
+            let (_shutdown_tx, shutdown_rx) = tokio::sync::broadcast::channel(1);
+            let (mut client, server_end) = tokio::io::duplex(4096);
+            let connection = tokio::spawn(handle_connection(
+                server_end,
+                state,
+                stats,
+                ConnectionSettings {
+                    idle_timeout: std::time::Duration::from_secs(30),
+                    heartbeat_interval: None,
+                    heartbeat_max_missed: 3,
+                    compression_codecs: Vec::new(),
+                    max_in_flight_requests: 1,
+                    tool_capabilities: CapabilityFlags::NONE,
+                    tools: Arc::default(),
+                },
+                shutdown_rx,
+            ));
+
+            // Only the header is sent: the claim alone must be answered
+            let mut header = BytesMut::new();
+            header.put_u32_le(MAGIC);
+            header.put_u16_le(PROTOCOL_VERSION_MAJOR);
+            header.put_u16_le(PROTOCOL_VERSION_MINOR);
+            header.put_u32_le(msg_type);
+            header.put_u32_le(0);
+            header.put_u32_le(7);
+            header.put_u32_le(payload_len);
+            client.write_all(&header).await.unwrap();
+
+            let mut reader = FrameReader::new(&mut client);
+            let error = reader.next_frame().await.unwrap().unwrap();
+            assert_eq!(error.msg_type, MessageType::Error);
+            let payload: ErrorPayload = parse_frame(&error).unwrap();
+            assert_eq!(payload.code, code);
+            assert_eq!(payload.details.get(detail), Some(&value));
+            assert!(reader.next_frame().await.unwrap().is_none());
+
+            connection.await.unwrap().unwrap();
+        }
+    }
+
+    /// Run `handle_connection` over an in-memory stream with heartbeats every 10s
+    fn spawn_heartbeat_connection(
+        state: Arc<RwLock<ServerState>>,
+    ) -> (
+        tokio::io::DuplexStream,
+        JoinHandle<Result<(), ProtocolError>>,
+    ) {
+        let stats = Arc::new(RwLock::new(ProtocolStats::default()));
+        let (shutdown_tx, shutdown_rx) = tokio::sync::broadcast::channel(1);
+        let (client, server_end) = tokio::io::duplex(4096);
+        let settings = ConnectionSettings {
+            idle_timeout: std::time::Duration::from_secs(300),
+            heartbeat_interval: Some(std::time::Duration::from_secs(10)),
+            heartbeat_max_missed: 3,
+            compression_codecs: Vec::new(),
+            max_in_flight_requests: 1,
+            tool_capabilities: CapabilityFlags::NONE,
+            tools: Arc::default(),
+        };
+        let connection = tokio::spawn(async move {
+            let _shutdown_tx = shutdown_tx;
+            handle_connection(server_end, state, stats, settings, shutdown_rx).await
+        });
+        (client, connection)
+    }
+
+    async fn send_frame<W: AsyncWrite + Unpin>(stream: &mut W, frame: Frame) {
+        let mut buf = BytesMut::new();
+        frame.encode(&mut buf).unwrap();
+        stream.write_all(&buf).await.unwrap();
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_silent_peer_reaped_after_missed_heartbeats() {
+        let state = Arc::new(RwLock::new(ServerState {
+            connections: HashMap::new(),
+            next_session_id: 1,
+            runs: Arc::default(),
+        }));
+        let (client, connection) = spawn_heartbeat_connection(state.clone());
+        let (client_read, mut client_write) = tokio::io::split(client);
+        let mut reader = FrameReader::new(client_read);
+
+        let started = tokio::time::Instant::now();
+        let hello = HelloPayload::new("test-cli", "1.0.0");
+        send_frame(
+            &mut client_write,
+            frame_message(MessageType::Hello, &hello, 7).unwrap(),
+        )
+        .await;
+        let ack = reader.next_frame().await.unwrap().unwrap();
+        assert_eq!(ack.correlation_id, 7);
+        assert_eq!(state.read().await.connections.len(), 1);
+
+        // The peer never answers: heartbeats at 10s and 20s, closed at 30s
+        let mut heartbeats = 0;
+        while let Some(frame) = reader.next_frame().await.unwrap() {
+            assert_eq!(frame.msg_type, MessageType::Heartbeat);
+            assert_eq!(frame.flags, FrameFlags::NONE);
+            assert_eq!(frame.correlation_id, 0);
+            assert!(frame.payload().is_empty());
+            heartbeats += 1;
+        }
+        assert_eq!(heartbeats, 2);
+        assert_eq!(started.elapsed(), std::time::Duration::from_secs(30));
+
+        connection.await.unwrap().unwrap();
+        assert!(state.read().await.connections.is_empty());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_heartbeat_replies_keep_connection_alive() {
+        let state = Arc::new(RwLock::new(ServerState {
+            connections: HashMap::new(),
+            next_session_id: 1,
+            runs: Arc::default(),
+        }));
+        let (client, connection) = spawn_heartbeat_connection(state.clone());
+        let (client_read, mut client_write) = tokio::io::split(client);
+        let mut reader = FrameReader::new(client_read);
+
+        let hello = HelloPayload::new("test-cli", "1.0.0");
+        send_frame(
+            &mut client_write,
+            frame_message(MessageType::Hello, &hello, 1).unwrap(),
+        )
+        .await;
+        reader.next_frame().await.unwrap().unwrap();
+
+        // Answer well past the point a silent peer would have been reaped
+        for _ in 0..6 {
+            let frame = reader.next_frame().await.unwrap().unwrap();
+            assert_eq!(frame.msg_type, MessageType::Heartbeat);
+            send_frame(
+                &mut client_write,
+                Frame::new(MessageType::Heartbeat, Vec::new()).unwrap(),
+            )
+            .await;
+        }
+        assert!(!connection.is_finished());
+        assert_eq!(state.read().await.connections.len(), 1);
+
+        drop(client_write);
+        drop(reader);
+        connection.await.unwrap().unwrap();
+    }
+
+    /// Start a TCP server and open a session on it, returning the session id
+    async fn open_session(addr: &str, buf: &mut BytesMut) -> (TcpStream, String) {
+        let (stream, ack) = negotiate(addr, buf, Encoding::Cbor).await;
+        (stream, ack.session_id)
+    }
+
+    /// Open a session that prefers `encoding`, returning the server's ack
+    async fn negotiate(
+        addr: &str,
+        buf: &mut BytesMut,
+        encoding: Encoding,
+    ) -> (TcpStream, HelloAckPayload) {
+        let mut stream = connect_with_retry(addr).await;
+        let hello = HelloPayload {
+            preferred_encoding: encoding,
+            ..HelloPayload::new("test-cli", "1.0.0")
+        };
+        write_frame(
+            &mut stream,
+            frame_message(MessageType::Hello, &hello, 1).unwrap(),
+        )
+        .await;
+        let ack = parse_frame(&read_frame(&mut stream, buf).await).unwrap();
+        (stream, ack)
+    }
+
+    async fn health_request(
+        stream: &mut TcpStream,
+        buf: &mut BytesMut,
+        session_id: Option<&str>,
+    ) -> HealthResultPayload {
+        let request = HealthRequestPayload {
+            detailed: false,
+            session_id: session_id.map(str::to_string),
+        };
+        write_frame(
+            stream,
+            frame_message(MessageType::HealthRequest, &request, 2).unwrap(),
+        )
+        .await;
+        parse_frame(&read_frame(stream, buf).await).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_health_request_reports_session_stats() {
+        let addr = free_tcp_addr();
+        let server = Server::new(ServerConfig {
+            tcp_bind: Some(addr.clone()),
+            socket_path: None,
+            ..Default::default()
+        });
+        let runner = {
+            let server = server.clone();
+            tokio::spawn(async move { server.run().await.map_err(|e| e.to_string()) })
+        };
+
+        let mut buf = BytesMut::new();
+        let (mut stream, session_id) = open_session(&addr, &mut buf).await;
+
+        // Hello and this request received, only the hello ack sent so far
+        let result = health_request(&mut stream, &mut buf, Some(&session_id)).await;
+        let stats = result.connection_stats.unwrap();
+        assert_eq!(stats.frames_received, 2);
+        assert_eq!(stats.frames_sent, 1);
+        assert!(stats.bytes_received > 0);
+
+        let unknown = health_request(&mut stream, &mut buf, Some("sess-unknown")).await;
+        assert!(unknown.connection_stats.is_none());
+        let unfiltered = health_request(&mut stream, &mut buf, None).await;
+        assert!(unfiltered.connection_stats.is_none());
+
+        drop(stream);
+        server
+            .shutdown_graceful(std::time::Duration::from_secs(1))
+            .await;
+        runner.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_connection_stats_isolated_and_sum_to_aggregate() {
+        let addr = free_tcp_addr();
+        let server = Server::new(ServerConfig {
+            tcp_bind: Some(addr.clone()),
+            socket_path: None,
+            ..Default::default()
+        });
+        let runner = {
+            let server = server.clone();
+            tokio::spawn(async move { server.run().await.map_err(|e| e.to_string()) })
+        };
+
+        let mut buf_a = BytesMut::new();
+        let mut buf_b = BytesMut::new();
+        let (mut a, session_a) = open_session(&addr, &mut buf_a).await;
+        let (_b, session_b) = open_session(&addr, &mut buf_b).await;
+        health_request(&mut a, &mut buf_a, None).await;
+        // Naming another session does not expose its stats
+        let foreign = health_request(&mut a, &mut buf_a, Some(&session_b)).await;
+        assert!(foreign.connection_stats.is_none());
+
+        // The last response is recorded just after it is written
+        for _ in 0..50 {
+            if server.stats().await.frames_sent == 4 {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+
+        let stats_a = server.connection_stats(&session_a).await.unwrap();
+        let stats_b = server.connection_stats(&session_b).await.unwrap();
+        assert_eq!((stats_a.frames_received, stats_a.frames_sent), (3, 3));
+        assert_eq!((stats_b.frames_received, stats_b.frames_sent), (1, 1));
+        assert!(server.connection_stats("sess-unknown").await.is_none());
+
+        let total = server.stats().await;
+        let mut sum = stats_a.clone();
+        sum.absorb(&stats_b);
+        assert_eq!(
+            ConnectionStatsPayload::from(&sum),
+            ConnectionStatsPayload::from(&total)
+        );
+
+        drop(a);
+        server
+            .shutdown_graceful(std::time::Duration::from_secs(1))
+            .await;
+        runner.await.unwrap().unwrap();
     }
 
     #[tokio::test]
     async fn test_protocol_stats() {
         let stats = Arc::new(RwLock::new(ProtocolStats::default()));
-        
+
         {
             let mut s = stats.write().await;
             s.frames_sent = 10;
             s.frames_received = 20;
         }
-        
+
         {
             let s = stats.read().await;
             assert_eq!(s.frames_sent, 10);
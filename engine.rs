@@ -1,8 +1,82 @@
-use crate::types::{DecisionInput, DecisionOutput, DecisionTrace};
+use crate::types::{DecisionInput, DecisionOutput, DecisionTrace, ValidationError};
 use std::collections::BTreeMap;
 use ordered_float::OrderedFloat;
 use anyhow::Result;
 
+/// Canonical names of every implemented criterion
+const SUPPORTED_ALGORITHMS: &[&str] = &[
+    "minimax_regret",
+    "maximin",
+    "maximax",
+    "weighted_sum",
+    "softmax",
+    "hurwicz",
+    "laplace",
+    "starr",
+    "hodges_lehmann",
+    "brown_robinson",
+    "nash",
+    "pareto",
+    "epsilon_contamination",
+];
+
+/// Names accepted for `algorithm`, beyond the canonical ones
+const ALGORITHM_ALIASES: &[(&str, &str)] = &[
+    ("savage", "minimax_regret"),
+    ("wald", "maximin"),
+    ("minimax", "maximin"),
+];
+
+/// Canonical algorithm names accepted by `evaluate`
+pub fn supported_algorithms() -> &'static [&'static str] {
+    SUPPORTED_ALGORITHMS
+}
+
+/// Algorithm used when `algorithm` is omitted
+pub fn default_algorithm() -> &'static str {
+    "minimax_regret"
+}
+
+/// Resolve an algorithm name (or alias) to its canonical name.
+///
+/// `None` resolves to `default_algorithm()`. An unrecognized name is an error in
+/// strict mode; otherwise it falls back to the default.
+pub fn resolve_algorithm(name: Option<&str>, strict: bool) -> Result<&'static str, ValidationError> {
+    let Some(name) = name else {
+        return Ok(default_algorithm());
+    };
+    if let Some(canonical) = SUPPORTED_ALGORITHMS.iter().find(|a| **a == name) {
+        return Ok(canonical);
+    }
+    if let Some((_, canonical)) = ALGORITHM_ALIASES.iter().find(|(alias, _)| *alias == name) {
+        return Ok(canonical);
+    }
+    if strict {
+        Err(ValidationError::UnknownAlgorithm(name.to_string()))
+    } else {
+        Ok(default_algorithm())
+    }
+}
+
+/// Route the input to the criterion named by `input.algorithm`
+pub fn evaluate(input: &DecisionInput) -> Result<DecisionOutput> {
+    match resolve_algorithm(input.algorithm.as_deref(), input.strict)? {
+        "maximin" => maximin(input),
+        "maximax" => maximax(input),
+        "weighted_sum" => weighted_sum(input),
+        "softmax" => softmax(input),
+        "hurwicz" => hurwicz(input),
+        "laplace" => laplace(input),
+        "starr" => starr(input),
+        "hodges_lehmann" => hodges_lehmann(input),
+        "brown_robinson" => brown_robinson(input),
+        "nash" => nash(input),
+        "pareto" => pareto(input),
+        "epsilon_contamination" => epsilon_contamination(input),
+        _ => minimax_regret(input),
+    }
+}
+
 pub fn minimax_regret(input: &DecisionInput) -> Result<DecisionOutput> {
     // 1. Calculate Max Utility per State: M(s) = max_a U(a, s)
     let mut max_state_utility: BTreeMap<&String, OrderedFloat<f64>> = BTreeMap::new();
@@ -60,6 +134,7 @@ pub fn minimax_regret(input: &DecisionInput) -> Result<DecisionOutput> {
             max_regret: Some(max_regret_per_action),
             min_utility: None,
             fingerprint: None, // Calculated by caller
+            ..Default::default()
         },
     })
 }
@@ -104,6 +179,53 @@ pub fn maximin(input: &DecisionInput) -> Result<DecisionOutput> {
             max_regret: None,
             min_utility: Some(min_utility_per_action),
             fingerprint: None,
+            ..Default::default()
+        },
+    })
+}
+
+pub fn maximax(input: &DecisionInput) -> Result<DecisionOutput> {
+    // 1. Calculate Max Utility per Action
+    let mut max_utility_per_action = BTreeMap::new();
+
+    for action in &input.actions {
+        let mut current_max = OrderedFloat(f64::NEG_INFINITY);
+
+        for state in &input.states {
+            // Safe due to validation
+            let util = input.outcomes.get(action).unwrap().get(state).unwrap();
+            if *util > current_max {
+                current_max = *util;
+            }
+        }
+        max_utility_per_action.insert(action.clone(), current_max);
+    }
+
+    // 2. Rank Actions (Maximize the Maximum Utility)
+    let mut ranked_actions = input.actions.clone();
+    ranked_actions.sort_by(|a, b| {
+        let max_a = max_utility_per_action.get(a).unwrap();
+        let max_b = max_utility_per_action.get(b).unwrap();
+        // Descending order for utility (higher is better)
+        match max_b.cmp(max_a) {
+            std::cmp::Ordering::Equal => a.cmp(b), // Tie-break: Lexicographic (asc)
+            other => other,
+        }
+    });
+
+    let recommended = ranked_actions.first().ok_or_else(|| anyhow::anyhow!("No actions provided"))?.clone();
+
+    Ok(DecisionOutput {
+        recommended_action: recommended,
+        ranking: ranked_actions,
+        trace: DecisionTrace {
+            algorithm: "maximax".to_string(),
+            regret_table: None,
+            max_regret: None,
+            min_utility: None,
+            max_utility: Some(max_utility_per_action),
+            fingerprint: None,
+            ..Default::default()
         },
     })
 }
@@ -152,6 +274,7 @@ pub fn weighted_sum(input: &DecisionInput) -> Result<DecisionOutput> {
             min_utility: None,
             weighted_scores: Some(weighted_scores),
             fingerprint: None,
+            ..Default::default()
         },
     })
 }
@@ -227,6 +350,7 @@ pub fn softmax(input: &DecisionInput) -> Result<DecisionOutput> {
             weighted_scores: Some(weighted_scores_trace),
             probabilities: Some(probabilities),
             fingerprint: None,
+            ..Default::default()
         },
     })
 }
@@ -279,6 +403,7 @@ pub fn hurwicz(input: &DecisionInput) -> Result<DecisionOutput> {
             probabilities: None,
             hurwicz_scores: Some(hurwicz_scores),
             fingerprint: None,
+            ..Default::default()
         },
     })
 }
@@ -328,6 +453,7 @@ pub fn laplace(input: &DecisionInput) -> Result<DecisionOutput> {
             hurwicz_scores: None,
             laplace_scores: Some(laplace_scores),
             fingerprint: None,
+            ..Default::default()
         },
     })
 }
@@ -391,6 +517,7 @@ pub fn starr(input: &DecisionInput) -> Result<DecisionOutput> {
             laplace_scores: None,
             starr_scores: Some(starr_scores),
             fingerprint: None,
+            ..Default::default()
         },
     })
 }
@@ -452,15 +579,69 @@ pub fn hodges_lehmann(input: &DecisionInput) -> Result<DecisionOutput> {
             starr_scores: None,
             hodges_lehmann_scores: Some(hl_scores),
             fingerprint: None,
+            ..Default::default()
         },
     })
 }
 
+/// Index of the largest value; exact ties go to the lowest index.
+fn argmax_lowest_index(values: &[f64]) -> usize {
+    let mut best = 0;
+    for (i, &v) in values.iter().enumerate().skip(1) {
+        if v > values[best] {
+            best = i;
+        }
+    }
+    best
+}
+
+/// Index of the smallest value; exact ties go to the lowest index.
+fn argmin_lowest_index(values: &[f64]) -> usize {
+    let mut best = 0;
+    for (i, &v) in values.iter().enumerate().skip(1) {
+        if v < values[best] {
+            best = i;
+        }
+    }
+    best
+}
+
+/// Kahan-compensated running sum, so long fictitious-play runs don't drift.
+#[derive(Clone, Copy, Default)]
+struct CompensatedSum {
+    sum: f64,
+    compensation: f64,
+}
+
+impl CompensatedSum {
+    fn add(&mut self, value: f64) {
+        let y = value - self.compensation;
+        let t = self.sum + y;
+        self.compensation = (t - self.sum) - y;
+        self.sum = t;
+    }
+}
+
+/// Fictitious play (Brown-Robinson) between the agent and nature.
+///
+/// Each round the agent picks the action with the highest accumulated payoff
+/// and nature the state with the lowest; exact ties go to the lowest index
+/// (input order), so the play sequence is reproducible on every platform.
+///
+/// After round `t` the game value lies between the lowest nature accumulator
+/// and the highest agent accumulator, each divided by `t`. When
+/// `convergence_epsilon` is set, play stops after the first round whose bounds
+/// are less than it apart; otherwise all `iterations` rounds are played. The rounds played and
+/// the final bounds are recorded in the trace.
 pub fn brown_robinson(input: &DecisionInput) -> Result<DecisionOutput> {
     let iterations = input.iterations.unwrap_or(1000);
     if iterations == 0 {
         return Err(anyhow::anyhow!("Iterations must be greater than 0"));
     }
+    let epsilon = input.convergence_epsilon.map(|e| e.0);
+    if epsilon.is_some_and(|e| !e.is_finite() || e < 0.0) {
+        return Err(anyhow::anyhow!("Convergence epsilon must be a finite, non-negative number"));
+    }
 
     let num_actions = input.actions.len();
     let num_states = input.states.len();
@@ -478,33 +659,25 @@ pub fn brown_robinson(input: &DecisionInput) -> Result<DecisionOutput> {
     let mut x_counts = vec![0; num_actions];
     // let mut y_counts = vec![0; num_states]; // Not strictly needed for result, but part of algo
 
-    let mut agent_accum = vec![0.0; num_actions]; // Accumulated payoff for Agent if they played row i against Nature's history
-    let mut nature_accum = vec![0.0; num_states]; // Accumulated payoff for Agent if Nature played col j against Agent's history
+    let mut agent_accum = vec![CompensatedSum::default(); num_actions]; // Accumulated payoff for Agent if they played row i against Nature's history
+    let mut nature_accum = vec![CompensatedSum::default(); num_states]; // Accumulated payoff for Agent if Nature played col j against Agent's history
+    let mut agent_totals = vec![0.0; num_actions];
+    let mut nature_totals = vec![0.0; num_states];
+    let mut rounds = 0;
+    let mut bounds = (0.0, 0.0);
 
-    for _ in 0..iterations {
+    while rounds < iterations {
         // 1. Agent chooses action i to maximize expected utility (agent_accum)
-        let mut best_action_idx = 0;
-        let mut max_val = f64::NEG_INFINITY;
-        
-        for i in 0..num_actions {
-            let val = agent_accum[i];
-            if val > max_val {
-                max_val = val;
-                best_action_idx = i;
-            }
+        for (total, acc) in agent_totals.iter_mut().zip(&agent_accum) {
+            *total = acc.sum;
         }
+        let best_action_idx = argmax_lowest_index(&agent_totals);
 
         // 2. Nature chooses state j to minimize Agent's utility (nature_accum)
-        let mut best_state_idx = 0;
-        let mut min_val = f64::INFINITY;
-
-        for j in 0..num_states {
-            let val = nature_accum[j];
-            if val < min_val {
-                min_val = val;
-                best_state_idx = j;
-            }
+        for (total, acc) in nature_totals.iter_mut().zip(&nature_accum) {
+            *total = acc.sum;
         }
+        let best_state_idx = argmin_lowest_index(&nature_totals);
 
         // 3. Update counts
         x_counts[best_action_idx] += 1;
@@ -512,16 +685,26 @@ pub fn brown_robinson(input: &DecisionInput) -> Result<DecisionOutput> {
 
         // 4. Update accumulators
         for i in 0..num_actions {
-            agent_accum[i] += matrix[i][best_state_idx];
+            agent_accum[i].add(matrix[i][best_state_idx]);
         }
         for j in 0..num_states {
-            nature_accum[j] += matrix[best_action_idx][j];
+            nature_accum[j].add(matrix[best_action_idx][j]);
+        }
+        rounds += 1;
+
+        // 5. Bound the game value by both players' best responses
+        let t = f64::from(rounds);
+        let upper = agent_accum.iter().map(|acc| acc.sum).fold(f64::NEG_INFINITY, f64::max) / t;
+        let lower = nature_accum.iter().map(|acc| acc.sum).fold(f64::INFINITY, f64::min) / t;
+        bounds = (lower, upper);
+        if epsilon.is_some_and(|e| upper - lower < e) {
+            break;
         }
     }
 
     // Calculate probabilities (frequencies)
     let mut scores = BTreeMap::new();
-    let total = iterations as f64;
+    let total = f64::from(rounds);
     for (i, count) in x_counts.iter().enumerate() {
         scores.insert(input.actions[i].clone(), OrderedFloat(*count as f64 / total));
     }
@@ -554,7 +737,10 @@ pub fn brown_robinson(input: &DecisionInput) -> Result<DecisionOutput> {
             starr_scores: None,
             hodges_lehmann_scores: None,
             brown_robinson_scores: Some(scores),
+            brown_robinson_iterations: Some(rounds),
+            brown_robinson_value_bounds: Some((OrderedFloat(bounds.0), OrderedFloat(bounds.1))),
             fingerprint: None,
+            ..Default::default()
         },
     })
 }
@@ -606,6 +792,36 @@ pub fn nash(input: &DecisionInput) -> Result<DecisionOutput> {
     // If equilibria exist, recommend the action from the first one
     if let Some(first_eq) = equilibria.first() {
         maximin_output.recommended_action = first_eq.0.clone();
+    } else {
+        // 2. No saddle point: solve for the optimal mixed strategy
+        let matrix: Vec<Vec<f64>> = input.actions.iter().map(|action| {
+            let state_map = input.outcomes.get(action).unwrap();
+            input.states.iter().map(|state| state_map.get(state).unwrap().0).collect()
+        }).collect();
+
+        let (weights, value) = solve_zero_sum(&matrix)
+            .ok_or_else(|| anyhow::anyhow!("Failed to solve mixed strategy"))?;
+
+        let mut mixed = BTreeMap::new();
+        for (i, action) in input.actions.iter().enumerate() {
+            mixed.insert(action.clone(), OrderedFloat(weights[i]));
+        }
+
+        // Rank by mixing probability, ties broken by action id
+        let mut ranked_actions = input.actions.clone();
+        ranked_actions.sort_by(|a, b| {
+            let p_a = mixed.get(a).unwrap();
+            let p_b = mixed.get(b).unwrap();
+            match p_b.cmp(p_a) {
+                std::cmp::Ordering::Equal => a.cmp(b),
+                other => other,
+            }
+        });
+
+        maximin_output.recommended_action = ranked_actions.first().ok_or_else(|| anyhow::anyhow!("No actions provided"))?.clone();
+        maximin_output.ranking = ranked_actions;
+        maximin_output.trace.nash_mixed_strategy = Some(mixed);
+        maximin_output.trace.nash_game_value = Some(OrderedFloat(value));
     }
 
     maximin_output.trace.algorithm = "nash".to_string();
@@ -615,6 +831,95 @@ pub fn nash(input: &DecisionInput) -> Result<DecisionOutput> {
     Ok(maximin_output)
 }
 
+/// Solve a zero-sum matrix game for the row player (Agent, maximizing).
+///
+/// Uses the classic LP reduction: shift payoffs positive, solve
+/// `max sum(y) s.t. A y <= 1, y >= 0` for Nature with a dense simplex tableau,
+/// and read the Agent's strategy off the dual (slack) prices.
+/// Bland's rule picks pivots by lowest index, so the result is deterministic.
+/// Probabilities and the value are snapped to 1e-9.
+///
+/// Returns (mixing probabilities per row, game value).
+fn solve_zero_sum(matrix: &[Vec<f64>]) -> Option<(Vec<f64>, f64)> {
+    const EPS: f64 = 1e-12;
+
+    let m = matrix.len();
+    let n = matrix.first()?.len();
+    if n == 0 {
+        return None;
+    }
+
+    let min = matrix.iter().flatten().fold(f64::INFINITY, |acc, v| acc.min(*v));
+    let shift = 1.0 - min;
+
+    // Columns: n structural (y), m slack, 1 rhs
+    let width = n + m + 1;
+    let mut tableau = vec![vec![0.0; width]; m + 1];
+    for i in 0..m {
+        for j in 0..n {
+            tableau[i][j] = matrix[i][j] + shift;
+        }
+        tableau[i][n + i] = 1.0;
+        tableau[i][width - 1] = 1.0;
+    }
+    for v in tableau[m].iter_mut().take(n) {
+        *v = -1.0;
+    }
+    let mut basis: Vec<usize> = (n..n + m).collect();
+
+    while let Some(pivot_col) = (0..n + m).find(|&j| tableau[m][j] < -EPS) {
+        let mut pivot_row: Option<usize> = None;
+        for i in 0..m {
+            if tableau[i][pivot_col] <= EPS {
+                continue;
+            }
+            let ratio = tableau[i][width - 1] / tableau[i][pivot_col];
+            pivot_row = match pivot_row {
+                None => Some(i),
+                Some(r) => {
+                    let best = tableau[r][width - 1] / tableau[r][pivot_col];
+                    if ratio < best - EPS || ((ratio - best).abs() <= EPS && basis[i] < basis[r]) {
+                        Some(i)
+                    } else {
+                        Some(r)
+                    }
+                }
+            };
+        }
+        // Positive payoffs keep the program bounded
+        let pivot_row = pivot_row?;
+
+        let pivot = tableau[pivot_row][pivot_col];
+        for v in tableau[pivot_row].iter_mut() {
+            *v /= pivot;
+        }
+        let pivot_values = tableau[pivot_row].clone();
+        for (i, row) in tableau.iter_mut().enumerate() {
+            if i == pivot_row {
+                continue;
+            }
+            let factor = row[pivot_col];
+            if factor.abs() <= EPS {
+                continue;
+            }
+            for (v, p) in row.iter_mut().zip(&pivot_values) {
+                *v -= factor * p;
+            }
+        }
+        basis[pivot_row] = pivot_col;
+    }
+
+    let total = tableau[m][width - 1];
+    if total <= EPS {
+        return None;
+    }
+
+    // Snap to a 1e-9 grid so pivot round-off cannot break exact ties
+    let snap = |v: f64| (v * 1e9).round() / 1e9;
+    let weights = (0..m).map(|i| snap((tableau[m][n + i] / total).max(0.0))).collect();
+    Some((weights, snap(1.0 / total - shift)))
+}
+
 pub fn pareto(input: &DecisionInput) -> Result<DecisionOutput> {
     let mut dominated = std::collections::HashSet::new();
     
@@ -679,6 +984,7 @@ pub fn pareto(input: &DecisionInput) -> Result<DecisionOutput> {
             nash_equilibria: None,
             pareto_frontier: Some(frontier),
             fingerprint: None,
+            ..Default::default()
         },
     })
 }
@@ -746,6 +1052,221 @@ pub fn epsilon_contamination(input: &DecisionInput) -> Result<DecisionOutput> {
             pareto_frontier: None,
             epsilon_contamination_scores: Some(scores),
             fingerprint: None,
+            ..Default::default()
         },
     })
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn game(algorithm: &str, outcomes: serde_json::Value) -> DecisionInput {
+        serde_json::from_value(serde_json::json!({
+            "actions": ["heads", "tails"],
+            "states": ["heads", "tails"],
+            "outcomes": outcomes,
+            "algorithm": algorithm,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn evaluate_routes_known_algorithm() {
+        let input = game("maximin", serde_json::json!({
+            "heads": {"heads": 3.0, "tails": 2.0},
+            "tails": {"heads": 1.0, "tails": 0.0},
+        }));
+        assert_eq!(evaluate(&input).unwrap().trace.algorithm, "maximin");
+
+        let mut aliased = input.clone();
+        aliased.algorithm = Some("wald".to_string());
+        assert_eq!(evaluate(&aliased).unwrap().trace.algorithm, "maximin");
+
+        let mut omitted = input;
+        omitted.algorithm = None;
+        assert_eq!(evaluate(&omitted).unwrap().trace.algorithm, default_algorithm());
+    }
+
+    #[test]
+    fn evaluate_unknown_algorithm_errors_in_strict_mode() {
+        let mut input = game("maxmin", serde_json::json!({
+            "heads": {"heads": 3.0, "tails": 2.0},
+            "tails": {"heads": 1.0, "tails": 0.0},
+        }));
+        assert_eq!(evaluate(&input).unwrap().trace.algorithm, "minimax_regret");
+
+        input.strict = true;
+        let err = evaluate(&input).unwrap_err();
+        assert_eq!(err.to_string(), "unknown algorithm: maxmin");
+        assert!(matches!(input.validate(), Err(ValidationError::UnknownAlgorithm(name)) if name == "maxmin"));
+    }
+
+    #[test]
+    fn supported_algorithms_lists_every_criterion() {
+        let input = game("", serde_json::json!({
+            "heads": {"heads": 3.0, "tails": 2.0},
+            "tails": {"heads": 1.0, "tails": 0.0},
+        }));
+        for name in supported_algorithms() {
+            let mut input = input.clone();
+            input.algorithm = Some(name.to_string());
+            input.strict = true;
+            input.weights = Some(BTreeMap::from([
+                ("heads".to_string(), OrderedFloat(0.5)),
+                ("tails".to_string(), OrderedFloat(0.5)),
+            ]));
+            assert_eq!(resolve_algorithm(Some(name), true).unwrap(), *name);
+            assert!(evaluate(&input).is_ok(), "{} failed to evaluate", name);
+        }
+        assert!(supported_algorithms().contains(&default_algorithm()));
+        for (_, canonical) in ALGORITHM_ALIASES {
+            assert!(supported_algorithms().contains(canonical));
+        }
+    }
+
+    #[test]
+    fn maximax_picks_highest_single_payoff() {
+        let input = game("maximax", serde_json::json!({
+            "heads": {"heads": 3.0, "tails": 2.0},
+            "tails": {"heads": 1.0, "tails": 5.0},
+        }));
+
+        let output = evaluate(&input).unwrap();
+        assert_eq!(output.recommended_action, "tails");
+        assert_eq!(output.ranking, vec!["tails".to_string(), "heads".to_string()]);
+        let max_utility = output.trace.max_utility.as_ref().unwrap();
+        assert_eq!(max_utility["heads"].0, 3.0);
+        assert_eq!(max_utility["tails"].0, 5.0);
+
+        // The pessimistic criterion disagrees on the same input
+        assert_eq!(maximin(&input).unwrap().recommended_action, "heads");
+        assert_eq!(serde_json::to_string(&output).unwrap(), serde_json::to_string(&maximax(&input).unwrap()).unwrap());
+    }
+
+    #[test]
+    fn maximax_ties_break_lexicographically() {
+        let input = game("maximax", serde_json::json!({
+            "heads": {"heads": 4.0, "tails": 0.0},
+            "tails": {"heads": 1.0, "tails": 4.0},
+        }));
+
+        assert_eq!(maximax(&input).unwrap().ranking, vec!["heads".to_string(), "tails".to_string()]);
+    }
+
+    #[test]
+    fn nash_matching_pennies_mixes_evenly() {
+        let input = game("nash", serde_json::json!({
+            "heads": {"heads": 1.0, "tails": -1.0},
+            "tails": {"heads": -1.0, "tails": 1.0},
+        }));
+
+        let output = nash(&input).unwrap();
+        let trace = &output.trace;
+        assert_eq!(trace.nash_equilibria.as_ref().unwrap().len(), 0);
+
+        let mixed = trace.nash_mixed_strategy.as_ref().unwrap();
+        assert!((mixed["heads"].0 - 0.5).abs() < 1e-9);
+        assert!((mixed["tails"].0 - 0.5).abs() < 1e-9);
+        assert!(trace.nash_game_value.unwrap().0.abs() < 1e-9);
+
+        // Equal mix falls back to the lexicographic tie-break
+        assert_eq!(output.recommended_action, "heads");
+        assert_eq!(serde_json::to_string(&output).unwrap(), serde_json::to_string(&nash(&input).unwrap()).unwrap());
+    }
+
+    #[test]
+    fn brown_robinson_ties_go_to_lowest_index() {
+        let mut input = game("brown_robinson", serde_json::json!({
+            "heads": {"heads": 1.0, "tails": -1.0},
+            "tails": {"heads": -1.0, "tails": 1.0},
+        }));
+
+        // Round 1 is an exact tie for both players, so both pick index 0;
+        // the agent stays on heads until its accumulated payoffs flip.
+        input.iterations = Some(4);
+        let scores = brown_robinson(&input).unwrap().trace.brown_robinson_scores.unwrap();
+        assert_eq!(scores["heads"].0, 0.75);
+        assert_eq!(scores["tails"].0, 0.25);
+
+        input.iterations = Some(1000);
+        let first = serde_json::to_string(&brown_robinson(&input).unwrap()).unwrap();
+        for _ in 0..3 {
+            assert_eq!(serde_json::to_string(&brown_robinson(&input).unwrap()).unwrap(), first);
+        }
+        let scores = brown_robinson(&input).unwrap().trace.brown_robinson_scores.unwrap();
+        assert!((scores["heads"].0 - 0.5).abs() < 0.05);
+    }
+
+    #[test]
+    fn brown_robinson_stops_once_value_bounds_meet_epsilon() {
+        // Saddle point at (heads, tails): the bounds close in as 2 + 1/t
+        let mut input = game("brown_robinson", serde_json::json!({
+            "heads": {"heads": 3.0, "tails": 2.0},
+            "tails": {"heads": 1.0, "tails": 0.0},
+        }));
+        input.convergence_epsilon = Some(OrderedFloat(0.1));
+
+        let trace = brown_robinson(&input).unwrap().trace;
+        assert_eq!(trace.brown_robinson_iterations, Some(11));
+        let (lower, upper) = trace.brown_robinson_value_bounds.unwrap();
+        assert_eq!(lower.0, 2.0);
+        assert!((upper.0 - (2.0 + 1.0 / 11.0)).abs() < 1e-12);
+        assert_eq!(trace.brown_robinson_scores.unwrap()["heads"].0, 1.0);
+
+        input.convergence_epsilon = Some(OrderedFloat(-1.0));
+        assert!(brown_robinson(&input).is_err());
+    }
+
+    #[test]
+    fn brown_robinson_ignores_contamination_epsilon() {
+        // `epsilon` belongs to epsilon-contamination; it must not cut play short
+        let mut input = game("brown_robinson", serde_json::json!({
+            "heads": {"heads": 3.0, "tails": 2.0},
+            "tails": {"heads": 1.0, "tails": 0.0},
+        }));
+        input.iterations = Some(200);
+        input.epsilon = Some(OrderedFloat(0.1));
+
+        let trace = brown_robinson(&input).unwrap().trace;
+        assert_eq!(trace.brown_robinson_iterations, Some(200));
+    }
+
+    #[test]
+    fn brown_robinson_without_epsilon_plays_every_round() {
+        let mut input = game("brown_robinson", serde_json::json!({
+            "heads": {"heads": 3.0, "tails": 2.0},
+            "tails": {"heads": 1.0, "tails": 0.0},
+        }));
+        input.iterations = Some(200);
+
+        let trace = brown_robinson(&input).unwrap().trace;
+        assert_eq!(trace.brown_robinson_iterations, Some(200));
+        let (lower, upper) = trace.brown_robinson_value_bounds.unwrap();
+        assert!(lower.0 <= upper.0 && upper.0 - lower.0 < 0.01);
+    }
+
+    #[test]
+    fn compensated_sum_tracks_small_increments() {
+        let mut sum = CompensatedSum::default();
+        sum.add(1e16);
+        for _ in 0..1000 {
+            sum.add(1.0);
+        }
+        assert_eq!(sum.sum, 1e16 + 1000.0);
+    }
+
+    #[test]
+    fn nash_pure_saddle_point_has_no_mixed_strategy() {
+        let input = game("nash", serde_json::json!({
+            "heads": {"heads": 3.0, "tails": 2.0},
+            "tails": {"heads": 1.0, "tails": 0.0},
+        }));
+
+        let output = nash(&input).unwrap();
+        assert_eq!(output.recommended_action, "heads");
+        assert_eq!(output.trace.nash_equilibria.as_ref().unwrap(), &vec![("heads".to_string(), "tails".to_string())]);
+        assert!(output.trace.nash_mixed_strategy.is_none());
+        assert!(output.trace.nash_game_value.is_none());
+    }
+}
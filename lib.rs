@@ -3,8 +3,8 @@ pub mod engine;
 pub mod types;
 
 use wasm_bindgen::prelude::*;
-use crate::types::{DecisionInput, DecisionOutput};
-use crate::engine::{minimax_regret, maximin, weighted_sum, softmax, hurwicz, laplace, starr, hodges_lehmann, brown_robinson, nash, pareto, epsilon_contamination};
+use crate::types::DecisionInput;
+use crate::engine::supported_algorithms;
 use crate::determinism::CanonicalJson;
 
 #[wasm_bindgen]
@@ -22,24 +22,8 @@ pub fn evaluate_decision(input_json: &str) -> Result<String, JsError> {
     input.validate()
         .map_err(|e| JsError::new(&format!("E_INVALID_INPUT: {}", e)))?;
 
-    // 3. Execute Engine (Minimax Regret)
-    let mut output = match input.algorithm.as_deref() {
-        Some("maximin") => maximin(&input),
-        Some("weighted_sum") => weighted_sum(&input),
-        Some("softmax") => softmax(&input),
-        Some("hurwicz") => hurwicz(&input),
-        Some("laplace") => laplace(&input),
-        Some("starr") => starr(&input),
-        Some("hodges_lehmann") => hodges_lehmann(&input),
-        Some("brown_robinson") => brown_robinson(&input),
-        Some("nash") => nash(&input),
-        Some("pareto") => pareto(&input),
-        Some("epsilon_contamination") => epsilon_contamination(&input),
-        Some("savage") => minimax_regret(&input),
-        Some("wald") => maximin(&input),
-        Some("minimax") => maximin(&input),
-        _ => minimax_regret(&input),
-    }
+    // 3. Execute Engine (strict inputs were rejected above on unknown algorithms)
+    let mut output = engine::evaluate(&input)
         .map_err(|e| JsError::new(&format!("E_INTERNAL: Engine failure: {}", e)))?;
 
     // 4. Compute Deterministic Fingerprint
@@ -90,6 +74,12 @@ pub fn validate_probabilities(input_json: &str) -> Result<bool, JsError> {
     }
 }
 
+#[wasm_bindgen]
+pub fn list_algorithms() -> Result<String, JsError> {
+    serde_json::to_string(supported_algorithms())
+        .map_err(|e| JsError::new(&format!("E_INTERNAL: Serialization failure: {}", e)))
+}
+
 #[wasm_bindgen(start)]
 pub fn init() {
     // Optional initialization hook
@@ -25,6 +25,9 @@ pub struct DecisionInput {
     pub iterations: Option<u32>,
     #[serde(default)]
     pub epsilon: Option<OrderedFloat<f64>>,
+    // Brown-Robinson stops once the value bounds are closer than this
+    #[serde(default)]
+    pub convergence_epsilon: Option<OrderedFloat<f64>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -34,7 +37,7 @@ pub struct DecisionOutput {
     pub trace: DecisionTrace,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct DecisionTrace {
     pub algorithm: String,
     // Map<ActionId, Map<StateId, RegretValue>>
@@ -46,6 +49,9 @@ pub struct DecisionTrace {
     // Map<ActionId, MinUtility>
     #[serde(skip_serializing_if = "Option::is_none")]
     pub min_utility: Option<BTreeMap<String, OrderedFloat<f64>>>,
+    // Map<ActionId, MaxUtility>
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_utility: Option<BTreeMap<String, OrderedFloat<f64>>>,
     // Map<ActionId, WeightedScore>
     #[serde(skip_serializing_if = "Option::is_none")]
     pub weighted_scores: Option<BTreeMap<String, OrderedFloat<f64>>>,
@@ -67,9 +73,21 @@ pub struct DecisionTrace {
     // Map<ActionId, BrownRobinsonScore>
     #[serde(skip_serializing_if = "Option::is_none")]
     pub brown_robinson_scores: Option<BTreeMap<String, OrderedFloat<f64>>>,
+    // Rounds of fictitious play actually run (fewer than requested on early stop)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub brown_robinson_iterations: Option<u32>,
+    // (lower, upper) bounds on the game value after the last round
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub brown_robinson_value_bounds: Option<(OrderedFloat<f64>, OrderedFloat<f64>)>,
     // List of (ActionId, StateId) representing pure Nash Equilibria
     #[serde(skip_serializing_if = "Option::is_none")]
     pub nash_equilibria: Option<Vec<(String, String)>>,
+    // Map<ActionId, MixingProbability> when no pure Nash Equilibrium exists
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nash_mixed_strategy: Option<BTreeMap<String, OrderedFloat<f64>>>,
+    // Value of the zero-sum game under the mixed strategy
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nash_game_value: Option<OrderedFloat<f64>>,
     // List of ActionIds in the Pareto frontier
     #[serde(skip_serializing_if = "Option::is_none")]
     pub pareto_frontier: Option<Vec<String>>,
@@ -94,6 +112,8 @@ pub enum ValidationError {
     InvalidWeightSum(f64),
     #[error("Probability value must be between 0.0 and 1.0 (got {0})")]
     InvalidProbability(f64),
+    #[error("unknown algorithm: {0}")]
+    UnknownAlgorithm(String),
 }
 
 impl DecisionInput {
@@ -113,6 +133,7 @@ impl DecisionInput {
         self.validate_outcomes()?;
 
         if self.strict {
+            crate::engine::resolve_algorithm(self.algorithm.as_deref(), true)?;
             self.validate_weights()?;
             self.validate_probabilities()?;
         }